@@ -2,8 +2,11 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
+use super::adapters::OutputSchemas;
 use super::orchestrator::RallyEvent;
+use crate::github::ChangedFile;
 
 /// Context information passed to agents
 #[derive(Debug, Clone)]
@@ -24,6 +27,47 @@ pub struct Context {
     pub local_mode: bool,
     /// ファイル名 → patch のペア（position 変換用）
     pub file_patches: Vec<(String, String)>,
+    /// Issues referenced from the PR body ("fixes #123"), shown to the reviewer
+    pub linked_issues: Vec<LinkedIssue>,
+}
+
+/// Build `file_patches` and the combined diff for a `Context` from changed files.
+///
+/// Submodule pointer updates are not code to review, so they are left out of the
+/// patches and only noted in the diff.
+pub fn patches_from_files(files: &[ChangedFile]) -> (Vec<(String, String)>, String) {
+    let file_patches: Vec<(String, String)> = files
+        .iter()
+        .filter(|f| f.submodule_change().is_none())
+        .filter_map(|f| f.patch.as_ref().map(|p| (f.filename.clone(), p.clone())))
+        .collect();
+
+    let submodule_notes = files.iter().filter_map(|f| {
+        f.submodule_change().map(|change| {
+            format!(
+                "# {}: submodule pointer updated ({}), not code to review",
+                f.filename,
+                change.label()
+            )
+        })
+    });
+
+    let diff = file_patches
+        .iter()
+        .map(|(_, p)| p.clone())
+        .chain(submodule_notes)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (file_patches, diff)
+}
+
+/// Issue referenced from the PR description
+#[derive(Debug, Clone)]
+pub struct LinkedIssue {
+    pub number: u32,
+    pub title: String,
+    pub body: String,
 }
 
 /// Comment from external tools (bots)
@@ -52,7 +96,11 @@ pub enum ReviewAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewComment {
     pub path: String,
+    /// Commented line, or the last line of a multi-line comment
     pub line: u32,
+    /// First line of a multi-line comment (None for a single line)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
     pub body: String,
     pub severity: CommentSeverity,
 }
@@ -66,6 +114,63 @@ pub enum CommentSeverity {
     Suggestion,
 }
 
+impl ReviewComment {
+    /// `line`, or `start-line` for a multi-line comment
+    pub fn line_range(&self) -> String {
+        match self.start_line {
+            Some(start) => format!("{}-{}", start, self.line),
+            None => self.line.to_string(),
+        }
+    }
+}
+
+/// Header of the reviews and inline comments posted by the AI Rally reviewer
+pub const REVIEWER_COMMENT_PREFIX: &str = "[AI Rally - Reviewer]";
+
+impl CommentSeverity {
+    /// All severities, most urgent first
+    pub const ALL: [CommentSeverity; 4] = [
+        CommentSeverity::Critical,
+        CommentSeverity::Major,
+        CommentSeverity::Minor,
+        CommentSeverity::Suggestion,
+    ];
+
+    /// Whether this severity is `threshold` or more urgent
+    pub fn is_at_least(self, threshold: CommentSeverity) -> bool {
+        let rank = |severity| Self::ALL.iter().position(|&s| s == severity);
+        rank(self) <= rank(threshold)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CommentSeverity::Critical => "critical",
+            CommentSeverity::Major => "major",
+            CommentSeverity::Minor => "minor",
+            CommentSeverity::Suggestion => "suggestion",
+        }
+    }
+
+    /// First line of a posted reviewer comment, e.g. `[AI Rally - Reviewer] (major)`
+    pub fn posted_comment_header(self) -> String {
+        format!("{} ({})", REVIEWER_COMMENT_PREFIX, self.label())
+    }
+
+    /// Read the severity back from a comment posted with [`Self::posted_comment_header`].
+    ///
+    /// Returns `None` for comments written by people or older versions without a severity.
+    pub fn from_posted_comment(body: &str) -> Option<Self> {
+        let label = body
+            .lines()
+            .next()?
+            .strip_prefix(REVIEWER_COMMENT_PREFIX)?
+            .trim()
+            .strip_prefix('(')?
+            .strip_suffix(')')?;
+        Self::ALL.into_iter().find(|s| s.label() == label)
+    }
+}
+
 /// Output from reviewer agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewerOutput {
@@ -73,6 +178,9 @@ pub struct ReviewerOutput {
     pub summary: String,
     pub comments: Vec<ReviewComment>,
     pub blocking_issues: Vec<String>,
+    /// Reviewer's rationale behind the review (optional schema field)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
 }
 
 /// Status from reviewee agent
@@ -104,6 +212,18 @@ pub struct RevieweeOutput {
     pub permission_request: Option<PermissionRequest>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_details: Option<String>,
+    /// Lines changed per file during the turn, measured by the orchestrator with
+    /// `git diff --numstat` (not part of the agent's output)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diff_stats: Vec<FileDiffStat>,
+}
+
+/// Lines added/removed in one file by a reviewee turn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDiffStat {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
 }
 
 /// Trait for agent adapters
@@ -113,16 +233,31 @@ pub struct RevieweeOutput {
 /// different agent implementations (Claude, Codex, Gemini, etc.).
 #[async_trait]
 pub trait AgentAdapter: Send + Sync {
-    /// Agent name (claude, codex, gemini, etc.)
+    /// Agent name (claude, codex, ollama, etc.)
     ///
     /// Currently unused but kept for future extensibility (e.g., logging which agent
     /// is running, multi-agent coordination, or user-facing agent identification).
     #[allow(dead_code)]
     fn name(&self) -> &str;
 
+    /// Check that the agent's CLI (or server) is installed and reachable
+    ///
+    /// Used to pick the first usable agent from the `adapters` fallback chain.
+    async fn check_availability(&self) -> Result<()>;
+
     /// Set event sender for streaming events
     fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>);
 
+    /// Set the token that cancels an in-flight run.
+    ///
+    /// Adapters that spawn a child process kill and reap it when the token is
+    /// cancelled and discard any partial output.
+    fn set_cancellation_token(&mut self, _token: CancellationToken) {}
+
+    /// Use custom output schemas (from `reviewer_schema_path` / `reviewee_schema_path`)
+    /// instead of the embedded ones
+    fn set_output_schemas(&mut self, _schemas: OutputSchemas) {}
+
     /// Run as reviewer
     async fn run_reviewer(&mut self, prompt: &str, context: &Context) -> Result<ReviewerOutput>;
 
@@ -148,14 +283,23 @@ pub trait AgentAdapter: Send + Sync {
     /// Used when user grants permission for a specific action (e.g., "Bash(git push:*)").
     /// This allows the reviewee to execute the permitted action in subsequent calls.
     fn add_reviewee_allowed_tool(&mut self, tool: &str);
+
+    /// Whether the reviewee can change the working tree
+    ///
+    /// A read-only reviewee (Ollama) never modifies files, so its empty fixes do not
+    /// mean the rally is stalled.
+    fn can_modify_files(&self) -> bool {
+        true
+    }
 }
 
 /// Supported agent types
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum SupportedAgent {
     Claude,
     Codex,
-    // Gemini, // Future
+    Ollama,
+    Gemini,
 }
 
 impl SupportedAgent {
@@ -163,18 +307,47 @@ impl SupportedAgent {
         match name.to_lowercase().as_str() {
             "claude" => Some(Self::Claude),
             "codex" => Some(Self::Codex),
-            // "gemini" => Some(Self::Gemini),
+            "ollama" => Some(Self::Ollama),
+            "gemini" => Some(Self::Gemini),
             _ => None,
         }
     }
 
-    // For multi-agent coordination (future extensibility)
-    #[allow(dead_code)]
     pub fn name(&self) -> &'static str {
         match self {
             Self::Claude => "claude",
             Self::Codex => "codex",
-            // Self::Gemini => "gemini",
+            Self::Ollama => "ollama",
+            Self::Gemini => "gemini",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posted_comment_header_roundtrip() {
+        for severity in CommentSeverity::ALL {
+            let body = format!("{}\n\nDetails", severity.posted_comment_header());
+            assert_eq!(CommentSeverity::from_posted_comment(&body), Some(severity));
+        }
+    }
+
+    #[test]
+    fn test_from_posted_comment_ignores_other_comments() {
+        assert_eq!(
+            CommentSeverity::from_posted_comment("Looks good to me"),
+            None
+        );
+        assert_eq!(
+            CommentSeverity::from_posted_comment("[AI Rally - Reviewer]\n\nOld format"),
+            None
+        );
+        assert_eq!(
+            CommentSeverity::from_posted_comment("[AI Rally - Reviewer] (blocker)"),
+            None
+        );
+    }
+}
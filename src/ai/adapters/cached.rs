@@ -0,0 +1,390 @@
+//! Opt-in on-disk cache of agent responses.
+//!
+//! Wraps another adapter and stores parsed outputs keyed by a hash of
+//! (adapter, model, role, schema, prompt). A hit replays the stored output
+//! without spawning the agent. If that turn is continued later (clarifications,
+//! output corrections), the agent first re-runs the replayed prompt so it has a
+//! session to continue.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::ai::adapter::{AgentAdapter, Context, RevieweeOutput, ReviewerOutput};
+use crate::ai::orchestrator::RallyEvent;
+
+use super::common::OutputSchemas;
+
+/// 64-bit FNV-1a. Stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+fn cache_key(adapter: &str, model: &str, role: &str, schema: &str, prompt: &str) -> String {
+    // NUL separators keep ("ab", "c") and ("a", "bc") from colliding
+    let input = [adapter, model, role, schema, prompt].join("\0");
+    format!("{:016x}", fnv1a(input.as_bytes()))
+}
+
+pub struct CachedAdapter {
+    inner: Box<dyn AgentAdapter>,
+    /// Model identifier included in the key (empty when the agent picks its own default)
+    model: String,
+    dir: PathBuf,
+    event_sender: Option<mpsc::Sender<RallyEvent>>,
+    /// Schemas the inner adapter sends, so a custom schema gets its own entries
+    schemas: OutputSchemas,
+    /// Last reviewer turn, when it was replayed: the inner adapter has no session for it
+    replayed_reviewer: Option<(String, Context)>,
+    /// Reviewee counterpart of `replayed_reviewer`
+    replayed_reviewee: Option<(String, Context)>,
+}
+
+impl CachedAdapter {
+    pub fn new(inner: Box<dyn AgentAdapter>, model: &str, dir: PathBuf) -> Self {
+        Self {
+            inner,
+            model: model.to_string(),
+            dir,
+            event_sender: None,
+            schemas: OutputSchemas::default(),
+            replayed_reviewer: None,
+            replayed_reviewee: None,
+        }
+    }
+
+    fn key(&self, role: &str, schema: &str, prompt: &str) -> String {
+        cache_key(self.inner.name(), &self.model, role, schema, prompt)
+    }
+
+    fn load<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let content = fs::read_to_string(self.dir.join(format!("{}.json", key))).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn store<T: Serialize>(&self, key: &str, output: &T) {
+        let result = fs::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(serde_json::to_string_pretty(output)?))
+            .and_then(|json| Ok(fs::write(self.dir.join(format!("{}.json", key)), json)?));
+        if let Err(e) = result {
+            warn!("Failed to write response cache: {}", e);
+        }
+    }
+
+    async fn log(&self, message: String) {
+        if let Some(ref sender) = self.event_sender {
+            let _ = sender.send(RallyEvent::Log(message)).await;
+        }
+    }
+
+    async fn notify_hit(&self, role: &str) {
+        self.log(format!(
+            "[cached] Replaying {} response from {}",
+            role,
+            self.inner.name()
+        ))
+        .await;
+    }
+
+    async fn notify_rerun(&self, role: &str) {
+        self.log(format!(
+            "[cached] Re-running the replayed {} turn on {} to continue it",
+            role,
+            self.inner.name()
+        ))
+        .await;
+    }
+}
+
+#[async_trait]
+impl AgentAdapter for CachedAdapter {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn check_availability(&self) -> Result<()> {
+        self.inner.check_availability().await
+    }
+
+    fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>) {
+        self.event_sender = Some(sender.clone());
+        self.inner.set_event_sender(sender);
+    }
+
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.inner.set_cancellation_token(token);
+    }
+
+    fn set_output_schemas(&mut self, schemas: OutputSchemas) {
+        self.schemas = schemas.clone();
+        self.inner.set_output_schemas(schemas);
+    }
+
+    async fn run_reviewer(&mut self, prompt: &str, context: &Context) -> Result<ReviewerOutput> {
+        let key = self.key("reviewer", self.schemas.reviewer(), prompt);
+        if let Some(output) = self.load::<ReviewerOutput>(&key) {
+            self.notify_hit("reviewer").await;
+            self.replayed_reviewer = Some((prompt.to_string(), context.clone()));
+            return Ok(output);
+        }
+
+        self.replayed_reviewer = None;
+        let output = self.inner.run_reviewer(prompt, context).await?;
+        self.store(&key, &output);
+        Ok(output)
+    }
+
+    async fn run_reviewee(&mut self, prompt: &str, context: &Context) -> Result<RevieweeOutput> {
+        let key = self.key("reviewee", self.schemas.reviewee(), prompt);
+        if let Some(output) = self.load::<RevieweeOutput>(&key) {
+            self.notify_hit("reviewee").await;
+            self.replayed_reviewee = Some((prompt.to_string(), context.clone()));
+            return Ok(output);
+        }
+
+        self.replayed_reviewee = None;
+        let output = self.inner.run_reviewee(prompt, context).await?;
+        // Replaying a fix would report files as modified without touching them,
+        // so only responses that changed nothing are cached
+        if output.files_modified.is_empty() {
+            self.store(&key, &output);
+        }
+        Ok(output)
+    }
+
+    // Continuations depend on the agent's live session, so they are never cached
+    async fn continue_reviewer(&mut self, message: &str) -> Result<ReviewerOutput> {
+        if let Some((prompt, context)) = self.replayed_reviewer.take() {
+            self.notify_rerun("reviewer").await;
+            self.inner.run_reviewer(&prompt, &context).await?;
+        }
+        self.inner.continue_reviewer(message).await
+    }
+
+    async fn continue_reviewee(&mut self, message: &str) -> Result<RevieweeOutput> {
+        if let Some((prompt, context)) = self.replayed_reviewee.take() {
+            self.notify_rerun("reviewee").await;
+            self.inner.run_reviewee(&prompt, &context).await?;
+        }
+        self.inner.continue_reviewee(message).await
+    }
+
+    fn add_reviewee_allowed_tool(&mut self, tool: &str) {
+        self.inner.add_reviewee_allowed_tool(tool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::{ReviewAction, RevieweeStatus};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingAdapter {
+        reviewer_calls: Arc<AtomicUsize>,
+        reviewee_calls: Arc<AtomicUsize>,
+        files_modified: Vec<String>,
+        /// Whether a run started a session that can be continued
+        reviewer_session: bool,
+        reviewee_session: bool,
+    }
+
+    #[async_trait]
+    impl AgentAdapter for CountingAdapter {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            self.reviewer_calls.fetch_add(1, Ordering::SeqCst);
+            self.reviewer_session = true;
+            Ok(ReviewerOutput {
+                action: ReviewAction::Approve,
+                summary: format!("reviewed: {}", prompt),
+                comments: vec![],
+                blocking_issues: vec![],
+                reasoning: None,
+            })
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            self.reviewee_calls.fetch_add(1, Ordering::SeqCst);
+            self.reviewee_session = true;
+            Ok(RevieweeOutput {
+                status: RevieweeStatus::Completed,
+                summary: "fixed".to_string(),
+                files_modified: self.files_modified.clone(),
+                question: None,
+                permission_request: None,
+                error_details: None,
+                diff_stats: Vec::new(),
+            })
+        }
+
+        async fn continue_reviewer(&mut self, message: &str) -> Result<ReviewerOutput> {
+            if !self.reviewer_session {
+                anyhow::bail!("No reviewer session to continue");
+            }
+            Ok(ReviewerOutput {
+                action: ReviewAction::Approve,
+                summary: format!("continued: {}", message),
+                comments: vec![],
+                blocking_issues: vec![],
+                reasoning: None,
+            })
+        }
+
+        async fn continue_reviewee(&mut self, message: &str) -> Result<RevieweeOutput> {
+            if !self.reviewee_session {
+                anyhow::bail!("No reviewee session to continue");
+            }
+            Ok(RevieweeOutput {
+                status: RevieweeStatus::Completed,
+                summary: format!("continued: {}", message),
+                files_modified: vec![],
+                question: None,
+                permission_request: None,
+                error_details: None,
+                diff_stats: Vec::new(),
+            })
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    fn test_context() -> Context {
+        Context {
+            repo: "owner/repo".to_string(),
+            pr_number: 1,
+            pr_title: "Title".to_string(),
+            pr_body: None,
+            diff: "+line".to_string(),
+            working_dir: None,
+            head_sha: "abc".to_string(),
+            base_branch: "main".to_string(),
+            external_comments: vec![],
+            local_mode: false,
+            file_patches: vec![],
+            linked_issues: vec![],
+        }
+    }
+
+    fn cached(
+        dir: &tempfile::TempDir,
+        model: &str,
+        files_modified: Vec<String>,
+    ) -> (CachedAdapter, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let reviewer_calls = Arc::new(AtomicUsize::new(0));
+        let reviewee_calls = Arc::new(AtomicUsize::new(0));
+        let inner = CountingAdapter {
+            reviewer_calls: reviewer_calls.clone(),
+            reviewee_calls: reviewee_calls.clone(),
+            files_modified,
+            reviewer_session: false,
+            reviewee_session: false,
+        };
+        let adapter = CachedAdapter::new(Box::new(inner), model, dir.path().to_path_buf());
+        (adapter, reviewer_calls, reviewee_calls)
+    }
+
+    #[tokio::test]
+    async fn test_identical_prompt_hits_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = test_context();
+        let (mut adapter, calls, _) = cached(&dir, "", vec![]);
+
+        let first = adapter.run_reviewer("prompt", &context).await.unwrap();
+        let second = adapter.run_reviewer("prompt", &context).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(second.summary, first.summary);
+
+        // A fresh wrapper over the same directory replays from disk
+        let (mut adapter, calls, _) = cached(&dir, "", vec![]);
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_different_inputs_miss_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = test_context();
+        let (mut adapter, calls, _) = cached(&dir, "model-a", vec![]);
+
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        adapter
+            .run_reviewer("other prompt", &context)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let (mut adapter, calls, _) = cached(&dir, "model-b", vec![]);
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reviewee_fix_with_modified_files_is_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = test_context();
+        let (mut adapter, _, calls) = cached(&dir, "", vec!["src/main.rs".to_string()]);
+
+        adapter.run_reviewee("prompt", &context).await.unwrap();
+        adapter.run_reviewee("prompt", &context).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_continuation_after_hit_reruns_the_replayed_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = test_context();
+        let (mut adapter, _, _) = cached(&dir, "", vec![]);
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        adapter.run_reviewee("prompt", &context).await.unwrap();
+
+        // A fresh wrapper replays both turns, so its agent has no session yet
+        let (mut adapter, reviewer_calls, reviewee_calls) = cached(&dir, "", vec![]);
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        adapter.run_reviewee("prompt", &context).await.unwrap();
+        assert_eq!(reviewer_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(reviewee_calls.load(Ordering::SeqCst), 0);
+
+        let review = adapter.continue_reviewer("answer").await.unwrap();
+        assert_eq!(review.summary, "continued: answer");
+        let fix = adapter.continue_reviewee("answer").await.unwrap();
+        assert_eq!(fix.summary, "continued: answer");
+        assert_eq!(reviewer_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(reviewee_calls.load(Ordering::SeqCst), 1);
+
+        // The session now exists, so later continuations don't re-run the turn
+        adapter.continue_reviewer("again").await.unwrap();
+        assert_eq!(reviewer_calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,18 +1,17 @@
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use crate::ai::adapter::{AgentAdapter, Context, RevieweeOutput, ReviewerOutput};
 use crate::ai::orchestrator::RallyEvent;
 use crate::config::AiConfig;
 
-const REVIEWER_SCHEMA: &str = include_str!("../schemas/reviewer.json");
-const REVIEWEE_SCHEMA: &str = include_str!("../schemas/reviewee.json");
-
 /// Claude Code adapter
 pub struct ClaudeAdapter {
     /// Cached allowed tools string for reviewer (built once at initialization)
@@ -22,6 +21,28 @@ pub struct ClaudeAdapter {
     reviewer_session_id: Option<String>,
     reviewee_session_id: Option<String>,
     event_sender: Option<mpsc::Sender<RallyEvent>>,
+    retry: RetryPolicy,
+    cancel_token: CancellationToken,
+    /// Refuses a turn that would overlap an unsettled one
+    turns: TurnTracker,
+    schemas: OutputSchemas,
+    /// `--model` for reviewer/reviewee turns (None uses the CLI's default)
+    reviewer_model: Option<String>,
+    reviewee_model: Option<String>,
+    /// Extra environment variables for the `claude` process (`[ai.env.claude]`)
+    env: HashMap<String, String>,
+}
+
+/// Join the `--allowedTools` list: `overrides` replace `base` when set, then `additional` is appended
+fn join_allowed_tools(base: &str, overrides: Option<&[String]>, additional: &[String]) -> String {
+    let base = overrides.map_or_else(|| base.to_string(), |tools| tools.join(","));
+    if additional.is_empty() {
+        base
+    } else if base.is_empty() {
+        additional.join(",")
+    } else {
+        format!("{},{}", base, additional.join(","))
+    }
 }
 
 impl ClaudeAdapter {
@@ -32,25 +53,34 @@ impl ClaudeAdapter {
             reviewer_session_id: None,
             reviewee_session_id: None,
             event_sender: None,
+            retry: RetryPolicy::from_config(config),
+            cancel_token: CancellationToken::new(),
+            turns: TurnTracker::default(),
+            schemas: OutputSchemas::default(),
+            reviewer_model: config.reviewer_model.clone(),
+            reviewee_model: config.reviewee_model.clone(),
+            env: config.agent_env("claude"),
         }
     }
 
     /// Build allowed tools string for reviewer.
     /// Base tools: Read, Glob, Grep, gh pr view/diff/checks, gh api GET
+    /// (replaced by reviewer_allowed_tools when set)
     pub(crate) fn build_reviewer_allowed_tools(config: &AiConfig) -> String {
         let base = "Read,Glob,Grep,Bash(gh pr view:*),Bash(gh pr diff:*),Bash(gh pr checks:*),Bash(gh api --method GET:*),Bash(gh api -X GET:*)";
 
-        if config.reviewer_additional_tools.is_empty() {
-            base.to_string()
-        } else {
-            format!("{},{}", base, config.reviewer_additional_tools.join(","))
-        }
+        join_allowed_tools(
+            base,
+            config.reviewer_allowed_tools.as_deref(),
+            &config.reviewer_additional_tools,
+        )
     }
 
     /// Build allowed tools string for reviewee.
     /// Base tools: File ops, git (without push), gh pr read-only, build/test commands.
     /// NOTE: git push is NOT included by default (Breaking change).
     /// To enable, add "Bash(git push:*)" to reviewee_additional_tools.
+    /// The base list is replaced by reviewee_allowed_tools when set.
     pub(crate) fn build_reviewee_allowed_tools(config: &AiConfig) -> String {
         // NOTE: git push is NOT included by default (Breaking change from v0.1.x).
         // Users must explicitly add "Bash(git push:*)" to reviewee_additional_tools to enable.
@@ -77,11 +107,11 @@ impl ClaudeAdapter {
             "Bash(bun install:*),Bash(bun test:*),Bash(bun run:*)"
         );
 
-        if config.reviewee_additional_tools.is_empty() {
-            base.to_string()
-        } else {
-            format!("{},{}", base, config.reviewee_additional_tools.join(","))
-        }
+        join_allowed_tools(
+            base,
+            config.reviewee_allowed_tools.as_deref(),
+            &config.reviewee_additional_tools,
+        )
     }
 
     async fn send_event(&self, event: RallyEvent) {
@@ -90,12 +120,41 @@ impl ClaudeAdapter {
         }
     }
 
+    /// Run Claude Code with streaming JSON output, retrying transient process failures
     // TODO: Consider using a builder/struct pattern for parameters if more options are added.
     async fn run_claude_streaming(
         &self,
         prompt: &str,
         schema: &str,
         allowed_tools: Option<&str>,
+        model: Option<&str>,
+        working_dir: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<ClaudeResponse> {
+        retry_transient(
+            self.retry,
+            self.event_sender.as_ref(),
+            &self.cancel_token,
+            || {
+                self.run_claude_attempt(
+                    prompt,
+                    schema,
+                    allowed_tools,
+                    model,
+                    working_dir,
+                    session_id,
+                )
+            },
+        )
+        .await
+    }
+
+    async fn run_claude_attempt(
+        &self,
+        prompt: &str,
+        schema: &str,
+        allowed_tools: Option<&str>,
+        model: Option<&str>,
         working_dir: Option<&str>,
         session_id: Option<&str>,
     ) -> Result<ClaudeResponse> {
@@ -108,6 +167,9 @@ impl ClaudeAdapter {
         if let Some(tools) = allowed_tools {
             cmd.arg("--allowedTools").arg(tools);
         }
+        if let Some(model) = model {
+            cmd.arg("--model").arg(model);
+        }
 
         if let Some(session) = session_id {
             cmd.arg("--resume").arg(session);
@@ -116,10 +178,13 @@ impl ClaudeAdapter {
         if let Some(dir) = working_dir {
             cmd.current_dir(dir);
         }
+        cmd.envs(&self.env);
 
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        // Don't leave the agent running if this future is dropped (e.g., task abort)
+        cmd.kill_on_drop(true);
 
         let mut child = cmd.spawn().with_context(|| {
             format!(
@@ -146,10 +211,15 @@ impl ClaudeAdapter {
 
         let mut final_response: Option<ClaudeResponse> = None;
         let mut error_lines = Vec::new();
+        // Failures such as a missing login arrive as an error result on stdout
+        let mut error_result: Option<String> = None;
 
         // Process NDJSON stream
         loop {
             tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    return Err(kill_cancelled_child(&mut child, "claude").await);
+                }
                 line = stdout_reader.next_line() => {
                     match line {
                         Ok(Some(l)) => {
@@ -161,7 +231,11 @@ impl ClaudeAdapter {
                                 self.handle_stream_event(&event).await;
 
                                 // Check if this is the final result
-                                if event.event_type == "result" {
+                                if event.event_type == "result" && event.is_error {
+                                    if let Some(serde_json::Value::String(ref message)) = event.result {
+                                        error_result = Some(message.clone());
+                                    }
+                                } else if event.event_type == "result" {
                                     // --json-schema uses structured_output, otherwise use result
                                     let result_value = event
                                         .structured_output
@@ -171,7 +245,7 @@ impl ClaudeAdapter {
                                         final_response = Some(ClaudeResponse {
                                             session_id: event.session_id.unwrap_or_default(),
                                             result: Some(result),
-                                            cost_usd: event.cost_usd,
+                                            cost_usd: event.total_cost_usd.or(event.cost_usd),
                                             duration_ms: event.duration_ms,
                                         });
                                     }
@@ -198,12 +272,33 @@ impl ClaudeAdapter {
             .context("Failed to wait for claude process")?;
 
         if !status.success() {
+            error_lines.extend(error_result);
             let stderr_output = error_lines.join("\n");
-            return Err(anyhow!(
-                "Claude process failed with status {}: {}",
+            // Retrying can't fix a missing or expired login
+            if let Some(message) = authentication_failure(&stderr_output) {
+                return Err(anyhow!(
+                    "Claude authentication failed: {}. Run `claude` and sign in with /login",
+                    message
+                ));
+            }
+            return Err(AgentExitError {
+                agent: "Claude",
                 status,
-                stderr_output
-            ));
+                stderr: stderr_output,
+            }
+            .into());
+        }
+
+        if let Some(ref response) = final_response {
+            if let Some(ref result) = response.result {
+                self.send_event(RallyEvent::AgentRawResult(result.clone()))
+                    .await;
+            }
+            self.send_event(RallyEvent::UsageReported {
+                cost_usd: response.cost_usd,
+                duration_ms: response.duration_ms,
+            })
+            .await;
         }
 
         final_response.ok_or_else(|| anyhow!("No result received from claude"))
@@ -317,10 +412,28 @@ impl AgentAdapter for ClaudeAdapter {
         "claude"
     }
 
+    async fn check_availability(&self) -> Result<()> {
+        let output = Command::new("claude").arg("--version").output().await;
+        match output {
+            Ok(o) if o.status.success() => Ok(()),
+            _ => Err(anyhow!(
+                "Claude Code CLI not found. Install it with: npm install -g @anthropic-ai/claude-code"
+            )),
+        }
+    }
+
     fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>) {
         self.event_sender = Some(sender);
     }
 
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel_token = token;
+    }
+
+    fn set_output_schemas(&mut self, schemas: OutputSchemas) {
+        self.schemas = schemas;
+    }
+
     async fn run_reviewer(&mut self, prompt: &str, context: &Context) -> Result<ReviewerOutput> {
         // Reviewer tools: read-only operations for code review
         // - Read/Glob/Grep: File reading and searching
@@ -337,12 +450,18 @@ impl AgentAdapter for ClaudeAdapter {
         // Additional tools can be configured via config.reviewer_additional_tools
 
         let response = self
-            .run_claude_streaming(
-                prompt,
-                REVIEWER_SCHEMA,
-                Some(&self.reviewer_allowed_tools),
-                context.working_dir.as_deref(),
-                None,
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                false,
+                self.run_claude_streaming(
+                    prompt,
+                    self.schemas.reviewer(),
+                    Some(&self.reviewer_allowed_tools),
+                    self.reviewer_model.as_deref(),
+                    context.working_dir.as_deref(),
+                    None,
+                ),
             )
             .await?;
 
@@ -376,12 +495,18 @@ impl AgentAdapter for ClaudeAdapter {
         // Additional tools can be configured via config.reviewee_additional_tools
 
         let response = self
-            .run_claude_streaming(
-                prompt,
-                REVIEWEE_SCHEMA,
-                Some(&self.reviewee_allowed_tools),
-                context.working_dir.as_deref(),
-                None,
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                false,
+                self.run_claude_streaming(
+                    prompt,
+                    self.schemas.reviewee(),
+                    Some(&self.reviewee_allowed_tools),
+                    self.reviewee_model.as_deref(),
+                    context.working_dir.as_deref(),
+                    None,
+                ),
             )
             .await?;
 
@@ -398,12 +523,18 @@ impl AgentAdapter for ClaudeAdapter {
             .clone();
 
         let response = self
-            .run_claude_streaming(
-                message,
-                REVIEWER_SCHEMA,
-                Some(&self.reviewer_allowed_tools),
-                None, // --resume restores the original session's context
-                Some(&session_id),
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                true,
+                self.run_claude_streaming(
+                    message,
+                    self.schemas.reviewer(),
+                    Some(&self.reviewer_allowed_tools),
+                    self.reviewer_model.as_deref(),
+                    None, // --resume restores the original session's context
+                    Some(&session_id),
+                ),
             )
             .await?;
         parse_reviewer_output(response.result.as_ref(), "claude")
@@ -417,12 +548,18 @@ impl AgentAdapter for ClaudeAdapter {
             .clone();
 
         let response = self
-            .run_claude_streaming(
-                message,
-                REVIEWEE_SCHEMA,
-                Some(&self.reviewee_allowed_tools),
-                None, // --resume restores the original session's context
-                Some(&session_id),
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                true,
+                self.run_claude_streaming(
+                    message,
+                    self.schemas.reviewee(),
+                    Some(&self.reviewee_allowed_tools),
+                    self.reviewee_model.as_deref(),
+                    None, // --resume restores the original session's context
+                    Some(&session_id),
+                ),
             )
             .await?;
         parse_reviewee_output(response.result.as_ref(), "claude")
@@ -433,7 +570,9 @@ impl AgentAdapter for ClaudeAdapter {
         if self.reviewee_allowed_tools.contains(tool) {
             return;
         }
-        self.reviewee_allowed_tools.push(',');
+        if !self.reviewee_allowed_tools.is_empty() {
+            self.reviewee_allowed_tools.push(',');
+        }
         self.reviewee_allowed_tools.push_str(tool);
     }
 }
@@ -464,8 +603,29 @@ struct StreamEvent {
     structured_output: Option<serde_json::Value>,
     #[serde(default)]
     cost_usd: Option<f64>,
+    /// Newer CLI versions report the cost as total_cost_usd
+    #[serde(default)]
+    total_cost_usd: Option<f64>,
     #[serde(default)]
     duration_ms: Option<u64>,
+    /// Set on a `result` event when the run failed; `result` then holds the message
+    #[serde(default)]
+    is_error: bool,
+}
+
+/// The line of Claude's error output that reports a missing, invalid or expired login
+fn authentication_failure(output: &str) -> Option<&str> {
+    const MARKERS: [&str; 5] = [
+        "invalid api key",
+        "/login",
+        "not logged in",
+        "authentication_error",
+        "oauth token has expired",
+    ];
+    output.lines().map(str::trim).find(|line| {
+        let line = line.to_lowercase();
+        MARKERS.iter().any(|marker| line.contains(marker))
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -508,23 +668,39 @@ struct ClaudeResponse {
     session_id: String,
     #[serde(default)]
     result: Option<serde_json::Value>,
-    // For monitoring and cost analysis (future feature)
+    /// Reported to the UI as RallyEvent::UsageReported
     #[serde(default)]
-    #[allow(dead_code)]
     cost_usd: Option<f64>,
-    // For performance monitoring (future feature)
     #[serde(default)]
-    #[allow(dead_code)]
     duration_ms: Option<u64>,
 }
 
-use super::common::{parse_reviewee_output, parse_reviewer_output, summarize_json, summarize_text};
+use super::common::{
+    kill_cancelled_child, parse_reviewee_output, parse_reviewer_output, retry_transient,
+    summarize_json, summarize_text, AgentExitError, OutputSchemas, RetryPolicy, TurnRole,
+    TurnTracker,
+};
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use insta::assert_snapshot;
 
+    #[test]
+    fn test_authentication_failure() {
+        assert_eq!(
+            authentication_failure("Invalid API key · Please run /login"),
+            Some("Invalid API key · Please run /login")
+        );
+        let output = "warning: slow network\nAPI Error: 401 {\"type\":\"authentication_error\"}";
+        assert_eq!(
+            authentication_failure(output),
+            Some("API Error: 401 {\"type\":\"authentication_error\"}")
+        );
+        assert_eq!(authentication_failure("API Error: 529 Overloaded"), None);
+        assert_eq!(authentication_failure(""), None);
+    }
+
     #[test]
     fn test_build_reviewer_allowed_tools_default() {
         let config = AiConfig::default();
@@ -579,6 +755,31 @@ mod tests {
         assert_snapshot!(tools, @"Read,Edit,Write,Glob,Grep,Bash(git status:*),Bash(git diff:*),Bash(git add:*),Bash(git commit:*),Bash(git log:*),Bash(git show:*),Bash(git branch:*),Bash(git switch:*),Bash(git stash:*),Bash(gh pr view:*),Bash(gh pr diff:*),Bash(gh pr checks:*),Bash(gh api --method GET:*),Bash(gh api -X GET:*),Bash(cargo build:*),Bash(cargo test:*),Bash(cargo check:*),Bash(cargo clippy:*),Bash(cargo fmt:*),Bash(cargo run:*),Bash(npm install:*),Bash(npm test:*),Bash(npm run:*),Bash(npm ci:*),Bash(pnpm install:*),Bash(pnpm test:*),Bash(pnpm run:*),Bash(bun install:*),Bash(bun test:*),Bash(bun run:*),Skill,Bash(git push:*)");
     }
 
+    #[test]
+    fn test_allowed_tools_override_replaces_defaults() {
+        let config = AiConfig {
+            reviewer_allowed_tools: Some(vec!["Read".to_string(), "Grep".to_string()]),
+            reviewee_allowed_tools: Some(vec!["Read".to_string(), "Bash(make:*)".to_string()]),
+            reviewee_additional_tools: vec!["Skill".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            ClaudeAdapter::build_reviewer_allowed_tools(&config),
+            "Read,Grep"
+        );
+        assert_eq!(
+            ClaudeAdapter::build_reviewee_allowed_tools(&config),
+            "Read,Bash(make:*),Skill"
+        );
+
+        let config = AiConfig {
+            reviewer_allowed_tools: Some(vec![]),
+            reviewer_additional_tools: vec!["Read".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ClaudeAdapter::build_reviewer_allowed_tools(&config), "Read");
+    }
+
     #[test]
     fn test_reviewee_base_tools_present() {
         let config = AiConfig::default();
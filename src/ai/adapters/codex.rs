@@ -1,16 +1,23 @@
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex, Weak};
+use std::time::{Duration, Instant};
 use tempfile::NamedTempFile;
 use thiserror::Error;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 
 use crate::ai::adapter::{AgentAdapter, Context, RevieweeOutput, ReviewerOutput};
 use crate::ai::orchestrator::RallyEvent;
+use crate::config::AiConfig;
 
 // Codex requires additionalProperties: false for all objects in the schema
 const REVIEWER_SCHEMA: &str = r#"{
@@ -34,18 +41,20 @@ const REVIEWER_SCHEMA: &str = r#"{
         "properties": {
           "path": {"type": "string"},
           "line": {"type": "integer"},
+          "start_line": {"type": ["integer", "null"]},
           "body": {"type": "string"},
           "severity": {"type": "string", "enum": ["critical", "major", "minor", "suggestion"]}
         },
-        "required": ["path", "line", "body", "severity"]
+        "required": ["path", "line", "start_line", "body", "severity"]
       }
     },
     "blocking_issues": {
       "type": "array",
       "items": {"type": "string"}
-    }
+    },
+    "reasoning": {"type": ["string", "null"]}
   },
-  "required": ["action", "summary", "comments", "blocking_issues"]
+  "required": ["action", "summary", "comments", "blocking_issues", "reasoning"]
 }"#;
 
 const REVIEWEE_SCHEMA: &str = r#"{
@@ -94,6 +103,10 @@ pub enum CodexError {
     AuthenticationFailed,
     #[error("Turn failed: {reason}")]
     TurnFailed { reason: String },
+    #[error("Codex turn timed out after {}s without completing", elapsed.as_secs())]
+    TurnTimeout { elapsed: Duration },
+    #[error("Codex sandbox blocked command: {command}")]
+    SandboxDenied { command: String },
     #[error("Invalid JSON event: {0}")]
     #[allow(dead_code)]
     InvalidJsonEvent(#[from] serde_json::Error),
@@ -102,24 +115,191 @@ pub enum CodexError {
     ChannelClosed,
 }
 
+/// Sandbox policy for a `codex exec` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodexSandbox {
+    /// Default policy: read-only filesystem access
+    ReadOnly,
+    /// `--full-auto`: workspace write access
+    WorkspaceWrite,
+    /// `--sandbox danger-full-access`: granted by the user after a sandbox denial
+    FullAccess,
+}
+
+impl CodexSandbox {
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            CodexSandbox::ReadOnly => &[],
+            CodexSandbox::WorkspaceWrite => &["--full-auto"],
+            CodexSandbox::FullAccess => &["--sandbox", "danger-full-access"],
+        }
+    }
+}
+
+/// Markers Codex prints on stderr when the sandbox refuses a command
+const SANDBOX_DENIAL_MARKERS: &[&str] = &[
+    "sandbox denied",
+    "denied by sandbox",
+    "blocked by sandbox",
+    "rejected by sandbox",
+    "sandbox error",
+];
+
+/// The command the sandbox refused, if stderr reports a sandbox denial.
+///
+/// The command is taken from the first backtick-quoted span (or the text after
+/// `command:`) on the denial line, falling back to the whole line.
+fn sandbox_denied_command(stderr: &str) -> Option<String> {
+    let line = stderr.lines().find(|line| {
+        let lower = line.to_lowercase();
+        SANDBOX_DENIAL_MARKERS.iter().any(|m| lower.contains(m))
+    })?;
+
+    let quoted = line
+        .split_once('`')
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(command, _)| command);
+    let after_label = line
+        .split_once("command:")
+        .map(|(_, command)| command.trim_matches(|c: char| c.is_whitespace() || c == '"'));
+    let command = quoted.or(after_label).unwrap_or(line.trim()).trim();
+    Some(command.to_string())
+}
+
+/// Environment variable naming a captured NDJSON stream to replay (same as `--replay`)
+pub const REPLAY_ENV: &str = "OCTORUS_CODEX_REPLAY";
+
+/// Lines of a replay file not replayed yet (`None` until the file is first read)
+type ReplayCursor = Mutex<Option<VecDeque<String>>>;
+
+/// Cursor of each replay file in use. The reviewer and reviewee adapters share it,
+/// so a recorded rally is replayed in order; it goes away with the last adapter.
+static REPLAY_CURSORS: LazyLock<Mutex<HashMap<PathBuf, Weak<ReplayCursor>>>> =
+    LazyLock::new(Default::default);
+
+/// A captured `codex exec --json` stream replayed instead of spawning the CLI.
+///
+/// The file may hold several turns back to back (e.g., a whole rally); each agent
+/// run consumes lines up to and including the next `turn.completed`/`turn.failed`,
+/// whichever adapter (reviewer or reviewee) runs it.
+struct CodexReplay {
+    path: PathBuf,
+    pending: Arc<ReplayCursor>,
+}
+
+impl CodexReplay {
+    fn new(path: PathBuf) -> Self {
+        let mut cursors = REPLAY_CURSORS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        cursors.retain(|_, cursor| cursor.strong_count() > 0);
+        let pending = match cursors.get(&path).and_then(Weak::upgrade) {
+            Some(pending) => pending,
+            None => {
+                let pending = Arc::new(Mutex::new(None));
+                cursors.insert(path.clone(), Arc::downgrade(&pending));
+                pending
+            }
+        };
+        Self { path, pending }
+    }
+
+    /// Take the lines of the next turn from the file
+    fn next_turn(&self) -> Result<Vec<String>> {
+        let mut pending = self
+            .pending
+            .lock()
+            .map_err(|_| anyhow!("Codex replay state poisoned"))?;
+        if pending.is_none() {
+            let content = std::fs::read_to_string(&self.path).with_context(|| {
+                format!("Failed to read Codex replay file {}", self.path.display())
+            })?;
+            *pending = Some(content.lines().map(str::to_string).collect());
+        }
+        let lines = pending.as_mut().expect("replay lines loaded above");
+
+        let mut turn = Vec::new();
+        while let Some(line) = lines.pop_front() {
+            let ends_turn = matches!(
+                serde_json::from_str::<CodexEvent>(&line),
+                Ok(CodexEvent::TurnCompleted { .. } | CodexEvent::TurnFailed { .. })
+            );
+            turn.push(line);
+            if ends_turn {
+                break;
+            }
+        }
+        if turn.iter().all(|line| line.trim().is_empty()) {
+            anyhow::bail!(
+                "Codex replay file {} has no more turns",
+                self.path.display()
+            );
+        }
+        Ok(turn)
+    }
+}
+
 /// OpenAI Codex CLI adapter
 pub struct CodexAdapter {
     reviewer_session_id: Option<String>,
     reviewee_session_id: Option<String>,
+    /// Working directory of each session's first turn; resumed turns run there too
+    reviewer_working_dir: Option<String>,
+    reviewee_working_dir: Option<String>,
+    /// Set once the user grants a permission after a sandbox denial
+    reviewee_full_access: bool,
     event_sender: Option<mpsc::Sender<RallyEvent>>,
+    /// Upper bound for a single `codex exec` turn (waiting for `turn.completed`)
+    turn_timeout: Duration,
+    retry: RetryPolicy,
+    cancel_token: CancellationToken,
+    /// Replay a captured stream instead of running the CLI (testing, demos)
+    replay: Option<CodexReplay>,
+    /// Refuses a turn that would overlap an unsettled one
+    turns: TurnTracker,
+    schemas: OutputSchemas,
+    /// `--model` for reviewer/reviewee turns (None uses the CLI's default)
+    reviewer_model: Option<String>,
+    reviewee_model: Option<String>,
+    /// Extra environment variables for the `codex` process (`[ai.env.codex]`)
+    env: HashMap<String, String>,
 }
 
 impl CodexAdapter {
-    pub fn new() -> Self {
+    pub fn new(config: &AiConfig) -> Self {
+        let replay = config
+            .codex_replay
+            .clone()
+            .or_else(|| std::env::var(REPLAY_ENV).ok().filter(|p| !p.is_empty()))
+            .map(|path| CodexReplay::new(PathBuf::from(path)));
         Self {
             reviewer_session_id: None,
             reviewee_session_id: None,
+            reviewer_working_dir: None,
+            reviewee_working_dir: None,
+            reviewee_full_access: false,
             event_sender: None,
+            turn_timeout: Duration::from_secs(config.codex_turn_timeout_secs),
+            retry: RetryPolicy::from_config(config),
+            cancel_token: CancellationToken::new(),
+            replay,
+            turns: TurnTracker::default(),
+            schemas: OutputSchemas::default(),
+            reviewer_model: config.reviewer_model.clone(),
+            reviewee_model: config.reviewee_model.clone(),
+            env: config.agent_env("codex"),
+        }
+    }
+
+    fn reviewee_sandbox(&self) -> CodexSandbox {
+        if self.reviewee_full_access {
+            CodexSandbox::FullAccess
+        } else {
+            CodexSandbox::WorkspaceWrite
         }
     }
 
     /// Check if Codex CLI is available
-    #[allow(dead_code)]
     pub fn check_availability() -> Result<(), CodexError> {
         let output = std::process::Command::new("codex")
             .arg("--version")
@@ -137,54 +317,53 @@ impl CodexAdapter {
         }
     }
 
-    /// Run Codex CLI with streaming JSON output
-    ///
-    /// If `session_id` is provided (resume case), and Codex does not emit a new
-    /// `thread.started` event, the existing session_id is preserved in the response.
+    /// Run Codex CLI with streaming JSON output, retrying transient process failures
     async fn run_codex_streaming(
         &self,
         prompt: &str,
         schema: &str,
-        full_auto: bool,
+        sandbox: CodexSandbox,
+        model: Option<&str>,
         working_dir: Option<&str>,
         session_id: Option<&str>,
     ) -> Result<CodexResponse> {
-        // Write schema to temporary file (Codex requires file path for --output-schema)
-        let mut schema_file =
-            NamedTempFile::new().context("Failed to create temporary schema file")?;
-        schema_file
-            .write_all(schema.as_bytes())
-            .context("Failed to write schema to temporary file")?;
-
-        let mut cmd = Command::new("codex");
-
-        // Handle session resume
-        // Usage: codex exec resume <SESSION_ID> [PROMPT]
-        // Use "-" to read prompt from stdin (avoids OS ARG_MAX limit for large diffs)
-        if let Some(sid) = session_id {
-            cmd.arg("exec").arg("resume").arg(sid).arg("-");
-        } else {
-            cmd.arg("exec").arg("-");
-        }
-
-        cmd.arg("--json");
-        cmd.arg("--output-schema").arg(schema_file.path());
-
-        // Set working directory
-        if let Some(dir) = working_dir {
-            cmd.arg("--cd").arg(dir);
+        if let Some(ref replay) = self.replay {
+            return self.replay_turn(replay, session_id).await;
         }
+        retry_transient(
+            self.retry,
+            self.event_sender.as_ref(),
+            &self.cancel_token,
+            || self.run_codex_attempt(prompt, schema, sandbox, model, working_dir, session_id),
+        )
+        .await
+    }
 
-        // Set sandbox mode
-        // - Reviewer: default (read-only)
-        // - Reviewee: --full-auto (workspace-write)
-        if full_auto {
-            cmd.arg("--full-auto");
-        }
+    /// Run Codex CLI once
+    ///
+    /// If `session_id` is provided (resume case), and Codex does not emit a new
+    /// `thread.started` event, the existing session_id is preserved in the response.
+    async fn run_codex_attempt(
+        &self,
+        prompt: &str,
+        schema: &str,
+        sandbox: CodexSandbox,
+        model: Option<&str>,
+        working_dir: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<CodexResponse> {
+        // Write schema to temporary file (Codex requires file path for --output-schema).
+        // It is deleted when schema_file is dropped, which also happens on every early
+        // return and when this future is dropped mid-turn (cancellation, task abort).
+        let schema_file = write_schema_file(schema)?;
 
+        let mut cmd = codex_command(schema_file.path(), sandbox, model, working_dir, session_id);
+        cmd.envs(&self.env);
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        // Don't leave the agent running if this future is dropped (e.g., task abort)
+        cmd.kill_on_drop(true);
 
         let mut child = cmd.spawn().with_context(|| {
             format!(
@@ -215,54 +394,87 @@ impl CodexAdapter {
         // This ensures we don't lose the session if Codex doesn't emit thread.started
         let mut thread_id: Option<String> = session_id.map(|s| s.to_string());
         let mut stream_error: Option<anyhow::Error> = None;
+        let mut cancelled = false;
+        // Reviewer comments already surfaced from partial agent_message updates
+        let mut streamed_comments = 0usize;
 
-        // Process NDJSON stream
-        loop {
-            tokio::select! {
-                line = stdout_reader.next_line() => {
-                    match line {
-                        Ok(Some(l)) => {
-                            if l.trim().is_empty() {
-                                continue;
-                            }
-                            // Parse Codex event
-                            match serde_json::from_str::<CodexEvent>(&l) {
-                                Ok(event) => {
-                                    match self.handle_codex_event(&event, &mut thread_id).await {
-                                        Ok(Some(result)) => {
-                                            final_response = Some(result);
-                                        }
-                                        Ok(None) => {}
-                                        Err(e) => {
-                                            // Capture error but continue to wait for process
-                                            stream_error = Some(e);
-                                            break;
+        // Process NDJSON stream. The whole turn is bounded by turn_timeout so a hung
+        // CLI that never emits turn.completed can't block the rally forever.
+        let turn_started = Instant::now();
+        let stream = async {
+            loop {
+                tokio::select! {
+                    _ = self.cancel_token.cancelled() => {
+                        cancelled = true;
+                        break;
+                    }
+                    line = stdout_reader.next_line() => {
+                        match line {
+                            Ok(Some(l)) => {
+                                if l.trim().is_empty() {
+                                    continue;
+                                }
+                                // Parse Codex event
+                                match serde_json::from_str::<CodexEvent>(&l) {
+                                    Ok(event) => {
+                                        let handled = self
+                                            .handle_codex_event(
+                                                &event,
+                                                &mut thread_id,
+                                                &mut streamed_comments,
+                                            )
+                                            .await;
+                                        match handled {
+                                            Ok(Some(result)) => {
+                                                final_response = Some(result);
+                                            }
+                                            Ok(None) => {}
+                                            Err(e) => {
+                                                // Capture error but continue to wait for process
+                                                stream_error = Some(e);
+                                                break;
+                                            }
                                         }
                                     }
-                                }
-                                Err(_) => {
-                                    // Unknown event format, ignore
+                                    Err(_) => {
+                                        // Unknown event format, ignore
+                                    }
                                 }
                             }
-                        }
-                        Ok(None) => break,
-                        Err(e) => {
-                            stream_error = Some(anyhow!("Error reading stdout: {}", e));
-                            break;
+                            Ok(None) => break,
+                            Err(e) => {
+                                stream_error = Some(anyhow!("Error reading stdout: {}", e));
+                                break;
+                            }
                         }
                     }
-                }
-                line = stderr_reader.next_line() => {
-                    match line {
-                        Ok(Some(l)) => error_lines.push(l),
-                        Ok(None) => {},
-                        Err(e) => {
-                            stream_error = Some(anyhow!("Error reading stderr: {}", e));
-                            break;
+                    line = stderr_reader.next_line() => {
+                        match line {
+                            Ok(Some(l)) => error_lines.push(l),
+                            Ok(None) => {},
+                            Err(e) => {
+                                stream_error = Some(anyhow!("Error reading stderr: {}", e));
+                                break;
+                            }
                         }
                     }
                 }
             }
+        };
+
+        if timeout(self.turn_timeout, stream).await.is_err() {
+            // kill() also reaps the child. The temp schema file is removed when
+            // schema_file is dropped on return.
+            let _ = child.kill().await;
+            return Err(CodexError::TurnTimeout {
+                elapsed: turn_started.elapsed(),
+            }
+            .into());
+        }
+
+        // Partial output read so far is discarded along with final_response
+        if cancelled {
+            return Err(kill_cancelled_child(&mut child, "codex").await);
         }
 
         // Always wait for the child process to terminate before returning
@@ -277,13 +489,14 @@ impl CodexAdapter {
             }
         };
 
+        // The child has terminated and no longer needs the schema
+        drop(schema_file);
+
         // Now that child has terminated, return any captured stream error
         if let Some(e) = stream_error {
             return Err(e);
         }
 
-        // schema_file is dropped here and the temporary file is deleted
-
         if !status.success() {
             let stderr_output = error_lines.join("\n");
 
@@ -292,21 +505,75 @@ impl CodexAdapter {
                 return Err(CodexError::AuthenticationFailed.into());
             }
 
-            return Err(anyhow!(
-                "Codex process failed with status {}: {}",
+            // Surface sandbox denials so the user can grant broader permissions
+            if let Some(command) = sandbox_denied_command(&stderr_output) {
+                return Err(CodexError::SandboxDenied { command }.into());
+            }
+
+            return Err(AgentExitError {
+                agent: "Codex",
                 status,
-                stderr_output
-            ));
+                stderr: stderr_output,
+            }
+            .into());
+        }
+
+        if let Some(result) = final_response.as_ref().and_then(|r| r.result.as_ref()) {
+            self.send_event(RallyEvent::AgentRawResult(result.clone()))
+                .await;
+        }
+
+        // Codex does not report cost; only the wall-clock turn time is known
+        if final_response.is_some() {
+            self.send_event(RallyEvent::UsageReported {
+                cost_usd: None,
+                duration_ms: Some(turn_started.elapsed().as_millis() as u64),
+            })
+            .await;
         }
 
         final_response.ok_or_else(|| anyhow!("No result received from codex"))
     }
 
+    /// Feed the next recorded turn through the same event handling as a live run
+    async fn replay_turn(
+        &self,
+        replay: &CodexReplay,
+        session_id: Option<&str>,
+    ) -> Result<CodexResponse> {
+        let mut thread_id: Option<String> = session_id.map(|s| s.to_string());
+        let mut streamed_comments = 0usize;
+        let mut final_response = None;
+
+        for line in replay.next_turn()? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Unknown event formats are ignored, as in a live run
+            if let Ok(event) = serde_json::from_str::<CodexEvent>(&line) {
+                if let Some(result) = self
+                    .handle_codex_event(&event, &mut thread_id, &mut streamed_comments)
+                    .await?
+                {
+                    final_response = Some(result);
+                }
+            }
+        }
+
+        final_response.ok_or_else(|| {
+            anyhow!(
+                "No result received from codex replay {}",
+                replay.path.display()
+            )
+        })
+    }
+
     /// Handle Codex streaming event and convert to RallyEvent
     async fn handle_codex_event(
         &self,
         event: &CodexEvent,
         thread_id: &mut Option<String>,
+        streamed_comments: &mut usize,
     ) -> Result<Option<CodexResponse>> {
         match event {
             CodexEvent::ThreadStarted { thread_id: tid } => {
@@ -337,11 +604,15 @@ impl CodexAdapter {
             }
             CodexEvent::ItemStarted { item } | CodexEvent::ItemUpdated { item } => {
                 // Non-completed items don't produce results, but we still propagate errors
-                self.handle_item_event(item, thread_id, false).await?;
+                self.handle_item_event(item, thread_id, false, streamed_comments)
+                    .await?;
             }
             CodexEvent::ItemCompleted { item } => {
                 // Check if this is the final agent_message with structured output
-                if let Some(result) = self.handle_item_event(item, thread_id, true).await? {
+                if let Some(result) = self
+                    .handle_item_event(item, thread_id, true, streamed_comments)
+                    .await?
+                {
                     return Ok(Some(result));
                 }
             }
@@ -352,6 +623,25 @@ impl CodexAdapter {
         Ok(None)
     }
 
+    /// Surface reviewer comments that became complete in a partial agent_message,
+    /// so long reviews show progress before the final result arrives
+    async fn send_streamed_comments(&self, partial: &str, streamed_comments: &mut usize) {
+        let comments = streamed_review_comments(partial);
+        if comments.len() <= *streamed_comments {
+            return;
+        }
+        for comment in &comments[*streamed_comments..] {
+            self.send_event(RallyEvent::ReviewCommentStreamed(comment.clone()))
+                .await;
+        }
+        *streamed_comments = comments.len();
+        self.send_event(RallyEvent::AgentThinking(format!(
+            "Reviewer produced {} comment(s) so far",
+            comments.len()
+        )))
+        .await;
+    }
+
     /// Handle item events and return result if it's the final agent_message
     ///
     /// Returns `Err` if the final result cannot be constructed (e.g., missing session_id).
@@ -360,6 +650,7 @@ impl CodexAdapter {
         item: &CodexItem,
         thread_id: &Option<String>,
         completed: bool,
+        streamed_comments: &mut usize,
     ) -> Result<Option<CodexResponse>> {
         match item.item_type.as_str() {
             "reasoning" => {
@@ -395,6 +686,7 @@ impl CodexAdapter {
                 } else if let Some(ref text) = item.text {
                     self.send_event(RallyEvent::AgentThinking(text.clone()))
                         .await;
+                    self.send_streamed_comments(text, streamed_comments).await;
                 }
             }
             "function_call" | "command" => {
@@ -422,11 +714,23 @@ impl CodexAdapter {
             "file_edit" | "file_change" => {
                 let path = item.path.clone().unwrap_or_else(|| "file".to_string());
                 if completed {
-                    self.send_event(RallyEvent::AgentToolResult(
-                        format!("edit:{}", path),
-                        "file modified".to_string(),
-                    ))
-                    .await;
+                    let paths = item.modified_paths();
+                    if paths.is_empty() {
+                        self.send_event(RallyEvent::AgentToolResult(
+                            format!("edit:{}", path),
+                            "file modified".to_string(),
+                        ))
+                        .await;
+                    }
+                    for path in paths {
+                        self.send_event(RallyEvent::AgentToolResult(
+                            format!("edit:{}", path),
+                            "file modified".to_string(),
+                        ))
+                        .await;
+                        self.send_event(RallyEvent::FileModified(PathBuf::from(path)))
+                            .await;
+                    }
                 } else {
                     self.send_event(RallyEvent::AgentToolUse(
                         format!("edit:{}", path),
@@ -443,7 +747,7 @@ impl CodexAdapter {
 
 impl Default for CodexAdapter {
     fn default() -> Self {
-        Self::new()
+        Self::new(&AiConfig::default())
     }
 }
 
@@ -453,10 +757,29 @@ impl AgentAdapter for CodexAdapter {
         "codex"
     }
 
+    async fn check_availability(&self) -> Result<()> {
+        // Replays never run the CLI
+        if self.replay.is_some() {
+            return Ok(());
+        }
+        tokio::task::spawn_blocking(CodexAdapter::check_availability)
+            .await
+            .context("Codex availability check panicked")??;
+        Ok(())
+    }
+
     fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>) {
         self.event_sender = Some(sender);
     }
 
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel_token = token;
+    }
+
+    fn set_output_schemas(&mut self, schemas: OutputSchemas) {
+        self.schemas = schemas;
+    }
+
     async fn run_reviewer(&mut self, prompt: &str, context: &Context) -> Result<ReviewerOutput> {
         // Reviewer runs in default sandbox mode (read-only)
         // Codex doesn't have fine-grained tool control like Claude's --allowedTools
@@ -464,16 +787,23 @@ impl AgentAdapter for CodexAdapter {
         // - default: read-only filesystem access
         // - full-auto: workspace write access
         let response = self
-            .run_codex_streaming(
-                prompt,
-                REVIEWER_SCHEMA,
-                false, // read-only sandbox for reviewer
-                context.working_dir.as_deref(),
-                None,
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                false,
+                self.run_codex_streaming(
+                    prompt,
+                    self.schemas.reviewer_or(REVIEWER_SCHEMA),
+                    CodexSandbox::ReadOnly,
+                    self.reviewer_model.as_deref(),
+                    context.working_dir.as_deref(),
+                    None,
+                ),
             )
             .await?;
 
         self.reviewer_session_id = Some(response.session_id.clone());
+        self.reviewer_working_dir = context.working_dir.clone();
 
         parse_reviewer_output(response.result.as_ref(), "codex")
     }
@@ -482,16 +812,23 @@ impl AgentAdapter for CodexAdapter {
         // Reviewee runs in full-auto mode (workspace-write)
         // NOTE: full-auto allows git push, but the prompt explicitly prohibits it
         let response = self
-            .run_codex_streaming(
-                prompt,
-                REVIEWEE_SCHEMA,
-                true, // full-auto sandbox for reviewee
-                context.working_dir.as_deref(),
-                None,
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                false,
+                self.run_codex_streaming(
+                    prompt,
+                    self.schemas.reviewee_or(REVIEWEE_SCHEMA),
+                    self.reviewee_sandbox(),
+                    self.reviewee_model.as_deref(),
+                    context.working_dir.as_deref(),
+                    None,
+                ),
             )
             .await?;
 
         self.reviewee_session_id = Some(response.session_id.clone());
+        self.reviewee_working_dir = context.working_dir.clone();
 
         parse_reviewee_output(response.result.as_ref(), "codex")
     }
@@ -504,7 +841,19 @@ impl AgentAdapter for CodexAdapter {
             .clone();
 
         let response = self
-            .run_codex_streaming(message, REVIEWER_SCHEMA, false, None, Some(&session_id))
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                true,
+                self.run_codex_streaming(
+                    message,
+                    self.schemas.reviewer_or(REVIEWER_SCHEMA),
+                    CodexSandbox::ReadOnly,
+                    self.reviewer_model.as_deref(),
+                    self.reviewer_working_dir.as_deref(),
+                    Some(&session_id),
+                ),
+            )
             .await?;
 
         parse_reviewer_output(response.result.as_ref(), "codex")
@@ -518,7 +867,19 @@ impl AgentAdapter for CodexAdapter {
             .clone();
 
         let response = self
-            .run_codex_streaming(message, REVIEWEE_SCHEMA, true, None, Some(&session_id))
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                true,
+                self.run_codex_streaming(
+                    message,
+                    self.schemas.reviewee_or(REVIEWEE_SCHEMA),
+                    self.reviewee_sandbox(),
+                    self.reviewee_model.as_deref(),
+                    self.reviewee_working_dir.as_deref(),
+                    Some(&session_id),
+                ),
+            )
             .await?;
 
         parse_reviewee_output(response.result.as_ref(), "codex")
@@ -526,9 +887,63 @@ impl AgentAdapter for CodexAdapter {
 
     fn add_reviewee_allowed_tool(&mut self, _tool: &str) {
         // Codex doesn't support granular tool permissions like Claude's --allowedTools.
-        // It uses sandbox policies (read-only vs full-auto) instead.
-        // This is a no-op for Codex.
+        // It uses sandbox policies (read-only vs full-auto) instead, so granting any
+        // permission lifts the reviewee's sandbox for the rest of the rally.
+        self.reviewee_full_access = true;
+    }
+}
+
+/// Prefix of the temporary `--output-schema` files, so leftovers are recognizable
+const SCHEMA_FILE_PREFIX: &str = "octorus-codex-schema-";
+
+/// Temporary file holding the output schema; deleted when dropped
+fn write_schema_file(schema: &str) -> Result<NamedTempFile> {
+    let mut schema_file = tempfile::Builder::new()
+        .prefix(SCHEMA_FILE_PREFIX)
+        .suffix(".json")
+        .tempfile()
+        .context("Failed to create temporary schema file")?;
+    schema_file
+        .write_all(schema.as_bytes())
+        .context("Failed to write schema to temporary file")?;
+    Ok(schema_file)
+}
+
+/// `codex exec` invocation for one turn; the prompt is written to stdin
+fn codex_command(
+    schema_path: &Path,
+    sandbox: CodexSandbox,
+    model: Option<&str>,
+    working_dir: Option<&str>,
+    session_id: Option<&str>,
+) -> Command {
+    let mut cmd = Command::new("codex");
+
+    // Handle session resume
+    // Usage: codex exec resume <SESSION_ID> [PROMPT]
+    // Use "-" to read prompt from stdin (avoids OS ARG_MAX limit for large diffs)
+    if let Some(sid) = session_id {
+        cmd.arg("exec").arg("resume").arg(sid).arg("-");
+    } else {
+        cmd.arg("exec").arg("-");
     }
+
+    cmd.arg("--json");
+    cmd.arg("--output-schema").arg(schema_path);
+    if let Some(model) = model {
+        cmd.arg("--model").arg(model);
+    }
+
+    // Set working directory
+    if let Some(dir) = working_dir {
+        cmd.arg("--cd").arg(dir);
+    }
+
+    // Set sandbox mode
+    // - Reviewer: default (read-only)
+    // - Reviewee: --full-auto (workspace-write), full access once granted
+    cmd.args(sandbox.args());
+    cmd
 }
 
 // Codex event types based on actual CLI output
@@ -592,6 +1007,30 @@ pub struct CodexItem {
     /// File path for file_edit
     #[serde(default)]
     pub path: Option<String>,
+    /// Files written by a file_change item
+    #[serde(default)]
+    pub changes: Vec<CodexFileChange>,
+}
+
+/// One file of a `file_change` item
+#[derive(Debug, Deserialize)]
+pub struct CodexFileChange {
+    pub path: String,
+    /// "add", "update" or "delete"
+    #[serde(default)]
+    #[allow(dead_code)]
+    pub kind: Option<String>,
+}
+
+impl CodexItem {
+    /// Paths a completed file_edit/file_change item wrote
+    fn modified_paths(&self) -> Vec<&str> {
+        self.path
+            .as_deref()
+            .into_iter()
+            .chain(self.changes.iter().map(|change| change.path.as_str()))
+            .collect()
+    }
 }
 
 /// Codex response structure
@@ -601,12 +1040,78 @@ struct CodexResponse {
     result: Option<serde_json::Value>,
 }
 
-use super::common::{parse_reviewee_output, parse_reviewer_output};
+use super::common::{
+    kill_cancelled_child, parse_reviewee_output, parse_reviewer_output, retry_transient,
+    streamed_review_comments, AgentExitError, OutputSchemas, RetryPolicy, TurnRole, TurnTracker,
+};
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_turn_timeout_from_config() {
+        let config = AiConfig {
+            codex_turn_timeout_secs: 42,
+            ..AiConfig::default()
+        };
+        assert_eq!(
+            CodexAdapter::new(&config).turn_timeout,
+            Duration::from_secs(42)
+        );
+        assert_eq!(
+            CodexAdapter::default().turn_timeout,
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn test_turn_timeout_error_message() {
+        let err = CodexError::TurnTimeout {
+            elapsed: Duration::from_millis(300_400),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Codex turn timed out after 300s without completing"
+        );
+    }
+
+    #[test]
+    fn test_sandbox_denied_command() {
+        assert_eq!(
+            sandbox_denied_command(
+                "2026-01-01 ERROR exec failed\nsandbox denied exec of `cargo publish --dry-run`"
+            ),
+            Some("cargo publish --dry-run".to_string())
+        );
+        assert_eq!(
+            sandbox_denied_command("error: rejected by sandbox, command: \"npm install\""),
+            Some("npm install".to_string())
+        );
+        // A plain EPERM is not necessarily the sandbox (file permissions, chmod, ...)
+        assert_eq!(
+            sandbox_denied_command("error: Operation not permitted (os error 1)"),
+            None
+        );
+        assert_eq!(
+            sandbox_denied_command("Blocked by sandbox"),
+            Some("Blocked by sandbox".to_string())
+        );
+        assert_eq!(sandbox_denied_command("error: unexpected argument"), None);
+    }
+
+    #[test]
+    fn test_granted_permission_lifts_reviewee_sandbox() {
+        let mut adapter = CodexAdapter::default();
+        assert_eq!(adapter.reviewee_sandbox(), CodexSandbox::WorkspaceWrite);
+        adapter.add_reviewee_allowed_tool("npm install");
+        assert_eq!(adapter.reviewee_sandbox(), CodexSandbox::FullAccess);
+        assert_eq!(
+            CodexSandbox::FullAccess.args(),
+            &["--sandbox", "danger-full-access"]
+        );
+    }
+
     #[test]
     fn test_parse_thread_started_event() {
         let json = r#"{"type": "thread.started", "thread_id": "thread_123"}"#;
@@ -687,10 +1192,279 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_file_change_item_reports_each_modified_file() {
+        let json = r#"{"type": "item.completed", "item": {"id": "item_2", "type": "file_change", "changes": [{"path": "src/a.rs", "kind": "update"}, {"path": "src/b.rs", "kind": "add"}], "status": "completed"}}"#;
+        let CodexEvent::ItemCompleted { item } = serde_json::from_str(json).unwrap() else {
+            panic!("Expected ItemCompleted event");
+        };
+        let mut adapter = CodexAdapter::default();
+        let (tx, mut rx) = mpsc::channel(10);
+        adapter.set_event_sender(tx);
+        adapter
+            .handle_item_event(&item, &None, true, &mut 0)
+            .await
+            .unwrap();
+
+        let mut modified = vec![];
+        while let Ok(event) = rx.try_recv() {
+            if let RallyEvent::FileModified(path) = event {
+                modified.push(path);
+            }
+        }
+        assert_eq!(
+            modified,
+            vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_reviewer_schema_requires_every_property() {
+        // Strict structured output rejects a schema whose properties are not all
+        // required, so optional fields are nullable instead
+        let schema: serde_json::Value = serde_json::from_str(REVIEWER_SCHEMA).unwrap();
+        let properties: Vec<&str> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(properties.contains(&"reasoning"));
+        assert!(properties.iter().all(|p| required.contains(p)));
+
+        let output: ReviewerOutput = serde_json::from_str(
+            r#"{"action":"approve","summary":"LGTM","comments":[],"blocking_issues":[],"reasoning":"Only docs changed"}"#,
+        )
+        .unwrap();
+        assert_eq!(output.reasoning.as_deref(), Some("Only docs changed"));
+    }
+
     #[test]
     fn test_parse_unknown_event() {
         let json = r#"{"type": "some.unknown.event", "data": "whatever"}"#;
         let event: CodexEvent = serde_json::from_str(json).unwrap();
         assert!(matches!(event, CodexEvent::Unknown));
     }
+
+    #[tokio::test]
+    async fn test_replay_feeds_recorded_turns_through_event_handling() {
+        let review = serde_json::json!({
+            "action": "request_changes",
+            "summary": "Needs a fix",
+            "comments": [],
+            "blocking_issues": ["Handle the error"]
+        });
+        let agent_message = serde_json::json!({
+            "type": "item.completed",
+            "item": {"id": "item_1", "type": "agent_message", "text": review.to_string()}
+        });
+        let stream = [
+            r#"{"type":"thread.started","thread_id":"thread-1"}"#.to_string(),
+            r#"{"type":"turn.started"}"#.to_string(),
+            r#"{"type":"item.completed","item":{"id":"item_0","type":"reasoning","text":"Reading the diff"}}"#.to_string(),
+            agent_message.to_string(),
+            r#"{"type":"turn.completed","usage":{}}"#.to_string(),
+            String::new(),
+            r#"{"type":"turn.started"}"#.to_string(),
+            r#"{"type":"turn.failed","error":{"message":"rate limited"}}"#.to_string(),
+        ]
+        .join("\n");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codex.ndjson");
+        std::fs::write(&path, stream).unwrap();
+
+        let config = AiConfig {
+            codex_replay: Some(path.to_string_lossy().to_string()),
+            ..AiConfig::default()
+        };
+        let mut adapter = CodexAdapter::new(&config);
+        let (tx, mut rx) = mpsc::channel(10);
+        adapter.set_event_sender(tx);
+        adapter.check_availability().await.unwrap();
+
+        // Turn 1: the reviewer result, with streamed events on the way
+        let response = adapter
+            .run_codex_streaming(
+                "prompt",
+                REVIEWER_SCHEMA,
+                CodexSandbox::ReadOnly,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.session_id, "thread-1");
+        let output = parse_reviewer_output(response.result.as_ref(), "codex").unwrap();
+        assert_eq!(output.blocking_issues, vec!["Handle the error"]);
+        let mut thinking = vec![];
+        while let Ok(event) = rx.try_recv() {
+            if let RallyEvent::AgentThinking(text) = event {
+                thinking.push(text);
+            }
+        }
+        assert!(thinking.contains(&"Reading the diff".to_string()));
+
+        // Turn 2: failures surface as they would from the CLI
+        let err = adapter
+            .run_codex_streaming(
+                "again",
+                REVIEWER_SCHEMA,
+                CodexSandbox::ReadOnly,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("rate limited"));
+
+        // Nothing left
+        let err = adapter
+            .run_codex_streaming(
+                "more",
+                REVIEWER_SCHEMA,
+                CodexSandbox::ReadOnly,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("has no more turns"));
+    }
+
+    #[tokio::test]
+    async fn test_schema_file_is_removed_when_turn_ends_early() {
+        let schema_files = || -> Vec<PathBuf> {
+            std::fs::read_dir(std::env::temp_dir())
+                .unwrap()
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(SCHEMA_FILE_PREFIX))
+                })
+                .collect()
+        };
+        let before = schema_files();
+
+        let schema = write_schema_file(REVIEWER_SCHEMA).unwrap();
+        let path = schema.path().to_path_buf();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), REVIEWER_SCHEMA);
+        drop(schema);
+        assert!(!path.exists());
+
+        // A cancelled turn returns early whether or not the CLI could be spawned
+        let mut adapter = CodexAdapter::new(&AiConfig::default());
+        let token = CancellationToken::new();
+        token.cancel();
+        adapter.set_cancellation_token(token);
+        let result = adapter
+            .run_codex_attempt(
+                "prompt",
+                REVIEWER_SCHEMA,
+                CodexSandbox::ReadOnly,
+                None,
+                None,
+                None,
+            )
+            .await;
+        assert!(result.is_err());
+
+        let leaked: Vec<_> = schema_files()
+            .into_iter()
+            .filter(|path| !before.contains(path))
+            .collect();
+        assert!(leaked.is_empty(), "{:?}", leaked);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_turn_keeps_session_working_dir() {
+        let review = serde_json::json!({
+            "action": "approve",
+            "summary": "Looks good",
+            "comments": [],
+            "blocking_issues": []
+        });
+        let agent_message = serde_json::json!({
+            "type": "item.completed",
+            "item": {"id": "item_0", "type": "agent_message", "text": review.to_string()}
+        });
+        let stream = [
+            r#"{"type":"thread.started","thread_id":"thread-1"}"#.to_string(),
+            agent_message.to_string(),
+            r#"{"type":"turn.completed","usage":{}}"#.to_string(),
+        ]
+        .join("\n");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codex.ndjson");
+        std::fs::write(&path, stream).unwrap();
+
+        let config = AiConfig {
+            codex_replay: Some(path.to_string_lossy().to_string()),
+            ..AiConfig::default()
+        };
+        let mut adapter = CodexAdapter::new(&config);
+        let context = Context {
+            repo: "owner/repo".to_string(),
+            pr_number: 1,
+            pr_title: "Title".to_string(),
+            pr_body: None,
+            diff: String::new(),
+            working_dir: Some("/work/repo".to_string()),
+            head_sha: "abc".to_string(),
+            base_branch: "main".to_string(),
+            external_comments: vec![],
+            local_mode: true,
+            file_patches: vec![],
+            linked_issues: vec![],
+        };
+        adapter.run_reviewer("prompt", &context).await.unwrap();
+        assert_eq!(adapter.reviewer_working_dir.as_deref(), Some("/work/repo"));
+
+        // The resume invocation is pointed at the same directory
+        let cmd = codex_command(
+            Path::new("schema.json"),
+            CodexSandbox::ReadOnly,
+            None,
+            adapter.reviewer_working_dir.as_deref(),
+            adapter.reviewer_session_id.as_deref(),
+        );
+        let args: Vec<_> = cmd.as_std().get_args().collect();
+        assert_eq!(&args[..4], ["exec", "resume", "thread-1", "-"]);
+        let cd = args.iter().position(|a| *a == "--cd").unwrap();
+        assert_eq!(args[cd + 1], "/work/repo");
+    }
+
+    #[test]
+    fn test_reviewer_and_reviewee_share_the_replay() {
+        let stream = [
+            r#"{"type":"thread.started","thread_id":"reviewer"}"#,
+            r#"{"type":"turn.completed","usage":{}}"#,
+            r#"{"type":"thread.started","thread_id":"reviewee"}"#,
+            r#"{"type":"turn.completed","usage":{}}"#,
+        ]
+        .join("\n");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codex.ndjson");
+        std::fs::write(&path, stream).unwrap();
+
+        let reviewer = CodexReplay::new(path.clone());
+        let reviewee = CodexReplay::new(path.clone());
+        assert!(reviewer.next_turn().unwrap()[0].contains("\"reviewer\""));
+        assert!(reviewee.next_turn().unwrap()[0].contains("\"reviewee\""));
+        assert!(reviewer.next_turn().is_err());
+
+        // A new rally (new adapters) starts the recording over
+        drop((reviewer, reviewee));
+        let replay = CodexReplay::new(path);
+        assert!(replay.next_turn().unwrap()[0].contains("\"reviewer\""));
+    }
 }
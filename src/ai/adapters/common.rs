@@ -1,12 +1,452 @@
 //! Common types and parsing functions shared between AI adapters (Claude, Codex, etc.)
 
-use anyhow::{anyhow, Context as AnyhowContext, Result};
+use std::future::Future;
+use std::process::ExitStatus;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context as _, Result};
 use serde::Deserialize;
+use thiserror::Error;
+use tokio::process::Child;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use crate::ai::adapter::{
     CommentSeverity, PermissionRequest, ReviewAction, ReviewComment, RevieweeOutput,
     RevieweeStatus, ReviewerOutput,
 };
+use crate::ai::orchestrator::RallyEvent;
+use crate::config::AiConfig;
+
+pub(crate) const REVIEWER_SCHEMA: &str = include_str!("../schemas/reviewer.json");
+pub(crate) const REVIEWEE_SCHEMA: &str = include_str!("../schemas/reviewee.json");
+
+static REVIEWER_VALIDATOR: LazyLock<jsonschema::Validator> =
+    LazyLock::new(|| compile_schema(REVIEWER_SCHEMA));
+static REVIEWEE_VALIDATOR: LazyLock<jsonschema::Validator> =
+    LazyLock::new(|| compile_schema(REVIEWEE_SCHEMA));
+
+fn compile_schema(schema: &str) -> jsonschema::Validator {
+    let schema: serde_json::Value =
+        serde_json::from_str(schema).expect("embedded schema should be valid JSON");
+    jsonschema::validator_for(&schema).expect("embedded schema should compile")
+}
+
+/// Output schemas the agents are asked to follow
+///
+/// The embedded schemas, unless `reviewer_schema_path` / `reviewee_schema_path` point to
+/// custom ones (e.g., adding a `risk_score` field). Responses are still validated against
+/// the embedded schemas, so a custom schema may add fields but must keep the required ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputSchemas {
+    reviewer: Option<String>,
+    reviewee: Option<String>,
+}
+
+impl OutputSchemas {
+    /// Read the custom schemas configured in `config`
+    pub fn load(config: &AiConfig) -> Result<Self> {
+        Ok(Self {
+            reviewer: config
+                .reviewer_schema_path
+                .as_deref()
+                .map(|path| load_custom_schema(path, REVIEWER_SCHEMA))
+                .transpose()?,
+            reviewee: config
+                .reviewee_schema_path
+                .as_deref()
+                .map(|path| load_custom_schema(path, REVIEWEE_SCHEMA))
+                .transpose()?,
+        })
+    }
+
+    pub fn reviewer(&self) -> &str {
+        self.reviewer_or(REVIEWER_SCHEMA)
+    }
+
+    pub fn reviewee(&self) -> &str {
+        self.reviewee_or(REVIEWEE_SCHEMA)
+    }
+
+    /// The custom reviewer schema, or `default` (for adapters with their own variant)
+    pub(super) fn reviewer_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.reviewer.as_deref().unwrap_or(default)
+    }
+
+    /// The custom reviewee schema, or `default`
+    pub(super) fn reviewee_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.reviewee.as_deref().unwrap_or(default)
+    }
+}
+
+/// Read a custom schema and check that it compiles and still requires every field the
+/// embedded `base` schema requires
+fn load_custom_schema(path: &str, base: &str) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read output schema {}", path))?;
+    let schema: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Output schema {} is not valid JSON", path))?;
+    jsonschema::validator_for(&schema)
+        .map_err(|e| anyhow!("Output schema {} is not a valid JSON schema: {}", path, e))?;
+
+    let required = |schema: &serde_json::Value| -> Vec<String> {
+        schema["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    };
+    let base: serde_json::Value =
+        serde_json::from_str(base).expect("embedded schema should be valid JSON");
+    let custom_required = required(&schema);
+    let missing: Vec<String> = required(&base)
+        .into_iter()
+        .filter(|field| !custom_required.contains(field))
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Output schema {} must require {} (custom schemas can only add fields)",
+            path,
+            missing.join(", ")
+        );
+    }
+    Ok(content)
+}
+
+/// An agent response that does not conform to the output schema.
+///
+/// Each entry in `errors` starts with the JSON pointer of the offending value, so the
+/// message can be sent back to the agent as-is to ask for a corrected response.
+#[derive(Debug, Error)]
+#[error("{agent} {role} output does not match the schema:\n{}", errors.join("\n"))]
+pub struct SchemaValidationError {
+    pub agent: String,
+    pub role: &'static str,
+    pub errors: Vec<String>,
+}
+
+/// Agent output that could not be turned into a review or fix result: not JSON at
+/// all, or values the parser does not understand.
+///
+/// Like [`SchemaValidationError`], the message is meant to be shown to the agent.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct OutputParseError(pub String);
+
+fn validate_output(
+    validator: &jsonschema::Validator,
+    value: &serde_json::Value,
+    agent_name: &str,
+    role: &'static str,
+) -> Result<(), SchemaValidationError> {
+    let errors: Vec<String> = validator
+        .iter_errors(value)
+        .map(|e| {
+            let pointer = e.instance_path().to_string();
+            let pointer = if pointer.is_empty() { "/" } else { &pointer };
+            format!("{}: {}", pointer, e)
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(SchemaValidationError {
+        agent: agent_name.to_string(),
+        role,
+        errors,
+    })
+}
+
+/// An agent CLI exited with a non-zero status.
+///
+/// This is the only failure [`retry_transient`] retries. Authentication errors,
+/// timeouts, cancellation and malformed output surface as other errors.
+#[derive(Debug, Error)]
+#[error("{agent} process failed with status {status}: {stderr}")]
+pub(super) struct AgentExitError {
+    pub agent: &'static str,
+    pub status: ExitStatus,
+    pub stderr: String,
+}
+
+/// How often a failed agent run is retried
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RetryPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further attempt
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &AiConfig) -> Self {
+        Self {
+            max_retries: config.agent_max_retries,
+            base_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Run `run`, retrying with exponential backoff while it fails with [`AgentExitError`].
+pub(super) async fn retry_transient<T, F, Fut>(
+    policy: RetryPolicy,
+    event_sender: Option<&mpsc::Sender<RallyEvent>>,
+    cancel_token: &CancellationToken,
+    mut run: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        let err = match run().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        if attempt >= policy.max_retries || !err.is::<AgentExitError>() {
+            return Err(err);
+        }
+        attempt += 1;
+
+        tracing::warn!(
+            "Agent run failed, retrying ({}/{}): {}",
+            attempt,
+            policy.max_retries,
+            err
+        );
+        if let Some(sender) = event_sender {
+            let _ = sender
+                .send(RallyEvent::AgentThinking(format!(
+                    "Retrying ({}/{})...",
+                    attempt, policy.max_retries
+                )))
+                .await;
+        }
+
+        let delay = policy.base_delay * 2u32.saturating_pow(attempt - 1);
+        tokio::select! {
+            _ = cancel_token.cancelled() => return Err(err),
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+/// Agent role a turn runs for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TurnRole {
+    Reviewer,
+    Reviewee,
+}
+
+impl TurnRole {
+    fn name(self) -> &'static str {
+        match self {
+            TurnRole::Reviewer => "reviewer",
+            TurnRole::Reviewee => "reviewee",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TurnState {
+    running: Option<TurnRole>,
+    /// The role's last turn was dropped (timeout, abort) before it settled, so the
+    /// adapter's session for that role is stale
+    reviewer_interrupted: bool,
+    reviewee_interrupted: bool,
+}
+
+impl TurnState {
+    fn interrupted(&mut self, role: TurnRole) -> &mut bool {
+        match role {
+            TurnRole::Reviewer => &mut self.reviewer_interrupted,
+            TurnRole::Reviewee => &mut self.reviewee_interrupted,
+        }
+    }
+}
+
+/// Per-adapter guard that keeps agent turns from overlapping.
+///
+/// `&mut self` on the adapter methods already prevents two turns at once, but a
+/// turn whose future is dropped mid-run never records its session. Continuing that
+/// role afterwards would resume the previous turn's session, so it is refused until
+/// a new turn starts the role over.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TurnTracker(Arc<Mutex<TurnState>>);
+
+impl TurnTracker {
+    /// Run one turn for `role`. `resume` marks a turn that continues the role's session.
+    pub async fn run<T>(
+        &self,
+        role: TurnRole,
+        resume: bool,
+        turn: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let guard = self.begin(role, resume)?;
+        let result = turn.await;
+        guard.finish();
+        result
+    }
+
+    fn begin(&self, role: TurnRole, resume: bool) -> Result<TurnGuard> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = state.running {
+            return Err(anyhow!(
+                "Cannot start a {} turn while the {} turn is still running",
+                role.name(),
+                running.name()
+            ));
+        }
+        if resume && *state.interrupted(role) {
+            return Err(anyhow!(
+                "Cannot continue the {} session: its last turn was interrupted before it finished",
+                role.name()
+            ));
+        }
+        state.running = Some(role);
+        Ok(TurnGuard {
+            state: Arc::clone(&self.0),
+            role,
+            finished: false,
+        })
+    }
+}
+
+/// Marks the running turn as settled on [`TurnGuard::finish`], or as interrupted if
+/// dropped first
+struct TurnGuard {
+    state: Arc<Mutex<TurnState>>,
+    role: TurnRole,
+    finished: bool,
+}
+
+impl TurnGuard {
+    fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for TurnGuard {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.running = None;
+        *state.interrupted(self.role) = !self.finished;
+    }
+}
+
+/// Build the instruction that enforces the output schema, for agents without a
+/// native structured-output option (Ollama, Gemini).
+pub(super) fn build_schema_prompt(schema: &str, note: Option<&str>) -> String {
+    let mut prompt = String::from(
+        "Respond with a single JSON object that conforms to the following JSON schema. \
+         Do not include any text outside the JSON object.\n\n",
+    );
+    prompt.push_str(schema.trim());
+    if let Some(note) = note {
+        prompt.push_str("\n\n");
+        prompt.push_str(note);
+    }
+    prompt
+}
+
+/// Extract the JSON value from the assistant message.
+///
+/// Even when told to return bare JSON, some models wrap the object in a markdown code fence.
+pub(super) fn extract_json(content: &str) -> Result<serde_json::Value> {
+    let trimmed = content.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|s| s.strip_suffix("```"))
+        .unwrap_or(trimmed);
+
+    serde_json::from_str(unfenced.trim())
+        .map_err(|e| OutputParseError(format!("Agent response is not valid JSON: {}", e)).into())
+}
+
+/// Review comments whose JSON objects are already complete in a partially
+/// streamed reviewer output, e.g. `{"action":"request_changes","comments":[{...},{"pa`.
+///
+/// Only the top-level `comments` array is scanned; objects that do not parse
+/// as a comment are skipped (the final output is still validated as a whole).
+pub(super) fn streamed_review_comments(partial: &str) -> Vec<ReviewComment> {
+    let mut comments = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_start = 0;
+    // Last string closed directly inside the top-level object (a key or a value)
+    let mut last_string: Option<&str> = None;
+    let mut in_comments = false;
+    let mut object_start: Option<usize> = None;
+
+    // Structural characters are ASCII, so byte offsets are valid char boundaries
+    for (i, b) in partial.bytes().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+                if depth == 1 {
+                    last_string = Some(&partial[string_start + 1..i]);
+                }
+            }
+            continue;
+        }
+        match b {
+            b'"' => {
+                in_string = true;
+                string_start = i;
+            }
+            b'[' => {
+                depth += 1;
+                if depth == 2 && last_string == Some("comments") {
+                    in_comments = true;
+                }
+            }
+            b'{' => {
+                depth += 1;
+                if in_comments && depth == 3 {
+                    object_start = Some(i);
+                }
+            }
+            b'}' => {
+                if in_comments && depth == 3 {
+                    if let Some(start) = object_start.take() {
+                        if let Ok(comment) = serde_json::from_str(&partial[start..=i]) {
+                            comments.push(comment);
+                        }
+                    }
+                }
+                depth = depth.saturating_sub(1);
+            }
+            b']' => {
+                if in_comments && depth == 2 {
+                    break;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            b',' if depth == 1 => last_string = None,
+            _ => {}
+        }
+    }
+    comments
+}
+
+/// Kill an agent process on cancellation and reap it.
+///
+/// `Child::kill` sends SIGKILL and then waits, so no zombie is left behind and the
+/// agent can't keep editing files or pushing after the rally was aborted.
+pub(crate) async fn kill_cancelled_child(child: &mut Child, agent: &str) -> anyhow::Error {
+    if let Err(e) = child.kill().await {
+        tracing::warn!("Failed to kill cancelled {} process: {}", agent, e);
+    }
+    anyhow!("{} run cancelled", agent)
+}
 
 /// Raw reviewer output structure shared by all adapters.
 #[derive(Debug, Deserialize)]
@@ -15,6 +455,8 @@ pub(crate) struct RawReviewerOutput {
     pub summary: String,
     pub comments: Vec<RawReviewComment>,
     pub blocking_issues: Vec<String>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
 }
 
 /// Raw review comment structure.
@@ -22,6 +464,8 @@ pub(crate) struct RawReviewerOutput {
 pub(crate) struct RawReviewComment {
     pub path: String,
     pub line: u32,
+    #[serde(default)]
+    pub start_line: Option<u32>,
     pub body: String,
     pub severity: String,
 }
@@ -55,15 +499,16 @@ pub(crate) fn parse_reviewer_output(
     agent_name: &str,
 ) -> Result<ReviewerOutput> {
     let result = result.ok_or_else(|| anyhow!("No result in {} response", agent_name))?;
+    validate_output(&REVIEWER_VALIDATOR, result, agent_name, "reviewer")?;
 
-    let raw: RawReviewerOutput =
-        serde_json::from_value(result.clone()).context("Failed to parse reviewer output")?;
+    let raw: RawReviewerOutput = serde_json::from_value(result.clone())
+        .map_err(|e| OutputParseError(format!("Failed to parse reviewer output: {}", e)))?;
 
     let action = match raw.action.as_str() {
         "approve" => ReviewAction::Approve,
         "request_changes" => ReviewAction::RequestChanges,
         "comment" => ReviewAction::Comment,
-        _ => return Err(anyhow!("Unknown review action: {}", raw.action)),
+        _ => return Err(OutputParseError(format!("Unknown review action: {}", raw.action)).into()),
     };
 
     let comments = raw
@@ -80,6 +525,8 @@ pub(crate) fn parse_reviewer_output(
             ReviewComment {
                 path: c.path,
                 line: c.line,
+                // A range must start above its last line
+                start_line: c.start_line.filter(|&start| start > 0 && start < c.line),
                 body: c.body,
                 severity,
             }
@@ -91,6 +538,8 @@ pub(crate) fn parse_reviewer_output(
         summary: raw.summary,
         comments,
         blocking_issues: raw.blocking_issues,
+        // Treat a blank rationale the same as none
+        reasoning: raw.reasoning.filter(|r| !r.trim().is_empty()),
     })
 }
 
@@ -102,16 +551,19 @@ pub(crate) fn parse_reviewee_output(
     agent_name: &str,
 ) -> Result<RevieweeOutput> {
     let result = result.ok_or_else(|| anyhow!("No result in {} response", agent_name))?;
+    validate_output(&REVIEWEE_VALIDATOR, result, agent_name, "reviewee")?;
 
-    let raw: RawRevieweeOutput =
-        serde_json::from_value(result.clone()).context("Failed to parse reviewee output")?;
+    let raw: RawRevieweeOutput = serde_json::from_value(result.clone())
+        .map_err(|e| OutputParseError(format!("Failed to parse reviewee output: {}", e)))?;
 
     let status = match raw.status.as_str() {
         "completed" => RevieweeStatus::Completed,
         "needs_clarification" => RevieweeStatus::NeedsClarification,
         "needs_permission" => RevieweeStatus::NeedsPermission,
         "error" => RevieweeStatus::Error,
-        _ => return Err(anyhow!("Unknown reviewee status: {}", raw.status)),
+        _ => {
+            return Err(OutputParseError(format!("Unknown reviewee status: {}", raw.status)).into())
+        }
     };
 
     let permission_request = raw.permission_request.map(|p| PermissionRequest {
@@ -126,6 +578,7 @@ pub(crate) fn parse_reviewee_output(
         question: raw.question,
         permission_request,
         error_details: raw.error_details,
+        diff_stats: Vec::new(),
     })
 }
 
@@ -162,6 +615,160 @@ pub(super) fn summarize_text(s: &str) -> String {
 mod tests {
     use super::*;
     use insta::assert_json_snapshot;
+    use std::cell::Cell;
+
+    fn exit_error() -> anyhow::Error {
+        use std::os::unix::process::ExitStatusExt;
+        AgentExitError {
+            agent: "Codex",
+            status: ExitStatus::from_raw(1 << 8),
+            stderr: "connection reset".to_string(),
+        }
+        .into()
+    }
+
+    const NO_DELAY: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::ZERO,
+    };
+
+    #[tokio::test]
+    async fn test_turn_tracker_rejects_overlapping_turns() {
+        let tracker = TurnTracker::default();
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let first = tracker.run(TurnRole::Reviewee, false, async {
+            released.await.ok();
+            Ok("fixed")
+        });
+        let second = async {
+            // Requested while the first turn is still waiting on the agent
+            tokio::task::yield_now().await;
+            let result = tracker
+                .run(TurnRole::Reviewee, true, async { Ok("continued") })
+                .await;
+            release.send(()).unwrap();
+            result
+        };
+        let (first, second) = tokio::join!(first, second);
+
+        assert_eq!(first.unwrap(), "fixed");
+        assert!(second
+            .unwrap_err()
+            .to_string()
+            .contains("while the reviewee turn is still running"));
+        // Once the first turn settled, the session can be continued
+        let third = tracker
+            .run(TurnRole::Reviewee, true, async { Ok("continued") })
+            .await;
+        assert_eq!(third.unwrap(), "continued");
+    }
+
+    #[tokio::test]
+    async fn test_turn_tracker_refuses_to_continue_interrupted_session() {
+        let tracker = TurnTracker::default();
+
+        // The orchestrator's timeout drops the turn before it settles
+        let timed_out = tokio::time::timeout(
+            Duration::from_millis(10),
+            tracker.run(
+                TurnRole::Reviewer,
+                false,
+                std::future::pending::<Result<()>>(),
+            ),
+        )
+        .await;
+        assert!(timed_out.is_err());
+
+        for _ in 0..3 {
+            let err = tracker
+                .run(TurnRole::Reviewer, true, async { Ok(()) })
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("was interrupted"));
+        }
+        // The other role is unaffected, and a fresh reviewer turn starts over
+        tracker
+            .run(TurnRole::Reviewee, true, async { Ok(()) })
+            .await
+            .unwrap();
+        assert!(tracker
+            .run(TurnRole::Reviewer, true, async { Ok(()) })
+            .await
+            .is_err());
+        tracker
+            .run(TurnRole::Reviewer, false, async { Ok(()) })
+            .await
+            .unwrap();
+        tracker
+            .run(TurnRole::Reviewer, true, async { Ok(()) })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_retries_process_failures() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let attempts = Cell::new(0);
+        let result = retry_transient(NO_DELAY, Some(&tx), &CancellationToken::new(), || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err(exit_error())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        let mut messages = Vec::new();
+        while let Ok(RallyEvent::AgentThinking(msg)) = rx.try_recv() {
+            messages.push(msg);
+        }
+        assert_eq!(messages, vec!["Retrying (1/2)...", "Retrying (2/2)..."]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_gives_up_after_max_retries() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = retry_transient(NO_DELAY, None, &CancellationToken::new(), || {
+            attempts.set(attempts.get() + 1);
+            async { Err(exit_error()) }
+        })
+        .await;
+
+        assert!(result.unwrap_err().is::<AgentExitError>());
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_does_not_retry_other_errors() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = retry_transient(NO_DELAY, None, &CancellationToken::new(), || {
+            attempts.set(attempts.get() + 1);
+            async { Err(anyhow!("Codex authentication failed")) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kill_cancelled_child_reaps_process() {
+        let mut child = tokio::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+
+        let err = kill_cancelled_child(&mut child, "claude").await;
+        assert_eq!(err.to_string(), "claude run cancelled");
+        // Already reaped: the exit status is available without blocking
+        assert!(child.try_wait().unwrap().is_some());
+    }
 
     // --- parse_reviewer_output tests ---
 
@@ -273,6 +880,34 @@ mod tests {
 
     // --- Error path tests ---
 
+    #[test]
+    fn test_streamed_review_comments_from_partial_output() {
+        let partial = r#"{"action":"request_changes","summary":"The \"comments\" [ key","comments":[{"path":"src/a.rs","line":3,"body":"Use } and { carefully","severity":"major"},{"path":"src/b.rs","line":7,"body":"Unfin"#;
+        let comments = streamed_review_comments(partial);
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].path, "src/a.rs");
+        assert_eq!(comments[0].body, "Use } and { carefully");
+        assert_eq!(comments[0].severity, CommentSeverity::Major);
+
+        let complete = format!(
+            r#"{}ished","severity":"minor"}}],"blocking_issues":[]}}"#,
+            partial
+        );
+        let paths: Vec<String> = streamed_review_comments(&complete)
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        assert_eq!(paths, vec!["src/a.rs", "src/b.rs"]);
+    }
+
+    #[test]
+    fn test_streamed_review_comments_ignores_other_output() {
+        assert!(streamed_review_comments("").is_empty());
+        assert!(
+            streamed_review_comments(r#"{"status":"completed","files_modified":["a"]}"#).is_empty()
+        );
+    }
+
     #[test]
     fn test_parse_reviewer_output_none_result() {
         let err = parse_reviewer_output(None, "test").unwrap_err();
@@ -289,7 +924,9 @@ mod tests {
         });
 
         let err = parse_reviewer_output(Some(&result), "test").unwrap_err();
-        assert!(err.to_string().contains("Unknown review action: reject"));
+        let err = err.downcast::<SchemaValidationError>().unwrap();
+        assert_eq!(err.errors.len(), 1);
+        assert!(err.errors[0].starts_with("/action: \"reject\" is not one of"));
     }
 
     #[test]
@@ -301,11 +938,31 @@ mod tests {
         });
 
         let err = parse_reviewee_output(Some(&result), "test").unwrap_err();
-        assert!(err.to_string().contains("Unknown reviewee status: pending"));
+        let err = err.downcast::<SchemaValidationError>().unwrap();
+        assert!(err.errors[0].starts_with("/status: \"pending\" is not one of"));
+    }
+
+    #[test]
+    fn test_parse_reviewer_output_line_range() {
+        let result = serde_json::json!({
+            "action": "comment",
+            "summary": "Review",
+            "comments": [
+                {"path": "a.rs", "line": 8, "start_line": 5, "body": "x", "severity": "minor"},
+                {"path": "a.rs", "line": 8, "start_line": null, "body": "x", "severity": "minor"},
+                {"path": "a.rs", "line": 8, "start_line": 9, "body": "x", "severity": "minor"}
+            ],
+            "blocking_issues": []
+        });
+
+        let output = parse_reviewer_output(Some(&result), "test").unwrap();
+        let ranges: Vec<String> = output.comments.iter().map(|c| c.line_range()).collect();
+        // A start below the last line is dropped
+        assert_eq!(ranges, vec!["5-8", "8", "8"]);
     }
 
     #[test]
-    fn test_parse_reviewer_output_unknown_severity_fallback() {
+    fn test_parse_reviewer_output_unknown_severity_rejected() {
         let result = serde_json::json!({
             "action": "comment",
             "summary": "Review",
@@ -320,9 +977,79 @@ mod tests {
             "blocking_issues": []
         });
 
+        // Schema validation rejects the value before the Minor fallback is reached
+        let err = parse_reviewer_output(Some(&result), "test").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "test reviewer output does not match the schema:\n\
+             /comments/0/severity: \"unknown_severity\" is not one of \"critical\", \"major\" or 2 other candidates"
+        );
+    }
+
+    #[test]
+    fn test_parse_reviewer_output_reports_every_schema_violation() {
+        let result = serde_json::json!({
+            "action": "approve",
+            "comments": [{"path": "src/lib.rs", "line": "ten", "body": "x", "severity": "minor"}],
+            "blocking_issues": []
+        });
+
+        let err = parse_reviewer_output(Some(&result), "test").unwrap_err();
+        let err = err.downcast::<SchemaValidationError>().unwrap();
+        let mut pointers: Vec<&str> = err
+            .errors
+            .iter()
+            .map(|e| e.split(": ").next().unwrap())
+            .collect();
+        pointers.sort();
+        assert_eq!(pointers, vec!["/", "/comments/0/line"]);
+    }
+
+    #[test]
+    fn test_parse_reviewer_output_ignores_extra_fields() {
+        // Fields added by a custom schema pass validation and are ignored by the parser
+        let result = serde_json::json!({
+            "action": "comment",
+            "summary": "Risky change",
+            "comments": [{"path": "a.rs", "line": 1, "body": "x", "severity": "minor", "risk": 3}],
+            "blocking_issues": [],
+            "risk_score": 7
+        });
+
         let output = parse_reviewer_output(Some(&result), "test").unwrap();
-        // Unknown severity falls back to Minor
-        assert_eq!(output.comments[0].severity, CommentSeverity::Minor);
+        assert_eq!(output.summary, "Risky change");
+        assert_eq!(output.comments.len(), 1);
+    }
+
+    #[test]
+    fn test_output_schemas_load_custom_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut schema: serde_json::Value = serde_json::from_str(REVIEWER_SCHEMA).unwrap();
+        schema["properties"]["risk_score"] = serde_json::json!({"type": "integer"});
+        let extended = dir.path().join("reviewer.json");
+        std::fs::write(&extended, schema.to_string()).unwrap();
+
+        let config = AiConfig {
+            reviewer_schema_path: Some(extended.display().to_string()),
+            ..AiConfig::default()
+        };
+        let schemas = OutputSchemas::load(&config).unwrap();
+        assert!(schemas.reviewer().contains("risk_score"));
+        assert_eq!(schemas.reviewee(), REVIEWEE_SCHEMA);
+
+        // Dropping a field the parser needs is refused
+        schema["required"] = serde_json::json!(["action", "comments"]);
+        let reduced = dir.path().join("reduced.json");
+        std::fs::write(&reduced, schema.to_string()).unwrap();
+        let config = AiConfig {
+            reviewer_schema_path: Some(reduced.display().to_string()),
+            ..AiConfig::default()
+        };
+        let err = OutputSchemas::load(&config).unwrap_err().to_string();
+        assert!(
+            err.contains("must require summary, blocking_issues"),
+            "{err}"
+        );
     }
 
     // --- Utility tests ---
@@ -0,0 +1,623 @@
+//! Google Gemini CLI adapter.
+//!
+//! Runs `gemini` non-interactively with `--output-format stream-json`. The CLI has no
+//! structured-output option, so the JSON schema is prepended to the prompt and the
+//! final assistant message is parsed as JSON.
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Stdio;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::common::{
+    build_schema_prompt, extract_json, kill_cancelled_child, parse_reviewee_output,
+    parse_reviewer_output, summarize_json, summarize_text, OutputSchemas, TurnRole, TurnTracker,
+};
+use crate::ai::adapter::{AgentAdapter, Context, RevieweeOutput, ReviewerOutput};
+use crate::ai::orchestrator::RallyEvent;
+use crate::config::AiConfig;
+
+/// Passed with `-p`; the CLI appends it to the prompt piped on stdin
+const STDIN_PROMPT_SUFFIX: &str = "Reply with the JSON object only.";
+
+/// Gemini-specific errors
+#[derive(Debug, Error)]
+pub enum GeminiError {
+    #[error("Gemini CLI not found. Install it with: npm install -g @google/gemini-cli")]
+    CliNotFound,
+    #[error("Gemini authentication failed. Run 'gemini' once to sign in or set GEMINI_API_KEY")]
+    AuthenticationFailed,
+    #[error("Gemini run failed: {reason}")]
+    RunFailed { reason: String },
+}
+
+/// Google Gemini CLI adapter
+pub struct GeminiAdapter {
+    reviewer_session_id: Option<String>,
+    reviewee_session_id: Option<String>,
+    /// Working directory of each session's first turn; resumed turns run there too
+    reviewer_working_dir: Option<String>,
+    reviewee_working_dir: Option<String>,
+    event_sender: Option<mpsc::Sender<RallyEvent>>,
+    cancel_token: CancellationToken,
+    /// Refuses a turn that would overlap an unsettled one
+    turns: TurnTracker,
+    schemas: OutputSchemas,
+    /// Extra environment variables for the `gemini` process (`[ai.env.gemini]`)
+    env: HashMap<String, String>,
+}
+
+impl GeminiAdapter {
+    pub fn new(config: &AiConfig) -> Self {
+        Self {
+            reviewer_session_id: None,
+            reviewee_session_id: None,
+            reviewer_working_dir: None,
+            reviewee_working_dir: None,
+            event_sender: None,
+            cancel_token: CancellationToken::new(),
+            turns: TurnTracker::default(),
+            schemas: OutputSchemas::default(),
+            env: config.agent_env("gemini"),
+        }
+    }
+
+    /// Check if Gemini CLI is available
+    pub fn check_availability() -> Result<(), GeminiError> {
+        let output = std::process::Command::new("gemini")
+            .arg("--version")
+            .output();
+
+        match output {
+            Ok(o) if o.status.success() => Ok(()),
+            _ => Err(GeminiError::CliNotFound),
+        }
+    }
+
+    async fn send_event(&self, event: RallyEvent) {
+        if let Some(ref sender) = self.event_sender {
+            let _ = sender.send(event).await;
+        }
+    }
+
+    /// Run Gemini CLI with streaming JSON output
+    ///
+    /// If `session_id` is provided the session is resumed; the schema is then
+    /// already part of the conversation and only `prompt` is sent.
+    async fn run_gemini_streaming(
+        &self,
+        prompt: &str,
+        schema: &str,
+        yolo: bool,
+        working_dir: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<GeminiResponse> {
+        let mut cmd = gemini_command(yolo, working_dir, session_id);
+        cmd.envs(&self.env);
+
+        cmd.stdin(Stdio::piped());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        // Don't leave the agent running if this future is dropped (e.g., task abort)
+        cmd.kill_on_drop(true);
+
+        let mut child = cmd.spawn().with_context(|| {
+            format!(
+                "Failed to spawn gemini process (command: {:?})",
+                cmd.as_std()
+            )
+        })?;
+
+        let full_prompt = if session_id.is_some() {
+            prompt.to_string()
+        } else {
+            format!("{}\n\n{}", build_schema_prompt(schema, None), prompt)
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            use tokio::io::AsyncWriteExt;
+            stdin
+                .write_all(full_prompt.as_bytes())
+                .await
+                .context("Failed to write prompt to gemini stdin")?;
+            drop(stdin); // Close stdin to signal EOF
+        }
+
+        let stdout = child.stdout.take().expect("stdout should be available");
+        let stderr = child.stderr.take().expect("stderr should be available");
+
+        let mut stdout_reader = BufReader::new(stdout).lines();
+        let mut stderr_reader = BufReader::new(stderr).lines();
+
+        let mut stream = StreamState {
+            session_id: session_id.map(|s| s.to_string()),
+            ..StreamState::default()
+        };
+        let mut error_lines = Vec::new();
+        let mut stream_error: Option<anyhow::Error> = None;
+
+        // Process NDJSON stream
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    return Err(kill_cancelled_child(&mut child, "gemini").await);
+                }
+                line = stdout_reader.next_line() => {
+                    match line {
+                        Ok(Some(l)) => {
+                            if l.trim().is_empty() {
+                                continue;
+                            }
+                            // Unknown event formats are ignored
+                            if let Ok(event) = serde_json::from_str::<GeminiEvent>(&l) {
+                                if let Err(e) = self.handle_gemini_event(event, &mut stream).await {
+                                    // Capture error but continue to wait for process
+                                    stream_error = Some(e);
+                                    break;
+                                }
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            stream_error = Some(anyhow!("Error reading stdout: {}", e));
+                            break;
+                        }
+                    }
+                }
+                line = stderr_reader.next_line() => {
+                    match line {
+                        Ok(Some(l)) => error_lines.push(l),
+                        Ok(None) => {},
+                        Err(e) => {
+                            stream_error = Some(anyhow!("Error reading stderr: {}", e));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Always wait for the child process so no zombie is left behind
+        let status = match child.wait().await {
+            Ok(s) => s,
+            Err(e) => {
+                let _ = child.kill().await;
+                return Err(anyhow!("Failed to wait for gemini process: {}", e));
+            }
+        };
+
+        if let Some(e) = stream_error {
+            return Err(e);
+        }
+
+        if !status.success() {
+            let stderr_output = error_lines.join("\n");
+            if stderr_output.contains("auth") || stderr_output.contains("API key") {
+                return Err(GeminiError::AuthenticationFailed.into());
+            }
+            return Err(anyhow!(
+                "Gemini process failed with status {}: {}",
+                status,
+                stderr_output
+            ));
+        }
+
+        stream.into_response()
+    }
+
+    /// Handle a Gemini streaming event and convert it to RallyEvents
+    async fn handle_gemini_event(
+        &self,
+        event: GeminiEvent,
+        stream: &mut StreamState,
+    ) -> Result<()> {
+        match event {
+            GeminiEvent::Init { session_id } => {
+                stream.session_id = Some(session_id);
+                self.send_event(RallyEvent::AgentThinking("Starting...".to_string()))
+                    .await;
+            }
+            GeminiEvent::Message {
+                role,
+                content,
+                delta,
+            } => {
+                if role == "assistant" {
+                    stream.push_assistant_text(&content, delta);
+                }
+            }
+            GeminiEvent::ToolUse {
+                tool_name,
+                parameters,
+            } => {
+                let input = parameters.as_ref().map(summarize_json).unwrap_or_default();
+                self.send_event(RallyEvent::AgentToolUse(tool_name, input))
+                    .await;
+            }
+            GeminiEvent::ToolResult {
+                tool_id,
+                status,
+                output,
+            } => {
+                let output = output.as_deref().map(summarize_text).unwrap_or(status);
+                self.send_event(RallyEvent::AgentToolResult(tool_id, output))
+                    .await;
+            }
+            GeminiEvent::Error { severity, message } => {
+                if severity == "error" {
+                    return Err(GeminiError::RunFailed { reason: message }.into());
+                }
+                self.send_event(RallyEvent::AgentThinking(message)).await;
+            }
+            GeminiEvent::Result { status, error } => {
+                if status != "success" {
+                    let reason = error
+                        .map(|e| e.message)
+                        .unwrap_or_else(|| format!("status {}", status));
+                    return Err(GeminiError::RunFailed { reason }.into());
+                }
+                stream.completed = true;
+                self.send_event(RallyEvent::AgentText("Review completed.".to_string()))
+                    .await;
+            }
+            GeminiEvent::Unknown => {}
+        }
+        Ok(())
+    }
+}
+
+impl Default for GeminiAdapter {
+    fn default() -> Self {
+        Self::new(&AiConfig::default())
+    }
+}
+
+#[async_trait]
+impl AgentAdapter for GeminiAdapter {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    async fn check_availability(&self) -> Result<()> {
+        tokio::task::spawn_blocking(GeminiAdapter::check_availability)
+            .await
+            .context("Gemini availability check panicked")??;
+        Ok(())
+    }
+
+    fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>) {
+        self.event_sender = Some(sender);
+    }
+
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel_token = token;
+    }
+
+    fn set_output_schemas(&mut self, schemas: OutputSchemas) {
+        self.schemas = schemas;
+    }
+
+    async fn run_reviewer(&mut self, prompt: &str, context: &Context) -> Result<ReviewerOutput> {
+        let response = self
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                false,
+                self.run_gemini_streaming(
+                    prompt,
+                    self.schemas.reviewer(),
+                    false, // default approval mode for reviewer
+                    context.working_dir.as_deref(),
+                    None,
+                ),
+            )
+            .await?;
+
+        self.reviewer_session_id = Some(response.session_id.clone());
+        self.reviewer_working_dir = context.working_dir.clone();
+
+        parse_reviewer_output(Some(&extract_json(&response.text)?), "gemini")
+    }
+
+    async fn run_reviewee(&mut self, prompt: &str, context: &Context) -> Result<RevieweeOutput> {
+        // NOTE: yolo allows git push, but the prompt explicitly prohibits it
+        let response = self
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                false,
+                self.run_gemini_streaming(
+                    prompt,
+                    self.schemas.reviewee(),
+                    true, // yolo approval mode for reviewee
+                    context.working_dir.as_deref(),
+                    None,
+                ),
+            )
+            .await?;
+
+        self.reviewee_session_id = Some(response.session_id.clone());
+        self.reviewee_working_dir = context.working_dir.clone();
+
+        parse_reviewee_output(Some(&extract_json(&response.text)?), "gemini")
+    }
+
+    async fn continue_reviewer(&mut self, message: &str) -> Result<ReviewerOutput> {
+        let session_id = self
+            .reviewer_session_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("No reviewer session to continue"))?
+            .clone();
+
+        let response = self
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                true,
+                self.run_gemini_streaming(
+                    message,
+                    self.schemas.reviewer(),
+                    false,
+                    self.reviewer_working_dir.as_deref(),
+                    Some(&session_id),
+                ),
+            )
+            .await?;
+
+        parse_reviewer_output(Some(&extract_json(&response.text)?), "gemini")
+    }
+
+    async fn continue_reviewee(&mut self, message: &str) -> Result<RevieweeOutput> {
+        let session_id = self
+            .reviewee_session_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("No reviewee session to continue"))?
+            .clone();
+
+        let response = self
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                true,
+                self.run_gemini_streaming(
+                    message,
+                    self.schemas.reviewee(),
+                    true,
+                    self.reviewee_working_dir.as_deref(),
+                    Some(&session_id),
+                ),
+            )
+            .await?;
+
+        parse_reviewee_output(Some(&extract_json(&response.text)?), "gemini")
+    }
+
+    fn add_reviewee_allowed_tool(&mut self, _tool: &str) {
+        // Gemini CLI uses approval modes (default vs yolo) instead of per-tool
+        // permissions, so this is a no-op.
+    }
+}
+
+/// `gemini` invocation for one turn; the prompt is written to stdin
+fn gemini_command(yolo: bool, working_dir: Option<&str>, session_id: Option<&str>) -> Command {
+    let mut cmd = Command::new("gemini");
+    cmd.arg("--output-format").arg("stream-json");
+    // Prompt body is piped via stdin to avoid the OS ARG_MAX limit
+    cmd.arg("-p").arg(STDIN_PROMPT_SUFFIX);
+
+    // - Reviewer: default approval mode (tools that need approval are refused)
+    // - Reviewee: yolo (edits and shell commands run without confirmation)
+    if yolo {
+        cmd.arg("--approval-mode").arg("yolo");
+    }
+
+    // Gemini keeps sessions per project directory, so a resume has to run where
+    // the session started
+    if let Some(sid) = session_id {
+        cmd.arg("--resume").arg(sid);
+    }
+
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+    cmd
+}
+
+/// Gemini `stream-json` event types
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum GeminiEvent {
+    Init {
+        session_id: String,
+    },
+    Message {
+        role: String,
+        #[serde(default)]
+        content: String,
+        /// true when `content` is a chunk to append to the current message
+        #[serde(default)]
+        delta: bool,
+    },
+    ToolUse {
+        tool_name: String,
+        #[serde(default)]
+        parameters: Option<serde_json::Value>,
+    },
+    ToolResult {
+        tool_id: String,
+        status: String,
+        #[serde(default)]
+        output: Option<String>,
+    },
+    Error {
+        #[serde(default)]
+        severity: String,
+        message: String,
+    },
+    Result {
+        status: String,
+        #[serde(default)]
+        error: Option<GeminiErrorInfo>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Error info in the result event
+#[derive(Debug, Deserialize)]
+struct GeminiErrorInfo {
+    #[serde(default)]
+    message: String,
+}
+
+/// Accumulated state of one streamed run
+#[derive(Debug, Default)]
+struct StreamState {
+    session_id: Option<String>,
+    /// Text of the latest assistant message (the final one holds the JSON result)
+    assistant_text: String,
+    /// Whether the last event was an assistant delta (so the next delta continues it)
+    in_assistant_message: bool,
+    completed: bool,
+}
+
+impl StreamState {
+    fn push_assistant_text(&mut self, content: &str, delta: bool) {
+        if !(delta && self.in_assistant_message) {
+            self.assistant_text.clear();
+        }
+        self.assistant_text.push_str(content);
+        self.in_assistant_message = delta;
+    }
+
+    fn into_response(self) -> Result<GeminiResponse> {
+        if !self.completed {
+            return Err(anyhow!("No result received from gemini"));
+        }
+        let session_id = self
+            .session_id
+            .ok_or_else(|| anyhow!("No session_id available: Gemini did not emit init"))?;
+        Ok(GeminiResponse {
+            session_id,
+            text: self.assistant_text,
+        })
+    }
+}
+
+/// Gemini response structure
+#[derive(Debug)]
+struct GeminiResponse {
+    session_id: String,
+    /// Final assistant message. JSON is extracted by the caller after the session
+    /// is stored, so an unparsable reply can still be corrected by continuing it.
+    text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::ReviewAction;
+    use std::path::Path;
+
+    async fn replay(lines: &[&str]) -> Result<GeminiResponse> {
+        let adapter = GeminiAdapter::default();
+        let mut stream = StreamState::default();
+        for line in lines {
+            let event: GeminiEvent = serde_json::from_str(line).unwrap();
+            adapter.handle_gemini_event(event, &mut stream).await?;
+        }
+        stream.into_response()
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_into_reviewer_output() {
+        let response = replay(&[
+            r#"{"type":"init","timestamp":"2025-01-01T00:00:00Z","session_id":"sess-1","model":"gemini-2.5-pro"}"#,
+            r#"{"type":"message","role":"user","content":"Review this PR"}"#,
+            r#"{"type":"tool_use","tool_name":"read_file","tool_id":"t1","parameters":{"path":"src/main.rs"}}"#,
+            r#"{"type":"tool_result","tool_id":"t1","status":"success","output":"fn main() {}"}"#,
+            r#"{"type":"message","role":"assistant","content":"{\"action\":\"request_changes\",\"summary\":\"Needs work\",","delta":true}"#,
+            r#"{"type":"message","role":"assistant","content":"\"comments\":[],\"blocking_issues\":[\"Missing tests\"]}","delta":true}"#,
+            r#"{"type":"result","status":"success","stats":{"total_tokens":100}}"#,
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(response.session_id, "sess-1");
+        let result = extract_json(&response.text).unwrap();
+        let output = parse_reviewer_output(Some(&result), "gemini").unwrap();
+        assert_eq!(output.action, ReviewAction::RequestChanges);
+        assert_eq!(output.blocking_issues, vec!["Missing tests"]);
+    }
+
+    #[tokio::test]
+    async fn test_only_last_assistant_message_is_parsed() {
+        let response = replay(&[
+            r#"{"type":"init","session_id":"sess-2"}"#,
+            r#"{"type":"message","role":"assistant","content":"Let me look at the diff.","delta":true}"#,
+            r#"{"type":"tool_use","tool_name":"read_file","tool_id":"t1"}"#,
+            r#"{"type":"message","role":"assistant","content":"```json\n{\"action\":\"approve\",\"summary\":\"LGTM\",\"comments\":[],\"blocking_issues\":[]}\n```"}"#,
+            r#"{"type":"result","status":"success"}"#,
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(extract_json(&response.text).unwrap()["action"], "approve");
+    }
+
+    #[tokio::test]
+    async fn test_error_result_fails_run() {
+        let err = replay(&[
+            r#"{"type":"init","session_id":"sess-3"}"#,
+            r#"{"type":"result","status":"error","error":{"type":"FatalError","message":"quota exceeded"}}"#,
+        ])
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(err.to_string(), "Gemini run failed: quota exceeded");
+    }
+
+    #[tokio::test]
+    async fn test_missing_result_event_is_an_error() {
+        let err = replay(&[r#"{"type":"init","session_id":"sess-4"}"#])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "No result received from gemini");
+    }
+
+    #[test]
+    fn test_parse_unknown_event() {
+        let event: GeminiEvent = serde_json::from_str(r#"{"type":"stats","tokens":1}"#).unwrap();
+        assert!(matches!(event, GeminiEvent::Unknown));
+    }
+
+    #[test]
+    fn test_resume_runs_in_the_sessions_working_dir() {
+        let adapter = GeminiAdapter {
+            reviewee_session_id: Some("sess-1".to_string()),
+            reviewee_working_dir: Some("/work/repo".to_string()),
+            ..GeminiAdapter::default()
+        };
+
+        let cmd = gemini_command(
+            true,
+            adapter.reviewee_working_dir.as_deref(),
+            adapter.reviewee_session_id.as_deref(),
+        );
+        let args: Vec<_> = cmd.as_std().get_args().collect();
+        let resume = args.iter().position(|a| *a == "--resume").unwrap();
+        assert_eq!(args[resume + 1], "sess-1");
+        assert_eq!(
+            cmd.as_std().get_current_dir(),
+            Some(Path::new("/work/repo"))
+        );
+    }
+}
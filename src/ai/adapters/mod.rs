@@ -1,9 +1,16 @@
+mod cached;
 mod claude;
 mod codex;
 mod common;
+mod gemini;
+mod ollama;
 
+pub use cached::CachedAdapter;
 pub use claude::ClaudeAdapter;
-pub use codex::CodexAdapter;
+pub use codex::{CodexAdapter, CodexError};
+pub use common::{OutputParseError, OutputSchemas, SchemaValidationError};
+pub use gemini::GeminiAdapter;
+pub use ollama::OllamaAdapter;
 
 use anyhow::{anyhow, Result};
 
@@ -13,17 +20,206 @@ use crate::config::AiConfig;
 /// Create an adapter from agent name.
 ///
 /// # Arguments
-/// * `name` - Agent name ("claude" or "codex")
-/// * `config` - AI configuration (used by Claude adapter for additional tools, ignored by Codex)
+/// * `name` - Agent name ("claude", "codex", "gemini" or "ollama")
+/// * `config` - AI configuration (tools and models for Claude, turn timeout and models for Codex, host/model for Ollama,
+///   environment variables for the CLI agents)
 pub fn create_adapter(name: &str, config: &AiConfig) -> Result<Box<dyn AgentAdapter>> {
-    let agent = SupportedAgent::from_name(name)
-        .ok_or_else(|| anyhow!("Unsupported agent: {}. Supported: claude, codex", name))?;
+    let agent = SupportedAgent::from_name(name).ok_or_else(|| {
+        anyhow!(
+            "Unsupported agent: {}. Supported: claude, codex, gemini, ollama",
+            name
+        )
+    })?;
 
-    match agent {
+    let schemas = OutputSchemas::load(config)?;
+    let adapter: Box<dyn AgentAdapter> = match agent {
         // Claude adapter uses config for additional tools
-        SupportedAgent::Claude => Ok(Box::new(ClaudeAdapter::new(config))),
-        // Codex adapter does not support fine-grained tool control
-        SupportedAgent::Codex => Ok(Box::new(CodexAdapter::new())),
-        // SupportedAgent::Gemini => Ok(Box::new(GeminiAdapter::new())),
+        SupportedAgent::Claude => Box::new(ClaudeAdapter::new(config)),
+        // Codex adapter does not support fine-grained tool control; config sets the turn timeout
+        SupportedAgent::Codex => Box::new(CodexAdapter::new(config)),
+        // Ollama adapter uses config for server host and model
+        SupportedAgent::Ollama => Box::new(OllamaAdapter::new(config)),
+        // Gemini uses approval modes instead of per-tool permissions; config sets its environment
+        SupportedAgent::Gemini => Box::new(GeminiAdapter::new(config)),
+    };
+
+    if !config.response_cache {
+        let mut adapter = adapter;
+        adapter.set_output_schemas(schemas);
+        return Ok(adapter);
+    }
+    let model = match agent {
+        SupportedAgent::Ollama => config.ollama_model.clone(),
+        // The same adapter may serve either role, so both models are part of the key
+        SupportedAgent::Claude | SupportedAgent::Codex => {
+            match (&config.reviewer_model, &config.reviewee_model) {
+                (None, None) => String::new(),
+                (reviewer, reviewee) => format!(
+                    "{}|{}",
+                    reviewer.as_deref().unwrap_or_default(),
+                    reviewee.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+        SupportedAgent::Gemini => String::new(),
+    };
+    let mut cached = CachedAdapter::new(adapter, &model, crate::cache::response_cache_dir());
+    cached.set_output_schemas(schemas);
+    Ok(Box::new(cached))
+}
+
+/// Create the first available adapter from an ordered fallback list.
+///
+/// Each candidate is checked with `check_availability()` in order. Returns the
+/// selected agent name together with its adapter, or an error listing every
+/// candidate that was tried when none are available.
+pub async fn create_first_available_adapter(
+    names: &[String],
+    config: &AiConfig,
+) -> Result<(String, Box<dyn AgentAdapter>)> {
+    select_first_available(names, |name| create_adapter(name, config)).await
+}
+
+async fn select_first_available<F>(
+    names: &[String],
+    mut factory: F,
+) -> Result<(String, Box<dyn AgentAdapter>)>
+where
+    F: FnMut(&str) -> Result<Box<dyn AgentAdapter>>,
+{
+    let mut tried = Vec::new();
+
+    for name in names {
+        let adapter = match factory(name) {
+            Ok(adapter) => adapter,
+            Err(e) => {
+                tried.push(format!("{} ({})", name, e));
+                continue;
+            }
+        };
+
+        match adapter.check_availability().await {
+            Ok(()) => return Ok((name.clone(), adapter)),
+            Err(e) => tried.push(format!("{} ({})", name, e)),
+        }
+    }
+
+    if tried.is_empty() {
+        return Err(anyhow!("No adapters configured"));
+    }
+    Err(anyhow!("No available adapter. Tried: {}", tried.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::{Context, RevieweeOutput, ReviewerOutput};
+    use crate::ai::orchestrator::RallyEvent;
+    use async_trait::async_trait;
+    use tokio::sync::mpsc;
+
+    struct MockAdapter {
+        name: String,
+        available: bool,
+    }
+
+    #[async_trait]
+    impl AgentAdapter for MockAdapter {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            if self.available {
+                Ok(())
+            } else {
+                Err(anyhow!("{} CLI not found", self.name))
+            }
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    fn mock_factory(
+        available: &'static [&'static str],
+    ) -> impl FnMut(&str) -> Result<Box<dyn AgentAdapter>> {
+        move |name| {
+            Ok(Box::new(MockAdapter {
+                name: name.to_string(),
+                available: available.contains(&name),
+            }) as Box<dyn AgentAdapter>)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_to_second_adapter() {
+        let names = vec!["codex".to_string(), "claude".to_string()];
+        let (selected, adapter) = select_first_available(&names, mock_factory(&["claude"]))
+            .await
+            .unwrap();
+        assert_eq!(selected, "claude");
+        assert_eq!(adapter.name(), "claude");
+    }
+
+    #[tokio::test]
+    async fn test_first_available_adapter_wins() {
+        let names = vec!["codex".to_string(), "claude".to_string()];
+        let (selected, _) = select_first_available(&names, mock_factory(&["codex", "claude"]))
+            .await
+            .unwrap();
+        assert_eq!(selected, "codex");
+    }
+
+    #[tokio::test]
+    async fn test_no_available_adapter_lists_tried() {
+        let names = vec!["codex".to_string(), "gemini".to_string()];
+        let err = select_first_available(&names, |name| {
+            if name == "gemini" {
+                return Err(anyhow!("Unsupported agent: gemini"));
+            }
+            mock_factory(&[])(name)
+        })
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "No available adapter. Tried: codex (codex CLI not found), gemini (Unsupported agent: gemini)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_adapter_list() {
+        let err = select_first_available(&[], mock_factory(&[]))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "No adapters configured");
     }
 }
@@ -0,0 +1,417 @@
+//! Ollama adapter for fully local reviews.
+//!
+//! Talks to a locally running Ollama server over its HTTP chat API (`/api/chat`).
+//! The output schema is sent as the request's `format` (Ollama structured outputs),
+//! so the model is constrained to the reviewer/reviewee shape. The model has no tool
+//! access, so the reviewee is read-only: it proposes changes as text in its summary
+//! instead of editing files.
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::common::{
+    build_schema_prompt, extract_json, parse_reviewee_output, parse_reviewer_output, OutputSchemas,
+    TurnRole, TurnTracker,
+};
+use crate::ai::adapter::{AgentAdapter, Context, RevieweeOutput, ReviewerOutput};
+use crate::ai::orchestrator::RallyEvent;
+use crate::config::AiConfig;
+
+const REVIEWEE_READ_ONLY_NOTE: &str = "You cannot edit files or run commands. \
+Describe the changes you would make (file, location and replacement code) in `summary`, \
+and always return an empty `files_modified` array.";
+
+/// Ollama (local model) adapter
+pub struct OllamaAdapter {
+    client: reqwest::Client,
+    host: String,
+    model: String,
+    reviewer_messages: Vec<ChatMessage>,
+    reviewee_messages: Vec<ChatMessage>,
+    event_sender: Option<mpsc::Sender<RallyEvent>>,
+    /// Refuses a turn that would overlap an unsettled one
+    turns: TurnTracker,
+    schemas: OutputSchemas,
+}
+
+impl OllamaAdapter {
+    pub fn new(config: &AiConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            host: config.ollama_host.trim_end_matches('/').to_string(),
+            model: config.ollama_model.clone(),
+            reviewer_messages: Vec::new(),
+            reviewee_messages: Vec::new(),
+            event_sender: None,
+            turns: TurnTracker::default(),
+            schemas: OutputSchemas::default(),
+        }
+    }
+
+    async fn send_event(&self, event: RallyEvent) {
+        if let Some(ref sender) = self.event_sender {
+            let _ = sender.send(event).await;
+        }
+    }
+
+    /// Send the conversation to `/api/chat` and return the assistant message.
+    ///
+    /// `schema` constrains the reply to a JSON value matching it.
+    async fn chat(&self, messages: &[ChatMessage], schema: &str) -> Result<ChatMessage> {
+        let url = format!("{}/api/chat", self.host);
+        let format = serde_json::from_str(schema).context("Output schema is not valid JSON")?;
+        let request = build_chat_request(&self.model, messages, format);
+
+        self.send_event(RallyEvent::AgentThinking(format!(
+            "Waiting for {} ({})...",
+            self.model, self.host
+        )))
+        .await;
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to Ollama at {}", self.host))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read Ollama response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Ollama request failed with status {}: {}",
+                status,
+                body
+            ));
+        }
+
+        let message = parse_chat_response(&body)?;
+        self.send_event(RallyEvent::AgentText(message.content.clone()))
+            .await;
+        Ok(message)
+    }
+}
+
+impl Default for OllamaAdapter {
+    fn default() -> Self {
+        Self::new(&AiConfig::default())
+    }
+}
+
+#[async_trait]
+impl AgentAdapter for OllamaAdapter {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    async fn check_availability(&self) -> Result<()> {
+        let url = format!("{}/api/tags", self.host);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Ollama server not reachable at {}", self.host))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Ollama server at {} returned status {}",
+                self.host,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_event_sender(&mut self, sender: mpsc::Sender<RallyEvent>) {
+        self.event_sender = Some(sender);
+    }
+
+    fn set_output_schemas(&mut self, schemas: OutputSchemas) {
+        self.schemas = schemas;
+    }
+
+    async fn run_reviewer(&mut self, prompt: &str, _context: &Context) -> Result<ReviewerOutput> {
+        let messages = vec![
+            ChatMessage::system(build_schema_prompt(self.schemas.reviewer(), None)),
+            ChatMessage::user(prompt),
+        ];
+        let reply = self
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                false,
+                self.chat(&messages, self.schemas.reviewer()),
+            )
+            .await?;
+        let result = extract_json(&reply.content);
+
+        // Keep the reply even if it is unusable, so a correction can continue the chat
+        self.reviewer_messages = messages;
+        self.reviewer_messages.push(reply);
+
+        parse_reviewer_output(Some(&result?), "ollama")
+    }
+
+    async fn run_reviewee(&mut self, prompt: &str, _context: &Context) -> Result<RevieweeOutput> {
+        let messages = vec![
+            ChatMessage::system(build_schema_prompt(
+                self.schemas.reviewee(),
+                Some(REVIEWEE_READ_ONLY_NOTE),
+            )),
+            ChatMessage::user(prompt),
+        ];
+        let reply = self
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                false,
+                self.chat(&messages, self.schemas.reviewee()),
+            )
+            .await?;
+        let result = extract_json(&reply.content);
+
+        self.reviewee_messages = messages;
+        self.reviewee_messages.push(reply);
+
+        parse_read_only_reviewee_output(&result?)
+    }
+
+    async fn continue_reviewer(&mut self, message: &str) -> Result<ReviewerOutput> {
+        if self.reviewer_messages.is_empty() {
+            return Err(anyhow!("No reviewer session to continue"));
+        }
+
+        let mut messages = self.reviewer_messages.clone();
+        messages.push(ChatMessage::user(message));
+        let reply = self
+            .turns
+            .run(
+                TurnRole::Reviewer,
+                true,
+                self.chat(&messages, self.schemas.reviewer()),
+            )
+            .await?;
+        let result = extract_json(&reply.content);
+
+        messages.push(reply);
+        self.reviewer_messages = messages;
+
+        parse_reviewer_output(Some(&result?), "ollama")
+    }
+
+    async fn continue_reviewee(&mut self, message: &str) -> Result<RevieweeOutput> {
+        if self.reviewee_messages.is_empty() {
+            return Err(anyhow!("No reviewee session to continue"));
+        }
+
+        let mut messages = self.reviewee_messages.clone();
+        messages.push(ChatMessage::user(message));
+        let reply = self
+            .turns
+            .run(
+                TurnRole::Reviewee,
+                true,
+                self.chat(&messages, self.schemas.reviewee()),
+            )
+            .await?;
+        let result = extract_json(&reply.content);
+
+        messages.push(reply);
+        self.reviewee_messages = messages;
+
+        parse_read_only_reviewee_output(&result?)
+    }
+
+    fn add_reviewee_allowed_tool(&mut self, _tool: &str) {
+        // Ollama has no tool access; permissions have nothing to unlock
+    }
+
+    fn can_modify_files(&self) -> bool {
+        false
+    }
+}
+
+/// Chat message in Ollama's `/api/chat` format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+impl ChatMessage {
+    fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Request body for `/api/chat`
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    /// Structured outputs: a JSON schema the reply must conform to
+    format: serde_json::Value,
+}
+
+/// Non-streaming response body from `/api/chat`
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ChatMessage,
+}
+
+fn build_chat_request<'a>(
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    format: serde_json::Value,
+) -> ChatRequest<'a> {
+    ChatRequest {
+        model,
+        messages,
+        stream: false,
+        format,
+    }
+}
+
+fn parse_chat_response(body: &str) -> Result<ChatMessage> {
+    let response: ChatResponse =
+        serde_json::from_str(body).context("Failed to parse Ollama chat response")?;
+    Ok(response.message)
+}
+
+/// Parse reviewee output, discarding any claimed file modifications.
+///
+/// The model cannot touch the working tree, so `files_modified` is always empty.
+fn parse_read_only_reviewee_output(result: &serde_json::Value) -> Result<RevieweeOutput> {
+    let mut output = parse_reviewee_output(Some(result), "ollama")?;
+    output.files_modified.clear();
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::{CommentSeverity, ReviewAction, RevieweeStatus};
+    use crate::ai::adapters::common::REVIEWEE_SCHEMA;
+    use insta::assert_json_snapshot;
+
+    #[test]
+    fn test_build_chat_request() {
+        let messages = vec![
+            ChatMessage::system("schema"),
+            ChatMessage::user("review this"),
+        ];
+        let format = serde_json::json!({"type": "object", "required": ["action"]});
+        let request = build_chat_request("qwen2.5-coder", &messages, format);
+        assert_json_snapshot!(request, @r#"
+        {
+          "model": "qwen2.5-coder",
+          "messages": [
+            {
+              "role": "system",
+              "content": "schema"
+            },
+            {
+              "role": "user",
+              "content": "review this"
+            }
+          ],
+          "stream": false,
+          "format": {
+            "required": [
+              "action"
+            ],
+            "type": "object"
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_build_schema_prompt_includes_schema_and_note() {
+        let prompt = build_schema_prompt(REVIEWEE_SCHEMA, Some(REVIEWEE_READ_ONLY_NOTE));
+        assert!(prompt.contains("\"title\": \"RevieweeOutput\""));
+        assert!(prompt.ends_with(REVIEWEE_READ_ONLY_NOTE));
+    }
+
+    #[test]
+    fn test_parse_chat_response_into_reviewer_output() {
+        let body = r#"{
+            "model": "qwen2.5-coder",
+            "created_at": "2024-01-01T00:00:00Z",
+            "message": {
+                "role": "assistant",
+                "content": "{\"action\":\"request_changes\",\"summary\":\"Needs work\",\"comments\":[{\"path\":\"src/main.rs\",\"line\":3,\"body\":\"Handle the error\",\"severity\":\"major\"}],\"blocking_issues\":[\"Unhandled error\"]}"
+            },
+            "done": true
+        }"#;
+
+        let message = parse_chat_response(body).unwrap();
+        assert_eq!(message.role, "assistant");
+
+        let result = extract_json(&message.content).unwrap();
+        let output = parse_reviewer_output(Some(&result), "ollama").unwrap();
+        assert_eq!(output.action, ReviewAction::RequestChanges);
+        assert_eq!(output.comments.len(), 1);
+        assert_eq!(output.comments[0].severity, CommentSeverity::Major);
+        assert_eq!(output.blocking_issues, vec!["Unhandled error"]);
+    }
+
+    #[test]
+    fn test_extract_json_strips_code_fence() {
+        let content = "```json\n{\"action\":\"approve\",\"summary\":\"LGTM\",\"comments\":[],\"blocking_issues\":[]}\n```";
+        let result = extract_json(content).unwrap();
+        assert_eq!(result["action"], "approve");
+    }
+
+    #[test]
+    fn test_extract_json_rejects_prose() {
+        assert!(extract_json("Looks good to me!").is_err());
+    }
+
+    #[test]
+    fn test_parse_chat_response_missing_message() {
+        assert!(parse_chat_response(r#"{"error":"model not found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_read_only_reviewee_clears_files_modified() {
+        let result = serde_json::json!({
+            "status": "completed",
+            "summary": "Replace unwrap() with ? in src/main.rs:3",
+            "files_modified": ["src/main.rs"]
+        });
+        let output = parse_read_only_reviewee_output(&result).unwrap();
+        assert_eq!(output.status, RevieweeStatus::Completed);
+        assert!(output.files_modified.is_empty());
+    }
+
+    #[test]
+    fn test_new_trims_trailing_slash_from_host() {
+        let config = AiConfig {
+            ollama_host: "http://localhost:11434/".to_string(),
+            ..AiConfig::default()
+        };
+        let adapter = OllamaAdapter::new(&config);
+        assert_eq!(adapter.host, "http://localhost:11434");
+        assert_eq!(adapter.model, config.ollama_model);
+    }
+}
@@ -0,0 +1,353 @@
+//! Benchmark mode: run the same reviewer pass on several adapters and compare
+//! the results side by side. Nothing is posted to the PR.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::time::timeout;
+
+use super::adapter::{AgentAdapter, CommentSeverity, Context, ReviewComment, ReviewerOutput};
+use super::adapters::create_adapter;
+use super::prompt_loader::PromptLoader;
+use crate::config::AiConfig;
+
+/// Comments on the same file within this many lines count as the same finding
+const AGREEMENT_LINE_TOLERANCE: u32 = 3;
+
+/// One adapter's reviewer run
+#[derive(Debug)]
+pub struct BenchmarkEntry {
+    pub adapter: String,
+    pub elapsed: Duration,
+    /// Reviewer output, or the error message when the run failed
+    pub result: Result<ReviewerOutput, String>,
+}
+
+/// Adapters to benchmark: `adapters` if set, otherwise the reviewer and reviewee agents
+pub fn benchmark_adapter_names(config: &AiConfig) -> Vec<String> {
+    if !config.adapters.is_empty() {
+        return config.adapters.clone();
+    }
+    let mut names = vec![config.reviewer.clone()];
+    if config.reviewee != config.reviewer {
+        names.push(config.reviewee.clone());
+    }
+    names
+}
+
+/// Run the reviewer prompt once on every benchmarked adapter
+pub async fn run_benchmark(config: &AiConfig, context: &Context) -> Vec<BenchmarkEntry> {
+    let names = benchmark_adapter_names(config);
+    let prompt = PromptLoader::new(config).load_reviewer_prompt(context, 1);
+    run_benchmark_with(&names, &prompt, context, config.timeout_secs, |name| {
+        create_adapter(name, config)
+    })
+    .await
+}
+
+async fn run_benchmark_with<F>(
+    names: &[String],
+    prompt: &str,
+    context: &Context,
+    timeout_secs: u64,
+    mut factory: F,
+) -> Vec<BenchmarkEntry>
+where
+    F: FnMut(&str) -> Result<Box<dyn AgentAdapter>>,
+{
+    let mut entries = Vec::new();
+
+    // Run sequentially so timings are not skewed by agents competing for the machine
+    for name in names {
+        let start = Instant::now();
+        let result = match factory(name) {
+            Ok(mut adapter) => {
+                let run = adapter.run_reviewer(prompt, context);
+                match timeout(Duration::from_secs(timeout_secs), run).await {
+                    Ok(Ok(output)) => Ok(output),
+                    Ok(Err(e)) => Err(format!("{:#}", e)),
+                    Err(_) => Err(format!("timeout after {} seconds", timeout_secs)),
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        entries.push(BenchmarkEntry {
+            adapter: name.clone(),
+            elapsed: start.elapsed(),
+            result,
+        });
+    }
+
+    entries
+}
+
+fn is_same_finding(a: &ReviewComment, b: &ReviewComment) -> bool {
+    a.path == b.path && a.line.abs_diff(b.line) <= AGREEMENT_LINE_TOLERANCE
+}
+
+/// Number of `entries[index]`'s comments that at least one other adapter also raised
+fn agreed_comment_count(entries: &[BenchmarkEntry], index: usize) -> usize {
+    let Ok(ref output) = entries[index].result else {
+        return 0;
+    };
+    output
+        .comments
+        .iter()
+        .filter(|comment| {
+            entries.iter().enumerate().any(|(i, other)| {
+                i != index
+                    && other
+                        .result
+                        .as_ref()
+                        .is_ok_and(|o| o.comments.iter().any(|c| is_same_finding(comment, c)))
+            })
+        })
+        .count()
+}
+
+fn count_severity(output: &ReviewerOutput, severity: CommentSeverity) -> usize {
+    output
+        .comments
+        .iter()
+        .filter(|c| c.severity == severity)
+        .count()
+}
+
+/// Render the benchmark results as a plain-text comparison table
+pub fn format_benchmark_table(entries: &[BenchmarkEntry]) -> String {
+    let mut out = format!(
+        "{:<10} {:<15} {:>8} {:>8} {:>6} {:>6} {:>10} {:>8} {:>9} {:>8}\n",
+        "Adapter",
+        "Action",
+        "Comments",
+        "Critical",
+        "Major",
+        "Minor",
+        "Suggestion",
+        "Blocking",
+        "Agreement",
+        "Time"
+    );
+
+    for (i, entry) in entries.iter().enumerate() {
+        let time = format!("{:.1}s", entry.elapsed.as_secs_f64());
+        match entry.result {
+            Ok(ref output) => {
+                out.push_str(&format!(
+                    "{:<10} {:<15} {:>8} {:>8} {:>6} {:>6} {:>10} {:>8} {:>9} {:>8}\n",
+                    entry.adapter,
+                    format!("{:?}", output.action),
+                    output.comments.len(),
+                    count_severity(output, CommentSeverity::Critical),
+                    count_severity(output, CommentSeverity::Major),
+                    count_severity(output, CommentSeverity::Minor),
+                    count_severity(output, CommentSeverity::Suggestion),
+                    output.blocking_issues.len(),
+                    format!(
+                        "{}/{}",
+                        agreed_comment_count(entries, i),
+                        output.comments.len()
+                    ),
+                    time
+                ));
+            }
+            Err(ref e) => {
+                out.push_str(&format!("{:<10} error ({}): {}\n", entry.adapter, time, e));
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::{ReviewAction, RevieweeOutput};
+    use crate::ai::orchestrator::RallyEvent;
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use insta::assert_snapshot;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::mpsc;
+
+    struct RecordingAdapter {
+        name: String,
+        calls: Arc<Mutex<Vec<String>>>,
+        output: ReviewerOutput,
+    }
+
+    #[async_trait]
+    impl AgentAdapter for RecordingAdapter {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            self.calls.lock().unwrap().push(self.name.clone());
+            Ok(self.output.clone())
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    fn comment(path: &str, line: u32, severity: CommentSeverity) -> ReviewComment {
+        ReviewComment {
+            path: path.to_string(),
+            line,
+            start_line: None,
+            body: "issue".to_string(),
+            severity,
+        }
+    }
+
+    fn review(action: ReviewAction, comments: Vec<ReviewComment>) -> ReviewerOutput {
+        ReviewerOutput {
+            action,
+            summary: "summary".to_string(),
+            comments,
+            blocking_issues: vec![],
+            reasoning: None,
+        }
+    }
+
+    fn test_context() -> Context {
+        Context {
+            repo: "owner/repo".to_string(),
+            pr_number: 1,
+            pr_title: "Title".to_string(),
+            pr_body: None,
+            diff: "+line".to_string(),
+            working_dir: None,
+            head_sha: "abc".to_string(),
+            base_branch: "main".to_string(),
+            external_comments: vec![],
+            local_mode: false,
+            file_patches: vec![],
+            linked_issues: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_runs_each_adapter_once() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let names = vec![
+            "claude".to_string(),
+            "codex".to_string(),
+            "gemini".to_string(),
+        ];
+
+        let factory_calls = calls.clone();
+        let entries = run_benchmark_with(&names, "prompt", &test_context(), 60, |name| {
+            if name == "gemini" {
+                return Err(anyhow!("Unsupported agent: gemini"));
+            }
+            let output = if name == "claude" {
+                review(
+                    ReviewAction::RequestChanges,
+                    vec![comment("src/a.rs", 10, CommentSeverity::Major)],
+                )
+            } else {
+                review(ReviewAction::Approve, vec![])
+            };
+            Ok(Box::new(RecordingAdapter {
+                name: name.to_string(),
+                calls: factory_calls.clone(),
+                output,
+            }) as Box<dyn AgentAdapter>)
+        })
+        .await;
+
+        assert_eq!(*calls.lock().unwrap(), vec!["claude", "codex"]);
+        let adapters: Vec<&str> = entries.iter().map(|e| e.adapter.as_str()).collect();
+        assert_eq!(adapters, vec!["claude", "codex", "gemini"]);
+        assert_eq!(entries[0].result.as_ref().unwrap().comments.len(), 1);
+        assert_eq!(
+            entries[1].result.as_ref().unwrap().action,
+            ReviewAction::Approve
+        );
+        assert_eq!(
+            entries[2].result.as_ref().unwrap_err(),
+            "Unsupported agent: gemini"
+        );
+    }
+
+    #[test]
+    fn test_format_benchmark_table() {
+        let entries = vec![
+            BenchmarkEntry {
+                adapter: "claude".to_string(),
+                elapsed: Duration::from_millis(41_200),
+                result: Ok(review(
+                    ReviewAction::RequestChanges,
+                    vec![
+                        comment("src/a.rs", 10, CommentSeverity::Critical),
+                        comment("src/b.rs", 5, CommentSeverity::Minor),
+                    ],
+                )),
+            },
+            BenchmarkEntry {
+                adapter: "codex".to_string(),
+                elapsed: Duration::from_millis(12_000),
+                result: Ok(review(
+                    ReviewAction::Comment,
+                    vec![comment("src/a.rs", 12, CommentSeverity::Major)],
+                )),
+            },
+            BenchmarkEntry {
+                adapter: "ollama".to_string(),
+                elapsed: Duration::from_millis(300),
+                result: Err("connection refused".to_string()),
+            },
+        ];
+
+        assert_snapshot!(format_benchmark_table(&entries), @r"
+        Adapter    Action          Comments Critical  Major  Minor Suggestion Blocking Agreement     Time
+        claude     RequestChanges         2        1      0      1          0        0       1/2    41.2s
+        codex      Comment                1        0      1      0          0        0       1/1    12.0s
+        ollama     error (0.3s): connection refused
+        ");
+    }
+
+    #[test]
+    fn test_benchmark_adapter_names_defaults_to_reviewer_and_reviewee() {
+        let config = AiConfig {
+            reviewer: "codex".to_string(),
+            reviewee: "claude".to_string(),
+            ..AiConfig::default()
+        };
+        assert_eq!(benchmark_adapter_names(&config), vec!["codex", "claude"]);
+
+        let config = AiConfig {
+            adapters: vec!["ollama".to_string()],
+            ..config
+        };
+        assert_eq!(benchmark_adapter_names(&config), vec!["ollama"]);
+    }
+}
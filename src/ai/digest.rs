@@ -0,0 +1,146 @@
+//! Digest rendering of reviewer output.
+//!
+//! Turns a `ReviewerOutput` into a single prioritized action list (a task-tracker
+//! friendly checklist) instead of scattered inline comments.
+
+use super::adapter::{CommentSeverity, ReviewComment, ReviewerOutput};
+
+/// Sort rank for a severity (lower = more urgent)
+fn severity_rank(severity: CommentSeverity) -> u8 {
+    match severity {
+        CommentSeverity::Critical => 0,
+        CommentSeverity::Major => 1,
+        CommentSeverity::Minor => 2,
+        CommentSeverity::Suggestion => 3,
+    }
+}
+
+/// Order comments by severity, then by impact.
+///
+/// The reviewer is asked to list comments in order of importance, so the original
+/// order is kept as the impact tiebreak within a severity (stable sort).
+fn prioritized_comments(review: &ReviewerOutput) -> Vec<&ReviewComment> {
+    let mut comments: Vec<&ReviewComment> = review.comments.iter().collect();
+    comments.sort_by_key(|c| severity_rank(c.severity));
+    comments
+}
+
+/// Render the review as a markdown digest: summary, blocking issues and a
+/// prioritized checklist with file references.
+pub fn format_digest(review: &ReviewerOutput) -> String {
+    let mut out = String::from("## Review digest\n\n");
+    out.push_str(review.summary.trim());
+    out.push('\n');
+
+    if !review.blocking_issues.is_empty() {
+        out.push_str("\n### Blocking issues\n\n");
+        for issue in &review.blocking_issues {
+            out.push_str(&format!("- {}\n", issue));
+        }
+    }
+
+    out.push_str("\n### Action items\n\n");
+    let comments = prioritized_comments(review);
+    if comments.is_empty() {
+        out.push_str("_No action items._\n");
+    }
+    for (i, comment) in comments.iter().enumerate() {
+        // Collapse multi-line bodies so each item stays on one checklist line
+        let body = comment
+            .body
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let location = if comment.line > 0 {
+            format!("{}:{}", comment.path, comment.line)
+        } else {
+            comment.path.clone()
+        };
+        out.push_str(&format!(
+            "{}. [ ] **{}** `{}`: {}\n",
+            i + 1,
+            comment.severity.label(),
+            location,
+            body
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::adapter::ReviewAction;
+    use insta::assert_snapshot;
+
+    fn comment(path: &str, line: u32, body: &str, severity: CommentSeverity) -> ReviewComment {
+        ReviewComment {
+            path: path.to_string(),
+            line,
+            start_line: None,
+            body: body.to_string(),
+            severity,
+        }
+    }
+
+    #[test]
+    fn test_format_digest_orders_by_severity() {
+        let review = ReviewerOutput {
+            action: ReviewAction::RequestChanges,
+            summary: "Error handling needs work.".to_string(),
+            comments: vec![
+                comment(
+                    "src/lib.rs",
+                    5,
+                    "Rename for clarity",
+                    CommentSeverity::Suggestion,
+                ),
+                comment(
+                    "src/main.rs",
+                    10,
+                    "Handle the\nerror",
+                    CommentSeverity::Major,
+                ),
+                comment("src/db.rs", 42, "SQL injection", CommentSeverity::Critical),
+                comment("src/main.rs", 20, "Missing timeout", CommentSeverity::Major),
+                comment("Cargo.toml", 0, "Pin the version", CommentSeverity::Minor),
+            ],
+            blocking_issues: vec!["SQL injection in query builder".to_string()],
+            reasoning: None,
+        };
+
+        assert_snapshot!(format_digest(&review), @r"
+        ## Review digest
+
+        Error handling needs work.
+
+        ### Blocking issues
+
+        - SQL injection in query builder
+
+        ### Action items
+
+        1. [ ] **critical** `src/db.rs:42`: SQL injection
+        2. [ ] **major** `src/main.rs:10`: Handle the error
+        3. [ ] **major** `src/main.rs:20`: Missing timeout
+        4. [ ] **minor** `Cargo.toml`: Pin the version
+        5. [ ] **suggestion** `src/lib.rs:5`: Rename for clarity
+        ");
+    }
+
+    #[test]
+    fn test_format_digest_without_comments() {
+        let review = ReviewerOutput {
+            action: ReviewAction::Approve,
+            summary: "LGTM".to_string(),
+            comments: vec![],
+            blocking_issues: vec![],
+            reasoning: None,
+        };
+
+        let digest = format_digest(&review);
+        assert!(!digest.contains("Blocking issues"));
+        assert!(digest.ends_with("_No action items._\n"));
+    }
+}
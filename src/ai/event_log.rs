@@ -0,0 +1,138 @@
+//! Optional JSONL log of every `RallyEvent`, for analysing rallies after the fact.
+//!
+//! When `event_log` is set, the orchestrator routes all events (including the
+//! agents' streaming events) through a forwarder that appends each one to the
+//! file as a JSON line before passing it on to the TUI.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::orchestrator::RallyEvent;
+
+/// One line of the event log
+#[derive(Serialize)]
+struct EventLogLine<'a> {
+    timestamp: &'a str,
+    repo: &'a str,
+    pr_number: u32,
+    event: &'a RallyEvent,
+}
+
+fn format_event_line(
+    timestamp: &str,
+    repo: &str,
+    pr_number: u32,
+    event: &RallyEvent,
+) -> serde_json::Result<String> {
+    let mut line = serde_json::to_string(&EventLogLine {
+        timestamp,
+        repo,
+        pr_number,
+        event,
+    })?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Spawn a forwarder that appends every event sent to the returned sender to
+/// `path`, then passes it on to `downstream`
+pub fn spawn_event_logger(
+    path: PathBuf,
+    repo: &str,
+    pr_number: u32,
+    downstream: mpsc::Sender<RallyEvent>,
+) -> mpsc::Sender<RallyEvent> {
+    let (tx, mut rx) = mpsc::channel(100);
+    let repo = repo.to_string();
+
+    tokio::spawn(async move {
+        let mut file = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                warn!("Failed to open event log {}: {}", path.display(), e);
+                let _ = downstream
+                    .send(RallyEvent::Log(format!(
+                        "Warning: Failed to open event log {}: {}",
+                        path.display(),
+                        e
+                    )))
+                    .await;
+                None
+            }
+        };
+
+        while let Some(event) = rx.recv().await {
+            if let Some(ref mut f) = file {
+                let timestamp = chrono::Utc::now().to_rfc3339();
+                match format_event_line(&timestamp, &repo, pr_number, &event) {
+                    Ok(line) => {
+                        // Lines are small, so a blocking append is fine here
+                        if let Err(e) = f.write_all(line.as_bytes()) {
+                            warn!("Failed to write event log, disabling it: {}", e);
+                            file = None;
+                        }
+                    }
+                    Err(e) => warn!("Failed to serialize rally event: {}", e),
+                }
+            }
+            if downstream.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::orchestrator::RallyState;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_format_event_line() {
+        let line = |event: RallyEvent| {
+            format_event_line("2026-01-01T00:00:00+00:00", "owner/repo", 7, &event).unwrap()
+        };
+
+        assert_snapshot!(line(RallyEvent::IterationStarted(2)), @r#"{"timestamp":"2026-01-01T00:00:00+00:00","repo":"owner/repo","pr_number":7,"event":{"type":"IterationStarted","data":2}}"#);
+        assert_snapshot!(line(RallyEvent::StateChanged(RallyState::Completed)), @r#"{"timestamp":"2026-01-01T00:00:00+00:00","repo":"owner/repo","pr_number":7,"event":{"type":"StateChanged","data":"Completed"}}"#);
+        assert!(line(RallyEvent::Approved("ok".to_string())).ends_with("}}\n"));
+    }
+
+    #[tokio::test]
+    async fn test_event_logger_appends_and_forwards() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let (downstream, mut received) = mpsc::channel(10);
+
+        let sender = spawn_event_logger(path.clone(), "owner/repo", 7, downstream);
+        sender.send(RallyEvent::IterationStarted(1)).await.unwrap();
+        sender
+            .send(RallyEvent::Log("hello".to_string()))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            received.recv().await,
+            Some(RallyEvent::IterationStarted(1))
+        ));
+        assert!(matches!(received.recv().await, Some(RallyEvent::Log(_))));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let types: Vec<String> = content
+            .lines()
+            .map(|l| {
+                let value: serde_json::Value = serde_json::from_str(l).unwrap();
+                value["event"]["type"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(types, vec!["IterationStarted", "Log"]);
+    }
+}
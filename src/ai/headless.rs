@@ -0,0 +1,296 @@
+//! Headless mode: run the rally without the TUI, for CI pipelines.
+//!
+//! Progress is printed to stderr as plain lines, the final reviewer output is
+//! printed to stdout as JSON, and the exit code reflects the reviewer's verdict.
+//! Nobody is around to answer the agents, so clarifications are skipped,
+//! permissions are denied, posts that need confirmation (`auto_post = false`)
+//! are not made and failed turns are not retried.
+//!
+//! Several PRs can be reviewed side by side; their rallies share a limit of
+//! `max_concurrent_rallies` agent turns at a time.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::sync::{mpsc, Semaphore};
+
+use super::adapter::{Context, ReviewAction, ReviewerOutput};
+use super::orchestrator::{Orchestrator, OrchestratorCommand, RallyEvent, RallyResult, RallyState};
+use crate::config::AiConfig;
+
+/// Exit code when the reviewer approved (or there was nothing to review)
+pub const EXIT_APPROVED: i32 = 0;
+/// Exit code when the rally failed or was aborted
+pub const EXIT_FAILED: i32 = 1;
+/// Exit code when the reviewer's last verdict was not an approval
+pub const EXIT_NOT_APPROVED: i32 = 2;
+
+/// Last review of one PR in a queue, as printed to stdout
+#[derive(Debug, Serialize)]
+struct QueuedReview {
+    pr: u32,
+    review: Option<ReviewerOutput>,
+}
+
+/// Run the rally on `context` and return the process exit code
+pub async fn run_headless(repo: &str, config: AiConfig, context: Context) -> Result<i32> {
+    let (result, last_review) = run_rally(repo, config, context, None, "").await?;
+    if let Some(ref review) = last_review {
+        println!("{}", serde_json::to_string_pretty(review)?);
+    }
+    Ok(exit_code(&result, last_review.as_ref()))
+}
+
+/// Run rallies on several PRs at once and return the worst exit code.
+///
+/// Progress lines are prefixed with the PR number, and stdout gets one JSON array
+/// with each PR's last review.
+pub async fn run_headless_queue(
+    repo: &str,
+    config: AiConfig,
+    contexts: Vec<Context>,
+) -> Result<i32> {
+    let slots = Arc::new(Semaphore::new(config.max_concurrent_rallies.max(1)));
+    let rallies: Vec<_> = contexts
+        .into_iter()
+        .map(|context| {
+            let pr_number = context.pr_number;
+            let (repo, config, slots) = (repo.to_string(), config.clone(), slots.clone());
+            let prefix = format!("[#{}] ", pr_number);
+            let rally = tokio::spawn(async move {
+                run_rally(&repo, config, context, Some(slots), &prefix).await
+            });
+            (pr_number, rally)
+        })
+        .collect();
+
+    let mut reviews = Vec::new();
+    let mut code = EXIT_APPROVED;
+    for (pr_number, rally) in rallies {
+        let (pr_code, review) = match rally.await? {
+            Ok((result, last_review)) => (exit_code(&result, last_review.as_ref()), last_review),
+            Err(e) => {
+                eprintln!("[#{}] [error] {:#}", pr_number, e);
+                (EXIT_FAILED, None)
+            }
+        };
+        code = worst_exit_code(code, pr_code);
+        reviews.push(QueuedReview {
+            pr: pr_number,
+            review,
+        });
+    }
+    println!("{}", serde_json::to_string_pretty(&reviews)?);
+    Ok(code)
+}
+
+/// Run one rally unattended, printing its progress to stderr with `prefix`.
+/// Returns the result and the last review.
+async fn run_rally(
+    repo: &str,
+    config: AiConfig,
+    context: Context,
+    agent_slots: Option<Arc<Semaphore>>,
+    prefix: &str,
+) -> Result<(RallyResult, Option<ReviewerOutput>)> {
+    let (event_tx, mut event_rx) = mpsc::channel(100);
+    let (cmd_tx, cmd_rx) = mpsc::channel(10);
+
+    let mut orchestrator =
+        Orchestrator::new(repo, context.pr_number, config, event_tx, Some(cmd_rx))?;
+    orchestrator.set_context(context);
+    if let Some(slots) = agent_slots {
+        orchestrator.set_agent_slots(slots);
+    }
+    let rally = tokio::spawn(async move { orchestrator.run().await });
+
+    // The channel closes once the orchestrator (and its agents) are dropped
+    let mut last_review = None;
+    while let Some(event) = event_rx.recv().await {
+        if let Some(line) = describe_event(&event) {
+            eprintln!("{}{}", prefix, line);
+        }
+        if let Some(command) = unattended_response(&event) {
+            let _ = cmd_tx.send(command).await;
+        }
+        if let RallyEvent::ReviewCompleted(review) = event {
+            last_review = Some(review);
+        }
+    }
+
+    Ok((rally.await??, last_review))
+}
+
+/// The more severe of two exit codes: failed, then not approved, then approved
+fn worst_exit_code(a: i32, b: i32) -> i32 {
+    let severity = |code| match code {
+        EXIT_APPROVED => 0,
+        EXIT_NOT_APPROVED => 1,
+        _ => 2,
+    };
+    if severity(b) > severity(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// Answer to a prompt that would wait for the user in the TUI
+fn unattended_response(event: &RallyEvent) -> Option<OrchestratorCommand> {
+    match event {
+        RallyEvent::ClarificationNeeded(_) => Some(OrchestratorCommand::SkipClarification),
+        RallyEvent::PermissionNeeded(_, _) => Some(OrchestratorCommand::PermissionResponse(false)),
+        RallyEvent::ReviewPostConfirmNeeded(_) | RallyEvent::FixPostConfirmNeeded(_) => {
+            Some(OrchestratorCommand::PostConfirmResponse(false))
+        }
+        RallyEvent::StateChanged(RallyState::Error) => Some(OrchestratorCommand::Abort),
+        _ => None,
+    }
+}
+
+/// Progress line for stderr; streaming agent output is left out
+fn describe_event(event: &RallyEvent) -> Option<String> {
+    let line = match event {
+        RallyEvent::StateChanged(state) => format!("[state] {:?}", state),
+        RallyEvent::IterationStarted(iteration) => format!("[iteration] {}", iteration),
+        RallyEvent::ReviewCompleted(review) => format!(
+            "[review] {:?} with {} comment(s): {}",
+            review.action,
+            review.comments.len(),
+            review.summary
+        ),
+        RallyEvent::FixCompleted(fix) => format!(
+            "[fix] {:?}, {} file(s) modified: {}",
+            fix.status,
+            fix.files_modified.len(),
+            fix.summary
+        ),
+        RallyEvent::ClarificationNeeded(question) => {
+            format!("[clarification] {} (skipped: running headless)", question)
+        }
+        RallyEvent::PermissionNeeded(action, reason) => format!(
+            "[permission] {} ({}) (denied: running headless)",
+            action, reason
+        ),
+        RallyEvent::ReviewPostConfirmNeeded(_) | RallyEvent::FixPostConfirmNeeded(_) => {
+            "[post] Not posted: set auto_post = true to post when running headless".to_string()
+        }
+        RallyEvent::Approved(summary) => format!("[approved] {}", summary),
+        RallyEvent::MaxIterationsReached(summary) => {
+            format!("[max iterations] {}", summary)
+        }
+        RallyEvent::MergeOutlook { detail, .. } => format!("[merge] {}", detail),
+        RallyEvent::FileModified(path) => format!("[modified] {}", path.display()),
+        RallyEvent::PushBlocked(target) => format!("[push blocked] {}", target),
+        RallyEvent::Error(message) => format!("[error] {}", message),
+        RallyEvent::Log(message) => format!("[log] {}", message),
+        RallyEvent::ReviewCommentStreamed(_)
+        | RallyEvent::AgentThinking(_)
+        | RallyEvent::AgentToolUse(_, _)
+        | RallyEvent::AgentToolResult(_, _)
+        | RallyEvent::AgentText(_)
+        | RallyEvent::AgentRawResult(_)
+        | RallyEvent::UsageReported { .. } => return None,
+    };
+    Some(line)
+}
+
+/// Exit code for the finished rally: the verdict of the last review when the
+/// rally ended normally
+fn exit_code(result: &RallyResult, last_review: Option<&ReviewerOutput>) -> i32 {
+    match result {
+        RallyResult::Approved { .. } | RallyResult::NoChanges => EXIT_APPROVED,
+        RallyResult::Aborted { .. } | RallyResult::Error { .. } => EXIT_FAILED,
+        RallyResult::ReviewOnly { .. }
+        | RallyResult::MaxIterationsReached { .. }
+        | RallyResult::Stalled { .. } => match last_review.map(|r| r.action) {
+            Some(ReviewAction::Approve) => EXIT_APPROVED,
+            Some(_) => EXIT_NOT_APPROVED,
+            None => EXIT_FAILED,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(action: ReviewAction) -> ReviewerOutput {
+        ReviewerOutput {
+            action,
+            summary: "Looks fine".to_string(),
+            comments: Vec::new(),
+            blocking_issues: Vec::new(),
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_code_follows_verdict() {
+        let approved = RallyResult::Approved {
+            iteration: 1,
+            summary: String::new(),
+        };
+        assert_eq!(exit_code(&approved, None), EXIT_APPROVED);
+        assert_eq!(exit_code(&RallyResult::NoChanges, None), EXIT_APPROVED);
+
+        let review_only = RallyResult::ReviewOnly { iteration: 1 };
+        let changes = review(ReviewAction::RequestChanges);
+        assert_eq!(exit_code(&review_only, Some(&changes)), EXIT_NOT_APPROVED);
+        let approve = review(ReviewAction::Approve);
+        assert_eq!(exit_code(&review_only, Some(&approve)), EXIT_APPROVED);
+
+        let max = RallyResult::MaxIterationsReached { iteration: 3 };
+        let comment = review(ReviewAction::Comment);
+        assert_eq!(exit_code(&max, Some(&comment)), EXIT_NOT_APPROVED);
+
+        let error = RallyResult::Error {
+            iteration: 1,
+            error: "agent crashed".to_string(),
+        };
+        assert_eq!(exit_code(&error, Some(&approve)), EXIT_FAILED);
+    }
+
+    #[test]
+    fn test_worst_exit_code() {
+        assert_eq!(worst_exit_code(EXIT_APPROVED, EXIT_APPROVED), EXIT_APPROVED);
+        assert_eq!(
+            worst_exit_code(EXIT_APPROVED, EXIT_NOT_APPROVED),
+            EXIT_NOT_APPROVED
+        );
+        assert_eq!(worst_exit_code(EXIT_FAILED, EXIT_NOT_APPROVED), EXIT_FAILED);
+        assert_eq!(worst_exit_code(EXIT_NOT_APPROVED, EXIT_FAILED), EXIT_FAILED);
+    }
+
+    #[test]
+    fn test_prompts_are_answered_without_user() {
+        assert!(matches!(
+            unattended_response(&RallyEvent::ClarificationNeeded("Which API?".to_string())),
+            Some(OrchestratorCommand::SkipClarification)
+        ));
+        assert!(matches!(
+            unattended_response(&RallyEvent::PermissionNeeded(
+                "rm -rf target".to_string(),
+                "clean build".to_string()
+            )),
+            Some(OrchestratorCommand::PermissionResponse(false))
+        ));
+        assert!(matches!(
+            unattended_response(&RallyEvent::StateChanged(RallyState::Error)),
+            Some(OrchestratorCommand::Abort)
+        ));
+        assert!(unattended_response(&RallyEvent::Log("hello".to_string())).is_none());
+
+        assert_eq!(
+            describe_event(&RallyEvent::ReviewCompleted(review(
+                ReviewAction::RequestChanges
+            ))),
+            Some("[review] RequestChanges with 0 comment(s): Looks fine".to_string())
+        );
+        assert_eq!(
+            describe_event(&RallyEvent::AgentText("partial".to_string())),
+            None
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Files left out of the diff the reviewer sees (lock files, vendored or
+//! generated code), so they don't use up the review budget.
+//!
+//! `ignore_globs` from the config and the repository's `.hxprignore` use the same
+//! gitignore syntax. A team can commit `.hxprignore` so everyone's reviews skip
+//! the same files.
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use tracing::warn;
+
+/// Name of the repo-local ignore file, read from the working directory
+pub const IGNORE_FILE: &str = ".hxprignore";
+
+/// Compiled `ignore_globs` and `.hxprignore` patterns
+#[derive(Debug)]
+pub struct IgnoreRules(Gitignore);
+
+impl IgnoreRules {
+    /// `globs`, followed by the `.hxprignore` in `ignore_file_dir` when given and present.
+    ///
+    /// Later patterns win, so `.hxprignore` can re-include (`!path`) a file a configured
+    /// glob skips. Invalid patterns are logged and skipped; the rest still apply.
+    pub fn new(globs: &[String], ignore_file_dir: Option<&Path>) -> Self {
+        let mut builder = GitignoreBuilder::new(ignore_file_dir.unwrap_or(Path::new("")));
+        for glob in globs {
+            if let Err(e) = builder.add_line(None, glob.trim().trim_start_matches("./")) {
+                warn!("Invalid ignore_globs pattern '{}': {}", glob, e);
+            }
+        }
+        if let Some(path) = ignore_file_dir
+            .map(|dir| dir.join(IGNORE_FILE))
+            .filter(|path| path.is_file())
+        {
+            if let Some(e) = builder.add(&path) {
+                warn!("Skipped invalid lines in {}: {}", path.display(), e);
+            }
+        }
+        Self(builder.build().unwrap_or_else(|e| {
+            warn!("Failed to build ignore patterns: {}", e);
+            Gitignore::empty()
+        }))
+    }
+
+    /// Whether there are no patterns at all
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether `path` (relative to the repository root) is ignored, either itself or
+    /// through one of its parent directories
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.trim_start_matches("./");
+        !path.is_empty()
+            && !Path::new(path).has_root()
+            && self.0.matched_path_or_any_parents(path, false).is_ignore()
+    }
+}
+
+/// Remove the sections of ignored files from a unified diff.
+///
+/// Returns the remaining diff and the paths that were skipped, in diff order.
+pub fn strip_ignored_files(diff: &str, rules: &IgnoreRules) -> (String, Vec<String>) {
+    if rules.is_empty() {
+        return (diff.to_string(), Vec::new());
+    }
+
+    let mut kept = String::with_capacity(diff.len());
+    let mut skipped = Vec::new();
+    let mut skipping = false;
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map_or(header.trim_end(), |(_, path)| path);
+            skipping = rules.is_ignored(path);
+            if skipping {
+                skipped.push(path.to_string());
+            }
+        }
+        if !skipping {
+            kept.push_str(line);
+        }
+    }
+    (kept, skipped)
+}
+
+/// Whether one of the git remotes of `dir` points at `repo` (`owner/name`).
+///
+/// In PR mode the working directory may be a checkout of another repository, whose
+/// `.hxprignore` must not apply. Runs `git`, so keep it off the UI thread.
+pub fn is_checkout_of(dir: &Path, repo: &str) -> bool {
+    let Ok(output) = std::process::Command::new("git")
+        .args(["config", "--get-regexp", r"^remote\..*\.url$"])
+        .current_dir(dir)
+        .output()
+    else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .any(|url| remote_url_is_repo(url, repo))
+}
+
+/// Whether a remote URL (`git@github.com:owner/name.git`, `https://github.com/owner/name`, ...)
+/// points at `repo`
+fn remote_url_is_repo(url: &str, repo: &str) -> bool {
+    let path = url.trim_end_matches('/').trim_end_matches(".git");
+    let Some(split) = path.len().checked_sub(repo.len()) else {
+        return false;
+    };
+    path.get(split..)
+        .is_some_and(|name| name.eq_ignore_ascii_case(repo))
+        && matches!(path[..split].chars().last(), Some('/' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob_match(pattern: &str, path: &str) -> bool {
+        IgnoreRules::new(&[pattern.to_string()], None).is_ignored(path)
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("**/*.lock", "Cargo.lock"));
+        assert!(glob_match("**/*.lock", "web/yarn.lock"));
+        assert!(glob_match("*.lock", "web/yarn.lock"));
+        assert!(glob_match("dist/**", "dist/js/app.min.js"));
+        assert!(glob_match("src/*.rs", "src/main.rs"));
+        assert!(glob_match("src/?.rs", "src/a.rs"));
+        assert!(glob_match("**/generated/**", "src/generated/api.rs"));
+        assert!(glob_match("./vendor/", "vendor/lib/a.c"));
+
+        assert!(!glob_match("src/*.rs", "src/ai/mod.rs"));
+        assert!(!glob_match("dist/**", "src/dist.rs"));
+        assert!(!glob_match("*.lock", "Cargo.lock.bak"));
+    }
+
+    #[test]
+    fn test_strip_ignored_files() {
+        let diff = "diff --git a/Cargo.lock b/Cargo.lock\n\
+                    --- a/Cargo.lock\n\
+                    +++ b/Cargo.lock\n\
+                    @@ -1 +1 @@\n\
+                    -a\n\
+                    +b\n\
+                    diff --git a/src/main.rs b/src/main.rs\n\
+                    --- a/src/main.rs\n\
+                    +++ b/src/main.rs\n\
+                    @@ -1 +1 @@\n\
+                    -c\n\
+                    +d\n";
+        let rules = IgnoreRules::new(&["*.lock".to_string()], None);
+        let (kept, skipped) = strip_ignored_files(diff, &rules);
+        assert_eq!(skipped, vec!["Cargo.lock"]);
+        assert_eq!(
+            kept,
+            "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-c\n+d\n"
+        );
+
+        let (unchanged, skipped) = strip_ignored_files(diff, &IgnoreRules::new(&[], None));
+        assert_eq!(unchanged, diff);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_ignore_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(IgnoreRules::new(&[], Some(dir.path())).is_empty());
+
+        std::fs::write(
+            dir.path().join(IGNORE_FILE),
+            "# generated\n*.pb.go\n/gen/\n!Cargo.lock\n",
+        )
+        .unwrap();
+        let rules = IgnoreRules::new(&["*.lock".to_string()], Some(dir.path()));
+        assert!(rules.is_ignored("api/user.pb.go"));
+        assert!(rules.is_ignored("gen/client.rs"));
+        assert!(rules.is_ignored("web/yarn.lock"));
+        assert!(!rules.is_ignored("src/gen/client.rs"));
+        assert!(!rules.is_ignored("src/main.rs"));
+        // .hxprignore comes after the configured globs, so it can re-include a file
+        assert!(!rules.is_ignored("Cargo.lock"));
+
+        let diff = "diff --git a/api/user.pb.go b/api/user.pb.go\n\
+                    +x\n\
+                    diff --git a/src/main.rs b/src/main.rs\n\
+                    +y\n";
+        let (kept, skipped) = strip_ignored_files(diff, &rules);
+        assert_eq!(skipped, vec!["api/user.pb.go"]);
+        assert_eq!(kept, "diff --git a/src/main.rs b/src/main.rs\n+y\n");
+    }
+
+    #[test]
+    fn test_remote_url_is_repo() {
+        assert!(remote_url_is_repo(
+            "https://github.com/owner/repo.git",
+            "owner/repo"
+        ));
+        assert!(remote_url_is_repo(
+            "git@github.com:owner/repo",
+            "owner/repo"
+        ));
+        assert!(remote_url_is_repo(
+            "ssh://git@ghe.example.com/Owner/Repo/",
+            "owner/repo"
+        ));
+        assert!(!remote_url_is_repo(
+            "https://github.com/fork-owner/repo.git",
+            "owner/repo"
+        ));
+        assert!(!remote_url_is_repo("owner/repo", "owner/repo"));
+    }
+}
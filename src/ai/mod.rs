@@ -1,8 +1,14 @@
 pub mod adapter;
 pub mod adapters;
+pub mod benchmark;
+pub mod digest;
+pub mod event_log;
+pub mod headless;
+pub mod ignore;
 pub mod orchestrator;
 pub mod prompt_loader;
 pub mod prompts;
+pub mod push_guard;
 pub mod session;
 
 pub use adapter::{Context, ReviewAction, RevieweeOutput, RevieweeStatus, ReviewerOutput};
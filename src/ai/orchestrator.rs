@@ -1,25 +1,41 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
 use tokio::time::timeout;
-use tracing::warn;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
 use crate::config::AiConfig;
-use crate::github;
+use crate::diff::DiffSide;
 use crate::github::comment::{fetch_discussion_comments, fetch_review_comments};
+use crate::github::{self, BranchProtection, DraftReviewComment, ReviewThread};
+use crate::loader::{checkout_status, CheckoutStatus};
 
 use super::adapter::{
-    AgentAdapter, Context, ExternalComment, ReviewAction, RevieweeOutput, RevieweeStatus,
-    ReviewerOutput,
+    AgentAdapter, CommentSeverity, Context, ExternalComment, LinkedIssue, PermissionRequest,
+    ReviewAction, ReviewComment, RevieweeOutput, RevieweeStatus, ReviewerOutput,
 };
-use super::adapters::create_adapter;
+use super::adapters::{
+    create_adapter, create_first_available_adapter, CodexError, OutputParseError, OutputSchemas,
+    SchemaValidationError,
+};
+use super::digest::format_digest;
+use super::event_log::spawn_event_logger;
+use super::ignore::{is_checkout_of, strip_ignored_files, IgnoreRules};
 use super::prompt_loader::PromptLoader;
 use super::prompts::{
-    build_clarification_prompt, build_clarification_skipped_prompt, build_permission_denied_prompt,
-    build_permission_granted_prompt,
+    build_clarification_prompt, build_clarification_skipped_prompt, build_focused_comment_prompt,
+    build_output_correction_prompt, build_permission_denied_prompt,
+    build_permission_granted_prompt, build_post_fix_check_failed_prompt,
+};
+use super::push_guard::{self, PushGuard};
+use super::session::{
+    read_session, write_history_entry, write_session, HistoryEntryType, RallySession,
 };
-use super::session::{write_history_entry, write_session, HistoryEntryType, RallySession};
 
 /// Bot suffixes to identify bot users
 const BOT_SUFFIXES: &[&str] = &["[bot]"];
@@ -27,6 +43,161 @@ const BOT_SUFFIXES: &[&str] = &["[bot]"];
 const BOT_EXACT_MATCHES: &[&str] = &["github-actions", "dependabot"];
 /// Maximum number of external comments to include in context
 const MAX_EXTERNAL_COMMENTS: usize = 20;
+/// Maximum number of issues referenced from the PR body that are fetched for the reviewer
+const MAX_LINKED_ISSUES: usize = 5;
+/// Maximum distance (in lines) an out-of-range comment is moved to land on the diff
+const MAX_COMMENT_RELOCATION: u32 = 3;
+
+/// Where a reviewer comment is placed when posting to the PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentPlacement {
+    /// Inline at `position`; `line` differs from the requested line when relocated.
+    /// `side` is `Left` when `line` is a removed line of the old file.
+    Inline {
+        line: u32,
+        side: DiffSide,
+        position: u32,
+    },
+    /// Not attached to a diff line; folded into the review summary instead
+    General,
+    /// Not about a particular line (empty path or line 0); posted as a PR conversation comment
+    Conversation,
+    /// Dropped because the file is not part of the diff
+    Skip,
+}
+
+/// Decide where a reviewer comment goes.
+///
+/// GitHub rejects inline comments on lines outside the diff (422), and models
+/// sometimes emit lines past the end of a hunk. `line: 0` marks feedback that is not
+/// about a specific line and goes to the PR conversation. A line that is not on the
+/// new side of the diff but is a removed line of the old file is placed on the LEFT
+/// side. Other out-of-range lines move to the nearest diff line if it is close enough,
+/// otherwise they are demoted to a general comment.
+fn place_review_comment(patch: Option<&str>, line: u32) -> CommentPlacement {
+    if line == 0 {
+        return CommentPlacement::Conversation;
+    }
+    let Some(patch) = patch else {
+        return CommentPlacement::Skip;
+    };
+    if let Some(position) = crate::diff::line_number_to_position(patch, line) {
+        return CommentPlacement::Inline {
+            line,
+            side: DiffSide::Right,
+            position,
+        };
+    }
+    if let Some(position) = crate::diff::removed_line_to_position(patch, line) {
+        return CommentPlacement::Inline {
+            line,
+            side: DiffSide::Left,
+            position,
+        };
+    }
+    match crate::diff::nearest_commentable_line(patch, line) {
+        Some((nearest, position)) if nearest.abs_diff(line) <= MAX_COMMENT_RELOCATION => {
+            CommentPlacement::Inline {
+                line: nearest,
+                side: DiffSide::Right,
+                position,
+            }
+        }
+        _ => CommentPlacement::General,
+    }
+}
+
+/// Whether new lines `start_line..=line` can carry one multi-line comment: GitHub
+/// requires the whole range to be inside one hunk, and adjacent hunks are merged, so
+/// every line of the range has to be on the diff
+fn is_commentable_range(patch: &str, start_line: u32, line: u32) -> bool {
+    start_line < line
+        && (start_line..=line).all(|l| crate::diff::line_number_to_position(patch, l).is_some())
+}
+
+/// Rally log message listing the comments that could not be posted inline
+fn format_unplaced_comments(placements: &[(&ReviewComment, CommentPlacement)]) -> Option<String> {
+    let items: Vec<String> = placements
+        .iter()
+        .filter_map(|(comment, placement)| {
+            let reason = match placement {
+                CommentPlacement::Inline { .. } | CommentPlacement::Conversation => return None,
+                CommentPlacement::General => "not on the diff, added to the summary",
+                CommentPlacement::Skip => "file is not part of the diff, skipped",
+            };
+            Some(format!("- {}:{} ({})", comment.path, comment.line, reason))
+        })
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Warning: {} comment(s) could not be placed on the diff:\n{}",
+        items.len(),
+        items.join("\n")
+    ))
+}
+
+/// Format comments that are not posted inline as a summary section under `heading`
+fn format_general_comments(heading: &str, comments: &[&ReviewComment]) -> String {
+    let items = comments
+        .iter()
+        .map(|c| {
+            let location = if c.line == 0 {
+                c.path.clone()
+            } else {
+                format!("{}:{}", c.path, c.line)
+            };
+            format!("- `{}`: {}", location, c.body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("**{}:**\n{}", heading, items)
+}
+
+/// The ways to post a review, in order: `(action, with inline comments)`.
+///
+/// GitHub rejects the whole review when one inline comment is invalid (422), so the
+/// inline comments are then moved into the summary. An approval that fails either
+/// way (e.g., on your own PR) falls back to a comment.
+fn review_post_attempts(
+    action: crate::app::ReviewAction,
+    has_inline: bool,
+) -> Vec<(crate::app::ReviewAction, bool)> {
+    let mut actions = vec![action];
+    if action == crate::app::ReviewAction::Approve {
+        actions.push(crate::app::ReviewAction::Comment);
+    }
+    actions
+        .into_iter()
+        .flat_map(|action| {
+            let inline = has_inline.then_some((action, true));
+            inline.into_iter().chain([(action, false)])
+        })
+        .collect()
+}
+
+/// Body of a PR conversation comment for feedback that is not tied to a line
+fn format_conversation_comment(comment: &ReviewComment) -> String {
+    let header = comment.severity.posted_comment_header();
+    if comment.path.trim().is_empty() {
+        format!("{}\n\n{}", header, comment.body)
+    } else {
+        format!("{}\n\n`{}`: {}", header, comment.path, comment.body)
+    }
+}
+
+/// Wrap the reviewer's reasoning in a collapsed `<details>` block.
+///
+/// Returns `None` when the reviewer gave no reasoning.
+fn format_reasoning_details(reasoning: Option<&str>) -> Option<String> {
+    let reasoning = reasoning.map(str::trim).filter(|r| !r.is_empty())?;
+    // Blank lines around the content let GitHub render markdown inside the block
+    Some(format!(
+        "<details>\n<summary>Reviewer reasoning</summary>\n\n{}\n\n</details>",
+        reasoning
+    ))
+}
 
 /// Rally state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,7 +215,6 @@ pub enum RallyState {
 
 impl RallyState {
     /// Rally が実行中（完了・エラー・中断以外）かどうか
-    #[allow(dead_code)]
     pub fn is_active(&self) -> bool {
         !matches!(
             self,
@@ -52,6 +222,14 @@ impl RallyState {
         )
     }
 
+    /// レビュアー・レビュイーのエージェントが実行中のターンかどうか
+    pub fn is_agent_turn(&self) -> bool {
+        matches!(
+            self,
+            RallyState::ReviewerReviewing | RallyState::RevieweeFix
+        )
+    }
+
     /// Rally が完了、中断、またはエラーで終了したかどうか
     #[allow(dead_code)]
     pub fn is_finished(&self) -> bool {
@@ -64,17 +242,23 @@ impl RallyState {
 
 /// Event emitted during rally for TUI updates
 ///
-/// Variants are used by TUI handlers (ui/ai_rally.rs) via mpsc channel
-#[derive(Debug, Clone)]
+/// Variants are used by TUI handlers (ui/ai_rally.rs) via mpsc channel.
+/// Serialized as `{"type": ..., "data": ...}` for the JSONL event log
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
 #[allow(dead_code)]
 pub enum RallyEvent {
     StateChanged(RallyState),
     IterationStarted(u32),
     ReviewCompleted(ReviewerOutput),
+    /// A reviewer comment parsed from output still being streamed (before `ReviewCompleted`)
+    ReviewCommentStreamed(ReviewComment),
     FixCompleted(RevieweeOutput),
     ClarificationNeeded(String),
     PermissionNeeded(String, String), // action, reason
     Approved(String),                 // summary
+    /// The rally hit `max_iterations` without an approval (summary)
+    MaxIterationsReached(String),
     ReviewPostConfirmNeeded(ReviewPostInfo),
     FixPostConfirmNeeded(FixPostInfo),
     Error(String),
@@ -84,6 +268,23 @@ pub enum RallyEvent {
     AgentToolUse(String, String),    // tool_name, input_summary
     AgentToolResult(String, String), // tool_name, result_summary
     AgentText(String),               // text output
+    /// The final `result` JSON an agent returned, as-is (shown in the debug pane)
+    AgentRawResult(serde_json::Value),
+    /// Whether the reviewer's approval would let the PR merge under branch protection
+    MergeOutlook {
+        unblocks: bool,
+        detail: String,
+    },
+    /// A file the agent wrote, as soon as it reports the write (Codex `file_change` items)
+    FileModified(PathBuf),
+    /// The reviewee tried to `git push` and the pre-push guard rejected it
+    /// ("<remote> <url>")
+    PushBlocked(String),
+    /// Cost and duration of one agent run, accumulated per rally by the UI
+    UsageReported {
+        cost_usd: Option<f64>,
+        duration_ms: Option<u64>,
+    },
 }
 
 /// Result of the rally process
@@ -93,26 +294,533 @@ pub enum RallyEvent {
 #[allow(dead_code)]
 pub enum RallyResult {
     Approved { iteration: u32, summary: String },
+    NoChanges, // empty diff; the agents were never run
+    ReviewOnly { iteration: u32 },
     MaxIterationsReached { iteration: u32 },
+    Stalled { iteration: u32, reason: String },
     Aborted { iteration: u32, reason: String },
     Error { iteration: u32, error: String },
 }
 
 /// Lightweight DTO for review post confirmation (sent via RallyEvent)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReviewPostInfo {
     pub action: String,
     pub summary: String,
     pub comment_count: usize,
+    /// Inline comments that will be posted with the review
+    pub comments: Vec<ReviewComment>,
 }
 
 /// Lightweight DTO for fix post confirmation (sent via RallyEvent)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FixPostInfo {
     pub summary: String,
     pub files_modified: Vec<String>,
 }
 
+/// Tracks consecutive reviewee completions that modified no files.
+///
+/// When the reviewee keeps answering "nothing to fix" while the reviewer keeps
+/// requesting changes, the rally would ping-pong until max_iterations.
+/// A fix counts as a change when the reviewee reported modified files or the
+/// working tree diff (`diff_stats`) shows one, since not every agent reports them.
+#[derive(Debug)]
+struct StallTracker {
+    /// Consecutive empty completions that count as a stall (0 = disabled)
+    limit: u32,
+    consecutive: u32,
+}
+
+impl StallTracker {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            consecutive: 0,
+        }
+    }
+
+    /// Record a completed fix. Returns true once the rally is stalled.
+    fn record_fix(&mut self, fix: &RevieweeOutput) -> bool {
+        if fix.files_modified.is_empty() && fix.diff_stats.is_empty() {
+            self.consecutive += 1;
+        } else {
+            self.consecutive = 0;
+        }
+        self.limit > 0 && self.consecutive >= self.limit
+    }
+}
+
+/// Most characters of check command output sent to an agent; a failing test suite
+/// can print far more than is useful in a prompt
+const CHECK_OUTPUT_LIMIT: usize = 8_000;
+
+/// Run a check command (post-fix lint, verify) in `working_dir` through the shell.
+///
+/// Returns the combined stdout/stderr when the command fails (only its end when
+/// longer than [`CHECK_OUTPUT_LIMIT`]), `None` when it passes. Dropping the future
+/// (e.g. on timeout) kills the command.
+async fn run_check_command(command: &str, working_dir: Option<&str>) -> Result<Option<String>> {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c").arg(command).kill_on_drop(true);
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| anyhow!("Failed to run '{}': {}", command, e))?;
+    if output.status.success() {
+        return Ok(None);
+    }
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(Some(tail_of_output(&text, CHECK_OUTPUT_LIMIT)))
+}
+
+/// The last `limit` characters of `text`; failures are usually reported at the end
+fn tail_of_output(text: &str, limit: usize) -> String {
+    let total = text.chars().count();
+    if total <= limit {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(total - limit).collect();
+    format!("[{} earlier characters omitted]\n{}", total - limit, tail)
+}
+
+/// Run the post-fix check, bounded by `timeout_secs`.
+///
+/// Returns the output when the check failed, `None` when it passed. A timeout is an
+/// error: there is no lint output to send to the reviewee.
+async fn run_post_fix_check(
+    command: &str,
+    working_dir: Option<&str>,
+    timeout_secs: u64,
+) -> Result<Option<String>> {
+    timeout(
+        Duration::from_secs(timeout_secs),
+        run_check_command(command, working_dir),
+    )
+    .await
+    .map_err(|_| anyhow!("Timed out after {} seconds", timeout_secs))?
+}
+
+/// Problems to show the agent when its output was unusable, or `None` when the
+/// failure has nothing to do with the output (process errors, timeouts, ...).
+fn output_correction_errors(err: &anyhow::Error) -> Option<Vec<String>> {
+    if let Some(invalid) = err.downcast_ref::<SchemaValidationError>() {
+        return Some(invalid.errors.clone());
+    }
+    err.downcast_ref::<OutputParseError>()
+        .map(|e| vec![e.to_string()])
+}
+
+/// Turn a Codex sandbox denial into a permission request, so the user can grant
+/// broader permissions instead of the rally failing.
+fn sandbox_denial_as_permission_request(err: &anyhow::Error) -> Option<PermissionRequest> {
+    let Some(CodexError::SandboxDenied { command }) = err.downcast_ref::<CodexError>() else {
+        return None;
+    };
+    Some(PermissionRequest {
+        action: command.clone(),
+        reason: "Blocked by the Codex sandbox; granting re-runs the reviewee with full access"
+            .to_string(),
+    })
+}
+
+/// Log an unusable response before asking the agent for a corrected one
+async fn notify_output_correction(
+    events: &mpsc::Sender<RallyEvent>,
+    role: &str,
+    errors: &[String],
+) {
+    warn!("Unusable {} output: {}", role, errors.join("; "));
+    let _ = events
+        .send(RallyEvent::Log(format!(
+            "{} output could not be parsed ({}); asking for a corrected response",
+            role,
+            errors.join("; ")
+        )))
+        .await;
+}
+
+/// Run the reviewer, re-prompting once with the parse errors and `schema` when its
+/// output is unusable. A second failure is returned as-is.
+async fn run_reviewer_with_correction(
+    reviewer: &mut dyn AgentAdapter,
+    prompt: &str,
+    context: &Context,
+    schema: &str,
+    events: &mpsc::Sender<RallyEvent>,
+) -> Result<ReviewerOutput> {
+    let err = match reviewer.run_reviewer(prompt, context).await {
+        Ok(output) => return Ok(output),
+        Err(e) => e,
+    };
+    let Some(errors) = output_correction_errors(&err) else {
+        return Err(err);
+    };
+
+    notify_output_correction(events, "Reviewer", &errors).await;
+    let prompt = build_output_correction_prompt(&errors, schema);
+    reviewer.continue_reviewer(&prompt).await
+}
+
+/// Reviewee counterpart of [`run_reviewer_with_correction`]
+async fn run_reviewee_with_correction(
+    reviewee: &mut dyn AgentAdapter,
+    prompt: &str,
+    context: &Context,
+    schema: &str,
+    events: &mpsc::Sender<RallyEvent>,
+) -> Result<RevieweeOutput> {
+    let err = match reviewee.run_reviewee(prompt, context).await {
+        Ok(output) => return Ok(output),
+        Err(e) => e,
+    };
+    let Some(errors) = output_correction_errors(&err) else {
+        return Err(err);
+    };
+
+    notify_output_correction(events, "Reviewee", &errors).await;
+    let prompt = build_output_correction_prompt(&errors, schema);
+    reviewee.continue_reviewee(&prompt).await
+}
+
+/// Run the verify command after the reviewer approves.
+///
+/// On failure (including timeouts and spawn errors) the approval is withheld: the review
+/// becomes a change request with the failure output as a new blocking issue.
+async fn verify_approval(
+    review: ReviewerOutput,
+    command: &str,
+    working_dir: Option<&str>,
+    timeout_secs: u64,
+) -> ReviewerOutput {
+    let failure = match timeout(
+        Duration::from_secs(timeout_secs),
+        run_check_command(command, working_dir),
+    )
+    .await
+    {
+        Ok(Ok(None)) => return review,
+        Ok(Ok(Some(output))) => output,
+        Ok(Err(e)) => format!("{:#}", e),
+        Err(_) => format!("Timed out after {} seconds", timeout_secs),
+    };
+
+    let mut review = review;
+    review.action = ReviewAction::RequestChanges;
+    review.blocking_issues.push(format!(
+        "Verification command `{}` failed:\n```\n{}\n```",
+        command,
+        failure.trim_end()
+    ));
+    review
+}
+
+/// Withhold an approval whose comments meet `blocking_severity_threshold`.
+///
+/// An approve with any comment at or above `threshold`, or any blocking issue,
+/// becomes a request for changes; every other review keeps the reviewer's action.
+fn apply_severity_threshold(review: ReviewerOutput, threshold: CommentSeverity) -> ReviewerOutput {
+    let blocking = !review.blocking_issues.is_empty()
+        || review
+            .comments
+            .iter()
+            .any(|c| c.severity.is_at_least(threshold));
+    if review.action == ReviewAction::Approve && blocking {
+        ReviewerOutput {
+            action: ReviewAction::RequestChanges,
+            ..review
+        }
+    } else {
+        review
+    }
+}
+
+/// Reason an approval must not be auto-merged, or `None` when it is a clean approval.
+///
+/// Only an approve that was submitted to GitHub as an approval, with no blocking
+/// issues or critical/major comments, reviewing exactly the PR head (no local
+/// reviewee changes), qualifies. `posted_action` is the action GitHub accepted,
+/// `None` when nothing was submitted (declined, dry-run, pending review or failure).
+fn auto_merge_blocker(
+    review: &ReviewerOutput,
+    posted_action: Option<ReviewAction>,
+    local_mode: bool,
+    reviewee_modified_files: bool,
+) -> Option<&'static str> {
+    if local_mode {
+        return Some("local mode has no PR to merge");
+    }
+    if review.action != ReviewAction::Approve {
+        return Some("review is not an approval");
+    }
+    if posted_action != Some(ReviewAction::Approve) {
+        return Some("the approval was not submitted to the PR");
+    }
+    if !review.blocking_issues.is_empty() {
+        return Some("approval still lists blocking issues");
+    }
+    if review.comments.iter().any(|c| {
+        matches!(
+            c.severity,
+            CommentSeverity::Critical | CommentSeverity::Major
+        )
+    }) {
+        return Some("approval has critical/major comments");
+    }
+    if reviewee_modified_files {
+        return Some("reviewee changed files locally, so the approved code is not the PR head");
+    }
+    None
+}
+
+/// Whether approving would let the PR merge, and why.
+///
+/// `protection` is `None` when the base branch protection could not be read (it needs
+/// admin access); an unprotected branch is the default (nothing required). `approvals`
+/// counts the approvals already on the PR, before the reviewer's; `not_counted` says
+/// why the reviewer's approval would not add to them, if it wouldn't.
+fn describe_merge_outlook(
+    mergeable_state: &str,
+    base_branch: &str,
+    protection: Option<&BranchProtection>,
+    approvals: u32,
+    not_counted: Option<&str>,
+    unpassed_checks: &[String],
+) -> (bool, String) {
+    match mergeable_state {
+        "clean" | "unstable" | "has_hooks" => {
+            return (
+                true,
+                "Already mergeable; branch protection does not need this approval".to_string(),
+            )
+        }
+        "dirty" => {
+            return (
+                false,
+                format!("Merge still blocked: the PR conflicts with {}", base_branch),
+            )
+        }
+        "behind" => {
+            return (
+                false,
+                format!(
+                    "Merge still blocked: the branch is behind {} and must be updated",
+                    base_branch
+                ),
+            )
+        }
+        "draft" => return (false, "Merge still blocked: the PR is a draft".to_string()),
+        _ => {}
+    }
+
+    let Some(protection) = protection else {
+        return (
+            false,
+            format!(
+                "Merge blocked (mergeable_state: {}); the protection of {} is not readable \
+                 without admin access, so it is unknown whether this approval is enough",
+                mergeable_state, base_branch
+            ),
+        );
+    };
+    let with_this_one = approvals + u32::from(not_counted.is_none());
+    let mut reasons = Vec::new();
+    if with_this_one < protection.required_approvals {
+        reasons.push(match not_counted {
+            None => format!(
+                "{} approvals required ({} with this one)",
+                protection.required_approvals, with_this_one
+            ),
+            Some(why) => format!(
+                "{} approvals required ({} so far, and this one does not count: {})",
+                protection.required_approvals, approvals, why
+            ),
+        });
+    }
+    if !unpassed_checks.is_empty() {
+        reasons.push(format!(
+            "required checks not passing: {}",
+            unpassed_checks.join(", ")
+        ));
+    }
+    if !reasons.is_empty() {
+        return (
+            false,
+            format!("Merge still blocked: {}", reasons.join("; ")),
+        );
+    }
+    if not_counted.is_none() && approvals < protection.required_approvals {
+        return (
+            true,
+            format!(
+                "Approving should unblock the merge ({}/{} approvals, required checks passing)",
+                with_this_one, protection.required_approvals
+            ),
+        );
+    }
+    (
+        false,
+        format!(
+            "Merge blocked by something other than approvals or required checks \
+             (mergeable_state: {}), e.g. rulesets or unresolved conversations",
+            mergeable_state
+        ),
+    )
+}
+
+/// Log message describing the review that `dry_run` withheld from GitHub
+fn format_dry_run_review(
+    action: crate::app::ReviewAction,
+    body: &str,
+    comments: &[DraftReviewComment],
+) -> String {
+    let mut message = format!(
+        "[dry-run] Would post review ({:?}) with {} inline comment(s):\n{}",
+        action,
+        comments.len(),
+        body
+    );
+    for comment in comments {
+        let location = match comment.range {
+            Some((start, end)) => format!("lines {}-{}", start, end),
+            None => format!("position {}", comment.position),
+        };
+        message.push_str(&format!(
+            "\n\n--- {} ({}) ---\n{}",
+            comment.path, location, comment.body
+        ));
+    }
+    message
+}
+
+/// First 7 characters of a commit sha, for log messages
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// Reviewee's note posted under a reviewer comment it addressed
+fn format_thread_reply(path: &str, summary: &str) -> String {
+    format!(
+        "[AI Rally - Reviewee]\n\nUpdated `{}` in response to this comment.\n\n{}",
+        path, summary
+    )
+}
+
+/// Unresolved threads started by the reviewer on one of `files_modified`
+fn addressed_threads<'a>(
+    threads: &'a [ReviewThread],
+    files_modified: &[String],
+) -> Vec<&'a ReviewThread> {
+    threads
+        .iter()
+        .filter(|thread| {
+            !thread.is_resolved
+                && CommentSeverity::from_posted_comment(&thread.body).is_some()
+                && files_modified
+                    .iter()
+                    .any(|f| f.trim_start_matches("./") == thread.path)
+        })
+        .collect()
+}
+
+/// Addressed threads that get the fix note as a reply: those whose commented lines the
+/// fix changed. Empty (the note is posted top-level instead) when replies are disabled
+/// or the fix touched none of the commented lines.
+fn reply_targets<'a>(
+    reply_to_addressed_comments: bool,
+    addressed: &[&'a ReviewThread],
+    changed_lines: &HashMap<String, Vec<(u32, u32)>>,
+) -> Vec<&'a ReviewThread> {
+    if !reply_to_addressed_comments {
+        return Vec::new();
+    }
+    addressed
+        .iter()
+        .copied()
+        .filter(|thread| {
+            let Some(line) = thread.line else {
+                return false;
+            };
+            let start = thread.start_line.unwrap_or(line).min(line);
+            changed_lines
+                .get(&thread.path)
+                .is_some_and(|ranges| ranges.iter().any(|&(from, to)| from <= line && start <= to))
+        })
+        .collect()
+}
+
+/// Extract one file's section (from its `diff --git` header) out of a unified diff
+fn extract_file_diff(diff: &str, path: &str) -> Option<String> {
+    let header_suffix = format!(" b/{}", path);
+    let mut section: Option<Vec<&str>> = None;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            if section.is_some() {
+                break;
+            }
+            if line.ends_with(&header_suffix) {
+                section = Some(vec![line]);
+            }
+        } else if let Some(ref mut lines) = section {
+            lines.push(line);
+        }
+    }
+
+    section.map(|lines| lines.join("\n"))
+}
+
+/// Narrow a rally context to a single file for a scoped re-review.
+///
+/// Prefers the file's section of `current_diff` (which includes local reviewee fixes)
+/// and falls back to the PR patch. Returns `None` when the file has no diff.
+fn scope_context_to_file(
+    context: &Context,
+    path: &str,
+    current_diff: Option<&str>,
+) -> Option<Context> {
+    let pr_patch = context
+        .file_patches
+        .iter()
+        .find(|(name, _)| name == path)
+        .map(|(_, patch)| patch.clone());
+    let diff = current_diff
+        .and_then(|d| extract_file_diff(d, path))
+        .or_else(|| pr_patch.clone())?;
+
+    let mut scoped = context.clone();
+    scoped.diff = diff;
+    scoped.file_patches = pr_patch
+        .map(|patch| vec![(path.to_string(), patch)])
+        .unwrap_or_default();
+    Some(scoped)
+}
+
+/// Review handed to the reviewee when the user picks the comments to address:
+/// only `comments`, and nothing else to fix
+fn targeted_review(comments: Vec<ReviewComment>) -> ReviewerOutput {
+    ReviewerOutput {
+        action: ReviewAction::RequestChanges,
+        summary: "The user selected these review comments for you to address. \
+                  Leave everything else as it is."
+            .to_string(),
+        comments,
+        blocking_issues: Vec::new(),
+        reasoning: None,
+    }
+}
+
+/// Whether an allowed tool or granted action (e.g., "Bash(git push:*)") permits `git push`
+fn allows_push(tool: &str) -> bool {
+    tool.contains("git push")
+}
+
 /// Command sent from TUI to Orchestrator
 #[derive(Debug)]
 pub enum OrchestratorCommand {
@@ -124,6 +832,15 @@ pub enum OrchestratorCommand {
     SkipClarification,
     /// User approved or skipped post confirmation
     PostConfirmResponse(bool),
+    /// User approved the review post after editing its summary or comments
+    PostConfirmEdited {
+        summary: String,
+        comments: Vec<ReviewComment>,
+    },
+    /// User selected a single reviewer comment for the reviewee to address
+    RequeueComment(ReviewComment),
+    /// User asked to re-run the agent turn that failed
+    Retry,
     /// User requested abort (stop the rally entirely)
     Abort,
 }
@@ -141,8 +858,26 @@ pub struct Orchestrator {
     last_fix: Option<RevieweeOutput>,
     event_sender: mpsc::Sender<RallyEvent>,
     prompt_loader: PromptLoader,
+    /// Schemas quoted back to an agent whose output did not match
+    output_schemas: OutputSchemas,
+    stall_tracker: StallTracker,
+    /// Agent turns allowed at once, shared by rallies that run side by side
+    agent_slots: Option<Arc<Semaphore>>,
+    /// Whether any reviewee turn changed files (local commits not in the PR head)
+    reviewee_modified_files: bool,
+    /// Working tree before the current reviewee turn, for measuring the fix
+    pre_fix_snapshot: Option<String>,
+    /// Reviewer comments the user requeued; the next reviewee turn addresses only these
+    requeued_comments: Vec<ReviewComment>,
+    /// Whether the user allowed the reviewee to `git push` (allowed tools or a granted
+    /// permission); otherwise a pre-push hook rejects pushes during reviewee turns
+    reviewee_may_push: bool,
+    /// `ignore_globs` plus the repository's `.hxprignore` (read when the rally starts)
+    ignore_rules: IgnoreRules,
     /// Command receiver for TUI commands
     command_receiver: Option<mpsc::Receiver<OrchestratorCommand>>,
+    /// Cancels in-flight agent runs (kills the agent process) when the TUI aborts
+    cancel_token: CancellationToken,
 }
 
 impl Orchestrator {
@@ -153,6 +888,14 @@ impl Orchestrator {
         event_sender: mpsc::Sender<RallyEvent>,
         command_receiver: Option<mpsc::Receiver<OrchestratorCommand>>,
     ) -> Result<Self> {
+        // Route every event (including the agents' streaming ones) through the JSONL logger
+        let event_sender = match config.event_log {
+            Some(ref path) => {
+                spawn_event_logger(PathBuf::from(path), repo, pr_number, event_sender)
+            }
+            None => event_sender,
+        };
+
         let mut reviewer_adapter = create_adapter(&config.reviewer, &config)?;
         let mut reviewee_adapter = create_adapter(&config.reviewee, &config)?;
 
@@ -162,6 +905,16 @@ impl Orchestrator {
 
         let session = RallySession::new(repo, pr_number);
         let prompt_loader = PromptLoader::new(&config);
+        let output_schemas = OutputSchemas::load(&config)?;
+        let stall_tracker = StallTracker::new(config.max_stalled_iterations);
+        let reviewee_may_push = config
+            .reviewee_allowed_tools
+            .iter()
+            .flatten()
+            .chain(&config.reviewee_additional_tools)
+            .any(|tool| allows_push(tool));
+
+        let ignore_rules = IgnoreRules::new(&config.ignore_globs, None);
 
         Ok(Self {
             repo: repo.to_string(),
@@ -175,7 +928,16 @@ impl Orchestrator {
             last_fix: None,
             event_sender,
             prompt_loader,
+            output_schemas,
+            stall_tracker,
+            agent_slots: None,
+            requeued_comments: Vec::new(),
+            reviewee_may_push,
+            ignore_rules,
+            reviewee_modified_files: false,
+            pre_fix_snapshot: None,
             command_receiver,
+            cancel_token: CancellationToken::new(),
         })
     }
 
@@ -184,102 +946,382 @@ impl Orchestrator {
         self.context = Some(context);
     }
 
-    /// Run the rally process
-    pub async fn run(&mut self) -> Result<RallyResult> {
+    /// Share a cancellation token with the agents; cancelling it kills their processes
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.reviewer_adapter.set_cancellation_token(token.clone());
+        self.reviewee_adapter.set_cancellation_token(token.clone());
+        self.cancel_token = token;
+    }
+
+    /// Share a limit on agent turns with other rallies; each turn waits for a permit
+    pub fn set_agent_slots(&mut self, slots: Arc<Semaphore>) {
+        self.agent_slots = Some(slots);
+    }
+
+    /// Wait for a free agent slot, when rallies share a limit.
+    /// The wait does not count towards the turn timeout.
+    async fn agent_slot(&self) -> Option<OwnedSemaphorePermit> {
+        let slots = self.agent_slots.clone()?;
+        slots.acquire_owned().await.ok()
+    }
+
+    /// Re-run the reviewer on a single file after the rally has finished.
+    ///
+    /// Only the reviewer runs; nothing is posted to the PR. The result is sent as a
+    /// regular `ReviewCompleted` event so the TUI merges it into the existing rally.
+    pub async fn run_scoped_review(&mut self, path: &str) -> Result<ReviewerOutput> {
+        let result = self.run_scoped_review_inner(path).await;
+        match result {
+            Ok(ref review) => {
+                self.send_event(RallyEvent::ReviewCompleted(review.clone()))
+                    .await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Completed))
+                    .await;
+            }
+            Err(ref e) => {
+                self.send_event(RallyEvent::Error(format!(
+                    "Scoped review of {} failed: {:#}",
+                    path, e
+                )))
+                .await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Error))
+                    .await;
+            }
+        }
+        result
+    }
+
+    async fn run_scoped_review_inner(&mut self, path: &str) -> Result<ReviewerOutput> {
+        if !self.config.adapters.is_empty() {
+            self.select_fallback_adapters().await?;
+        }
+        self.fetch_linked_issues().await;
+
         let context = self
             .context
             .as_ref()
             .ok_or_else(|| anyhow!("Context not set"))?
             .clone();
 
-        self.send_event(RallyEvent::StateChanged(RallyState::Initializing))
+        self.send_event(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+            .await;
+        self.send_event(RallyEvent::Log(format!("Re-reviewing {} only", path)))
             .await;
 
-        // Main loop
-        while self.session.iteration < self.config.max_iterations {
-            self.session.increment_iteration();
-            let iteration = self.session.iteration;
+        let current_diff = match self.fetch_current_diff().await {
+            Ok(diff) => Some(diff),
+            Err(e) => {
+                warn!("Failed to fetch current diff, using PR patch: {}", e);
+                None
+            }
+        };
+        let scoped = scope_context_to_file(&context, path, current_diff.as_deref())
+            .ok_or_else(|| anyhow!("No diff found for {}", path))?;
 
-            self.send_event(RallyEvent::IterationStarted(iteration))
-                .await;
+        let prompt = self.prompt_loader.load_reviewer_prompt(&scoped, 1);
+        let duration = Duration::from_secs(self.config.timeout_secs);
+        timeout(
+            duration,
+            run_reviewer_with_correction(
+                self.reviewer_adapter.as_mut(),
+                &prompt,
+                &scoped,
+                self.output_schemas.reviewer(),
+                &self.event_sender,
+            ),
+        )
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "Reviewer timeout after {} seconds",
+                self.config.timeout_secs
+            )
+        })?
+    }
 
-            // Update head_sha at start of each iteration.
-            // Note: The reviewee does NOT push changes; commits are local only.
-            // This update is primarily for when the user manually pushes changes between iterations,
-            // or when external tools/CI update the PR branch.
-            if iteration > 1 {
-                if let Err(e) = self.update_head_sha().await {
-                    warn!("Failed to update head_sha: {}", e);
-                }
+    /// Run the reviewee once on `comments` only, outside of a rally.
+    ///
+    /// The reviewee prompt lists just these comments; the reviewer does not run and
+    /// nothing is posted to the PR. The result is sent as a regular `FixCompleted`
+    /// event so the TUI merges it into the existing rally.
+    ///
+    /// There is no command channel to answer a question or grant a permission, so a
+    /// turn that ends in anything but `Completed` fails with what the reviewee needed.
+    pub async fn run_reviewee_for(
+        &mut self,
+        comments: Vec<ReviewComment>,
+    ) -> Result<RevieweeOutput> {
+        let result = self.run_reviewee_for_inner(comments).await;
+        match result {
+            Ok(ref fix) => {
+                self.send_event(RallyEvent::FixCompleted(fix.clone())).await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Completed))
+                    .await;
             }
-
-            // Run reviewer
-            self.session.update_state(RallyState::ReviewerReviewing);
-            self.send_event(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
-                .await;
-            if let Err(e) = write_session(&self.session) {
-                warn!("Failed to write session: {}", e);
-                self.send_event(RallyEvent::Log(format!(
-                    "Warning: Failed to write session: {}",
+            Err(ref e) => {
+                self.send_event(RallyEvent::Error(format!(
+                    "Targeted reviewee turn failed: {:#}",
                     e
                 )))
                 .await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Error))
+                    .await;
             }
+        }
+        result
+    }
 
-            let review_result = match self.run_reviewer_with_timeout(&context, iteration).await {
-                Ok(result) => result,
-                Err(e) => {
-                    self.session.update_state(RallyState::Error);
-                    let _ = write_session(&self.session);
-                    self.send_event(RallyEvent::Error(format!("Reviewer failed: {:#}", e)))
-                        .await;
-                    self.send_event(RallyEvent::StateChanged(RallyState::Error))
-                        .await;
-                    return Err(e);
-                }
-            };
+    async fn run_reviewee_for_inner(
+        &mut self,
+        comments: Vec<ReviewComment>,
+    ) -> Result<RevieweeOutput> {
+        if comments.is_empty() {
+            return Err(anyhow!("No comments selected"));
+        }
+        if !self.config.adapters.is_empty() {
+            self.select_fallback_adapters().await?;
+        }
 
-            // Store the review for later use
-            if let Err(e) = write_history_entry(
-                &self.repo,
-                self.pr_number,
-                iteration,
-                &HistoryEntryType::Review(review_result.clone()),
-            ) {
-                warn!("Failed to write review history: {}", e);
-                self.send_event(RallyEvent::Log(format!(
-                    "Warning: Failed to write review history: {}",
-                    e
-                )))
-                .await;
-            }
+        let context = self
+            .context
+            .as_ref()
+            .ok_or_else(|| anyhow!("Context not set"))?
+            .clone();
 
-            self.send_event(RallyEvent::ReviewCompleted(review_result.clone()))
+        self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
+            .await;
+        self.send_event(RallyEvent::Log(format!(
+            "Running reviewee on {} selected comment(s)",
+            comments.len()
+        )))
+        .await;
+
+        self.pre_fix_snapshot =
+            crate::loader::snapshot_working_tree(context.working_dir.as_deref())
+                .await
+                .map_err(|e| warn!("Failed to snapshot working tree before fix: {}", e))
+                .ok();
+
+        let review = targeted_review(comments);
+        let fix = self.run_reviewee_with_timeout(&context, &review, 1).await?;
+        match fix.status {
+            RevieweeStatus::Completed => Ok(self.with_diff_stats(fix).await),
+            RevieweeStatus::NeedsClarification => Err(anyhow!(
+                "Reviewee needs clarification: {} (start an AI Rally to answer)",
+                fix.question.as_deref().unwrap_or(&fix.summary)
+            )),
+            RevieweeStatus::NeedsPermission => Err(anyhow!(
+                "Reviewee needs permission: {} (start an AI Rally to grant it)",
+                fix.permission_request
+                    .as_ref()
+                    .map_or(fix.summary.as_str(), |p| p.action.as_str())
+            )),
+            RevieweeStatus::Error => Err(anyhow!(
+                "Reviewee reported an error: {}",
+                fix.error_details.as_deref().unwrap_or(&fix.summary)
+            )),
+        }
+    }
+
+    /// Replace reviewer/reviewee with the first available agent from `config.adapters`
+    async fn select_fallback_adapters(&mut self) -> Result<()> {
+        let (name, mut reviewer_adapter) =
+            create_first_available_adapter(&self.config.adapters, &self.config).await?;
+        let mut reviewee_adapter = create_adapter(&name, &self.config)?;
+
+        reviewer_adapter.set_event_sender(self.event_sender.clone());
+        reviewee_adapter.set_event_sender(self.event_sender.clone());
+        reviewer_adapter.set_cancellation_token(self.cancel_token.clone());
+        reviewee_adapter.set_cancellation_token(self.cancel_token.clone());
+        self.reviewer_adapter = reviewer_adapter;
+        self.reviewee_adapter = reviewee_adapter;
+
+        info!(
+            "Selected adapter '{}' from fallback chain [{}]",
+            name,
+            self.config.adapters.join(", ")
+        );
+        self.send_event(RallyEvent::Log(format!(
+            "Using {} (fallback chain: {})",
+            name,
+            self.config.adapters.join(" → ")
+        )))
+        .await;
+        Ok(())
+    }
+
+    /// Run the rally process
+    pub async fn run(&mut self) -> Result<RallyResult> {
+        if self.context.is_none() {
+            return Err(anyhow!("Context not set"));
+        }
+
+        self.send_event(RallyEvent::StateChanged(RallyState::Initializing))
+            .await;
+
+        self.prepare_working_dir().await;
+        self.warn_leftover_push_guard().await;
+
+        if !self.config.adapters.is_empty() {
+            if let Err(e) = self.select_fallback_adapters().await {
+                self.session.update_state(RallyState::Error);
+                let _ = write_session(&self.session);
+                self.send_event(RallyEvent::Error(format!("{:#}", e))).await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Error))
+                    .await;
+                return Err(e);
+            }
+        }
+
+        self.fetch_linked_issues().await;
+        self.apply_incremental_diff().await;
+        self.apply_ignore_globs().await;
+
+        // Snapshot after the startup fetches so the first review sees their results
+        let context = self
+            .context
+            .clone()
+            .ok_or_else(|| anyhow!("Context not set"))?;
+
+        // An empty diff gives the reviewer nothing to look at; finish without running it
+        if context.diff.trim().is_empty() {
+            self.session.update_state(RallyState::Completed);
+            if let Err(e) = write_session(&self.session) {
+                warn!("Failed to write session: {}", e);
+            }
+            self.send_event(RallyEvent::Log(
+                "No changes to review: the diff is empty".to_string(),
+            ))
+            .await;
+            self.send_event(RallyEvent::StateChanged(RallyState::Completed))
                 .await;
-            self.last_review = Some(review_result.clone());
+            return Ok(RallyResult::NoChanges);
+        }
 
-            // Update head_sha before posting review (ensure we have the latest commit)
-            if let Err(e) = self.update_head_sha().await {
-                warn!("Failed to update head_sha before posting review: {}", e);
+        // Main loop
+        while self.session.iteration < self.config.max_iterations {
+            self.session.increment_iteration();
+            let iteration = self.session.iteration;
+
+            self.send_event(RallyEvent::IterationStarted(iteration))
+                .await;
+
+            // Update head_sha at start of each iteration.
+            // Note: The reviewee does NOT push changes; commits are local only.
+            // This update is primarily for when the user manually pushes changes between iterations,
+            // or when external tools/CI update the PR branch.
+            if iteration > 1 {
+                if let Err(e) = self.update_head_sha().await {
+                    warn!("Failed to update head_sha: {}", e);
+                }
             }
 
-            // Post review to PR (with confirmation if auto_post is false)
-            if let Err(e) = self.maybe_post_review_to_pr(&review_result).await {
-                // Check if abort was triggered during post confirmation
-                if self.session.state == RallyState::Aborted {
-                    return Ok(RallyResult::Aborted {
-                        iteration,
-                        reason: e.to_string(),
-                    });
+            // Run reviewer
+            self.session.update_state(RallyState::ReviewerReviewing);
+            self.send_event(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+                .await;
+            if let Err(e) = write_session(&self.session) {
+                warn!("Failed to write session: {}", e);
+                self.send_event(RallyEvent::Log(format!(
+                    "Warning: Failed to write session: {}",
+                    e
+                )))
+                .await;
+            }
+
+            let review_result = loop {
+                match self.run_reviewer_with_timeout(&context, iteration).await {
+                    Ok(result) => break result,
+                    Err(e) => {
+                        self.report_turn_error(format!("Reviewer failed: {:#}", e))
+                            .await;
+                        if !self.wait_for_retry(RallyState::ReviewerReviewing).await {
+                            return Err(e);
+                        }
+                    }
+                }
+            };
+
+            let review_result = match self.config.blocking_severity_threshold {
+                Some(threshold) => {
+                    let requested = review_result.action;
+                    let review = apply_severity_threshold(review_result, threshold);
+                    if review.action != requested {
+                        self.send_event(RallyEvent::Log(format!(
+                            "Reviewer chose {:?}; using {:?} from blocking_severity_threshold = \"{}\"",
+                            requested,
+                            review.action,
+                            threshold.label()
+                        )))
+                        .await;
+                    }
+                    review
                 }
-                warn!("Failed to post review to PR: {}", e);
+                None => review_result,
+            };
+
+            // Becomes the next rally's default --since
+            if !context.local_mode {
+                self.session.last_reviewed_sha = self.context.as_ref().map(|c| c.head_sha.clone());
+            }
+
+            // Withhold approval until the verify command passes; the history and the
+            // UI get the review as it stands after verification
+            let review_result = if review_result.action == ReviewAction::Approve {
+                self.verify_approval(&context, review_result).await
+            } else {
+                review_result
+            };
+
+            // Store the review for later use
+            if let Err(e) = write_history_entry(
+                &self.repo,
+                self.pr_number,
+                iteration,
+                &HistoryEntryType::Review(review_result.clone()),
+            ) {
+                warn!("Failed to write review history: {}", e);
                 self.send_event(RallyEvent::Log(format!(
-                    "Warning: Failed to post review to PR: {}",
+                    "Warning: Failed to write review history: {}",
                     e
                 )))
                 .await;
             }
 
+            self.send_event(RallyEvent::ReviewCompleted(review_result.clone()))
+                .await;
+            self.last_review = Some(review_result.clone());
+
+            // Update head_sha before posting review (ensure we have the latest commit)
+            if let Err(e) = self.update_head_sha().await {
+                warn!("Failed to update head_sha before posting review: {}", e);
+            }
+
+            if review_result.action == ReviewAction::Approve {
+                self.report_merge_outlook().await;
+            }
+
+            // Post review to PR (with confirmation if auto_post is false)
+            let posted_action = match self.maybe_post_review_to_pr(&review_result).await {
+                Ok(posted_action) => posted_action,
+                Err(e) => {
+                    // Check if abort was triggered during post confirmation
+                    if self.session.state == RallyState::Aborted {
+                        return Ok(RallyResult::Aborted {
+                            iteration,
+                            reason: e.to_string(),
+                        });
+                    }
+                    warn!("Failed to post review to PR: {}", e);
+                    self.send_event(RallyEvent::Log(format!(
+                        "Warning: Failed to post review to PR: {}",
+                        e
+                    )))
+                    .await;
+                    None
+                }
+            };
+
             // Check for approval
             if review_result.action == ReviewAction::Approve {
                 self.session.update_state(RallyState::Completed);
@@ -292,12 +1334,31 @@ impl Orchestrator {
                 self.send_event(RallyEvent::StateChanged(RallyState::Completed))
                     .await;
 
+                if self.config.auto_merge {
+                    self.maybe_auto_merge(&review_result, posted_action).await;
+                }
+
                 return Ok(RallyResult::Approved {
                     iteration,
                     summary: review_result.summary,
                 });
             }
 
+            // Review-only mode: the user does the fixes, so the rally ends with the review
+            if self.config.review_only {
+                self.session.update_state(RallyState::Completed);
+                if let Err(e) = write_session(&self.session) {
+                    warn!("Failed to write session: {}", e);
+                }
+                self.send_event(RallyEvent::Log(
+                    "Review only: skipping the reviewee fix loop".to_string(),
+                ))
+                .await;
+                self.send_event(RallyEvent::StateChanged(RallyState::Completed))
+                    .await;
+                return Ok(RallyResult::ReviewOnly { iteration });
+            }
+
             // Run reviewee to fix issues
             self.session.update_state(RallyState::RevieweeFix);
             self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
@@ -331,38 +1392,121 @@ impl Orchestrator {
                 .ok_or_else(|| anyhow!("Context not set"))?
                 .clone();
 
-            let fix_result = match self
-                .run_reviewee_with_timeout(&context, &review_result, iteration)
-                .await
-            {
-                Ok(result) => result,
-                Err(e) => {
-                    self.session.update_state(RallyState::Error);
-                    let _ = write_session(&self.session);
-                    self.send_event(RallyEvent::Error(format!("Reviewee failed: {:#}", e)))
-                        .await;
-                    self.send_event(RallyEvent::StateChanged(RallyState::Error))
-                        .await;
-                    return Err(e);
+            self.pre_fix_snapshot =
+                crate::loader::snapshot_working_tree(context.working_dir.as_deref())
+                    .await
+                    .map_err(|e| warn!("Failed to snapshot working tree before fix: {}", e))
+                    .ok();
+
+            self.drain_requeued_comments();
+            // A retried turn addresses the same requeued comments as the failed one
+            let requeued = self.requeued_comments.clone();
+            let fix_result = loop {
+                let reviewee_result = if self.requeued_comments.is_empty() {
+                    self.run_reviewee_with_timeout(&context, &review_result, iteration)
+                        .await
+                } else {
+                    self.run_focused_reviewee_with_timeout(&context, iteration)
+                        .await
+                };
+                let fix_result = match reviewee_result {
+                    Ok(result) => result,
+                    Err(e) => match sandbox_denial_as_permission_request(&e) {
+                        Some(permission) => {
+                            self.send_event(RallyEvent::Log(format!("Reviewee: {}", e)))
+                                .await;
+                            // The denied turn never finished, so there is no session to
+                            // continue (or only the previous iteration's): once granted,
+                            // the whole turn is re-run with the sandbox lifted
+                            match self.wait_for_permission(&permission).await {
+                                Some(true) => {
+                                    self.grant_reviewee_permission(&permission.action).await;
+                                    self.session.update_state(RallyState::RevieweeFix);
+                                    let _ = write_session(&self.session);
+                                    self.send_event(RallyEvent::StateChanged(
+                                        RallyState::RevieweeFix,
+                                    ))
+                                    .await;
+                                }
+                                Some(false) => {
+                                    let error = format!(
+                                        "The Codex sandbox blocked `{}` and permission was not granted",
+                                        permission.action
+                                    );
+                                    self.report_turn_error(error.clone()).await;
+                                    if !self.wait_for_retry(RallyState::RevieweeFix).await {
+                                        return Ok(RallyResult::Error { iteration, error });
+                                    }
+                                }
+                                None => {
+                                    let reason =
+                                        format!("Permission aborted: {}", permission.action);
+                                    self.session.update_state(RallyState::Aborted);
+                                    let _ = write_session(&self.session);
+                                    self.send_event(RallyEvent::Log(reason.clone())).await;
+                                    self.send_event(RallyEvent::StateChanged(RallyState::Aborted))
+                                        .await;
+                                    return Ok(RallyResult::Aborted { iteration, reason });
+                                }
+                            }
+                            self.requeued_comments.extend(requeued.iter().cloned());
+                            continue;
+                        }
+                        None => {
+                            self.report_turn_error(format!("Reviewee failed: {:#}", e))
+                                .await;
+                            if !self.wait_for_retry(RallyState::RevieweeFix).await {
+                                return Err(e);
+                            }
+                            self.requeued_comments.extend(requeued.iter().cloned());
+                            continue;
+                        }
+                    },
+                };
+                let fix_result = self.with_diff_stats(fix_result).await;
+
+                if let Err(e) = write_history_entry(
+                    &self.repo,
+                    self.pr_number,
+                    iteration,
+                    &HistoryEntryType::Fix(fix_result.clone()),
+                ) {
+                    warn!("Failed to write fix history: {}", e);
+                    self.send_event(RallyEvent::Log(format!(
+                        "Warning: Failed to write fix history: {}",
+                        e
+                    )))
+                    .await;
                 }
-            };
 
-            if let Err(e) = write_history_entry(
-                &self.repo,
-                self.pr_number,
-                iteration,
-                &HistoryEntryType::Fix(fix_result.clone()),
-            ) {
-                warn!("Failed to write fix history: {}", e);
-                self.send_event(RallyEvent::Log(format!(
-                    "Warning: Failed to write fix history: {}",
-                    e
-                )))
-                .await;
-            }
+                self.send_event(RallyEvent::FixCompleted(fix_result.clone()))
+                    .await;
 
-            self.send_event(RallyEvent::FixCompleted(fix_result.clone()))
-                .await;
+                // Run the configured formatter/linter before re-review
+                let fix_result = if fix_result.status == RevieweeStatus::Completed {
+                    self.apply_post_fix_check(&context, iteration, fix_result)
+                        .await
+                } else {
+                    fix_result
+                };
+
+                // An error the reviewee reported itself (also in the post-fix follow-up)
+                // can be retried the same way
+                if fix_result.status != RevieweeStatus::Error {
+                    break fix_result;
+                }
+                let error = fix_result
+                    .error_details
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                self.report_turn_error(error.clone()).await;
+                if !self.wait_for_retry(RallyState::RevieweeFix).await {
+                    return Ok(RallyResult::Error { iteration, error });
+                }
+                self.requeued_comments.extend(requeued.iter().cloned());
+            };
+            if !fix_result.files_modified.is_empty() {
+                self.reviewee_modified_files = true;
+            }
 
             // Handle reviewee status
             match fix_result.status {
@@ -387,7 +1531,28 @@ impl Orchestrator {
                         .await;
                     }
 
-                    // Continue to next iteration
+                    // A read-only reviewee never changes files, which is not a stall
+                    if self.reviewee_adapter.can_modify_files()
+                        && self.stall_tracker.record_fix(&fix_result)
+                    {
+                        let reason = format!(
+                            "Stalled: reviewee made no file changes in {} consecutive iterations \
+                             while the reviewer still requests changes (reviewer and reviewee disagree)",
+                            self.stall_tracker.consecutive
+                        );
+                        // Stalled is a terminal state (not an error), like max iterations
+                        self.session.update_state(RallyState::Completed);
+                        if let Err(e) = write_session(&self.session) {
+                            warn!("Failed to write session: {}", e);
+                        }
+                        self.send_event(RallyEvent::Log(reason.clone())).await;
+                        self.send_event(RallyEvent::StateChanged(RallyState::Completed))
+                            .await;
+                        return Ok(RallyResult::Stalled { iteration, reason });
+                    }
+
+                    // The reviewee's own "completed" never approves the PR: the next
+                    // iteration re-reviews the changes, and only that review can approve
                 }
                 RevieweeStatus::NeedsClarification => {
                     if let Some(question) = &fix_result.question {
@@ -432,8 +1597,9 @@ impl Orchestrator {
                                     .await;
 
                                     let prompt = build_clarification_skipped_prompt(question);
-                                    match self.reviewee_adapter.continue_reviewee(&prompt).await {
+                                    match self.continue_reviewee_with_timeout(&prompt).await {
                                         Ok(output) => {
+                                            let output = self.with_diff_stats(output).await;
                                             // Write history entry for the follow-up fix
                                             if let Err(e) = write_history_entry(
                                                 &self.repo,
@@ -461,6 +1627,9 @@ impl Orchestrator {
 
                                             self.send_event(RallyEvent::FixCompleted(output.clone()))
                                                 .await;
+                                            if !output.files_modified.is_empty() {
+                                                self.reviewee_modified_files = true;
+                                            }
                                             self.last_fix = Some(output);
                                         }
                                         Err(e) => {
@@ -549,8 +1718,9 @@ impl Orchestrator {
 
                                         let prompt =
                                             build_permission_denied_prompt(&perm.action, &perm.reason);
-                                        match self.reviewee_adapter.continue_reviewee(&prompt).await {
+                                        match self.continue_reviewee_with_timeout(&prompt).await {
                                             Ok(output) => {
+                                                let output = self.with_diff_stats(output).await;
                                                 // Write history entry for the follow-up fix
                                                 if let Err(e) = write_history_entry(
                                                     &self.repo,
@@ -580,6 +1750,9 @@ impl Orchestrator {
                                                     output.clone(),
                                                 ))
                                                 .await;
+                                                if !output.files_modified.is_empty() {
+                                                    self.reviewee_modified_files = true;
+                                                }
                                                 self.last_fix = Some(output);
                                             }
                                             Err(e) => {
@@ -626,17 +1799,12 @@ impl Orchestrator {
                         }
                     }
                 }
+                // Normally reported (and retried) in the turn loop above
                 RevieweeStatus::Error => {
-                    self.session.update_state(RallyState::Error);
-                    let _ = write_session(&self.session);
-
                     let error = fix_result
                         .error_details
                         .unwrap_or_else(|| "Unknown error".to_string());
-                    self.send_event(RallyEvent::Error(error.clone())).await;
-                    self.send_event(RallyEvent::StateChanged(RallyState::Error))
-                        .await;
-
+                    self.report_turn_error(error.clone()).await;
                     return Ok(RallyResult::Error { iteration, error });
                 }
             }
@@ -648,8 +1816,8 @@ impl Orchestrator {
             warn!("Failed to write session: {}", e);
         }
 
-        self.send_event(RallyEvent::Log(format!(
-            "Max iterations ({}) reached",
+        self.send_event(RallyEvent::MaxIterationsReached(format!(
+            "Max iterations ({}) reached, not approved",
             self.config.max_iterations
         )))
         .await;
@@ -661,44 +1829,385 @@ impl Orchestrator {
         })
     }
 
-    /// Wait for a command from the TUI
-    async fn wait_for_command(&mut self) -> Option<OrchestratorCommand> {
-        let rx = self.command_receiver.as_mut()?;
-        rx.recv().await
-    }
+    /// Merge the PR after a clean approval (`auto_merge`).
+    ///
+    /// Failures never change the rally result; they are logged with the reason.
+    async fn maybe_auto_merge(&self, review: &ReviewerOutput, posted_action: Option<ReviewAction>) {
+        let Some(context) = self.context.as_ref() else {
+            return;
+        };
 
-    /// Handle clarification response from user
-    async fn handle_clarification_response(&mut self, answer: &str) -> Result<()> {
-        self.send_event(RallyEvent::Log(format!(
-            "User provided clarification: {}",
-            answer
-        )))
-        .await;
+        if let Some(reason) = auto_merge_blocker(
+            review,
+            posted_action,
+            context.local_mode,
+            self.reviewee_modified_files,
+        ) {
+            self.send_event(RallyEvent::Log(format!("Auto-merge skipped: {}", reason)))
+                .await;
+            return;
+        }
 
-        // Ask reviewer for clarification and log the response
-        let prompt = build_clarification_prompt(answer);
-        let reviewer_response = self.reviewer_adapter.continue_reviewer(&prompt).await?;
+        if self.config.auto_merge_require_ci {
+            match github::fetch_checks_passed(&self.repo, &context.head_sha, &context.base_branch)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.send_event(RallyEvent::Log(
+                        "Auto-merge skipped: CI checks have not all passed".to_string(),
+                    ))
+                    .await;
+                    return;
+                }
+                Err(e) => {
+                    self.send_event(RallyEvent::Log(format!(
+                        "Auto-merge skipped: failed to fetch CI checks: {:#}",
+                        e
+                    )))
+                    .await;
+                    return;
+                }
+            }
+        }
 
-        // Log the reviewer's response for debugging/audit purposes
-        self.send_event(RallyEvent::Log(format!(
-            "Reviewer clarification response: {}",
-            reviewer_response.summary
-        )))
-        .await;
+        let method = self.config.merge_method;
+        match github::merge_pr(&self.repo, self.pr_number, method, &context.head_sha).await {
+            Ok(()) => {
+                info!(
+                    "Auto-merged PR #{} ({})",
+                    self.pr_number,
+                    method.as_api_value()
+                );
+                self.send_event(RallyEvent::Log(format!(
+                    "Auto-merged PR #{} ({})",
+                    self.pr_number,
+                    method.as_api_value()
+                )))
+                .await;
+            }
+            Err(e) => {
+                warn!("Auto-merge failed: {:#}", e);
+                self.send_event(RallyEvent::Error(format!("Auto-merge failed: {:#}", e)))
+                    .await;
+            }
+        }
+    }
 
-        // Continue reviewee with the answer
-        self.reviewee_adapter.continue_reviewee(answer).await?;
+    /// Tell the UI whether the reviewer's approval would unblock the merge.
+    /// Failures only skip the report; they never affect the rally.
+    async fn report_merge_outlook(&self) {
+        let Some(context) = self.context.as_ref().filter(|c| !c.local_mode) else {
+            return;
+        };
 
-        self.session.update_state(RallyState::RevieweeFix);
-        self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
-            .await;
-        let _ = write_session(&self.session);
+        let mergeable_state = match github::fetch_mergeable_state(&self.repo, self.pr_number).await
+        {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Failed to fetch mergeable state: {:#}", e);
+                return;
+            }
+        };
+        let protection =
+            match github::fetch_branch_protection(&self.repo, &context.base_branch).await {
+                Ok(protection) => Some(protection.unwrap_or_default()),
+                Err(e) => {
+                    warn!(
+                        "Failed to read branch protection for {}: {:#}",
+                        context.base_branch, e
+                    );
+                    None
+                }
+            };
+        let reviews = match github::fetch_reviews(&self.repo, self.pr_number).await {
+            Ok(reviews) => reviews,
+            Err(e) => {
+                warn!("Failed to fetch reviews: {:#}", e);
+                Vec::new()
+            }
+        };
+        let approvals = github::count_approvals(&reviews);
+        let not_counted = self.approval_not_counted(&reviews).await;
+        let unpassed_checks = match protection {
+            Some(ref protection) => github::fetch_unpassed_checks(
+                &self.repo,
+                &context.head_sha,
+                &protection.required_checks,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to fetch check runs: {:#}", e);
+                protection.required_checks.clone()
+            }),
+            None => Vec::new(),
+        };
 
-        Ok(())
+        let (unblocks, detail) = describe_merge_outlook(
+            &mergeable_state,
+            &context.base_branch,
+            protection.as_ref(),
+            approvals,
+            not_counted,
+            &unpassed_checks,
+        );
+        self.send_event(RallyEvent::MergeOutlook { unblocks, detail })
+            .await;
+    }
+
+    /// Why the reviewer's approval would not add to the PR's approvals, if it wouldn't.
+    ///
+    /// It is posted as the `gh` user: GitHub ignores the author's own approval and
+    /// counts one approval per user. Lookup failures assume it counts.
+    async fn approval_not_counted(
+        &self,
+        reviews: &[github::comment::Review],
+    ) -> Option<&'static str> {
+        let viewer = github::fetch_viewer_login()
+            .await
+            .map_err(|e| warn!("Failed to fetch the gh user: {:#}", e))
+            .ok()?;
+        if github::has_approved(reviews, &viewer) {
+            return Some("you have already approved this PR");
+        }
+        match github::fetch_pr(&self.repo, self.pr_number).await {
+            Ok(pr) if pr.user.login == viewer => Some("you are the PR author"),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Failed to fetch the PR author: {:#}", e);
+                None
+            }
+        }
+    }
+
+    /// Run `verify_command` for an approving review (no-op when not configured)
+    async fn verify_approval(&self, context: &Context, review: ReviewerOutput) -> ReviewerOutput {
+        let Some(command) = self.config.verify_command.as_deref() else {
+            return review;
+        };
+
+        self.send_event(RallyEvent::Log(format!(
+            "Reviewer approved, running verify command: {}",
+            command
+        )))
+        .await;
+
+        let review = verify_approval(
+            review,
+            command,
+            context.working_dir.as_deref(),
+            self.config.verify_timeout_secs,
+        )
+        .await;
+
+        if review.action == ReviewAction::Approve {
+            self.send_event(RallyEvent::Log("Verify command passed".to_string()))
+                .await;
+        } else {
+            warn!("Verify command failed, withholding approval");
+            self.send_event(RallyEvent::Log(
+                "Verify command failed, approval withheld; sending failure to reviewee".to_string(),
+            ))
+            .await;
+        }
+        review
+    }
+
+    /// Attach per-file line counts since the pre-fix snapshot (best effort: left empty
+    /// when the working dir is not a git repository)
+    async fn with_diff_stats(&self, mut fix: RevieweeOutput) -> RevieweeOutput {
+        let Some(ref base) = self.pre_fix_snapshot else {
+            return fix;
+        };
+        let working_dir = self.context.as_ref().and_then(|c| c.working_dir.as_deref());
+        match crate::loader::diff_stats_since(working_dir, base).await {
+            Ok(stats) => fix.diff_stats = stats,
+            Err(e) => warn!("Failed to compute fix diff stats: {}", e),
+        }
+        fix
+    }
+
+    /// Run `post_fix_command` after a completed fix.
+    ///
+    /// When the command fails, the reviewee gets one continuation with the output and its
+    /// follow-up replaces the original fix. Check errors (including timeouts) are logged
+    /// and the fix is kept.
+    async fn apply_post_fix_check(
+        &mut self,
+        context: &Context,
+        iteration: u32,
+        fix_result: RevieweeOutput,
+    ) -> RevieweeOutput {
+        let Some(command) = self.config.post_fix_command.clone() else {
+            return fix_result;
+        };
+
+        self.send_event(RallyEvent::Log(format!(
+            "Running post-fix check: {}",
+            command
+        )))
+        .await;
+
+        let follow_up = match run_post_fix_check(
+            &command,
+            context.working_dir.as_deref(),
+            self.config.post_fix_timeout_secs,
+        )
+        .await
+        {
+            Ok(Some(output)) => {
+                let prompt = build_post_fix_check_failed_prompt(&command, &output);
+                self.continue_reviewee_with_timeout(&prompt).await.map(Some)
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        };
+
+        match follow_up {
+            Ok(None) => {
+                self.send_event(RallyEvent::Log("Post-fix check passed".to_string()))
+                    .await;
+                fix_result
+            }
+            Ok(Some(follow_up)) => {
+                let follow_up = self.with_diff_stats(follow_up).await;
+                self.send_event(RallyEvent::Log(
+                    "Post-fix check failed, sent output back to reviewee".to_string(),
+                ))
+                .await;
+                if let Err(e) = write_history_entry(
+                    &self.repo,
+                    self.pr_number,
+                    iteration,
+                    &HistoryEntryType::Fix(follow_up.clone()),
+                ) {
+                    warn!("Failed to write post-fix history: {}", e);
+                }
+                self.send_event(RallyEvent::FixCompleted(follow_up.clone()))
+                    .await;
+                follow_up
+            }
+            Err(e) => {
+                warn!("Post-fix check did not complete: {}", e);
+                self.send_event(RallyEvent::Log(format!(
+                    "Warning: Post-fix check did not complete: {:#}",
+                    e
+                )))
+                .await;
+                fix_result
+            }
+        }
+    }
+
+    /// Wait for a command from the TUI
+    ///
+    /// Requeued comments can arrive at any time, so they are stashed here and never
+    /// returned to the waiting caller.
+    async fn wait_for_command(&mut self) -> Option<OrchestratorCommand> {
+        loop {
+            let rx = self.command_receiver.as_mut()?;
+            match rx.recv().await? {
+                OrchestratorCommand::RequeueComment(comment) => {
+                    self.requeued_comments.push(comment);
+                }
+                command => return Some(command),
+            }
+        }
+    }
+
+    /// Enter the error state after a failed agent turn
+    async fn report_turn_error(&mut self, error: String) {
+        self.session.update_state(RallyState::Error);
+        let _ = write_session(&self.session);
+        self.send_event(RallyEvent::Error(error)).await;
+        self.send_event(RallyEvent::StateChanged(RallyState::Error))
+            .await;
+    }
+
+    /// Wait in the error state until the user retries the failed turn.
+    ///
+    /// Returns `true` (back in `turn_state`) on `Retry`, `false` on abort or when no
+    /// TUI is attached to ask.
+    async fn wait_for_retry(&mut self, turn_state: RallyState) -> bool {
+        loop {
+            match self.wait_for_command().await {
+                Some(OrchestratorCommand::Retry) => break,
+                Some(OrchestratorCommand::Abort) | None => return false,
+                Some(other) => warn!("Ignoring command while waiting for retry: {:?}", other),
+            }
+        }
+
+        self.session.update_state(turn_state);
+        if let Err(e) = write_session(&self.session) {
+            warn!("Failed to write session: {}", e);
+        }
+        self.send_event(RallyEvent::Log("Retrying the failed turn".to_string()))
+            .await;
+        self.send_event(RallyEvent::StateChanged(turn_state)).await;
+        true
+    }
+
+    /// Collect comments requeued while the orchestrator was busy (non-blocking)
+    fn drain_requeued_comments(&mut self) {
+        let Some(rx) = self.command_receiver.as_mut() else {
+            return;
+        };
+        while let Ok(command) = rx.try_recv() {
+            match command {
+                OrchestratorCommand::RequeueComment(comment) => {
+                    self.requeued_comments.push(comment);
+                }
+                other => warn!("Ignoring command outside of a waiting state: {:?}", other),
+            }
+        }
+    }
+
+    /// Handle clarification response from user
+    async fn handle_clarification_response(&mut self, answer: &str) -> Result<()> {
+        self.send_event(RallyEvent::Log(format!(
+            "User provided clarification: {}",
+            answer
+        )))
+        .await;
+
+        // Ask reviewer for clarification and log the response
+        let prompt = build_clarification_prompt(answer);
+        let reviewer_response = self.continue_reviewer_with_timeout(&prompt).await?;
+
+        // Log the reviewer's response for debugging/audit purposes
+        self.send_event(RallyEvent::Log(format!(
+            "Reviewer clarification response: {}",
+            reviewer_response.summary
+        )))
+        .await;
+
+        // Continue reviewee with the answer
+        self.continue_reviewee_with_timeout(answer).await?;
+
+        self.session.update_state(RallyState::RevieweeFix);
+        self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
+            .await;
+        let _ = write_session(&self.session);
+
+        Ok(())
     }
 
     /// Handle permission granted from user
     async fn handle_permission_granted(&mut self, action: &str) -> Result<()> {
+        self.grant_reviewee_permission(action).await;
+
+        let prompt = build_permission_granted_prompt(action);
+        self.continue_reviewee_with_timeout(&prompt).await?;
+
+        self.session.update_state(RallyState::RevieweeFix);
+        self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
+            .await;
+        let _ = write_session(&self.session);
+
+        Ok(())
+    }
+
+    async fn grant_reviewee_permission(&mut self, action: &str) {
         self.send_event(RallyEvent::Log(format!(
             "User granted permission for: {}",
             action
@@ -708,16 +2217,33 @@ impl Orchestrator {
         // Add the granted action to reviewee's allowed tools
         // This allows the reviewee to execute the action without being blocked
         self.reviewee_adapter.add_reviewee_allowed_tool(action);
+        if allows_push(action) {
+            self.reviewee_may_push = true;
+        }
+    }
 
-        let prompt = build_permission_granted_prompt(action);
-        self.reviewee_adapter.continue_reviewee(&prompt).await?;
-
-        self.session.update_state(RallyState::RevieweeFix);
-        self.send_event(RallyEvent::StateChanged(RallyState::RevieweeFix))
-            .await;
+    /// Ask the user for `permission`: `Some(granted)`, or `None` when the rally was aborted
+    async fn wait_for_permission(&mut self, permission: &PermissionRequest) -> Option<bool> {
+        self.session.update_state(RallyState::WaitingForPermission);
         let _ = write_session(&self.session);
+        self.send_event(RallyEvent::PermissionNeeded(
+            permission.action.clone(),
+            permission.reason.clone(),
+        ))
+        .await;
+        self.send_event(RallyEvent::StateChanged(RallyState::WaitingForPermission))
+            .await;
 
-        Ok(())
+        loop {
+            match self.wait_for_command().await {
+                Some(OrchestratorCommand::PermissionResponse(granted)) => return Some(granted),
+                Some(OrchestratorCommand::Abort) | None => return None,
+                _ => {
+                    warn!("Received invalid command during WaitingForPermission, ignoring");
+                    continue;
+                }
+            }
+        }
     }
 
     /// Continue after clarification answer (legacy, kept for compatibility)
@@ -741,10 +2267,13 @@ impl Orchestrator {
             self.prompt_loader.load_reviewer_prompt(context, iteration)
         } else {
             // Re-review after fixes - fetch updated diff and include fix summary
-            let updated_diff = self.fetch_current_diff().await.unwrap_or_else(|e| {
-                warn!("Failed to fetch updated diff: {}", e);
-                context.diff.clone()
-            });
+            let updated_diff = match self.fetch_current_diff().await {
+                Ok(diff) => strip_ignored_files(&diff, &self.ignore_rules).0,
+                Err(e) => {
+                    warn!("Failed to fetch updated diff: {}", e);
+                    context.diff.clone()
+                }
+            };
 
             let changes_summary = self
                 .last_fix
@@ -768,9 +2297,16 @@ impl Orchestrator {
 
         let duration = Duration::from_secs(self.config.timeout_secs);
 
+        let _slot = self.agent_slot().await;
         timeout(
             duration,
-            self.reviewer_adapter.run_reviewer(&prompt, context),
+            run_reviewer_with_correction(
+                self.reviewer_adapter.as_mut(),
+                &prompt,
+                context,
+                self.output_schemas.reviewer(),
+                &self.event_sender,
+            ),
         )
         .await
         .map_err(|_| {
@@ -792,12 +2328,94 @@ impl Orchestrator {
             .load_reviewee_prompt(context, review, iteration);
         let duration = Duration::from_secs(self.config.timeout_secs);
 
-        timeout(
+        let _slot = self.agent_slot().await;
+        let guard = self.guard_push().await;
+        let result = timeout(
             duration,
-            self.reviewee_adapter.run_reviewee(&prompt, context),
+            run_reviewee_with_correction(
+                self.reviewee_adapter.as_mut(),
+                &prompt,
+                context,
+                self.output_schemas.reviewee(),
+                &self.event_sender,
+            ),
         )
-        .await
-        .map_err(|_| {
+        .await;
+        self.report_blocked_pushes(guard).await;
+        result.map_err(|_| {
+            anyhow!(
+                "Reviewee timeout after {} seconds",
+                self.config.timeout_secs
+            )
+        })?
+    }
+
+    /// Run the reviewee on the requeued comments only, instead of the whole review.
+    ///
+    /// From the second iteration on the reviewee already has a session, so the focused
+    /// prompt continues it; otherwise a new session is started with that prompt.
+    async fn run_focused_reviewee_with_timeout(
+        &mut self,
+        context: &Context,
+        iteration: u32,
+    ) -> Result<RevieweeOutput> {
+        let prompt = self
+            .requeued_comments
+            .drain(..)
+            .map(|comment| build_focused_comment_prompt(&comment))
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+
+        self.send_event(RallyEvent::Log(
+            "Sending requeued comment(s) to reviewee".to_string(),
+        ))
+        .await;
+
+        let duration = Duration::from_secs(self.config.timeout_secs);
+        let _slot = self.agent_slot().await;
+        let guard = self.guard_push().await;
+        let run = async {
+            if iteration > 1 {
+                self.reviewee_adapter.continue_reviewee(&prompt).await
+            } else {
+                self.reviewee_adapter.run_reviewee(&prompt, context).await
+            }
+        };
+        let result = timeout(duration, run).await;
+        self.report_blocked_pushes(guard).await;
+        result.map_err(|_| {
+            anyhow!(
+                "Reviewee timeout after {} seconds",
+                self.config.timeout_secs
+            )
+        })?
+    }
+
+    /// Continue the reviewer's session (clarifications), bounded by the turn timeout.
+    ///
+    /// Dropping the agent future on timeout kills its process.
+    async fn continue_reviewer_with_timeout(&mut self, prompt: &str) -> Result<ReviewerOutput> {
+        let duration = Duration::from_secs(self.config.timeout_secs);
+        let _slot = self.agent_slot().await;
+        timeout(duration, self.reviewer_adapter.continue_reviewer(prompt))
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Reviewer timeout after {} seconds",
+                    self.config.timeout_secs
+                )
+            })?
+    }
+
+    /// Continue the reviewee's session (clarifications, permission answers), bounded
+    /// by the turn timeout
+    async fn continue_reviewee_with_timeout(&mut self, prompt: &str) -> Result<RevieweeOutput> {
+        let duration = Duration::from_secs(self.config.timeout_secs);
+        let _slot = self.agent_slot().await;
+        let guard = self.guard_push().await;
+        let result = timeout(duration, self.reviewee_adapter.continue_reviewee(prompt)).await;
+        self.report_blocked_pushes(guard).await;
+        result.map_err(|_| {
             anyhow!(
                 "Reviewee timeout after {} seconds",
                 self.config.timeout_secs
@@ -805,6 +2423,39 @@ impl Orchestrator {
         })?
     }
 
+    /// Install the pre-push guard for a reviewee turn, unless the user allowed pushes.
+    ///
+    /// A working directory that is not a git repository has nothing to push from,
+    /// so failing to install is only logged.
+    async fn guard_push(&self) -> Option<PushGuard> {
+        if self.reviewee_may_push {
+            return None;
+        }
+        let working_dir = self.context.as_ref().and_then(|c| c.working_dir.as_deref());
+        PushGuard::install(working_dir)
+            .await
+            .map_err(|e| warn!("Failed to install the pre-push guard: {:#}", e))
+            .ok()
+    }
+
+    /// Point out a push guard or hook backup an interrupted rally left behind
+    async fn warn_leftover_push_guard(&self) {
+        let working_dir = self.context.as_ref().and_then(|c| c.working_dir.as_deref());
+        if let Some(warning) = push_guard::leftover_warning(working_dir).await {
+            warn!("{}", warning);
+            self.send_event(RallyEvent::Log(format!("Warning: {}", warning)))
+                .await;
+        }
+    }
+
+    /// Remove the pre-push guard and report the pushes it rejected
+    async fn report_blocked_pushes(&self, guard: Option<PushGuard>) {
+        for attempt in guard.map(PushGuard::finish).unwrap_or_default() {
+            warn!("Blocked a git push by the reviewee: {}", attempt);
+            self.send_event(RallyEvent::PushBlocked(attempt)).await;
+        }
+    }
+
     async fn send_event(&self, event: RallyEvent) {
         let _ = self.event_sender.send(event).await;
     }
@@ -813,13 +2464,17 @@ impl Orchestrator {
     /// - local_mode: skip posting entirely
     /// - auto_post: post directly without confirmation
     /// - otherwise: send confirmation event and wait for user response
-    async fn maybe_post_review_to_pr(&mut self, review: &ReviewerOutput) -> Result<()> {
+    async fn maybe_post_review_to_pr(
+        &mut self,
+        review: &ReviewerOutput,
+    ) -> Result<Option<ReviewAction>> {
         // local_mode is handled inside post_review_to_pr
         if self.context.as_ref().is_some_and(|c| c.local_mode) {
             return self.post_review_to_pr(review).await;
         }
 
-        if self.config.auto_post {
+        // dry_run only logs the review, so there is nothing to confirm
+        if self.config.auto_post || self.config.dry_run {
             return self.post_review_to_pr(review).await;
         }
 
@@ -828,6 +2483,7 @@ impl Orchestrator {
             action: format!("{:?}", review.action),
             summary: review.summary.clone(),
             comment_count: review.comments.len(),
+            comments: review.comments.clone(),
         };
 
         self.session
@@ -850,12 +2506,24 @@ impl Orchestrator {
                     .await;
                     return self.post_review_to_pr(review).await;
                 }
+                Some(OrchestratorCommand::PostConfirmEdited { summary, comments }) => {
+                    self.send_event(RallyEvent::Log(
+                        "User edited the review before posting".to_string(),
+                    ))
+                    .await;
+                    let edited = ReviewerOutput {
+                        summary,
+                        comments,
+                        ..review.clone()
+                    };
+                    return self.post_review_to_pr(&edited).await;
+                }
                 Some(OrchestratorCommand::PostConfirmResponse(false)) => {
                     self.send_event(RallyEvent::Log(
                         "User skipped review posting".to_string(),
                     ))
                     .await;
-                    return Ok(());
+                    return Ok(None);
                 }
                 Some(OrchestratorCommand::Abort) | None => {
                     self.session.update_state(RallyState::Aborted);
@@ -880,7 +2548,7 @@ impl Orchestrator {
             return self.post_fix_comment(fix).await;
         }
 
-        if self.config.auto_post {
+        if self.config.auto_post || self.config.dry_run {
             return self.post_fix_comment(fix).await;
         }
 
@@ -933,14 +2601,14 @@ impl Orchestrator {
         }
     }
 
-    /// Post review to PR (summary comment + inline comments)
-    async fn post_review_to_pr(&self, review: &ReviewerOutput) -> Result<()> {
+    /// Post review to PR as a single GitHub review (decision + summary + inline comments)
+    async fn post_review_to_pr(&self, review: &ReviewerOutput) -> Result<Option<ReviewAction>> {
         if self.context.as_ref().is_some_and(|c| c.local_mode) {
             self.send_event(RallyEvent::Log(
                 "Local mode: skipping review posting to PR".to_string(),
             ))
             .await;
-            return Ok(());
+            return Ok(None);
         }
 
         let context = self
@@ -955,75 +2623,263 @@ impl Orchestrator {
             ReviewAction::Comment => crate::app::ReviewAction::Comment,
         };
 
-        // Copy for potential fallback use (app_action is moved into submit_review)
-        let app_action_for_fallback = app_action;
-
-        // Add prefix to summary
-        let summary_with_prefix = format!("[AI Rally - Reviewer]\n\n{}", review.summary);
-
-        // Post summary comment using gh pr review
-        // If approve fails (e.g., can't approve own PR), fall back to comment
-        let result =
-            github::submit_review(&self.repo, self.pr_number, app_action, &summary_with_prefix)
-                .await;
-
-        if result.is_err() && matches!(app_action_for_fallback, crate::app::ReviewAction::Approve) {
-            warn!("Approve failed, falling back to comment");
-            github::submit_review(
-                &self.repo,
-                self.pr_number,
-                crate::app::ReviewAction::Comment,
-                &summary_with_prefix,
-            )
-            .await?;
+        // Validate comment lines against the diff before posting.
+        // Digest mode folds every comment into the summary, so nothing is posted inline.
+        let placements: Vec<(&ReviewComment, CommentPlacement)> = if self.config.digest {
+            Vec::new()
         } else {
-            result?;
-        }
+            review
+                .comments
+                .iter()
+                .map(|comment| {
+                    if comment.path.trim().is_empty() {
+                        return (comment, CommentPlacement::Conversation);
+                    }
+                    let patch = context
+                        .file_patches
+                        .iter()
+                        .find(|(name, _)| name == &comment.path)
+                        .map(|(_, p)| p.as_str());
+                    (comment, place_review_comment(patch, comment.line))
+                })
+                .collect()
+        };
 
-        // Post inline comments with rate limit handling
-        for comment in &review.comments {
-            // Convert line number to patch position
-            let patch = context
-                .file_patches
+        // A pending review can't hold conversation comments, so they stay in the summary
+        let general_comments: Vec<&ReviewComment> = placements
+            .iter()
+            .filter(|(_, placement)| match placement {
+                CommentPlacement::General => true,
+                CommentPlacement::Conversation => self.config.pending_review,
+                _ => false,
+            })
+            .map(|(comment, _)| *comment)
+            .collect();
+        let conversation_comments: Vec<String> = if self.config.pending_review {
+            Vec::new()
+        } else {
+            placements
                 .iter()
-                .find(|(name, _)| name == &comment.path)
-                .map(|(_, p)| p.as_str());
+                .filter(|(_, placement)| *placement == CommentPlacement::Conversation)
+                .map(|(comment, _)| format_conversation_comment(comment))
+                .collect()
+        };
 
-            let Some(patch) = patch else {
-                warn!("No patch found for {}, skipping comment", comment.path);
-                continue;
-            };
+        // Add prefix to summary
+        let body = if self.config.digest {
+            format_digest(review)
+        } else {
+            review.summary.clone()
+        };
+        let mut summary_with_prefix = format!("[AI Rally - Reviewer]\n\n{}", body);
+        if !general_comments.is_empty() {
+            warn!(
+                "{} comment(s) could not be placed on the diff, adding them to the summary",
+                general_comments.len()
+            );
+            summary_with_prefix.push_str("\n\n");
+            summary_with_prefix.push_str(&format_general_comments(
+                "Comments outside the diff",
+                &general_comments,
+            ));
+        }
+        if self.config.attach_reasoning {
+            if let Some(details) = format_reasoning_details(review.reasoning.as_deref()) {
+                summary_with_prefix.push_str("\n\n");
+                summary_with_prefix.push_str(&details);
+            }
+        }
 
-            let Some(position) = crate::diff::line_number_to_position(patch, comment.line) else {
-                warn!(
-                    "Could not convert line {} to position for {}, skipping comment",
-                    comment.line, comment.path
-                );
-                continue;
+        // Collect inline comments so they are posted together with the summary
+        let mut drafts = Vec::new();
+        let mut inline_comments = Vec::new();
+        for (comment, placement) in &placements {
+            let (line, side, position) = match *placement {
+                CommentPlacement::Inline {
+                    line,
+                    side,
+                    position,
+                } => {
+                    if side == DiffSide::Left {
+                        info!(
+                            "Placing comment on removed line {} of {} (LEFT side)",
+                            line, comment.path
+                        );
+                    }
+                    (line, side, position)
+                }
+                CommentPlacement::General | CommentPlacement::Conversation => continue,
+                CommentPlacement::Skip => {
+                    warn!("No patch found for {}, skipping comment", comment.path);
+                    continue;
+                }
             };
 
             // Add prefix to inline comment
-            let body_with_prefix = format!("[AI Rally - Reviewer]\n\n{}", comment.body);
-            if let Err(e) = github::create_review_comment(
-                &self.repo,
-                self.pr_number,
-                &context.head_sha,
-                &comment.path,
-                position,
-                &body_with_prefix,
-            )
-            .await
-            {
+            let mut body_with_prefix = format!(
+                "{}\n\n{}",
+                comment.severity.posted_comment_header(),
+                comment.body
+            );
+            if line != comment.line {
                 warn!(
-                    "Failed to post inline comment on {}:{} (position {}): {}",
-                    comment.path, comment.line, position, e
+                    "Line {} is outside the diff for {}, moved comment to line {}",
+                    comment.line, comment.path, line
                 );
+                body_with_prefix.push_str(&format!(
+                    "\n\n_(Originally on line {}, which is outside the diff)_",
+                    comment.line
+                ));
             }
-            // Rate limit mitigation: small delay between API calls
-            tokio::time::sleep(Duration::from_millis(100)).await;
-        }
 
-        Ok(())
+            // A range that does not fit the diff is posted on its last line only
+            let range = comment
+                .start_line
+                .filter(|&start| {
+                    line == comment.line
+                        && side == DiffSide::Right
+                        && context
+                            .file_patches
+                            .iter()
+                            .find(|(name, _)| name == &comment.path)
+                            .is_some_and(|(_, patch)| is_commentable_range(patch, start, line))
+                })
+                .map(|start| (start, line));
+
+            drafts.push(DraftReviewComment {
+                path: comment.path.clone(),
+                position,
+                body: body_with_prefix,
+                range,
+            });
+            inline_comments.push(*comment);
+        }
+
+        if let Some(message) = format_unplaced_comments(&placements) {
+            self.send_event(RallyEvent::Log(message)).await;
+        }
+
+        if self.config.dry_run {
+            self.send_event(RallyEvent::Log(format_dry_run_review(
+                app_action,
+                &summary_with_prefix,
+                &drafts,
+            )))
+            .await;
+            for body in &conversation_comments {
+                self.send_event(RallyEvent::Log(format!(
+                    "[dry-run] Would post PR comment:\n{}",
+                    body
+                )))
+                .await;
+            }
+            return Ok(None);
+        }
+
+        // Stage the review as a pending (draft) review; the user submits it later
+        if self.config.pending_review {
+            if github::find_pending_review(&self.repo, self.pr_number)
+                .await?
+                .is_some()
+            {
+                self.send_event(RallyEvent::Log(
+                    "A pending review already exists on this PR; submit or discard it to stage a new one"
+                        .to_string(),
+                ))
+                .await;
+                return Ok(None);
+            }
+            github::create_pending_review(
+                &self.repo,
+                self.pr_number,
+                &context.head_sha,
+                &summary_with_prefix,
+                &drafts,
+            )
+            .await?;
+            self.send_event(RallyEvent::Log(format!(
+                "Staged the review as a pending review ({} inline comment(s), reviewer suggests: {:?}). \
+                 Submit it from the file list or on GitHub",
+                drafts.len(),
+                review.action
+            )))
+            .await;
+            return Ok(None);
+        }
+
+        // Post the decision, summary and inline comments as a single PR review
+        let summary_with_inline = format!(
+            "{}\n\n{}",
+            summary_with_prefix,
+            format_general_comments("Inline comments GitHub rejected", &inline_comments)
+        );
+        let mut posted = None;
+        let mut last_error = None;
+        for (action, inline) in review_post_attempts(app_action, !drafts.is_empty()) {
+            let (body, comments) = if inline || drafts.is_empty() {
+                (&summary_with_prefix, drafts.as_slice())
+            } else {
+                (&summary_with_inline, &[][..])
+            };
+            match github::create_review(
+                &self.repo,
+                self.pr_number,
+                &context.head_sha,
+                action,
+                body,
+                comments,
+            )
+            .await
+            {
+                Ok(_) => {
+                    posted = Some((action, inline));
+                    break;
+                }
+                Err(e) => {
+                    warn!(
+                        "Posting the review as {:?} ({} inline comments) failed: {:#}",
+                        action,
+                        if inline { "with" } else { "without" },
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        let Some((action, inline)) = posted else {
+            return Err(last_error.unwrap_or_else(|| anyhow!("The review was not posted")));
+        };
+        if !inline && !drafts.is_empty() {
+            self.send_event(RallyEvent::Log(format!(
+                "GitHub rejected the inline comments; posted {} of them in the review summary instead",
+                drafts.len()
+            )))
+            .await;
+        }
+        if action != app_action {
+            self.send_event(RallyEvent::Log(
+                "Approving failed; posted the review as a comment".to_string(),
+            ))
+            .await;
+        }
+        let posted_action = match action {
+            crate::app::ReviewAction::Approve => ReviewAction::Approve,
+            crate::app::ReviewAction::RequestChanges => ReviewAction::RequestChanges,
+            crate::app::ReviewAction::Comment => ReviewAction::Comment,
+        };
+
+        for body in &conversation_comments {
+            github::create_issue_comment(&self.repo, self.pr_number, body).await?;
+        }
+        if !conversation_comments.is_empty() {
+            self.send_event(RallyEvent::Log(format!(
+                "Posted {} comment(s) not tied to a line to the PR conversation",
+                conversation_comments.len()
+            )))
+            .await;
+        }
+
+        Ok(Some(posted_action))
     }
 
     /// Post fix summary comment to PR
@@ -1052,245 +2908,2183 @@ impl Orchestrator {
             fix.summary, files_list
         );
 
-        // Post as a comment (not a review)
+        let threads = if fix.files_modified.is_empty() {
+            Vec::new()
+        } else {
+            match github::fetch_review_threads(&self.repo, self.pr_number).await {
+                Ok(threads) => threads,
+                Err(e) => {
+                    warn!("Failed to fetch review threads: {}", e);
+                    Vec::new()
+                }
+            }
+        };
+        let addressed = addressed_threads(&threads, &fix.files_modified);
+
+        // With reply_to_addressed_comments the note goes under the reviewer's comments
+        // on lines the fix changed, falling back to a top-level comment when there are none
+        let changed_lines = if self.config.reply_to_addressed_comments && !addressed.is_empty() {
+            self.changed_lines_since_snapshot().await
+        } else {
+            HashMap::new()
+        };
+        let mut replied = 0;
+        for thread in reply_targets(
+            self.config.reply_to_addressed_comments,
+            &addressed,
+            &changed_lines,
+        ) {
+            if self.reply_to_thread(thread, &fix.summary).await {
+                replied += 1;
+            }
+        }
+        if replied == 0 {
+            self.post_fix_summary(&comment_body).await?;
+        }
+
+        self.resolve_addressed_threads(&addressed).await;
+
+        Ok(())
+    }
+
+    /// Lines changed since the pre-fix snapshot, per file (empty when unknown)
+    async fn changed_lines_since_snapshot(&self) -> HashMap<String, Vec<(u32, u32)>> {
+        let Some(ref base) = self.pre_fix_snapshot else {
+            return HashMap::new();
+        };
+        let working_dir = self.context.as_ref().and_then(|c| c.working_dir.as_deref());
+        crate::loader::changed_lines_since(working_dir, base)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to compute lines changed by the fix: {}", e);
+                HashMap::new()
+            })
+    }
+
+    /// Post the fix note as a top-level PR comment (not a review)
+    async fn post_fix_summary(&self, comment_body: &str) -> Result<()> {
+        if self.config.dry_run {
+            self.send_event(RallyEvent::Log(format!(
+                "[dry-run] Would post fix comment:\n{}",
+                comment_body
+            )))
+            .await;
+            return Ok(());
+        }
         github::submit_review(
             &self.repo,
             self.pr_number,
             crate::app::ReviewAction::Comment,
-            &comment_body,
+            comment_body,
         )
-        .await?;
+        .await
+    }
+
+    /// Resolve the reviewer's open threads on files the reviewee modified (best-effort),
+    /// reacting to their comment first when `addressed_comment_reaction` is set.
+    ///
+    /// A modified file does not prove the comment was addressed, but the next
+    /// review pass re-raises anything that is still wrong.
+    async fn resolve_addressed_threads(&self, threads: &[&ReviewThread]) {
+        for &thread in threads {
+            self.react_to_thread(thread).await;
+            if self.config.dry_run {
+                self.send_event(RallyEvent::Log(format!(
+                    "[dry-run] Would resolve review thread on {}",
+                    thread.path
+                )))
+                .await;
+                continue;
+            }
+            match github::resolve_review_thread(&thread.id).await {
+                Ok(()) => {
+                    self.send_event(RallyEvent::Log(format!(
+                        "Resolved review thread on {}",
+                        thread.path
+                    )))
+                    .await;
+                }
+                Err(e) => {
+                    warn!("Failed to resolve review thread {}: {}", thread.id, e);
+                    self.send_event(RallyEvent::Log(format!(
+                        "Warning: Failed to resolve review thread on {}: {}",
+                        thread.path, e
+                    )))
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Reply to the first comment of `thread` with the fix note (best-effort);
+    /// `true` when the reply was posted (or would be, in dry-run mode)
+    async fn reply_to_thread(&self, thread: &ReviewThread, summary: &str) -> bool {
+        let Some(comment_id) = thread.comment_id else {
+            return false;
+        };
+        let body = format_thread_reply(&thread.path, summary);
+        if self.config.dry_run {
+            self.send_event(RallyEvent::Log(format!(
+                "[dry-run] Would reply to review comment on {}:\n{}",
+                thread.path, body
+            )))
+            .await;
+            return true;
+        }
+        match github::create_reply_comment(&self.repo, self.pr_number, comment_id, &body).await {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Failed to reply to comment {}: {}", comment_id, e);
+                self.send_event(RallyEvent::Log(format!(
+                    "Warning: Failed to reply to review comment on {}: {}",
+                    thread.path, e
+                )))
+                .await;
+                false
+            }
+        }
+    }
+
+    /// Add the configured reaction to the first comment of `thread` (best-effort)
+    async fn react_to_thread(&self, thread: &ReviewThread) {
+        let (Some(reaction), Some(comment_id)) =
+            (self.config.addressed_comment_reaction, thread.comment_id)
+        else {
+            return;
+        };
+        if self.config.dry_run {
+            self.send_event(RallyEvent::Log(format!(
+                "[dry-run] Would react {} to review comment on {}",
+                reaction.as_api_value(),
+                thread.path
+            )))
+            .await;
+            return;
+        }
+        if let Err(e) = github::add_comment_reaction(&self.repo, comment_id, reaction).await {
+            warn!("Failed to add reaction to comment {}: {}", comment_id, e);
+            self.send_event(RallyEvent::Log(format!(
+                "Warning: Failed to react to review comment on {}: {}",
+                thread.path, e
+            )))
+            .await;
+        }
+    }
+
+    /// Fetch external comments from bots (Copilot, CodeRabbit, etc.)
+    async fn fetch_external_comments(&self) -> Vec<ExternalComment> {
+        if self.context.as_ref().is_some_and(|c| c.local_mode) {
+            return Vec::new();
+        }
+
+        let mut comments = Vec::new();
+
+        // Fetch review comments (inline comments on diff)
+        if let Ok(review_comments) = fetch_review_comments(&self.repo, self.pr_number).await {
+            for c in review_comments {
+                if is_bot_user(&c.user.login) {
+                    comments.push(ExternalComment {
+                        source: c.user.login.clone(),
+                        path: Some(c.path.clone()),
+                        line: c.line,
+                        body: c.body.clone(),
+                    });
+                }
+            }
+        }
+
+        // Fetch discussion comments (general PR comments)
+        if let Ok(discussion) = fetch_discussion_comments(&self.repo, self.pr_number).await {
+            for c in discussion {
+                if is_bot_user(&c.user.login) {
+                    comments.push(ExternalComment {
+                        source: c.user.login.clone(),
+                        path: None,
+                        line: None,
+                        body: c.body.clone(),
+                    });
+                }
+            }
+        }
+
+        // Limit the number of comments
+        comments.truncate(MAX_EXTERNAL_COMMENTS);
+        comments
+    }
+
+    /// Make sure the reviewee edits a checkout of the PR head (PR mode only).
+    ///
+    /// With `checkout_pr` a missing directory is cloned and a different HEAD is
+    /// switched with `gh pr checkout`; otherwise a mismatch is only reported.
+    async fn prepare_working_dir(&mut self) {
+        let Some(ctx) = self.context.as_ref().filter(|c| !c.local_mode) else {
+            return;
+        };
+        let Some(dir) = ctx.working_dir.clone() else {
+            return;
+        };
+        let head_sha = ctx.head_sha.clone();
+
+        let mut status = checkout_status(&dir, &head_sha).await;
+        if status != CheckoutStatus::OnHead && self.config.checkout_pr {
+            match self.checkout_pr_in(&dir, &status).await {
+                Ok(()) => {
+                    self.send_event(RallyEvent::Log(format!(
+                        "Checked out PR #{} in {}",
+                        self.pr_number, dir
+                    )))
+                    .await;
+                }
+                Err(e) => {
+                    warn!("Failed to check out PR #{}: {:#}", self.pr_number, e);
+                    self.send_event(RallyEvent::Log(format!(
+                        "Warning: Failed to check out PR #{} in {}: {:#}",
+                        self.pr_number, dir, e
+                    )))
+                    .await;
+                }
+            }
+            status = checkout_status(&dir, &head_sha).await;
+        }
+
+        let problem = match status {
+            CheckoutStatus::OnHead => return,
+            CheckoutStatus::Missing => format!("{} does not exist", dir),
+            CheckoutStatus::NotGitRepo => format!("{} is not a git repository", dir),
+            CheckoutStatus::Mismatch { head } => format!(
+                "{} is at {} but the PR head is {}",
+                dir,
+                short_sha(&head),
+                short_sha(&head_sha)
+            ),
+        };
+        let hint = if self.config.checkout_pr {
+            String::new()
+        } else {
+            format!(
+                " (run `gh pr checkout {}` there or pass --checkout)",
+                self.pr_number
+            )
+        };
+        warn!("Working directory is not the PR checkout: {}", problem);
+        self.send_event(RallyEvent::Log(format!(
+            "Warning: {}; reviewee edits may target the wrong tree{}",
+            problem, hint
+        )))
+        .await;
+    }
+
+    /// Clone the repository into `dir` if it is missing, then `gh pr checkout` the PR there
+    async fn checkout_pr_in(&self, dir: &str, status: &CheckoutStatus) -> Result<()> {
+        if *status == CheckoutStatus::Missing {
+            self.send_event(RallyEvent::Log(format!(
+                "Cloning {} into {}",
+                self.repo, dir
+            )))
+            .await;
+            github::clone_repo(&self.repo, dir).await?;
+        }
+        github::checkout_pr(&self.repo, self.pr_number, dir).await
+    }
+
+    /// Fetch issues referenced from the PR body (e.g., "fixes #123") into the context
+    /// so the reviewer sees what the PR is meant to solve
+    async fn fetch_linked_issues(&mut self) {
+        let Some(ctx) = self.context.as_ref() else {
+            return;
+        };
+        if ctx.local_mode || !ctx.linked_issues.is_empty() {
+            return;
+        }
+
+        let numbers: Vec<u32> =
+            github::extract_issue_references(&self.repo, ctx.pr_body.as_deref().unwrap_or(""))
+                .into_iter()
+                .filter(|&number| number != self.pr_number)
+                .take(MAX_LINKED_ISSUES)
+                .collect();
+
+        let mut issues = Vec::new();
+        for number in numbers {
+            // #n can be a PR too; those and missing issues are skipped
+            match github::fetch_issue(&self.repo, number).await {
+                Ok(issue) if issue.is_pull_request() => {}
+                Ok(issue) => issues.push(LinkedIssue {
+                    number: issue.number,
+                    title: issue.title,
+                    body: issue.body.unwrap_or_default(),
+                }),
+                Err(e) => warn!("Failed to fetch linked issue #{}: {:#}", number, e),
+            }
+        }
+
+        if !issues.is_empty() {
+            self.send_event(RallyEvent::Log(format!(
+                "Fetched {} linked issue(s)",
+                issues.len()
+            )))
+            .await;
+        }
+        if let Some(ref mut ctx) = self.context {
+            ctx.linked_issues = issues;
+        }
+    }
+
+    /// Narrow the initial diff to the commits after `--since`, or after the head the
+    /// previous rally reviewed. Falls back to the full PR diff when that commit is no
+    /// longer an ancestor of the head (e.g., after a force-push).
+    async fn apply_incremental_diff(&mut self) {
+        let Some(ctx) = self.context.as_ref() else {
+            return;
+        };
+        if ctx.local_mode {
+            return;
+        }
+
+        let previous = match read_session(&self.repo, self.pr_number) {
+            Ok(session) => session.and_then(|s| s.last_reviewed_sha),
+            Err(e) => {
+                warn!("Failed to read previous rally session: {}", e);
+                None
+            }
+        };
+        // Keep the previous value until this rally reviews something itself
+        self.session.last_reviewed_sha = previous.clone();
+
+        let Some(since) = self.config.since_commit.clone().or(previous) else {
+            return;
+        };
+        let head_sha = ctx.head_sha.clone();
+        let short = &since[..since.len().min(7)];
+        if since == head_sha {
+            self.send_event(RallyEvent::Log(format!(
+                "No new commits since {}, reviewing the full diff",
+                short
+            )))
+            .await;
+            return;
+        }
+
+        let message = match github::fetch_compare_diff(&self.repo, &since, &head_sha).await {
+            Ok(Some(diff)) => {
+                if let Some(ref mut ctx) = self.context {
+                    // file_patches stay intact: comment positions are relative to the PR diff
+                    ctx.diff = diff;
+                }
+                format!("Reviewing only the changes since {}", short)
+            }
+            Ok(None) => format!(
+                "{} is not an ancestor of the PR head, reviewing the full diff",
+                short
+            ),
+            Err(e) => {
+                warn!("Failed to compare {}...{}: {:#}", since, head_sha, e);
+                format!(
+                    "Could not compare {} with the PR head, reviewing the full diff",
+                    short
+                )
+            }
+        };
+        self.send_event(RallyEvent::Log(message)).await;
+    }
+
+    /// Drop files matching `ignore_globs` or the repository's `.hxprignore` from the
+    /// diff the reviewer sees.
+    /// file_patches stay intact: they only place comments, and are not in the prompt.
+    /// In PR mode `.hxprignore` is only read from a checkout of the PR's repository,
+    /// like the file list does.
+    async fn apply_ignore_globs(&mut self) {
+        let Some(ctx) = self.context.as_ref() else {
+            return;
+        };
+        let repo_dir = PathBuf::from(ctx.working_dir.as_deref().unwrap_or("."));
+        let read_ignore_file = ctx.local_mode || {
+            let (dir, repo) = (repo_dir.clone(), self.repo.clone());
+            tokio::task::spawn_blocking(move || is_checkout_of(&dir, &repo))
+                .await
+                .unwrap_or(false)
+        };
+        self.ignore_rules = IgnoreRules::new(
+            &self.config.ignore_globs,
+            read_ignore_file.then_some(repo_dir.as_path()),
+        );
+        let Some(ref mut ctx) = self.context else {
+            return;
+        };
+        let (diff, skipped) = strip_ignored_files(&ctx.diff, &self.ignore_rules);
+        if skipped.is_empty() {
+            return;
+        }
+        ctx.diff = diff;
+        self.send_event(RallyEvent::Log(format!(
+            "{} file(s) skipped by ignore_globs/.hxprignore: {}",
+            skipped.len(),
+            skipped.join(", ")
+        )))
+        .await;
+    }
+
+    /// Update head_sha from PR
+    ///
+    /// Note: The reviewee does NOT push changes; commits are local only.
+    /// This update is for when the user manually pushes between iterations,
+    /// or when external tools/CI update the PR branch.
+    async fn update_head_sha(&mut self) -> Result<()> {
+        if self.context.as_ref().is_some_and(|c| c.local_mode) {
+            return Ok(());
+        }
+
+        let pr = github::fetch_pr(&self.repo, self.pr_number).await?;
+        if let Some(ref mut ctx) = self.context {
+            ctx.head_sha = pr.head.sha.clone();
+        }
+        Ok(())
+    }
+
+    /// Fetch current diff, preferring local git diff over GitHub API.
+    ///
+    /// This allows the reviewer to see uncommitted/unpushed changes made by the reviewee.
+    /// Falls back to GitHub API if local git diff fails or returns empty.
+    async fn fetch_current_diff(&self) -> Result<String> {
+        // ローカルモードでは git fetch をスキップし、直接 diff を取得
+        if let Some(ref ctx) = self.context {
+            if ctx.local_mode {
+                return self.fetch_local_working_diff(ctx).await;
+            }
+        }
+
+        // Timeout for git operations (30 seconds)
+        const GIT_TIMEOUT_SECS: u64 = 30;
+
+        // Try local git diff first if we have working_dir and base_branch
+        if let Some(ref ctx) = self.context {
+            if let Some(ref working_dir) = ctx.working_dir {
+                let base_branch = &ctx.base_branch;
+
+                // Fetch latest base branch reference to ensure accurate diff
+                // Use timeout to prevent hanging on slow remotes or credential prompts
+                let fetch_future = tokio::process::Command::new("git")
+                    .args(["fetch", "origin", base_branch])
+                    .current_dir(working_dir)
+                    .output();
+
+                match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), fetch_future).await {
+                    Ok(Ok(output)) if output.status.success() => {
+                        // Fetch succeeded
+                    }
+                    Ok(Ok(_)) => {
+                        warn!("git fetch failed, continuing with potentially stale ref");
+                    }
+                    Ok(Err(e)) => {
+                        warn!(
+                            "git fetch command failed: {}, continuing with potentially stale ref",
+                            e
+                        );
+                    }
+                    Err(_) => {
+                        warn!(
+                            "git fetch timed out after {} seconds, continuing with potentially stale ref",
+                            GIT_TIMEOUT_SECS
+                        );
+                    }
+                }
+
+                // Try git diff against origin/base_branch using merge-base (three-dot) comparison
+                // This matches GitHub PR diff semantics and avoids including unrelated base-branch changes
+                // Wrap in timeout to prevent hanging on network issues or auth prompts
+                let git_diff_future = tokio::process::Command::new("git")
+                    .args(["diff", &format!("origin/{}...HEAD", base_branch)])
+                    .current_dir(working_dir)
+                    .output();
+
+                match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await {
+                    Ok(Ok(output)) if output.status.success() => {
+                        let diff = String::from_utf8_lossy(&output.stdout).to_string();
+                        if !diff.trim().is_empty() {
+                            self.send_event(RallyEvent::Log(
+                                "Using local git diff for re-review".to_string(),
+                            ))
+                            .await;
+                            return Ok(diff);
+                        }
+                    }
+                    Ok(Ok(_)) => {
+                        // git diff failed, fall through to GitHub API
+                    }
+                    Ok(Err(e)) => {
+                        warn!("git diff command failed: {}", e);
+                    }
+                    Err(_) => {
+                        warn!(
+                            "git diff timed out after {} seconds, falling back to GitHub API",
+                            GIT_TIMEOUT_SECS
+                        );
+                    }
+                }
+
+                self.send_event(RallyEvent::Log(
+                    "Local git diff empty or failed, falling back to GitHub API".to_string(),
+                ))
+                .await;
+            }
+        }
+
+        // Fallback to GitHub API
+        github::fetch_pr_diff(&self.repo, self.pr_number).await
+    }
+
+    /// ローカルモード専用の diff 取得
+    ///
+    /// `git diff HEAD` を最優先し、working tree + staged の最新変更を取得。
+    /// 空の場合は `origin/{base}...HEAD` でコミット済み差分を試行。
+    /// どちらも空の場合は空文字列を返す（stale な初期 diff にフォールバックしない）。
+    async fn fetch_local_working_diff(&self, ctx: &super::adapter::Context) -> Result<String> {
+        const GIT_TIMEOUT_SECS: u64 = 30;
+
+        let working_dir = ctx.working_dir.as_deref().unwrap_or(".");
+        let base_branch = &ctx.base_branch;
+
+        // 1. git diff HEAD（working tree + staged の最新変更を優先）
+        let git_diff_future = tokio::process::Command::new("git")
+            .args(["diff", "HEAD"])
+            .current_dir(working_dir)
+            .output();
+
+        match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await {
+            Ok(Ok(output)) if output.status.success() => {
+                let diff = String::from_utf8_lossy(&output.stdout).to_string();
+                if !diff.trim().is_empty() {
+                    self.send_event(RallyEvent::Log(
+                        "Using local git diff HEAD for re-review".to_string(),
+                    ))
+                    .await;
+                    return Ok(diff);
+                }
+            }
+            _ => {}
+        }
+
+        // 2. Fallback: origin/{base}...HEAD（コミット済み差分）
+        let origin_ref = format!("origin/{}...HEAD", base_branch);
+        let git_diff_future = tokio::process::Command::new("git")
+            .args(["diff", &origin_ref])
+            .current_dir(working_dir)
+            .output();
+
+        if let Ok(Ok(output)) =
+            timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await
+        {
+            if output.status.success() {
+                let diff = String::from_utf8_lossy(&output.stdout).to_string();
+                if !diff.trim().is_empty() {
+                    self.send_event(RallyEvent::Log(
+                        "Using local git diff (origin base) for re-review".to_string(),
+                    ))
+                    .await;
+                    return Ok(diff);
+                }
+            }
+        }
+
+        // 両方空の場合は空文字列を返す（stale な ctx.diff にフォールバックしない）
+        self.send_event(RallyEvent::Log(
+            "Local diff is empty (no changes detected)".to_string(),
+        ))
+        .await;
+        Ok(String::new())
+    }
+
+    // For debugging and session inspection
+    #[allow(dead_code)]
+    pub fn session(&self) -> &RallySession {
+        &self.session
+    }
+}
+
+/// Check if a user is a bot
+fn is_bot_user(login: &str) -> bool {
+    BOT_SUFFIXES.iter().any(|suffix| login.ends_with(suffix)) || BOT_EXACT_MATCHES.contains(&login)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::mpsc;
+
+    const PLACEMENT_PATCH: &str =
+        "@@ -1,3 +1,4 @@\n line 1\n-old line 2\n+new line 2\n+added line\n line 3";
+
+    #[test]
+    fn test_place_review_comment_valid_line() {
+        assert_eq!(
+            place_review_comment(Some(PLACEMENT_PATCH), 2),
+            CommentPlacement::Inline {
+                line: 2,
+                side: DiffSide::Right,
+                position: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_place_review_comment_removed_line_goes_left() {
+        // New side has lines 10-11; old lines 11 and 12 were removed
+        let patch = "@@ -10,4 +10,2 @@\n line 10\n-old 11\n-old 12\n line 13";
+        assert_eq!(
+            place_review_comment(Some(patch), 12),
+            CommentPlacement::Inline {
+                line: 12,
+                side: DiffSide::Left,
+                position: 3
+            }
+        );
+        // A line that exists on the new side stays on the RIGHT
+        assert_eq!(
+            place_review_comment(Some(patch), 11),
+            CommentPlacement::Inline {
+                line: 11,
+                side: DiffSide::Right,
+                position: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_commentable_range() {
+        assert!(is_commentable_range(PLACEMENT_PATCH, 1, 3));
+        assert!(!is_commentable_range(PLACEMENT_PATCH, 3, 3));
+        // New lines 2-9 are not in the diff: the range spans two hunks
+        let two_hunks = "@@ -1,2 +1,2 @@\n line 1\n-a\n+b\n@@ -10,2 +10,2 @@\n line 10\n-c\n+d";
+        assert!(is_commentable_range(two_hunks, 10, 11));
+        assert!(!is_commentable_range(two_hunks, 1, 11));
+    }
+
+    #[test]
+    fn test_place_review_comment_line_zero_is_conversation() {
+        assert_eq!(
+            place_review_comment(Some(PLACEMENT_PATCH), 0),
+            CommentPlacement::Conversation
+        );
+        // Line 0 is about the file as a whole, even if it is not part of the diff
+        assert_eq!(
+            place_review_comment(None, 0),
+            CommentPlacement::Conversation
+        );
+    }
+
+    #[test]
+    fn test_place_review_comment_out_of_range_nearby_is_relocated() {
+        // Last commentable line is 4 (position 5); line 6 is within the relocation distance
+        assert_eq!(
+            place_review_comment(Some(PLACEMENT_PATCH), 6),
+            CommentPlacement::Inline {
+                line: 4,
+                side: DiffSide::Right,
+                position: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_place_review_comment_out_of_range_far_is_general() {
+        assert_eq!(
+            place_review_comment(Some(PLACEMENT_PATCH), 500),
+            CommentPlacement::General
+        );
+    }
+
+    #[test]
+    fn test_place_review_comment_missing_patch_is_skipped() {
+        assert_eq!(place_review_comment(None, 2), CommentPlacement::Skip);
+    }
+
+    #[test]
+    fn test_format_general_comments() {
+        let line_zero = ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 0,
+            start_line: None,
+            body: "Missing module docs".to_string(),
+            severity: crate::ai::adapter::CommentSeverity::Minor,
+        };
+        let far = ReviewComment {
+            path: "src/main.rs".to_string(),
+            line: 500,
+            start_line: None,
+            body: "Unused import".to_string(),
+            severity: crate::ai::adapter::CommentSeverity::Suggestion,
+        };
+        assert_eq!(
+            format_general_comments("Comments outside the diff", &[&line_zero, &far]),
+            "**Comments outside the diff:**\n- `src/lib.rs`: Missing module docs\n- `src/main.rs:500`: Unused import"
+        );
+    }
+
+    #[test]
+    fn test_review_post_attempts() {
+        use crate::app::ReviewAction::{Approve, Comment, RequestChanges};
+        // A rejected inline comment retries the same action without inline comments
+        assert_eq!(
+            review_post_attempts(RequestChanges, true),
+            vec![(RequestChanges, true), (RequestChanges, false)]
+        );
+        // The comment fallback tries the inline comments again before dropping them
+        assert_eq!(
+            review_post_attempts(Approve, true),
+            vec![
+                (Approve, true),
+                (Approve, false),
+                (Comment, true),
+                (Comment, false)
+            ]
+        );
+        assert_eq!(
+            review_post_attempts(Approve, false),
+            vec![(Approve, false), (Comment, false)]
+        );
+    }
+
+    #[test]
+    fn test_format_conversation_comment() {
+        let comment = |path: &str| ReviewComment {
+            path: path.to_string(),
+            line: 0,
+            start_line: None,
+            body: "The overall approach needs rethinking".to_string(),
+            severity: crate::ai::adapter::CommentSeverity::Major,
+        };
+        assert_eq!(
+            format_conversation_comment(&comment("")),
+            "[AI Rally - Reviewer] (major)\n\nThe overall approach needs rethinking"
+        );
+        assert_eq!(
+            format_conversation_comment(&comment("src/lib.rs")),
+            "[AI Rally - Reviewer] (major)\n\n`src/lib.rs`: The overall approach needs rethinking"
+        );
+    }
+
+    #[test]
+    fn test_format_unplaced_comments() {
+        let comment = |path: &str, line| ReviewComment {
+            path: path.to_string(),
+            line,
+            start_line: None,
+            body: "Fix this".to_string(),
+            severity: crate::ai::adapter::CommentSeverity::Minor,
+        };
+        let (inline, far, missing) = (
+            comment("src/lib.rs", 2),
+            comment("src/lib.rs", 500),
+            comment("src/gone.rs", 1),
+        );
+        let placements = [
+            (&inline, place_review_comment(Some(PLACEMENT_PATCH), 2)),
+            (&far, place_review_comment(Some(PLACEMENT_PATCH), 500)),
+            (&missing, place_review_comment(None, 1)),
+        ];
+        assert_eq!(
+            format_unplaced_comments(&placements).unwrap(),
+            "Warning: 2 comment(s) could not be placed on the diff:\n\
+             - src/lib.rs:500 (not on the diff, added to the summary)\n\
+             - src/gone.rs:1 (file is not part of the diff, skipped)"
+        );
+        assert_eq!(format_unplaced_comments(&placements[..1]), None);
+    }
+
+    #[test]
+    fn test_format_dry_run_review() {
+        let drafts = vec![DraftReviewComment {
+            path: "src/main.rs".to_string(),
+            position: 4,
+            body: "[AI Rally - Reviewer] (major)\n\nHandle the error".to_string(),
+            range: None,
+        }];
+        assert_snapshot!(
+            format_dry_run_review(
+                crate::app::ReviewAction::RequestChanges,
+                "[AI Rally - Reviewer]\n\nNeeds work",
+                &drafts,
+            ),
+            @r"
+        [dry-run] Would post review (RequestChanges) with 1 inline comment(s):
+        [AI Rally - Reviewer]
+
+        Needs work
+
+        --- src/main.rs (position 4) ---
+        [AI Rally - Reviewer] (major)
+
+        Handle the error
+        "
+        );
+    }
+
+    #[test]
+    fn test_format_reasoning_details() {
+        assert_eq!(
+            format_reasoning_details(Some("Checked error paths first.\n")).unwrap(),
+            "<details>\n<summary>Reviewer reasoning</summary>\n\nChecked error paths first.\n\n</details>"
+        );
+    }
+
+    #[test]
+    fn test_format_reasoning_details_omitted_without_reasoning() {
+        assert_eq!(format_reasoning_details(None), None);
+        assert_eq!(format_reasoning_details(Some("  \n")), None);
+    }
+
+    #[test]
+    fn test_addressed_threads_only_matches_open_reviewer_threads_on_modified_files() {
+        let thread = |id: &str, path: &str, is_resolved: bool, body: &str| ReviewThread {
+            id: id.to_string(),
+            path: path.to_string(),
+            is_resolved,
+            body: body.to_string(),
+            comment_id: None,
+            line: None,
+            start_line: None,
+        };
+        let header = CommentSeverity::Major.posted_comment_header();
+        let threads = vec![
+            thread("t1", "src/a.rs", false, &format!("{}\n\nFix this", header)),
+            thread("t2", "src/a.rs", true, &format!("{}\n\nDone", header)),
+            thread("t3", "src/a.rs", false, "Human comment"),
+            thread("t4", "src/b.rs", false, &format!("{}\n\nUntouched", header)),
+        ];
+
+        let ids: Vec<&str> = addressed_threads(&threads, &["./src/a.rs".to_string()])
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["t1"]);
+    }
+
+    #[test]
+    fn test_reply_targets_only_include_threads_on_changed_lines() {
+        let thread = |id: &str, line: Option<u32>, start_line: Option<u32>| ReviewThread {
+            id: id.to_string(),
+            path: "src/a.rs".to_string(),
+            is_resolved: false,
+            body: String::new(),
+            comment_id: Some(1),
+            line,
+            start_line,
+        };
+        let threads = [
+            thread("single", Some(5), None),
+            thread("range", Some(22), Some(18)),
+            thread("untouched", Some(40), None),
+            thread("outdated", None, None),
+        ];
+        let addressed: Vec<&ReviewThread> = threads.iter().collect();
+        let changed_lines = HashMap::from([("src/a.rs".to_string(), vec![(5, 6), (15, 18)])]);
+
+        let ids: Vec<&str> = reply_targets(true, &addressed, &changed_lines)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["single", "range"]);
+    }
+
+    #[test]
+    fn test_reply_targets_fall_back_to_top_level_note() {
+        let thread = ReviewThread {
+            id: "t1".to_string(),
+            path: "src/a.rs".to_string(),
+            is_resolved: false,
+            body: String::new(),
+            comment_id: Some(1),
+            line: Some(40),
+            start_line: None,
+        };
+        let addressed = vec![&thread];
+
+        // The file was modified, but not around the commented line
+        let elsewhere = HashMap::from([("src/a.rs".to_string(), vec![(1, 3)])]);
+        assert!(reply_targets(true, &addressed, &elsewhere).is_empty());
+        // Line changes unknown (e.g. not a git checkout)
+        assert!(reply_targets(true, &addressed, &HashMap::new()).is_empty());
+        // Replies disabled
+        let touched = HashMap::from([("src/a.rs".to_string(), vec![(40, 40)])]);
+        assert!(reply_targets(false, &addressed, &touched).is_empty());
+        assert_eq!(reply_targets(true, &addressed, &touched).len(), 1);
+    }
+
+    #[test]
+    fn test_format_thread_reply() {
+        assert_eq!(
+            format_thread_reply("src/a.rs", "Handled the error"),
+            "[AI Rally - Reviewee]\n\nUpdated `src/a.rs` in response to this comment.\n\nHandled the error"
+        );
+    }
+
+    /// Reviewee mock that records its continuations in a shared log
+    struct ContinuationCounter {
+        continuations: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for ContinuationCounter {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, message: &str) -> Result<RevieweeOutput> {
+            self.continuations.lock().unwrap().push(message.to_string());
+            Ok(RevieweeOutput {
+                summary: "Fixed lint".to_string(),
+                files_modified: vec!["src/main.rs".to_string()],
+                ..empty_fix()
+            })
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    /// Run `post_fix_command` on a completed fix with a [`ContinuationCounter`]
+    /// reviewee; returns the resulting fix and the reviewee's continuations
+    async fn run_post_fix(
+        repo: &str,
+        command: &str,
+        timeout_secs: u64,
+    ) -> (RevieweeOutput, Vec<String>) {
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            post_fix_command: Some(command.to_string()),
+            post_fix_timeout_secs: timeout_secs,
+            ..AiConfig::default()
+        };
+        let (tx, _rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, None).unwrap();
+        let continuations = Arc::new(Mutex::new(Vec::new()));
+        orchestrator.reviewee_adapter = Box::new(ContinuationCounter {
+            continuations: continuations.clone(),
+        });
+        let context = Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        };
+        orchestrator.set_context(context.clone());
+
+        let fix = orchestrator
+            .apply_post_fix_check(&context, 1, empty_fix())
+            .await;
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        let continuations = continuations.lock().unwrap().clone();
+        (fix, continuations)
+    }
+
+    #[tokio::test]
+    async fn test_failing_post_fix_check_continues_reviewee() {
+        let (follow_up, continuations) = run_post_fix(
+            "octorus-test/post-fix-fails",
+            "echo 'src/main.rs: missing semicolon'; exit 1",
+            300,
+        )
+        .await;
+
+        assert_eq!(follow_up.summary, "Fixed lint");
+        assert_eq!(continuations.len(), 1);
+        assert!(continuations[0].contains("src/main.rs: missing semicolon"));
+    }
+
+    #[tokio::test]
+    async fn test_passing_post_fix_check_does_not_continue_reviewee() {
+        let (fix, continuations) = run_post_fix("octorus-test/post-fix-passes", "true", 300).await;
+
+        assert_eq!(fix.summary, empty_fix().summary);
+        assert!(continuations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_post_fix_check_timeout_keeps_the_fix() {
+        let (fix, continuations) =
+            run_post_fix("octorus-test/post-fix-timeout", "sleep 5", 1).await;
+
+        assert_eq!(fix.summary, empty_fix().summary);
+        assert!(continuations.is_empty());
+    }
+
+    /// Reviewer mock whose first response fails schema validation
+    struct SchemaViolatingReviewer {
+        continuations: Vec<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for SchemaViolatingReviewer {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            Err(SchemaValidationError {
+                agent: "mock".to_string(),
+                role: "reviewer",
+                errors: vec!["/action: \"reject\" is not one of the allowed values".to_string()],
+            }
+            .into())
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewer(&mut self, message: &str) -> Result<ReviewerOutput> {
+            self.continuations.push(message.to_string());
+            Ok(approving_review())
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    #[tokio::test]
+    async fn test_schema_violation_reprompts_reviewer_once() {
+        let mut reviewer = SchemaViolatingReviewer {
+            continuations: vec![],
+        };
+        let (tx, mut rx) = mpsc::channel(10);
+        let review = run_reviewer_with_correction(
+            &mut reviewer,
+            "prompt",
+            &two_file_context(),
+            OutputSchemas::default().reviewer(),
+            &tx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(review.action, ReviewAction::Approve);
+        assert_eq!(reviewer.continuations.len(), 1);
+        assert!(reviewer.continuations[0].contains("- /action: \"reject\""));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(RallyEvent::Log(msg)) if msg.starts_with("Reviewer output could not be parsed")
+        ));
+    }
+
+    #[test]
+    fn test_output_correction_errors_only_for_unusable_output() {
+        let not_json = anyhow::Error::from(OutputParseError(
+            "Agent response is not valid JSON: expected value at line 1 column 1".to_string(),
+        ))
+        .context("gemini reviewer");
+        assert_eq!(
+            output_correction_errors(&not_json),
+            Some(vec![
+                "Agent response is not valid JSON: expected value at line 1 column 1".to_string()
+            ])
+        );
+        assert_eq!(
+            output_correction_errors(&anyhow!("Reviewer timeout after 600 seconds")),
+            None
+        );
+    }
+
+    fn approving_review() -> ReviewerOutput {
+        ReviewerOutput {
+            action: ReviewAction::Approve,
+            summary: "LGTM".to_string(),
+            comments: vec![],
+            blocking_issues: vec![],
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn test_severity_threshold_decides_action() {
+        let comment = |severity| ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 3,
+            start_line: None,
+            body: "Check this".to_string(),
+            severity,
+        };
+        let review = |action, severity| ReviewerOutput {
+            action,
+            comments: vec![comment(severity)],
+            ..approving_review()
+        };
+
+        // A major comment blocks an approval when the threshold is major
+        let decided = apply_severity_threshold(
+            review(ReviewAction::Approve, CommentSeverity::Major),
+            CommentSeverity::Major,
+        );
+        assert_eq!(decided.action, ReviewAction::RequestChanges);
+
+        // Below the threshold the reviewer's action is kept
+        let decided = apply_severity_threshold(
+            review(ReviewAction::Approve, CommentSeverity::Minor),
+            CommentSeverity::Major,
+        );
+        assert_eq!(decided.action, ReviewAction::Approve);
+        let decided = apply_severity_threshold(
+            review(ReviewAction::RequestChanges, CommentSeverity::Minor),
+            CommentSeverity::Major,
+        );
+        assert_eq!(decided.action, ReviewAction::RequestChanges);
+
+        // Requested changes with no comments are not turned into an approval
+        let decided = apply_severity_threshold(
+            ReviewerOutput {
+                action: ReviewAction::RequestChanges,
+                ..approving_review()
+            },
+            CommentSeverity::Major,
+        );
+        assert_eq!(decided.action, ReviewAction::RequestChanges);
+
+        // Blocking issues still request changes
+        let mut blocked = review(ReviewAction::Approve, CommentSeverity::Suggestion);
+        blocked.blocking_issues = vec!["Tests are missing".to_string()];
+        assert_eq!(
+            apply_severity_threshold(blocked, CommentSeverity::Critical).action,
+            ReviewAction::RequestChanges
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_verify_withholds_approval() {
+        let review = verify_approval(
+            approving_review(),
+            "echo 'test result: FAILED'; exit 101",
+            None,
+            60,
+        )
+        .await;
+
+        // Not an approval, so the rally runs another reviewee fix iteration
+        assert_eq!(review.action, ReviewAction::RequestChanges);
+        assert_eq!(review.blocking_issues.len(), 1);
+        assert!(review.blocking_issues[0].contains("test result: FAILED"));
+    }
+
+    #[tokio::test]
+    async fn test_long_verify_output_keeps_its_end() {
+        let review = verify_approval(approving_review(), "seq 1 5000; exit 1", None, 60).await;
+
+        let issue = &review.blocking_issues[0];
+        assert!(issue.len() < CHECK_OUTPUT_LIMIT + 200);
+        assert!(issue.contains("earlier characters omitted"));
+        assert!(issue.contains("\n5000\n"));
+    }
+
+    #[tokio::test]
+    async fn test_passing_verify_keeps_approval() {
+        let review = verify_approval(approving_review(), "true", None, 60).await;
+        assert_eq!(review.action, ReviewAction::Approve);
+        assert!(review.blocking_issues.is_empty());
+    }
+
+    #[test]
+    fn test_auto_merge_blocker_allows_clean_approval() {
+        let mut review = approving_review();
+        review.comments.push(ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "Nit".to_string(),
+            severity: CommentSeverity::Suggestion,
+        });
+        assert_eq!(
+            auto_merge_blocker(&review, Some(ReviewAction::Approve), false, false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_auto_merge_blocker_conditions() {
+        let review = approving_review();
+        let approved = Some(ReviewAction::Approve);
+        assert!(auto_merge_blocker(&review, approved, true, false).is_some());
+        assert!(auto_merge_blocker(&review, approved, false, true).is_some());
+        // Declined, dry-run, staged as pending, failed, or downgraded to a comment
+        assert!(auto_merge_blocker(&review, None, false, false).is_some());
+        assert!(auto_merge_blocker(&review, Some(ReviewAction::Comment), false, false).is_some());
+
+        let not_approved = ReviewerOutput {
+            action: ReviewAction::Comment,
+            ..approving_review()
+        };
+        assert!(auto_merge_blocker(&not_approved, approved, false, false).is_some());
+
+        let blocking = ReviewerOutput {
+            blocking_issues: vec!["Missing test".to_string()],
+            ..approving_review()
+        };
+        assert!(auto_merge_blocker(&blocking, approved, false, false).is_some());
+
+        let mut major = approving_review();
+        major.comments.push(ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "Leaks a file handle".to_string(),
+            severity: CommentSeverity::Major,
+        });
+        assert!(auto_merge_blocker(&major, approved, false, false).is_some());
+    }
+
+    #[test]
+    fn test_describe_merge_outlook() {
+        let protection = BranchProtection {
+            required_checks: vec!["ci/test".to_string()],
+            required_approvals: 2,
+        };
+        let outlook =
+            |state: &str, protection: Option<&BranchProtection>, approvals, unpassed: &[&str]| {
+                let unpassed: Vec<String> = unpassed.iter().map(|s| s.to_string()).collect();
+                describe_merge_outlook(state, "main", protection, approvals, None, &unpassed)
+            };
+
+        // The reviewer's approval is the missing one
+        let (unblocks, detail) = outlook("blocked", Some(&protection), 1, &[]);
+        assert!(unblocks);
+        assert_eq!(
+            detail,
+            "Approving should unblock the merge (2/2 approvals, required checks passing)"
+        );
+
+        let (unblocks, detail) = outlook("blocked", Some(&protection), 0, &["ci/test"]);
+        assert!(!unblocks);
+        assert_eq!(
+            detail,
+            "Merge still blocked: 2 approvals required (1 with this one); required checks not passing: ci/test"
+        );
+
+        assert!(outlook("clean", None, 0, &[]).0);
+        assert!(!outlook("dirty", Some(&protection), 5, &[]).0);
+        // Unreadable protection, or blocked by something approvals can't fix
+        assert!(!outlook("blocked", None, 1, &[]).0);
+        assert!(!outlook("blocked", Some(&protection), 2, &[]).0);
+
+        // The author's own approval (or a second one from the same user) adds nothing
+        let (unblocks, detail) = describe_merge_outlook(
+            "blocked",
+            "main",
+            Some(&protection),
+            1,
+            Some("you are the PR author"),
+            &[],
+        );
+        assert!(!unblocks);
+        assert_eq!(
+            detail,
+            "Merge still blocked: 2 approvals required (1 so far, and this one does not count: you are the PR author)"
+        );
+    }
+
+    fn two_file_context() -> Context {
+        Context {
+            repo: "owner/repo".to_string(),
+            pr_number: 1,
+            pr_title: "Title".to_string(),
+            pr_body: None,
+            diff: "@@ -1 +1 @@\n-a\n+b\n@@ -1 +1 @@\n-c\n+d".to_string(),
+            working_dir: None,
+            head_sha: "abc".to_string(),
+            base_branch: "main".to_string(),
+            external_comments: vec![],
+            local_mode: false,
+            file_patches: vec![
+                ("src/a.rs".to_string(), "@@ -1 +1 @@\n-a\n+b".to_string()),
+                ("src/b.rs".to_string(), "@@ -1 +1 @@\n-c\n+d".to_string()),
+            ],
+            linked_issues: vec![],
+        }
+    }
+
+    #[test]
+    fn test_scope_context_to_file_uses_only_selected_file_diff() {
+        let current_diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+fixed\ndiff --git a/src/b.rs b/src/b.rs\n--- a/src/b.rs\n+++ b/src/b.rs\n@@ -1 +1 @@\n-c\n+d";
+        let scoped =
+            scope_context_to_file(&two_file_context(), "src/a.rs", Some(current_diff)).unwrap();
+
+        assert_eq!(
+            scoped.diff,
+            "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+fixed"
+        );
+        assert!(!scoped.diff.contains("src/b.rs"));
+        assert_eq!(scoped.file_patches.len(), 1);
+        assert_eq!(scoped.file_patches[0].0, "src/a.rs");
+    }
+
+    #[test]
+    fn test_scope_context_to_file_falls_back_to_pr_patch() {
+        let scoped = scope_context_to_file(&two_file_context(), "src/b.rs", None).unwrap();
+        assert_eq!(scoped.diff, "@@ -1 +1 @@\n-c\n+d");
+        assert!(scope_context_to_file(&two_file_context(), "src/missing.rs", None).is_none());
+    }
+
+    fn empty_fix() -> RevieweeOutput {
+        RevieweeOutput {
+            status: RevieweeStatus::Completed,
+            summary: "Nothing to fix".to_string(),
+            files_modified: vec![],
+            question: None,
+            permission_request: None,
+            error_details: None,
+            diff_stats: Vec::new(),
+        }
+    }
+
+    /// Agent mock that replays scripted reviews, always completes its fixes, and
+    /// records every turn ("reviewer: <prompt>" / "reviewee: <prompt>") in a shared log
+    struct ScriptedAgent {
+        reviews: VecDeque<ReviewerOutput>,
+        turns: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for ScriptedAgent {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            self.turns
+                .lock()
+                .unwrap()
+                .push(format!("reviewer: {}", prompt));
+            self.reviews
+                .pop_front()
+                .ok_or_else(|| anyhow!("no scripted review left"))
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            self.turns
+                .lock()
+                .unwrap()
+                .push(format!("reviewee: {}", prompt));
+            Ok(RevieweeOutput {
+                summary: "Fixed the reported issue".to_string(),
+                files_modified: vec!["src/a.rs".to_string()],
+                ..empty_fix()
+            })
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            // Simulates an agent that never answers a continuation
+            std::future::pending().await
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    fn requesting_changes() -> ReviewerOutput {
+        ReviewerOutput {
+            action: ReviewAction::RequestChanges,
+            summary: "Still broken".to_string(),
+            blocking_issues: vec!["Handle the error".to_string()],
+            ..approving_review()
+        }
+    }
+
+    /// Run a local-mode rally with scripted agents; returns the result, the turn log
+    /// and every event sent
+    async fn run_scripted_rally(
+        repo: &str,
+        reviews: Vec<ReviewerOutput>,
+        config: AiConfig,
+        context: Context,
+    ) -> (RallyResult, Vec<String>, Vec<RallyEvent>) {
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            ..config
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, None).unwrap();
+
+        let turns = Arc::new(Mutex::new(Vec::new()));
+        orchestrator.reviewer_adapter = Box::new(ScriptedAgent {
+            reviews: reviews.into(),
+            turns: turns.clone(),
+        });
+        orchestrator.reviewee_adapter = Box::new(ScriptedAgent {
+            reviews: Default::default(),
+            turns: turns.clone(),
+        });
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..context
+        });
+
+        let result = orchestrator.run().await.unwrap();
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        drop(orchestrator);
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        let turns = turns.lock().unwrap().clone();
+        (result, turns, events)
+    }
+
+    #[tokio::test]
+    async fn test_run_reviewee_for_addresses_only_selected_comments() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            ..AiConfig::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let mut orchestrator =
+            Orchestrator::new("octorus-test/targeted-reviewee", 1, config, tx, None).unwrap();
+        let turns = Arc::new(Mutex::new(Vec::new()));
+        orchestrator.reviewer_adapter = Box::new(ScriptedAgent {
+            reviews: Default::default(),
+            turns: turns.clone(),
+        });
+        orchestrator.reviewee_adapter = Box::new(ScriptedAgent {
+            reviews: Default::default(),
+            turns: turns.clone(),
+        });
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let selected = ReviewComment {
+            path: "src/b.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "Rename this variable".to_string(),
+            severity: CommentSeverity::Minor,
+        };
+        let fix = orchestrator.run_reviewee_for(vec![selected]).await.unwrap();
+        assert_eq!(fix.summary, "Fixed the reported issue");
+        drop(orchestrator);
+
+        // Only the reviewee ran, on a prompt listing just the selected comment
+        let turns = turns.lock().unwrap().clone();
+        assert_eq!(turns.len(), 1);
+        assert!(turns[0].starts_with("reviewee: "));
+        assert!(turns[0].contains("src/b.rs:1: Rename this variable"));
+        assert!(!turns[0].contains("src/a.rs:"));
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(matches!(
+            &events[events.len() - 2..],
+            [
+                RallyEvent::FixCompleted(_),
+                RallyEvent::StateChanged(RallyState::Completed)
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_reviewee_for_fails_when_the_reviewee_has_a_question() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            ..AiConfig::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let mut orchestrator =
+            Orchestrator::new("octorus-test/targeted-question", 1, config, tx, None).unwrap();
+        orchestrator.reviewee_adapter = Box::new(AskingReviewee);
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let comment = ReviewComment {
+            path: "src/b.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "Rename this variable".to_string(),
+            severity: CommentSeverity::Minor,
+        };
+        let err = orchestrator
+            .run_reviewee_for(vec![comment])
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Which name should it use?"));
+        drop(orchestrator);
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, RallyEvent::FixCompleted(_))));
+        assert!(matches!(
+            events.last(),
+            Some(RallyEvent::StateChanged(RallyState::Error))
+        ));
+    }
+
+    /// Reviewee that asks a question instead of fixing anything
+    struct AskingReviewee;
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for AskingReviewee {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            Ok(RevieweeOutput {
+                status: RevieweeStatus::NeedsClarification,
+                summary: "Need a name".to_string(),
+                question: Some("Which name should it use?".to_string()),
+                ..empty_fix()
+            })
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    #[tokio::test]
+    async fn test_completed_fix_is_approved_only_by_a_re_review() {
+        let (result, turns, events) = run_scripted_rally(
+            "octorus-test/rereview-approves",
+            vec![requesting_changes(), approving_review()],
+            AiConfig::default(),
+            two_file_context(),
+        )
+        .await;
+
+        assert!(matches!(result, RallyResult::Approved { iteration: 2, .. }));
+        let kinds: Vec<&str> = turns.iter().map(|t| &t[..8]).collect();
+        assert_eq!(kinds, vec!["reviewer", "reviewee", "reviewer"]);
+        // The approving review was a re-review of the reviewee's changes
+        assert!(turns[2].contains("Fixed the reported issue"));
+
+        // Approved follows the re-review, never the reviewee's completion
+        let approved = events
+            .iter()
+            .position(|e| matches!(e, RallyEvent::Approved(_)))
+            .unwrap();
+        assert!(matches!(
+            events[..approved]
+                .iter()
+                .rfind(|e| matches!(e, RallyEvent::ReviewCompleted(_) | RallyEvent::FixCompleted(_))),
+            Some(RallyEvent::ReviewCompleted(review)) if review.action == ReviewAction::Approve
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_review_is_reported_after_verification() {
+        let (result, _, events) = run_scripted_rally(
+            "octorus-test/verify-before-report",
+            vec![approving_review()],
+            AiConfig {
+                max_iterations: 1,
+                verify_command: Some("exit 1".to_string()),
+                ..AiConfig::default()
+            },
+            two_file_context(),
+        )
+        .await;
+
+        // The UI never saw the approval the verify command withheld
+        assert!(matches!(
+            result,
+            RallyResult::MaxIterationsReached { iteration: 1 }
+        ));
+        let reviews: Vec<ReviewAction> = events
+            .iter()
+            .filter_map(|e| match e {
+                RallyEvent::ReviewCompleted(review) => Some(review.action),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reviews, vec![ReviewAction::RequestChanges]);
+    }
+
+    #[tokio::test]
+    async fn test_completed_fix_keeps_iterating_while_reviewer_requests_changes() {
+        let (result, turns, events) = run_scripted_rally(
+            "octorus-test/rereview-rejects",
+            vec![requesting_changes(); 3],
+            AiConfig {
+                max_iterations: 3,
+                ..AiConfig::default()
+            },
+            two_file_context(),
+        )
+        .await;
+
+        // Every reviewee completion was re-reviewed, and none was approved
+        assert!(matches!(
+            result,
+            RallyResult::MaxIterationsReached { iteration: 3 }
+        ));
+        let kinds: Vec<&str> = turns.iter().map(|t| &t[..8]).collect();
+        assert_eq!(
+            kinds,
+            vec!["reviewer", "reviewee", "reviewer", "reviewee", "reviewer", "reviewee"]
+        );
+        assert!(!events.iter().any(|e| matches!(e, RallyEvent::Approved(_))));
+    }
+
+    /// Reviewer whose first turn fails and every later turn approves
+    struct FlakyReviewer {
+        calls: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for FlakyReviewer {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            self.calls += 1;
+            if self.calls == 1 {
+                return Err(anyhow!("agent crashed"));
+            }
+            Ok(approving_review())
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
+
+    async fn run_flaky_rally(
+        repo: &str,
+        command_receiver: Option<mpsc::Receiver<OrchestratorCommand>>,
+    ) -> (Result<RallyResult>, Vec<RallyEvent>) {
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            ..AiConfig::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, command_receiver).unwrap();
+        orchestrator.reviewer_adapter = Box::new(FlakyReviewer { calls: 0 });
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let result = orchestrator.run().await;
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        drop(orchestrator);
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        (result, events)
+    }
+
+    /// Reviewee whose fix completes but whose post-fix follow-up reports an error
+    struct FailingFollowUpReviewee;
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for FailingFollowUpReviewee {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            Ok(RevieweeOutput {
+                summary: "Fixed the reported issue".to_string(),
+                files_modified: vec!["src/a.rs".to_string()],
+                ..empty_fix()
+            })
+        }
+
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
+
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            Ok(RevieweeOutput {
+                status: RevieweeStatus::Error,
+                summary: "Could not fix the lint".to_string(),
+                error_details: Some("cargo not found".to_string()),
+                ..empty_fix()
+            })
+        }
+
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_post_fix_follow_up_error_is_reported_as_turn_error() {
+        let repo = "octorus-test/post-fix-follow-up-error";
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            post_fix_command: Some("exit 1".to_string()),
+            ..AiConfig::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, None).unwrap();
+        orchestrator.reviewer_adapter = Box::new(ScriptedAgent {
+            reviews: vec![requesting_changes()].into(),
+            turns: Default::default(),
+        });
+        orchestrator.reviewee_adapter = Box::new(FailingFollowUpReviewee);
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let result = orchestrator.run().await.unwrap();
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        drop(orchestrator);
+
+        // Without a TUI to retry, the follow-up's error ends the rally
+        assert!(matches!(
+            result,
+            RallyResult::Error { iteration: 1, ref error } if error == "cargo not found"
+        ));
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(matches!(
+            events.last(),
+            Some(RallyEvent::StateChanged(RallyState::Error))
+        ));
     }
 
-    /// Fetch external comments from bots (Copilot, CodeRabbit, etc.)
-    async fn fetch_external_comments(&self) -> Vec<ExternalComment> {
-        if self.context.as_ref().is_some_and(|c| c.local_mode) {
-            return Vec::new();
+    /// Codex-like reviewee whose sandbox blocks every turn until permission is granted
+    struct SandboxedReviewee {
+        granted: bool,
+        turns: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AgentAdapter for SandboxedReviewee {
+        fn name(&self) -> &str {
+            "mock"
         }
 
-        let mut comments = Vec::new();
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
 
-        // Fetch review comments (inline comments on diff)
-        if let Ok(review_comments) = fetch_review_comments(&self.repo, self.pr_number).await {
-            for c in review_comments {
-                if is_bot_user(&c.user.login) {
-                    comments.push(ExternalComment {
-                        source: c.user.login.clone(),
-                        path: Some(c.path.clone()),
-                        line: c.line,
-                        body: c.body.clone(),
-                    });
-                }
-            }
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
+
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
         }
 
-        // Fetch discussion comments (general PR comments)
-        if let Ok(discussion) = fetch_discussion_comments(&self.repo, self.pr_number).await {
-            for c in discussion {
-                if is_bot_user(&c.user.login) {
-                    comments.push(ExternalComment {
-                        source: c.user.login.clone(),
-                        path: None,
-                        line: None,
-                        body: c.body.clone(),
-                    });
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            self.turns.lock().unwrap().push("run".to_string());
+            if !self.granted {
+                return Err(CodexError::SandboxDenied {
+                    command: "npm install".to_string(),
                 }
+                .into());
             }
+            Ok(RevieweeOutput {
+                summary: "Fixed the reported issue".to_string(),
+                files_modified: vec!["src/a.rs".to_string()],
+                ..empty_fix()
+            })
         }
 
-        // Limit the number of comments
-        comments.truncate(MAX_EXTERNAL_COMMENTS);
-        comments
-    }
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
 
-    /// Update head_sha from PR
-    ///
-    /// Note: The reviewee does NOT push changes; commits are local only.
-    /// This update is for when the user manually pushes between iterations,
-    /// or when external tools/CI update the PR branch.
-    async fn update_head_sha(&mut self) -> Result<()> {
-        if self.context.as_ref().is_some_and(|c| c.local_mode) {
-            return Ok(());
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            self.turns.lock().unwrap().push("continue".to_string());
+            Err(anyhow!("No reviewee session to continue"))
         }
 
-        let pr = github::fetch_pr(&self.repo, self.pr_number).await?;
-        if let Some(ref mut ctx) = self.context {
-            ctx.head_sha = pr.head.sha.clone();
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {
+            self.granted = true;
         }
-        Ok(())
     }
 
-    /// Fetch current diff, preferring local git diff over GitHub API.
-    ///
-    /// This allows the reviewer to see uncommitted/unpushed changes made by the reviewee.
-    /// Falls back to GitHub API if local git diff fails or returns empty.
-    async fn fetch_current_diff(&self) -> Result<String> {
-        // ローカルモードでは git fetch をスキップし、直接 diff を取得
-        if let Some(ref ctx) = self.context {
-            if ctx.local_mode {
-                return self.fetch_local_working_diff(ctx).await;
+    #[tokio::test]
+    async fn test_sandbox_grant_reruns_the_denied_turn() {
+        let repo = "octorus-test/sandbox-grant-rerun";
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            ..AiConfig::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1000);
+        let (cmd_tx, cmd_rx) = mpsc::channel(10);
+        // Grants the permission once asked, like the TUI
+        let tui = tokio::spawn(async move {
+            let mut asked = Vec::new();
+            while let Some(event) = rx.recv().await {
+                if let RallyEvent::PermissionNeeded(action, _) = event {
+                    asked.push(action);
+                    cmd_tx
+                        .send(OrchestratorCommand::PermissionResponse(true))
+                        .await
+                        .unwrap();
+                }
             }
-        }
+            asked
+        });
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, Some(cmd_rx)).unwrap();
+        orchestrator.reviewer_adapter = Box::new(ScriptedAgent {
+            reviews: vec![requesting_changes(), approving_review()].into(),
+            turns: Default::default(),
+        });
+        let turns = Arc::new(Mutex::new(Vec::new()));
+        orchestrator.reviewee_adapter = Box::new(SandboxedReviewee {
+            granted: false,
+            turns: turns.clone(),
+        });
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let result = orchestrator.run().await.unwrap();
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        drop(orchestrator);
+
+        // The first turn had no session to continue, so it was run again from scratch
+        assert!(matches!(result, RallyResult::Approved { iteration: 2, .. }));
+        assert_eq!(*turns.lock().unwrap(), ["run", "run"]);
+        assert_eq!(tui.await.unwrap(), ["npm install"]);
+    }
 
-        // Timeout for git operations (30 seconds)
-        const GIT_TIMEOUT_SECS: u64 = 30;
+    #[tokio::test]
+    async fn test_retry_reruns_the_failed_turn() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(10);
+        // Queued up front: the TUI's answer to the error state
+        cmd_tx.send(OrchestratorCommand::Retry).await.unwrap();
+        let (result, events) = run_flaky_rally("octorus-test/retry-turn", Some(cmd_rx)).await;
+
+        // The same iteration's review was re-run and approved
+        assert!(matches!(
+            result,
+            Ok(RallyResult::Approved { iteration: 1, .. })
+        ));
+        let failed = events
+            .iter()
+            .position(|e| matches!(e, RallyEvent::StateChanged(RallyState::Error)))
+            .unwrap();
+        assert!(matches!(
+            events[failed + 1..]
+                .iter()
+                .find(|e| matches!(e, RallyEvent::StateChanged(_))),
+            Some(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+        ));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, RallyEvent::IterationStarted(_)))
+                .count(),
+            1
+        );
+    }
 
-        // Try local git diff first if we have working_dir and base_branch
-        if let Some(ref ctx) = self.context {
-            if let Some(ref working_dir) = ctx.working_dir {
-                let base_branch = &ctx.base_branch;
+    #[tokio::test]
+    async fn test_failed_turn_ends_rally_without_tui() {
+        let (result, events) = run_flaky_rally("octorus-test/retry-no-tui", None).await;
+
+        assert!(result.is_err());
+        assert!(matches!(
+            events.last(),
+            Some(RallyEvent::StateChanged(RallyState::Error))
+        ));
+    }
 
-                // Fetch latest base branch reference to ensure accurate diff
-                // Use timeout to prevent hanging on slow remotes or credential prompts
-                let fetch_future = tokio::process::Command::new("git")
-                    .args(["fetch", "origin", base_branch])
-                    .current_dir(working_dir)
-                    .output();
+    #[test]
+    fn test_stall_tracker_stalls_after_consecutive_empty_fixes() {
+        let mut tracker = StallTracker::new(3);
+        assert!(!tracker.record_fix(&empty_fix()));
+        assert!(!tracker.record_fix(&empty_fix()));
+        assert!(tracker.record_fix(&empty_fix()));
+    }
 
-                match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), fetch_future).await {
-                    Ok(Ok(output)) if output.status.success() => {
-                        // Fetch succeeded
-                    }
-                    Ok(Ok(_)) => {
-                        warn!("git fetch failed, continuing with potentially stale ref");
-                    }
-                    Ok(Err(e)) => {
-                        warn!(
-                            "git fetch command failed: {}, continuing with potentially stale ref",
-                            e
-                        );
-                    }
-                    Err(_) => {
-                        warn!(
-                            "git fetch timed out after {} seconds, continuing with potentially stale ref",
-                            GIT_TIMEOUT_SECS
-                        );
-                    }
-                }
+    #[test]
+    fn test_stall_tracker_resets_on_file_changes() {
+        let mut tracker = StallTracker::new(2);
+        assert!(!tracker.record_fix(&empty_fix()));
+        let fix = RevieweeOutput {
+            files_modified: vec!["src/main.rs".to_string()],
+            ..empty_fix()
+        };
+        assert!(!tracker.record_fix(&fix));
+        assert!(!tracker.record_fix(&empty_fix()));
+        assert!(tracker.record_fix(&empty_fix()));
+    }
 
-                // Try git diff against origin/base_branch using merge-base (three-dot) comparison
-                // This matches GitHub PR diff semantics and avoids including unrelated base-branch changes
-                // Wrap in timeout to prevent hanging on network issues or auth prompts
-                let git_diff_future = tokio::process::Command::new("git")
-                    .args(["diff", &format!("origin/{}...HEAD", base_branch)])
-                    .current_dir(working_dir)
-                    .output();
+    #[test]
+    fn test_stall_tracker_counts_unreported_changes() {
+        let mut tracker = StallTracker::new(1);
+        let fix = RevieweeOutput {
+            diff_stats: vec![crate::ai::adapter::FileDiffStat {
+                path: "src/main.rs".to_string(),
+                additions: 1,
+                deletions: 0,
+            }],
+            ..empty_fix()
+        };
+        assert!(!tracker.record_fix(&fix));
+        assert!(tracker.record_fix(&empty_fix()));
+    }
 
-                match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await {
-                    Ok(Ok(output)) if output.status.success() => {
-                        let diff = String::from_utf8_lossy(&output.stdout).to_string();
-                        if !diff.trim().is_empty() {
-                            self.send_event(RallyEvent::Log(
-                                "Using local git diff for re-review".to_string(),
-                            ))
-                            .await;
-                            return Ok(diff);
-                        }
-                    }
-                    Ok(Ok(_)) => {
-                        // git diff failed, fall through to GitHub API
-                    }
-                    Ok(Err(e)) => {
-                        warn!("git diff command failed: {}", e);
-                    }
-                    Err(_) => {
-                        warn!(
-                            "git diff timed out after {} seconds, falling back to GitHub API",
-                            GIT_TIMEOUT_SECS
-                        );
-                    }
-                }
+    /// Ollama-like reviewee: answers every turn without touching the working tree
+    struct ReadOnlyReviewee;
 
-                self.send_event(RallyEvent::Log(
-                    "Local git diff empty or failed, falling back to GitHub API".to_string(),
-                ))
-                .await;
-            }
+    #[async_trait::async_trait]
+    impl AgentAdapter for ReadOnlyReviewee {
+        fn name(&self) -> &str {
+            "mock"
         }
 
-        // Fallback to GitHub API
-        github::fetch_pr_diff(&self.repo, self.pr_number).await
-    }
-
-    /// ローカルモード専用の diff 取得
-    ///
-    /// `git diff HEAD` を最優先し、working tree + staged の最新変更を取得。
-    /// 空の場合は `origin/{base}...HEAD` でコミット済み差分を試行。
-    /// どちらも空の場合は空文字列を返す（stale な初期 diff にフォールバックしない）。
-    async fn fetch_local_working_diff(&self, ctx: &super::adapter::Context) -> Result<String> {
-        const GIT_TIMEOUT_SECS: u64 = 30;
+        async fn check_availability(&self) -> Result<()> {
+            Ok(())
+        }
 
-        let working_dir = ctx.working_dir.as_deref().unwrap_or(".");
-        let base_branch = &ctx.base_branch;
+        fn set_event_sender(&mut self, _sender: mpsc::Sender<RallyEvent>) {}
 
-        // 1. git diff HEAD（working tree + staged の最新変更を優先）
-        let git_diff_future = tokio::process::Command::new("git")
-            .args(["diff", "HEAD"])
-            .current_dir(working_dir)
-            .output();
+        async fn run_reviewer(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
 
-        match timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await {
-            Ok(Ok(output)) if output.status.success() => {
-                let diff = String::from_utf8_lossy(&output.stdout).to_string();
-                if !diff.trim().is_empty() {
-                    self.send_event(RallyEvent::Log(
-                        "Using local git diff HEAD for re-review".to_string(),
-                    ))
-                    .await;
-                    return Ok(diff);
-                }
-            }
-            _ => {}
+        async fn run_reviewee(
+            &mut self,
+            _prompt: &str,
+            _context: &Context,
+        ) -> Result<RevieweeOutput> {
+            Ok(RevieweeOutput {
+                summary: "Suggested a fix".to_string(),
+                ..empty_fix()
+            })
         }
 
-        // 2. Fallback: origin/{base}...HEAD（コミット済み差分）
-        let origin_ref = format!("origin/{}...HEAD", base_branch);
-        let git_diff_future = tokio::process::Command::new("git")
-            .args(["diff", &origin_ref])
-            .current_dir(working_dir)
-            .output();
+        async fn continue_reviewer(&mut self, _message: &str) -> Result<ReviewerOutput> {
+            unimplemented!()
+        }
 
-        if let Ok(Ok(output)) =
-            timeout(Duration::from_secs(GIT_TIMEOUT_SECS), git_diff_future).await
-        {
-            if output.status.success() {
-                let diff = String::from_utf8_lossy(&output.stdout).to_string();
-                if !diff.trim().is_empty() {
-                    self.send_event(RallyEvent::Log(
-                        "Using local git diff (origin base) for re-review".to_string(),
-                    ))
-                    .await;
-                    return Ok(diff);
-                }
-            }
+        async fn continue_reviewee(&mut self, _message: &str) -> Result<RevieweeOutput> {
+            unimplemented!()
         }
 
-        // 両方空の場合は空文字列を返す（stale な ctx.diff にフォールバックしない）
-        self.send_event(RallyEvent::Log(
-            "Local diff is empty (no changes detected)".to_string(),
-        ))
-        .await;
-        Ok(String::new())
+        fn add_reviewee_allowed_tool(&mut self, _tool: &str) {}
+
+        fn can_modify_files(&self) -> bool {
+            false
+        }
     }
 
-    // For debugging and session inspection
-    #[allow(dead_code)]
-    pub fn session(&self) -> &RallySession {
-        &self.session
+    #[tokio::test]
+    async fn test_read_only_reviewee_does_not_stall() {
+        let repo = "octorus-test/read-only-reviewee";
+        let working_dir = tempfile::tempdir().unwrap();
+        let config = AiConfig {
+            response_cache: false,
+            max_iterations: 3,
+            max_stalled_iterations: 1,
+            ..AiConfig::default()
+        };
+        let (tx, _rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, None).unwrap();
+        orchestrator.reviewer_adapter = Box::new(ScriptedAgent {
+            reviews: vec![requesting_changes(); 3].into(),
+            turns: Default::default(),
+        });
+        orchestrator.reviewee_adapter = Box::new(ReadOnlyReviewee);
+        orchestrator.set_context(Context {
+            working_dir: Some(working_dir.path().display().to_string()),
+            local_mode: true,
+            ..two_file_context()
+        });
+
+        let result = orchestrator.run().await.unwrap();
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        assert!(matches!(
+            result,
+            RallyResult::MaxIterationsReached { iteration: 3 }
+        ));
     }
-}
 
-/// Check if a user is a bot
-fn is_bot_user(login: &str) -> bool {
-    BOT_SUFFIXES.iter().any(|suffix| login.ends_with(suffix)) || BOT_EXACT_MATCHES.contains(&login)
-}
+    #[tokio::test]
+    async fn test_agent_turns_wait_for_a_shared_slot() {
+        let (tx, _rx) = mpsc::channel(10);
+        let mut orchestrator =
+            Orchestrator::new("octorus-test/agent-slots", 1, AiConfig::default(), tx, None)
+                .unwrap();
+        // Without a shared limit turns never wait
+        assert!(orchestrator.agent_slot().await.is_none());
+
+        let slots = Arc::new(Semaphore::new(1));
+        orchestrator.set_agent_slots(slots.clone());
+        let other_rally = slots.clone().acquire_owned().await.unwrap();
+        let waiting = timeout(Duration::from_millis(50), orchestrator.agent_slot()).await;
+        assert!(waiting.is_err());
+
+        drop(other_rally);
+        let slot = orchestrator.agent_slot().await;
+        assert!(slot.is_some());
+        assert_eq!(slots.available_permits(), 0);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tokio::sync::mpsc;
+    #[test]
+    fn test_stall_tracker_disabled_with_zero_limit() {
+        let mut tracker = StallTracker::new(0);
+        for _ in 0..10 {
+            assert!(!tracker.record_fix(&empty_fix()));
+        }
+    }
 
     #[test]
     fn test_orchestrator_command_variants() {
@@ -1343,6 +5137,19 @@ mod tests {
             _ => panic!("Expected PostConfirmResponse"),
         }
 
+        // Test PostConfirmEdited
+        let cmd = OrchestratorCommand::PostConfirmEdited {
+            summary: "edited summary".to_string(),
+            comments: vec![],
+        };
+        match cmd {
+            OrchestratorCommand::PostConfirmEdited { summary, comments } => {
+                assert_eq!(summary, "edited summary");
+                assert!(comments.is_empty());
+            }
+            _ => panic!("Expected PostConfirmEdited"),
+        }
+
         // Test Abort
         let cmd = OrchestratorCommand::Abort;
         assert!(matches!(cmd, OrchestratorCommand::Abort));
@@ -1514,12 +5321,24 @@ mod tests {
         assert!(RallyState::Error.is_finished());
     }
 
+    #[test]
+    fn test_sandbox_denial_becomes_permission_request() {
+        let err = anyhow::Error::from(CodexError::SandboxDenied {
+            command: "npm install".to_string(),
+        });
+        let permission = sandbox_denial_as_permission_request(&err).unwrap();
+        assert_eq!(permission.action, "npm install");
+
+        assert!(sandbox_denial_as_permission_request(&anyhow!("Codex process failed")).is_none());
+    }
+
     #[test]
     fn test_review_post_info() {
         let info = ReviewPostInfo {
             action: "Approve".to_string(),
             summary: "Looks good".to_string(),
             comment_count: 3,
+            comments: vec![],
         };
         assert_eq!(info.action, "Approve");
         assert_eq!(info.summary, "Looks good");
@@ -1535,4 +5354,74 @@ mod tests {
         assert_eq!(info.summary, "Fixed issues");
         assert_eq!(info.files_modified.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_stalled_continuation_times_out() {
+        let repo = "octorus-test/continuation-timeout";
+        let config = AiConfig {
+            timeout_secs: 1,
+            response_cache: false,
+            ..AiConfig::default()
+        };
+        let (tx, _rx) = mpsc::channel(1000);
+        let mut orchestrator = Orchestrator::new(repo, 1, config, tx, None).unwrap();
+        orchestrator.reviewee_adapter = Box::new(ScriptedAgent {
+            reviews: Default::default(),
+            turns: Default::default(),
+        });
+
+        let err = orchestrator
+            .handle_permission_granted("Bash(cargo test:*)")
+            .await
+            .unwrap_err();
+        let _ = crate::ai::session::cleanup_session(repo, 1);
+        assert_eq!(err.to_string(), "Reviewee timeout after 1 seconds");
+    }
+
+    #[tokio::test]
+    async fn test_empty_diff_completes_without_running_agents() {
+        let (result, turns, events) = run_scripted_rally(
+            "octorus-test/empty-diff",
+            vec![approving_review()],
+            AiConfig::default(),
+            Context {
+                diff: "\n".to_string(),
+                file_patches: vec![],
+                ..two_file_context()
+            },
+        )
+        .await;
+
+        assert!(matches!(result, RallyResult::NoChanges));
+        assert!(turns.is_empty());
+        assert!(events.iter().any(|e| match e {
+            RallyEvent::Log(msg) => msg.starts_with("No changes to review"),
+            _ => false,
+        }));
+        assert!(matches!(
+            events.last(),
+            Some(RallyEvent::StateChanged(RallyState::Completed))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_review_only_stops_after_the_review() {
+        let (result, turns, events) = run_scripted_rally(
+            "octorus-test/review-only",
+            vec![requesting_changes()],
+            AiConfig {
+                review_only: true,
+                ..AiConfig::default()
+            },
+            two_file_context(),
+        )
+        .await;
+
+        assert!(matches!(result, RallyResult::ReviewOnly { iteration: 1 }));
+        assert_eq!(turns.len(), 1);
+        assert!(turns[0].starts_with("reviewer: "));
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, RallyEvent::StateChanged(RallyState::RevieweeFix))));
+    }
 }
@@ -4,7 +4,20 @@ use std::path::PathBuf;
 
 use crate::config::AiConfig;
 
-use super::adapter::{Context, ReviewAction, ReviewerOutput};
+use super::adapter::{Context, LinkedIssue, ReviewAction, ReviewerOutput};
+
+/// Maximum characters of each linked issue body included in the reviewer prompt
+const LINKED_ISSUE_BODY_LIMIT: usize = 1500;
+
+/// Checklist used in the reviewer prompt when `review_focus` is not configured
+const DEFAULT_REVIEW_FOCUS: &[&str] = &[
+    "Code quality issues",
+    "Potential bugs",
+    "Security vulnerabilities",
+    "Performance concerns",
+    "Style and consistency issues",
+    "Missing tests or documentation",
+];
 
 /// Default prompt templates embedded in the binary
 mod defaults {
@@ -16,6 +29,8 @@ mod defaults {
 /// Prompt loader that reads templates from files or uses defaults
 pub struct PromptLoader {
     prompt_dir: Option<PathBuf>,
+    review_language: String,
+    review_focus: Vec<String>,
 }
 
 impl PromptLoader {
@@ -28,10 +43,23 @@ impl PromptLoader {
                 .map(|dirs| dirs.get_config_home().join("prompts"))
         });
 
-        Self { prompt_dir }
+        Self {
+            prompt_dir,
+            review_language: config.review_language.clone(),
+            review_focus: config
+                .review_focus
+                .iter()
+                .map(|area| area.trim().to_string())
+                .filter(|area| !area.is_empty())
+                .collect(),
+        }
     }
 
     /// Load the reviewer prompt with variable substitution
+    ///
+    /// `<prompt_dir>/reviewer.md` replaces the built-in template when it exists.
+    /// Placeholders: `{{repo}}`, `{{pr_number}}`, `{{pr_title}}`, `{{pr_body}}`,
+    /// `{{diff}}`, `{{iteration}}`, `{{linked_issues}}`, `{{review_focus}}`.
     pub fn load_reviewer_prompt(&self, context: &Context, iteration: u32) -> String {
         let template = self.load_template("reviewer.md", defaults::REVIEWER);
 
@@ -47,11 +75,27 @@ impl PromptLoader {
         vars.insert("pr_body", pr_body.to_string());
         vars.insert("diff", context.diff.clone());
         vars.insert("iteration", iteration.to_string());
+        vars.insert(
+            "linked_issues",
+            format_linked_issues(&context.linked_issues),
+        );
+        vars.insert("review_focus", self.format_review_focus());
 
-        render_template(&template, &vars)
+        // Custom templates without the placeholder still get the configured focus
+        let prompt = if template.contains("{{review_focus}}") {
+            render_template(&template, &vars)
+        } else {
+            self.with_focus_instruction(render_template(&template, &vars))
+        };
+        self.with_language_instruction(prompt)
     }
 
     /// Load the reviewee prompt with variable substitution
+    ///
+    /// `<prompt_dir>/reviewee.md` replaces the built-in template when it exists.
+    /// Placeholders: `{{repo}}`, `{{pr_number}}`, `{{pr_title}}`, `{{iteration}}`,
+    /// `{{review_summary}}`, `{{review_action}}`, `{{review_comments}}`,
+    /// `{{blocking_issues}}`, `{{external_comments}}`.
     pub fn load_reviewee_prompt(
         &self,
         context: &Context,
@@ -68,7 +112,7 @@ impl PromptLoader {
                     "- [{severity:?}] {path}:{line}: {body}",
                     severity = c.severity,
                     path = c.path,
-                    line = c.line,
+                    line = c.line_range(),
                     body = c.body
                 )
             })
@@ -139,10 +183,14 @@ Note: Address these comments if they are relevant and valid. Don't wait for more
         vars.insert("blocking_issues", blocking_text);
         vars.insert("external_comments", external_section);
 
-        render_template(&template, &vars)
+        self.with_language_instruction(render_template(&template, &vars))
     }
 
     /// Load the re-review prompt with variable substitution
+    ///
+    /// `<prompt_dir>/rereview.md` replaces the built-in template when it exists.
+    /// Placeholders: `{{repo}}`, `{{pr_number}}`, `{{pr_title}}`, `{{iteration}}`,
+    /// `{{changes_summary}}`, `{{updated_diff}}`.
     pub fn load_rereview_prompt(
         &self,
         context: &Context,
@@ -160,7 +208,59 @@ Note: Address these comments if they are relevant and valid. Don't wait for more
         vars.insert("changes_summary", changes_summary.to_string());
         vars.insert("updated_diff", updated_diff.to_string());
 
-        render_template(&template, &vars)
+        self.with_language_instruction(
+            self.with_focus_instruction(render_template(&template, &vars)),
+        )
+    }
+
+    /// Checklist bullets for the `{{review_focus}}` placeholder
+    fn format_review_focus(&self) -> String {
+        let areas: Vec<&str> = if self.review_focus.is_empty() {
+            DEFAULT_REVIEW_FOCUS.to_vec()
+        } else {
+            self.review_focus.iter().map(String::as_str).collect()
+        };
+        areas
+            .iter()
+            .map(|area| format!("   - {}", area))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Append the configured focus areas (no-op when `review_focus` is empty)
+    fn with_focus_instruction(&self, prompt: String) -> String {
+        if self.review_focus.is_empty() {
+            return prompt;
+        }
+        format!(
+            r#"{prompt}
+
+## Review Focus
+
+Concentrate on these areas and skip unrelated nitpicks: {areas}.
+"#,
+            areas = self.review_focus.join(", ")
+        )
+    }
+
+    /// Append an instruction to write prose in `review_language` (no-op for English).
+    ///
+    /// Only prose is localized: JSON keys and enum values (action, severity) must stay
+    /// as-is so the output still parses.
+    fn with_language_instruction(&self, prompt: String) -> String {
+        let language = self.review_language.trim();
+        if language.is_empty() || language.eq_ignore_ascii_case("english") {
+            return prompt;
+        }
+        format!(
+            r#"{prompt}
+
+## Output Language
+
+Write all prose (summary, comment bodies, blocking issues, questions) in {language}.
+Keep JSON keys, enum values such as `action` and `severity`, file paths and code identifiers exactly as specified in English.
+"#
+        )
     }
 
     /// Load a template from file or return default
@@ -177,6 +277,34 @@ Note: Address these comments if they are relevant and valid. Don't wait for more
     }
 }
 
+/// "Linked Issues" section for the reviewer prompt (empty when the PR references none)
+fn format_linked_issues(issues: &[LinkedIssue]) -> String {
+    if issues.is_empty() {
+        return String::new();
+    }
+    let text = issues
+        .iter()
+        .map(|issue| {
+            let body = if issue.body.trim().is_empty() {
+                "(No description provided)".to_string()
+            } else {
+                truncate(&issue.body, LINKED_ISSUE_BODY_LIMIT)
+            };
+            format!("#### #{}: {}\n{}", issue.number, issue.title, body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!(
+        r#"
+### Linked Issues
+
+The PR description references these issues. Check that the changes actually address them.
+
+{text}
+"#
+    )
+}
+
 /// Render a template by replacing {{key}} with values from vars
 fn render_template(template: &str, vars: &HashMap<&str, String>) -> String {
     let mut result = template.to_string();
@@ -219,9 +347,40 @@ mod tests {
             external_comments: Vec::new(),
             local_mode: false,
             file_patches: Vec::new(),
+            linked_issues: Vec::new(),
         }
     }
 
+    #[test]
+    fn test_load_reviewer_prompt_with_linked_issues() {
+        let config = AiConfig::default();
+        let loader = PromptLoader::new(&config);
+        let mut context = create_test_context();
+
+        let prompt = loader.load_reviewer_prompt(&context, 1);
+        assert!(!prompt.contains("Linked Issues"));
+        assert!(!prompt.contains("{{linked_issues}}"));
+
+        context.linked_issues = vec![
+            LinkedIssue {
+                number: 42,
+                title: "Crash on empty input".to_string(),
+                body: "Steps to reproduce: run with no args".to_string(),
+            },
+            LinkedIssue {
+                number: 7,
+                title: "Docs".to_string(),
+                body: String::new(),
+            },
+        ];
+        let prompt = loader.load_reviewer_prompt(&context, 1);
+        assert!(prompt.contains("### Linked Issues"));
+        assert!(
+            prompt.contains("#### #42: Crash on empty input\nSteps to reproduce: run with no args")
+        );
+        assert!(prompt.contains("#### #7: Docs\n(No description provided)"));
+    }
+
     #[test]
     fn test_render_template() {
         let template = "Hello {{name}}, you have {{count}} messages.";
@@ -243,6 +402,61 @@ mod tests {
         assert_eq!(result, "Hello Bob, {{unknown}} variable.");
     }
 
+    #[test]
+    fn test_review_focus_replaces_default_checklist() {
+        let context = create_test_context();
+
+        let prompt = PromptLoader::new(&AiConfig::default()).load_reviewer_prompt(&context, 1);
+        assert!(prompt.contains("   - Potential bugs"));
+        assert!(!prompt.contains("## Review Focus"));
+
+        let config = AiConfig {
+            review_focus: vec!["security".to_string(), " perf ".to_string()],
+            ..AiConfig::default()
+        };
+        let loader = PromptLoader::new(&config);
+        let prompt = loader.load_reviewer_prompt(&context, 1);
+        assert!(prompt.contains("   - security\n   - perf\n"));
+        assert!(!prompt.contains("Potential bugs"));
+        assert!(!prompt.contains("{{review_focus}}"));
+
+        let prompt = loader.load_rereview_prompt(&context, 2, "fixed", "diff");
+        assert!(prompt
+            .contains("Concentrate on these areas and skip unrelated nitpicks: security, perf."));
+    }
+
+    #[test]
+    fn test_language_instruction_injected_when_configured() {
+        let config = AiConfig {
+            review_language: "Japanese".to_string(),
+            ..AiConfig::default()
+        };
+        let loader = PromptLoader::new(&config);
+        let context = create_test_context();
+
+        let prompt = loader.load_reviewer_prompt(&context, 1);
+        assert!(prompt.contains("## Output Language"));
+        assert!(prompt.contains("in Japanese"));
+        assert!(prompt.contains("`severity`"));
+
+        let review = ReviewerOutput {
+            action: ReviewAction::RequestChanges,
+            summary: "Fix it".to_string(),
+            comments: vec![],
+            blocking_issues: vec![],
+            reasoning: None,
+        };
+        let prompt = loader.load_reviewee_prompt(&context, &review, 1);
+        assert!(prompt.contains("in Japanese"));
+    }
+
+    #[test]
+    fn test_language_instruction_omitted_for_english() {
+        let loader = PromptLoader::new(&AiConfig::default());
+        let prompt = loader.load_reviewer_prompt(&create_test_context(), 1);
+        assert!(!prompt.contains("## Output Language"));
+    }
+
     #[test]
     fn test_load_reviewer_prompt() {
         let config = AiConfig::default();
@@ -271,10 +485,12 @@ mod tests {
             comments: vec![ReviewComment {
                 path: "src/main.rs".to_string(),
                 line: 10,
+                start_line: None,
                 body: "Missing error handling".to_string(),
                 severity: CommentSeverity::Major,
             }],
             blocking_issues: vec!["Fix error handling".to_string()],
+            reasoning: None,
         };
 
         let prompt = loader.load_reviewee_prompt(&context, &review, 1);
@@ -313,6 +529,7 @@ mod tests {
             summary: "Please fix the issues".to_string(),
             comments: vec![],
             blocking_issues: vec![],
+            reasoning: None,
         };
 
         let prompt = loader.load_reviewee_prompt(&context, &review, 1);
@@ -1,3 +1,5 @@
+use super::adapter::ReviewComment;
+
 // For Clarification/Permission flow (not yet implemented)
 // See CLAUDE.md "Known Limitations"
 #[allow(dead_code)]
@@ -72,11 +74,122 @@ If you're completely uncertain, make minimal changes and document your assumptio
     )
 }
 
+/// Prompt asking the reviewee to address a single reviewer comment
+pub fn build_focused_comment_prompt(comment: &ReviewComment) -> String {
+    format!(
+        r#"The user selected ONE reviewer comment for you to address.
+
+## Comment
+- File: {path}:{line}
+- Severity: {severity:?}
+
+{body}
+
+## Your Task
+
+Address ONLY this comment. Do not make changes for any other review feedback.
+Then report the result in the same format as before."#,
+        path = comment.path,
+        line = comment.line_range(),
+        severity = comment.severity,
+        body = comment.body,
+    )
+}
+
+/// Prompt for when the post-fix formatter/linter command fails
+pub fn build_post_fix_check_failed_prompt(command: &str, output: &str) -> String {
+    format!(
+        r#"Your changes did not pass the post-fix check.
+
+## Command
+`{command}`
+
+## Output
+```
+{output}
+```
+
+## Your Task
+
+Fix the issues reported above (formatting, lint or compile errors) without changing
+the intent of your previous fixes. Then report the result in the same format as before."#,
+        command = command,
+        output = output.trim_end(),
+    )
+}
+
+/// Ask the agent to resend a response that could not be parsed.
+///
+/// `errors` describe what was wrong (schema violations are prefixed with the JSON
+/// pointer of the offending value); `schema` is repeated as a reminder.
+pub fn build_output_correction_prompt(errors: &[String], schema: &str) -> String {
+    format!(
+        r#"Your previous response could not be parsed.
+
+## Errors
+{errors}
+
+## Required JSON Schema
+```json
+{schema}
+```
+
+## Your Task
+
+Send the same response again as a single JSON object that conforms to the schema above.
+Do not repeat any work; only correct the output format."#,
+        errors = errors
+            .iter()
+            .map(|e| format!("- {}", e))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        schema = schema.trim(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ai::adapter::CommentSeverity;
     use insta::assert_snapshot;
 
+    #[test]
+    fn test_build_focused_comment_prompt_contains_only_selected_comment() {
+        let comments = [
+            ReviewComment {
+                path: "src/main.rs".to_string(),
+                line: 10,
+                start_line: None,
+                body: "Handle the error instead of unwrap()".to_string(),
+                severity: CommentSeverity::Major,
+            },
+            ReviewComment {
+                path: "src/lib.rs".to_string(),
+                line: 3,
+                start_line: None,
+                body: "Rename this function".to_string(),
+                severity: CommentSeverity::Suggestion,
+            },
+        ];
+        let result = build_focused_comment_prompt(&comments[0]);
+        assert_snapshot!(result, @r"
+        The user selected ONE reviewer comment for you to address.
+
+        ## Comment
+        - File: src/main.rs:10
+        - Severity: Major
+
+        Handle the error instead of unwrap()
+
+        ## Your Task
+
+        Address ONLY this comment. Do not make changes for any other review feedback.
+        Then report the result in the same format as before.
+        ");
+        assert!(!result.contains("src/lib.rs"));
+        assert!(!result.contains("Rename this function"));
+    }
+
     #[test]
     fn test_build_clarification_prompt() {
         let result = build_clarification_prompt("How should error handling work?");
@@ -145,4 +258,32 @@ mod tests {
         If you're completely uncertain, make minimal changes and document your assumptions in the summary.
         "#);
     }
+
+    #[test]
+    fn test_build_output_correction_prompt() {
+        let result = build_output_correction_prompt(
+            &[
+                "/status: \"done\" is not one of \"completed\", \"needs_clarification\" or 2 other candidates".to_string(),
+                "/: \"summary\" is a required property".to_string(),
+            ],
+            r#"{"type": "object"}"#,
+        );
+        assert_snapshot!(result, @r#"
+        Your previous response could not be parsed.
+
+        ## Errors
+        - /status: "done" is not one of "completed", "needs_clarification" or 2 other candidates
+        - /: "summary" is a required property
+
+        ## Required JSON Schema
+        ```json
+        {"type": "object"}
+        ```
+
+        ## Your Task
+
+        Send the same response again as a single JSON object that conforms to the schema above.
+        Do not repeat any work; only correct the output format.
+        "#);
+    }
 }
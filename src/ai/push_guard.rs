@@ -0,0 +1,238 @@
+//! Pre-push hook installed in the working directory while the reviewee runs.
+//!
+//! Reviewees with write access (Codex `--full-auto`, Gemini yolo) can run
+//! `git push` even though the prompt forbids it. The hook rejects every push and
+//! records the attempt so the rally can report it. `git push --no-verify` skips
+//! hooks, so this guards against mistakes rather than sandboxing the agent.
+//!
+//! The hook only goes into the repository's own git directory. When
+//! `core.hooksPath` points elsewhere (a tracked `.husky/`, or a directory shared
+//! by other repositories) the guard is not installed at all.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::loader::{git_common_dir, git_path, work_tree_root};
+
+/// Second line of the installed hook; tells it apart from the user's own hook
+const HOOK_MARKER: &str = "# octorus: blocks git push during an AI Rally reviewee turn";
+
+/// The user's own pre-push hook is moved here while the guard is installed
+const BACKUP_NAME: &str = "pre-push.octorus-backup";
+
+/// An installed pre-push hook; dropping it restores the user's hook
+pub struct PushGuard {
+    hook: PathBuf,
+    backup: Option<PathBuf>,
+    /// The hook appends "<remote> <url>" here for every push it rejects
+    attempts: PathBuf,
+}
+
+impl PushGuard {
+    /// Install the hook in the repository at `working_dir` (the current directory
+    /// when `None`)
+    pub async fn install(working_dir: Option<&str>) -> Result<Self> {
+        let hooks_dir = own_hooks_dir(working_dir).await?;
+        let attempts = git_path(working_dir, "octorus-push-attempts").await?;
+        fs::create_dir_all(&hooks_dir)
+            .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+
+        let hook = hooks_dir.join("pre-push");
+        let backup = hooks_dir.join(BACKUP_NAME);
+        if hook.exists() && !is_guard_hook(&hook) {
+            fs::rename(&hook, &backup)
+                .with_context(|| format!("Failed to move {} aside", hook.display()))?;
+        }
+        // A backup left by an interrupted run is still the user's hook
+        let backup = backup.exists().then_some(backup);
+
+        let _ = fs::remove_file(&attempts);
+        let guard = Self {
+            hook,
+            backup,
+            attempts,
+        };
+        fs::write(&guard.hook, hook_script(&guard.attempts))
+            .with_context(|| format!("Failed to write {}", guard.hook.display()))?;
+        set_executable(&guard.hook)?;
+        Ok(guard)
+    }
+
+    /// Remove the hook and return the pushes it rejected ("<remote> <url>")
+    pub fn finish(self) -> Vec<String> {
+        fs::read_to_string(&self.attempts)
+            .map(|content| {
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Describe a guard hook or hook backup left behind by an interrupted rally, if any
+pub async fn leftover_warning(working_dir: Option<&str>) -> Option<String> {
+    let hooks_dir = git_path(working_dir, "hooks").await.ok()?;
+    let hook = hooks_dir.join("pre-push");
+    let backup = hooks_dir.join(BACKUP_NAME);
+    if is_guard_hook(&hook) {
+        Some(format!(
+            "{} is a leftover octorus push guard and rejects every git push; \
+             it is removed after the next reviewee turn, or delete it by hand",
+            hook.display()
+        ))
+    } else if backup.exists() {
+        Some(format!(
+            "{} holds a pre-push hook moved aside by an interrupted rally; \
+             rename it back to pre-push to restore it",
+            backup.display()
+        ))
+    } else {
+        None
+    }
+}
+
+/// The hooks directory git uses, as long as it is inside the repository's git directory
+async fn own_hooks_dir(working_dir: Option<&str>) -> Result<PathBuf> {
+    let hooks_dir = git_path(working_dir, "hooks").await?;
+    if hooks_dir.starts_with(git_common_dir(working_dir).await?) {
+        return Ok(hooks_dir);
+    }
+    let location = if hooks_dir.starts_with(work_tree_root(working_dir).await?) {
+        "inside the work tree"
+    } else {
+        "outside the repository"
+    };
+    anyhow::bail!(
+        "core.hooksPath points {} ({}); not installing the pre-push guard there",
+        location,
+        hooks_dir.display()
+    )
+}
+
+impl Drop for PushGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.attempts);
+        let _ = match self.backup {
+            Some(ref backup) => fs::rename(backup, &self.hook),
+            None => fs::remove_file(&self.hook),
+        };
+    }
+}
+
+fn is_guard_hook(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(HOOK_MARKER))
+}
+
+fn hook_script(attempts: &Path) -> String {
+    // Single-quoted for sh: a quote inside the path becomes '\''
+    let attempts = attempts.display().to_string().replace('\'', r"'\''");
+    format!(
+        "#!/bin/sh\n\
+         {HOOK_MARKER}\n\
+         echo \"$1 $2\" >> '{attempts}'\n\
+         echo 'git push is blocked while the octorus AI Rally reviewee is running' >&2\n\
+         exit 1\n"
+    )
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "octorus-test")
+            .env("GIT_AUTHOR_EMAIL", "octorus-test@example.com")
+            .env("GIT_COMMITTER_NAME", "octorus-test")
+            .env("GIT_COMMITTER_EMAIL", "octorus-test@example.com")
+            .output()
+            .expect("failed to run git")
+            .status
+            .success()
+    }
+
+    #[tokio::test]
+    async fn test_push_is_rejected_and_users_hook_restored() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let remote = tempdir.path().join("remote.git");
+        let work = tempdir.path().join("work");
+        fs::create_dir(&work).unwrap();
+        assert!(git(tempdir.path(), &["init", "--bare", "-q", "remote.git"]));
+        assert!(git(&work, &["init", "-q"]));
+        assert!(git(&work, &["commit", "-q", "--allow-empty", "-m", "init"]));
+        assert!(git(
+            &work,
+            &["remote", "add", "origin", remote.to_str().unwrap()]
+        ));
+        let users_hook = "#!/bin/sh\nexit 0\n";
+        let hook = work.join(".git/hooks/pre-push");
+        fs::write(&hook, users_hook).unwrap();
+        set_executable(&hook).unwrap();
+
+        let guard = PushGuard::install(work.to_str()).await.unwrap();
+        assert!(!git(&work, &["push", "-q", "origin", "HEAD:main"]));
+        let attempts = guard.finish();
+
+        assert_eq!(attempts.len(), 1);
+        assert!(attempts[0].starts_with("origin "), "{attempts:?}");
+        assert_eq!(fs::read_to_string(&hook).unwrap(), users_hook);
+        assert!(!work.join(".git/hooks").join(BACKUP_NAME).exists());
+        // With the user's hook back in place the push goes through
+        assert!(git(&work, &["push", "-q", "origin", "HEAD:main"]));
+    }
+
+    #[tokio::test]
+    async fn test_custom_hooks_path_is_left_alone() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let work = tempdir.path();
+        assert!(git(work, &["init", "-q"]));
+        assert!(git(work, &["config", "core.hooksPath", ".husky"]));
+
+        let err = PushGuard::install(work.to_str()).await.err().unwrap();
+        assert!(err.to_string().contains("inside the work tree"), "{err}");
+        assert!(!work.join(".husky").exists());
+        assert!(!work.join(".git/hooks/pre-push").exists());
+    }
+
+    #[tokio::test]
+    async fn test_leftover_warning() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let work = tempdir.path();
+        assert!(git(work, &["init", "-q"]));
+        assert_eq!(leftover_warning(work.to_str()).await, None);
+
+        let hooks = work.join(".git/hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("pre-push"), hook_script(Path::new("attempts"))).unwrap();
+        assert!(leftover_warning(work.to_str())
+            .await
+            .is_some_and(|w| w.contains("rejects every git push")));
+
+        fs::remove_file(hooks.join("pre-push")).unwrap();
+        fs::write(hooks.join(BACKUP_NAME), "#!/bin/sh\n").unwrap();
+        assert!(leftover_warning(work.to_str())
+            .await
+            .is_some_and(|w| w.contains(BACKUP_NAME)));
+    }
+}
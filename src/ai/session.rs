@@ -2,10 +2,9 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use xdg::BaseDirectories;
 
 use super::{RallyState, RevieweeOutput, ReviewerOutput};
-use crate::cache::sanitize_repo_name;
+use crate::cache::{cache_dir, sanitize_repo_name};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RallySession {
@@ -15,6 +14,9 @@ pub struct RallySession {
     pub state: RallyState,
     pub started_at: String,
     pub updated_at: String,
+    /// PR head the reviewer last reviewed; the next rally's default `--since`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reviewed_sha: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,16 +34,9 @@ pub enum HistoryEntryType {
 
 fn rally_dir(repo: &str, pr_number: u32) -> Result<PathBuf> {
     let safe_repo = sanitize_repo_name(repo)?;
-    let dir = BaseDirectories::with_prefix("octorus")
-        .map(|dirs| {
-            dirs.get_cache_home()
-                .join("rally")
-                .join(format!("{}_{}", safe_repo, pr_number))
-        })
-        .unwrap_or_else(|_| {
-            PathBuf::from(".cache/octorus/rally").join(format!("{}_{}", safe_repo, pr_number))
-        });
-    Ok(dir)
+    Ok(cache_dir()
+        .join("rally")
+        .join(format!("{}_{}", safe_repo, pr_number)))
 }
 
 pub fn session_path(repo: &str, pr_number: u32) -> Result<PathBuf> {
@@ -52,8 +47,6 @@ pub fn history_dir(repo: &str, pr_number: u32) -> Result<PathBuf> {
     Ok(rally_dir(repo, pr_number)?.join("history"))
 }
 
-// For --resume-rally feature (not yet implemented)
-#[allow(dead_code)]
 pub fn read_session(repo: &str, pr_number: u32) -> Result<Option<RallySession>> {
     let path = session_path(repo, pr_number)?;
     if !path.exists() {
@@ -161,6 +154,7 @@ impl RallySession {
             state: RallyState::Initializing,
             started_at: now.clone(),
             updated_at: now,
+            last_reviewed_sha: None,
         }
     }
 
@@ -202,6 +196,28 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_rally_session_without_last_reviewed_sha_parses() {
+        let json = r#"{
+            "repo": "owner/repo",
+            "pr_number": 42,
+            "iteration": 3,
+            "state": "Completed",
+            "started_at": "1",
+            "updated_at": "2"
+        }"#;
+        let session: RallySession = serde_json::from_str(json).unwrap();
+        assert_eq!(session.last_reviewed_sha, None);
+
+        let session = RallySession {
+            last_reviewed_sha: Some("abc123".to_string()),
+            ..session
+        };
+        let roundtrip: RallySession =
+            serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+        assert_eq!(roundtrip.last_reviewed_sha.as_deref(), Some("abc123"));
+    }
+
     #[test]
     fn test_rally_session_update_state() {
         let mut session = RallySession::new("owner/repo", 1);
@@ -241,10 +257,12 @@ mod tests {
                 comments: vec![ReviewComment {
                     path: "src/main.rs".to_string(),
                     line: 10,
+                    start_line: None,
                     body: "Fix this".to_string(),
                     severity: CommentSeverity::Major,
                 }],
                 blocking_issues: vec!["Error handling".to_string()],
+                reasoning: None,
             }),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -284,6 +302,7 @@ mod tests {
                 question: None,
                 permission_request: None,
                 error_details: None,
+                diff_stats: Vec::new(),
             }),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -8,26 +8,33 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::io::Stdout;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
 
+use crate::ai::adapter::{CommentSeverity, RevieweeStatus};
+use crate::ai::ignore::{self, IgnoreRules};
 use crate::ai::orchestrator::{OrchestratorCommand, RallyEvent};
 use crate::ai::{Context, Orchestrator, RallyState};
 use crate::cache::{PrCacheKey, PrData, SessionCache};
 use crate::config::Config;
 use crate::github::comment::{DiscussionComment, ReviewComment};
-use crate::github::{self, ChangedFile, PrStateFilter, PullRequest, PullRequestSummary};
+use crate::github::{
+    self, ChangedFile, ChecksSummary, GithubProvider, PrStateFilter, PullRequest,
+    PullRequestSummary, VcsProvider,
+};
 use crate::keybinding::{
     event_to_keybinding, KeyBinding, KeySequence, SequenceMatch, SEQUENCE_TIMEOUT,
 };
-use crate::loader::{CommentSubmitResult, DataLoadResult};
+use crate::loader::{self, CommentSubmitResult, DataLoadResult};
 use crate::syntax::ParserPool;
 use crate::ui;
 use crate::ui::text_area::{TextArea, TextAreaAction};
 use notify::Watcher;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
@@ -41,6 +48,11 @@ const MAX_HIGHLIGHTED_CACHE_ENTRIES: usize = 50;
 /// 大規模PRで全ファイルをクローンしないよう制限。
 const MAX_PREFETCH_FILES: usize = 50;
 
+/// Rally 中断時、キャンセル通知からタスク強制中断までの猶予
+///
+/// エージェントのプロセスを kill して回収するのに十分な時間。
+const RALLY_CANCEL_GRACE: std::time::Duration = std::time::Duration::from_millis(500);
+
 /// PR番号と紐づいたレシーバー（発信元PRを追跡してクロスPRキャッシュ汚染を防止）
 type PrReceiver<T> = Option<(u32, mpsc::Receiver<T>)>;
 
@@ -117,6 +129,29 @@ pub fn hash_string(s: &str) -> u64 {
     hasher.finish()
 }
 
+/// 手動リフレッシュ完了時の通知。PR の head が進んでいれば新旧の SHA を示す
+fn refresh_message(previous_head: &str, head: &str, local_mode: bool) -> String {
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+    if local_mode {
+        "Refreshed".to_string()
+    } else if previous_head == head {
+        "Refreshed: no new commits".to_string()
+    } else {
+        format!(
+            "Refreshed: new commits ({} → {})",
+            short(previous_head),
+            short(head)
+        )
+    }
+}
+
+/// 手動リフレッシュ（`R`）を始めた時点の PR の状態
+#[derive(Debug, Clone)]
+struct RefreshOrigin {
+    head_sha: String,
+    selected_file: Option<String>,
+}
+
 /// 行ベース入力のコンテキスト（コメント/サジェスチョン共通）
 #[derive(Debug, Clone)]
 pub struct LineInputContext {
@@ -149,6 +184,7 @@ pub enum AppState {
     TextInput,
     CommentList,
     Help,
+    PrDetails,
     AiRally,
     SplitViewFileList,
     SplitViewDiff,
@@ -182,6 +218,8 @@ pub struct LogEntry {
     pub timestamp: String,
     pub event_type: LogEventType,
     pub message: String,
+    /// レビューコメント1件分のエントリの場合、そのコメント（reviewee への再送用）
+    pub review_comment: Option<crate::ai::adapter::ReviewComment>,
 }
 
 impl LogEntry {
@@ -191,6 +229,19 @@ impl LogEntry {
             timestamp: now.format("%H:%M:%S").to_string(),
             event_type,
             message,
+            review_comment: None,
+        }
+    }
+
+    /// レビューコメント1件を表すエントリ（詳細表示から reviewee に再送できる）
+    pub fn for_review_comment(comment: &crate::ai::adapter::ReviewComment) -> Self {
+        let message = format!(
+            "[{:?}] {}:{}: {}",
+            comment.severity, comment.path, comment.line, comment.body
+        );
+        Self {
+            review_comment: Some(comment.clone()),
+            ..Self::new(LogEventType::Review, message)
         }
     }
 }
@@ -221,13 +272,50 @@ pub struct AiRallyState {
     pub pending_permission: Option<PermissionInfo>,
     /// Pending review post confirmation
     pub pending_review_post: Option<crate::ai::orchestrator::ReviewPostInfo>,
+    /// Item selected for editing in the review confirmation (None = summary)
+    pub review_post_selection: Option<usize>,
+    /// Whether the pending review was edited by the user
+    pub review_post_edited: bool,
     /// Pending fix post confirmation
     pub pending_fix_post: Option<crate::ai::orchestrator::FixPostInfo>,
     /// Last rendered visible log height (updated by UI render)
     pub last_visible_log_height: usize,
+    /// Accumulated cost reported by agents (None until an agent reports one)
+    pub total_cost_usd: Option<f64>,
+    /// Accumulated agent run time reported so far
+    pub total_duration_ms: u64,
+    /// When the current reviewer/reviewee turn started (None outside agent turns)
+    pub turn_started_at: Option<Instant>,
+    /// Raw `result` JSON of the last agent run, for the debug pane
+    pub last_raw_result: Option<serde_json::Value>,
+    /// Whether the raw result debug pane (F12) is visible
+    pub showing_raw_result: bool,
+    /// Scroll offset of the raw result debug pane
+    pub raw_result_scroll: u16,
+    /// Failure reason the reviewee reported with `status: error`
+    pub error_details: Option<String>,
+    /// Scroll offset of the error details pane
+    pub error_details_scroll: u16,
+    /// When the rally was started
+    pub started_at: Instant,
+    /// Start of each iteration, oldest first
+    pub iteration_started_at: Vec<(u32, Instant)>,
+    /// When the rally completed (None while it is running)
+    pub finished_at: Option<Instant>,
+    /// Whether the clarification answer popup is open (typed into `App::input_text_area`)
+    pub answering_clarification: bool,
+    /// Files the reviewee wrote in its current turn, in the order it reported them
+    pub files_changed: Vec<PathBuf>,
 }
 
 impl AiRallyState {
+    /// Record a file the reviewee wrote in this turn (each path once)
+    pub fn record_file_modified(&mut self, path: PathBuf) {
+        if !self.files_changed.contains(&path) {
+            self.files_changed.push(path);
+        }
+    }
+
     /// Push a new log entry, auto-following to the bottom if the selection is at the tail.
     /// This keeps auto-scroll active when the user is watching the latest logs.
     pub fn push_log(&mut self, entry: LogEntry) {
@@ -241,6 +329,116 @@ impl AiRallyState {
         }
     }
 
+    /// 確認画面での編集対象を サマリー → コメント1 → … → サマリー の順に切り替える
+    pub fn cycle_review_post_selection(&mut self) {
+        let Some(ref info) = self.pending_review_post else {
+            return;
+        };
+        self.review_post_selection = match self.review_post_selection {
+            None if !info.comments.is_empty() => Some(0),
+            Some(i) if i + 1 < info.comments.len() => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    /// ログの選択を 1 ページ（表示行数）分移動する。末尾に到達したら自動追従に戻る
+    pub fn page_logs(&mut self, down: bool) {
+        let total = self.logs.len();
+        if total == 0 {
+            return;
+        }
+        let page = self.last_visible_log_height.max(1);
+        let last = total - 1;
+        let current = self.selected_log_index.unwrap_or(last);
+        let selected = if down {
+            (current + page).min(last)
+        } else {
+            current.saturating_sub(page)
+        };
+        self.selected_log_index = Some(selected);
+
+        if selected == last {
+            self.log_scroll_offset = 0; // 0 means auto-scroll to bottom
+            return;
+        }
+        // 選択と同じだけ表示範囲もずらす（1 が最小、0 は自動追従）
+        let top = if self.log_scroll_offset == 0 {
+            total.saturating_sub(page)
+        } else {
+            self.log_scroll_offset
+        };
+        let top = if down {
+            top + page
+        } else {
+            top.saturating_sub(page)
+        };
+        let top = top.min(total.saturating_sub(page)).min(selected);
+        self.log_scroll_offset = top.max(1);
+    }
+
+    /// Add one agent run's reported usage to the rally totals
+    pub fn record_usage(&mut self, cost_usd: Option<f64>, duration_ms: Option<u64>) {
+        if let Some(cost) = cost_usd {
+            *self.total_cost_usd.get_or_insert(0.0) += cost;
+        }
+        self.total_duration_ms += duration_ms.unwrap_or(0);
+    }
+
+    /// Wall-clock time of each iteration, oldest first. An iteration lasts until
+    /// the next one starts; the last one until the rally finished (or now).
+    pub fn iteration_durations(&self) -> Vec<(u32, Duration)> {
+        let end = self.finished_at.unwrap_or_else(Instant::now);
+        self.iteration_started_at
+            .iter()
+            .enumerate()
+            .map(|(i, &(iteration, started))| {
+                let next = self
+                    .iteration_started_at
+                    .get(i + 1)
+                    .map_or(end, |&(_, next)| next);
+                (iteration, next.saturating_duration_since(started))
+            })
+            .collect()
+    }
+
+    /// Wall-clock time since the rally started (until it finished)
+    pub fn total_elapsed(&self) -> Duration {
+        self.finished_at
+            .unwrap_or_else(Instant::now)
+            .saturating_duration_since(self.started_at)
+    }
+
+    /// Summary of the latest reviewer output still in the history
+    pub fn latest_review_summary(&self) -> Option<&str> {
+        self.history.iter().rev().find_map(|event| match event {
+            RallyEvent::ReviewCompleted(review) => Some(review.summary.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Copy `text` to the system clipboard and log the outcome
+    pub fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        let entry = match crate::clipboard::copy(text) {
+            Ok(()) => LogEntry::new(LogEventType::Info, format!("Copied {} to clipboard", what)),
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                LogEntry::new(LogEventType::Error, e.to_string())
+            }
+        };
+        self.push_log(entry);
+    }
+
+    /// Blocking issue counts of each retained reviewer output, oldest first
+    pub fn blocking_issue_counts(&self) -> Vec<usize> {
+        self.history
+            .iter()
+            .filter_map(|event| match event {
+                RallyEvent::ReviewCompleted(review) => Some(review.blocking_issues.len()),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Check if the current selection is at the tail (last log) or unset
     fn is_selection_at_tail(&self) -> bool {
         match self.selected_log_index {
@@ -267,6 +465,45 @@ pub enum CommentTab {
     Discussion,
 }
 
+/// レビューコメント一覧の重要度フィルタ（何も選択していなければ全件表示）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeverityFilter {
+    enabled: Vec<CommentSeverity>,
+}
+
+impl SeverityFilter {
+    pub fn is_active(&self) -> bool {
+        !self.enabled.is_empty()
+    }
+
+    pub fn toggle(&mut self, severity: CommentSeverity) {
+        if let Some(pos) = self.enabled.iter().position(|s| *s == severity) {
+            self.enabled.remove(pos);
+        } else {
+            self.enabled.push(severity);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.enabled.clear();
+    }
+
+    /// 重要度を持たないコメント（人間のコメントなど）はフィルタ有効時に除外する
+    pub fn matches(&self, severity: Option<CommentSeverity>) -> bool {
+        !self.is_active() || severity.is_some_and(|s| self.enabled.contains(&s))
+    }
+
+    /// ヘッダー表示用のラベル（例: "critical+major"）
+    pub fn label(&self) -> String {
+        CommentSeverity::ALL
+            .into_iter()
+            .filter(|s| self.enabled.contains(s))
+            .map(CommentSeverity::label)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
 /// リトライリクエストの種類（統一リトライループで使用）
 #[derive(Debug, Clone)]
 pub enum RefreshRequest {
@@ -330,6 +567,8 @@ pub struct App {
     pub pr_list_state_filter: PrStateFilter,
     /// PR一覧から開始したかどうか（戻り先判定用）
     pub started_from_pr_list: bool,
+    /// `--pr 10,11,12` で渡されたレビューキュー（PR一覧をこの PR 群に限定する）
+    pub pr_queue: Option<Vec<u32>>,
     /// ローカル差分監視モードかどうか
     local_mode: bool,
     /// `--auto-focus` オプション（ローカル差分時）
@@ -351,8 +590,10 @@ pub struct App {
     pub diff_view_return_state: AppState,
     /// CommentPreview/SuggestionPreview の戻り先
     pub preview_return_state: AppState,
-    /// Help/CommentList など汎用的な戻り先
+    /// CommentList / PrDetails など汎用的な戻り先
     pub previous_state: AppState,
+    /// ヘルプポップアップを閉じたときに戻る画面
+    pub help_return_state: AppState,
     pub selected_file: usize,
     pub file_list_scroll_offset: usize,
     pub selected_line: usize,
@@ -368,7 +609,15 @@ pub struct App {
     pub review_comments: Option<Vec<ReviewComment>>,
     pub selected_comment: usize,
     pub comment_list_scroll_offset: usize,
+    /// 選択中レビューコメントの詳細ペインのスクロールオフセット（行単位）
+    pub review_comment_detail_scroll: usize,
     pub comments_loading: bool,
+    /// コメント一覧で表示する重要度（1-4 でトグル、0 でリセット）
+    pub comment_severity_filter: SeverityFilter,
+    /// コメント一覧で本文を全文表示しているコメントの ID（Space / o でトグル）
+    pub expanded_comment_ids: HashSet<u64>,
+    /// reviewee に対応させるため m で選んだレビューコメントの ID（f で送る）
+    pub marked_comment_ids: HashSet<u64>,
     // Comment positions in current diff view
     pub file_comment_positions: Vec<CommentPosition>,
     // Set of diff line indices with comments (for fast lookup in render)
@@ -388,23 +637,37 @@ pub struct App {
     pub discussion_comments_loading: bool,
     pub discussion_comment_detail_mode: bool,
     pub discussion_comment_detail_scroll: usize,
+    /// Scroll offset of the PR details view
+    pub pr_details_scroll: usize,
+    /// CI チェックの集計（取得元の PR 番号付き。ファイル一覧ヘッダに表示）
+    pub ci_checks: Option<(u32, ChecksSummary)>,
     // Comment tab state
     pub comment_tab: CommentTab,
     // AI Rally state
     pub ai_rally_state: Option<AiRallyState>,
     pub working_dir: Option<String>,
+    /// working_dir の `.hxprignore` と、working_dir が PR のリポジトリのチェックアウトか
+    /// （`.hxprignore` がなければ None）
+    hxprignore: Option<(IgnoreRules, bool)>,
+    /// バックグラウンドで読み込み中の hxprignore
+    hxprignore_receiver: Option<tokio::sync::oneshot::Receiver<Option<(IgnoreRules, bool)>>>,
     // Receivers
     // PR-specific receivers carry the originating PR number to avoid
     // cross-PR cache contamination when the user switches PRs mid-flight.
     data_receiver: PrReceiver<DataLoadResult>,
     retry_sender: Option<mpsc::Sender<RefreshRequest>>,
+    /// PR ホスティングサービスへのアクセス（既定は GitHub）
+    vcs: Arc<dyn VcsProvider>,
     comment_receiver: PrReceiver<Result<Vec<ReviewComment>, String>>,
     diff_cache_receiver: Option<mpsc::Receiver<DiffCache>>,
     prefetch_receiver: Option<mpsc::Receiver<DiffCache>>,
     discussion_comment_receiver: PrReceiver<Result<Vec<DiscussionComment>, String>>,
+    ci_checks_receiver: PrReceiver<Result<ChecksSummary, String>>,
     rally_event_receiver: Option<mpsc::Receiver<RallyEvent>>,
     // Handle for aborting the rally orchestrator task
     rally_abort_handle: Option<AbortHandle>,
+    // Cancels the agent run of the rally task (kills the agent process)
+    rally_cancel_token: Option<CancellationToken>,
     // Command sender to communicate with the orchestrator
     rally_command_sender: Option<mpsc::Sender<OrchestratorCommand>>,
     // Flag to start AI Rally when data is loaded (set by --ai-rally CLI flag)
@@ -418,6 +681,8 @@ pub struct App {
     pub submission_result: Option<(bool, String)>,
     /// Timestamp when result was set (for auto-hide)
     submission_result_time: Option<Instant>,
+    /// 手動リフレッシュ前の状態（再取得後の選択復元と通知に使う）
+    refresh_origin: Option<RefreshOrigin>,
     /// Spinner animation frame counter (incremented each tick)
     pub spinner_frame: usize,
     /// インラインコメントパネル内の選択インデックス
@@ -434,6 +699,64 @@ pub struct App {
     pub session_cache: SessionCache,
 }
 
+/// 開始直後（Initializing）の Rally 状態
+fn new_rally_state(max_iterations: u32) -> AiRallyState {
+    AiRallyState {
+        iteration: 0,
+        max_iterations,
+        state: RallyState::Initializing,
+        history: Vec::new(),
+        logs: Vec::new(),
+        log_scroll_offset: 0,
+        selected_log_index: None,
+        showing_log_detail: false,
+        pending_question: None,
+        pending_permission: None,
+        pending_review_post: None,
+        review_post_selection: None,
+        review_post_edited: false,
+        pending_fix_post: None,
+        last_visible_log_height: 10,
+        total_cost_usd: None,
+        total_duration_ms: 0,
+        turn_started_at: None,
+        last_raw_result: None,
+        showing_raw_result: false,
+        raw_result_scroll: 0,
+        error_details: None,
+        error_details_scroll: 0,
+        started_at: Instant::now(),
+        iteration_started_at: Vec::new(),
+        finished_at: None,
+        answering_clarification: false,
+        files_changed: Vec::new(),
+    }
+}
+
+/// コメント一覧のレビューコメントを reviewee 向けのコメントに変換する。
+/// reviewer が投稿したコメントは見出し行を除き、その重要度を使う（人のコメントは major 扱い）
+fn reviewee_comment(comment: &ReviewComment) -> Option<crate::ai::adapter::ReviewComment> {
+    let line = comment.line?;
+    let (severity, body) = match CommentSeverity::from_posted_comment(&comment.body) {
+        Some(severity) => (
+            severity,
+            comment
+                .body
+                .split_once('\n')
+                .map_or("", |(_, rest)| rest)
+                .trim(),
+        ),
+        None => (CommentSeverity::Major, comment.body.trim()),
+    };
+    Some(crate::ai::adapter::ReviewComment {
+        path: comment.path.clone(),
+        line,
+        start_line: comment.start_line.filter(|&start| start < line),
+        body: body.to_string(),
+        severity,
+    })
+}
+
 impl App {
     /// Loading状態で開始
     pub fn new_loading(
@@ -455,6 +778,7 @@ impl App {
             pr_list_has_more: false,
             pr_list_state_filter: PrStateFilter::default(),
             started_from_pr_list: false,
+            pr_queue: None,
             local_mode: false,
             local_auto_focus: false,
             local_file_signatures: HashMap::new(),
@@ -467,6 +791,7 @@ impl App {
             diff_view_return_state: AppState::FileList,
             preview_return_state: AppState::DiffView,
             previous_state: AppState::FileList,
+            help_return_state: AppState::FileList,
             selected_file: 0,
             file_list_scroll_offset: 0,
             selected_line: 0,
@@ -479,6 +804,10 @@ impl App {
             review_comments: None,
             selected_comment: 0,
             comment_list_scroll_offset: 0,
+            review_comment_detail_scroll: 0,
+            comment_severity_filter: SeverityFilter::default(),
+            expanded_comment_ids: HashSet::new(),
+            marked_comment_ids: HashSet::new(),
             comments_loading: false,
             file_comment_positions: vec![],
             file_comment_lines: HashSet::new(),
@@ -492,17 +821,24 @@ impl App {
             discussion_comments_loading: false,
             discussion_comment_detail_mode: false,
             discussion_comment_detail_scroll: 0,
+            pr_details_scroll: 0,
+            ci_checks: None,
             comment_tab: CommentTab::default(),
             ai_rally_state: None,
             working_dir: None,
+            hxprignore: None,
+            hxprignore_receiver: None,
             data_receiver: Some((pr_number, rx)),
             retry_sender: None,
+            vcs: Arc::new(GithubProvider),
             comment_receiver: None,
             diff_cache_receiver: None,
             prefetch_receiver: None,
             discussion_comment_receiver: None,
+            ci_checks_receiver: None,
             rally_event_receiver: None,
             rally_abort_handle: None,
+            rally_cancel_token: None,
             rally_command_sender: None,
             start_ai_rally_on_load: false,
             pending_ai_rally: false,
@@ -510,6 +846,7 @@ impl App {
             comment_submitting: false,
             submission_result: None,
             submission_result_time: None,
+            refresh_origin: None,
             spinner_frame: 0,
             selected_inline_comment: 0,
             jump_stack: Vec::new(),
@@ -536,10 +873,12 @@ impl App {
             pr_list_has_more: false,
             pr_list_state_filter: PrStateFilter::default(),
             started_from_pr_list: true,
+            pr_queue: None,
             pr_list_receiver: None,
             diff_view_return_state: AppState::FileList,
             preview_return_state: AppState::DiffView,
             previous_state: AppState::PullRequestList,
+            help_return_state: AppState::PullRequestList,
             selected_file: 0,
             file_list_scroll_offset: 0,
             selected_line: 0,
@@ -552,6 +891,10 @@ impl App {
             review_comments: None,
             selected_comment: 0,
             comment_list_scroll_offset: 0,
+            review_comment_detail_scroll: 0,
+            comment_severity_filter: SeverityFilter::default(),
+            expanded_comment_ids: HashSet::new(),
+            marked_comment_ids: HashSet::new(),
             comments_loading: false,
             file_comment_positions: vec![],
             file_comment_lines: HashSet::new(),
@@ -565,17 +908,24 @@ impl App {
             discussion_comments_loading: false,
             discussion_comment_detail_mode: false,
             discussion_comment_detail_scroll: 0,
+            pr_details_scroll: 0,
+            ci_checks: None,
             comment_tab: CommentTab::default(),
             ai_rally_state: None,
             working_dir: None,
+            hxprignore: None,
+            hxprignore_receiver: None,
             data_receiver: None,
             retry_sender: None,
+            vcs: Arc::new(GithubProvider),
             comment_receiver: None,
             diff_cache_receiver: None,
             prefetch_receiver: None,
             discussion_comment_receiver: None,
+            ci_checks_receiver: None,
             rally_event_receiver: None,
             rally_abort_handle: None,
+            rally_cancel_token: None,
             rally_command_sender: None,
             start_ai_rally_on_load: false,
             pending_ai_rally: false,
@@ -583,6 +933,7 @@ impl App {
             comment_submitting: false,
             submission_result: None,
             submission_result_time: None,
+            refresh_origin: None,
             spinner_frame: 0,
             selected_inline_comment: 0,
             jump_stack: Vec::new(),
@@ -601,6 +952,14 @@ impl App {
         }
     }
 
+    /// PR一覧を指定された PR 群（レビューキュー）に限定する
+    ///
+    /// 明示的に指定された PR なので、マージ済み・クローズ済みも含めて表示する
+    pub fn set_pr_queue(&mut self, pr_numbers: Vec<u32>) {
+        self.pr_queue = Some(pr_numbers);
+        self.pr_list_state_filter = PrStateFilter::All;
+    }
+
     /// PR一覧受信チャンネルを設定
     pub fn set_pr_list_receiver(&mut self, rx: mpsc::Receiver<Result<github::PrListPage, String>>) {
         self.pr_list_receiver = Some(rx);
@@ -615,6 +974,11 @@ impl App {
         self.retry_sender = Some(tx);
     }
 
+    /// バックグラウンドのデータ取得で使う PR ホスティングサービス
+    pub fn vcs_provider(&self) -> Arc<dyn VcsProvider> {
+        self.vcs.clone()
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let mut terminal = ui::setup_terminal()?;
 
@@ -637,6 +1001,7 @@ impl App {
             self.poll_diff_cache_updates();
             self.poll_prefetch_updates();
             self.poll_discussion_comment_updates();
+            self.poll_ci_checks_updates();
             self.poll_comment_submit_updates();
             self.poll_rally_events();
             terminal.draw(|frame| ui::render(frame, self))?;
@@ -644,9 +1009,7 @@ impl App {
         }
 
         // Graceful shutdown: abort any running rally
-        if let Some(handle) = self.rally_abort_handle.take() {
-            handle.abort();
-        }
+        self.stop_rally_task();
 
         ui::restore_terminal(&mut terminal)?;
         Ok(())
@@ -659,6 +1022,26 @@ impl App {
 
     pub fn set_working_dir(&mut self, dir: Option<String>) {
         self.working_dir = dir;
+        self.load_hxprignore();
+    }
+
+    /// working_dir の `.hxprignore` をバックグラウンドで読み込む
+    ///
+    /// チェックアウトの確認は git を実行するため UI スレッドでは行わない
+    fn load_hxprignore(&mut self) {
+        let dir = PathBuf::from(self.working_dir.as_deref().unwrap_or("."));
+        let repo = self.repo.clone();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let rules = IgnoreRules::new(&[], Some(&dir));
+            let loaded = (!rules.is_empty()).then(|| {
+                let is_checkout = ignore::is_checkout_of(&dir, &repo);
+                (rules, is_checkout)
+            });
+            let _ = tx.send(loaded);
+        });
+        self.hxprignore = None;
+        self.hxprignore_receiver = Some(rx);
     }
 
     pub fn set_local_mode(&mut self, local: bool) {
@@ -746,6 +1129,8 @@ impl App {
                 self.highlighted_cache_store.clear();
                 self.review_comments = None;
                 self.discussion_comments = None;
+                self.expanded_comment_ids.clear();
+                self.marked_comment_ids.clear();
             }
 
             // restore_view_snapshot がスナップショットの pr_number で上書きする可能性があるため、
@@ -1042,14 +1427,42 @@ impl App {
         }
     }
 
+    /// `.hxprignore` に一致するファイルをファイル一覧から除外する
+    ///
+    /// ファイルがなければ何もしない。キャッシュにも除外後の一覧が入る。
+    /// PR モードでは working_dir が別リポジトリのこともあるため、
+    /// PR のリポジトリのチェックアウトである場合だけ適用する
+    fn drop_ignored_files(&self, result: &mut DataLoadResult) {
+        let DataLoadResult::Success { files, .. } = result else {
+            return;
+        };
+        let Some((rules, is_checkout)) = &self.hxprignore else {
+            return;
+        };
+        if !self.local_mode && !is_checkout {
+            return;
+        }
+        files.retain(|file| !rules.is_ignored(&file.filename));
+    }
+
     /// バックグラウンドタスクからのデータ更新をポーリング
     fn poll_data_updates(&mut self) {
+        // `.hxprignore` の読み込みが終わるまでデータはチャンネルに残しておく
+        if let Some(rx) = self.hxprignore_receiver.as_mut() {
+            match rx.try_recv() {
+                Ok(loaded) => self.hxprignore = loaded,
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return,
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {}
+            }
+            self.hxprignore_receiver = None;
+        }
         let Some((_origin_pr, rx)) = self.data_receiver.as_mut() else {
             return;
         };
 
         match rx.try_recv() {
-            Ok(result) => {
+            Ok(mut result) => {
+                self.drop_ignored_files(&mut result);
                 // メッセージ自体から発信元PR番号を取得（mutable な origin_pr に依存しない）
                 let source_pr = match &result {
                     DataLoadResult::Success { pr, .. } => Some(pr.number),
@@ -1106,6 +1519,7 @@ impl App {
                     self.review_comments = Some(comments);
                     self.selected_comment = 0;
                     self.comment_list_scroll_offset = 0;
+                    self.review_comment_detail_scroll = 0;
                     self.comments_loading = false;
                     // Update comment positions if in diff view or side-by-side
                     if matches!(
@@ -1322,6 +1736,29 @@ impl App {
         }
     }
 
+    /// CI チェック取得のポーリング
+    fn poll_ci_checks_updates(&mut self) {
+        let Some((origin_pr, rx)) = self.ci_checks_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+
+        match rx.try_recv() {
+            Ok(Ok(summary)) => {
+                self.ci_checks = Some((origin_pr, summary));
+                self.ci_checks_receiver = None;
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to fetch CI checks: {}", e);
+                self.ci_checks_receiver = None;
+            }
+            Err(mpsc::error::TryRecvError::Empty) => {}
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.ci_checks_receiver = None;
+            }
+        }
+    }
+
     /// コメント送信結果のポーリング
     fn poll_comment_submit_updates(&mut self) {
         // Clear old submission result after 3 seconds
@@ -1388,7 +1825,32 @@ impl App {
                     if let Some(ref mut rally_state) = self.ai_rally_state {
                         match &event {
                             RallyEvent::StateChanged(state) => {
+                                // エージェントのターンが切り替わったら経過時間を測り直す
+                                if state.is_agent_turn() {
+                                    if rally_state.state != *state
+                                        || rally_state.turn_started_at.is_none()
+                                    {
+                                        rally_state.turn_started_at = Some(Instant::now());
+                                    }
+                                } else {
+                                    rally_state.turn_started_at = None;
+                                }
+                                // 新しい reviewee ターンでは変更ファイル一覧をリセットする
+                                // （許可・質問への回答後の再開は同じターン扱い）
+                                if *state == RallyState::RevieweeFix
+                                    && !matches!(
+                                        rally_state.state,
+                                        RallyState::RevieweeFix
+                                            | RallyState::WaitingForPermission
+                                            | RallyState::WaitingForClarification
+                                    )
+                                {
+                                    rally_state.files_changed.clear();
+                                }
                                 rally_state.state = *state;
+                                if *state != RallyState::WaitingForClarification {
+                                    rally_state.answering_clarification = false;
+                                }
                                 // Clear pending post info on terminal states
                                 if matches!(
                                     state,
@@ -1399,9 +1861,13 @@ impl App {
                                     rally_state.pending_review_post = None;
                                     rally_state.pending_fix_post = None;
                                 }
+                                if *state == RallyState::Completed {
+                                    rally_state.finished_at.get_or_insert_with(Instant::now);
+                                }
                             }
                             RallyEvent::IterationStarted(i) => {
                                 rally_state.iteration = *i;
+                                rally_state.iteration_started_at.push((*i, Instant::now()));
                                 rally_state.push_log(LogEntry::new(
                                     LogEventType::Info,
                                     format!("Starting iteration {}", i),
@@ -1436,21 +1902,67 @@ impl App {
                                 rally_state
                                     .push_log(LogEntry::new(LogEventType::Text, text.clone()));
                             }
-                            RallyEvent::ReviewCompleted(_) => {
+                            RallyEvent::ReviewCompleted(review) => {
                                 rally_state.push_log(LogEntry::new(
                                     LogEventType::Review,
                                     "Review completed".to_string(),
                                 ));
+                                for comment in &review.comments {
+                                    rally_state.push_log(LogEntry::for_review_comment(comment));
+                                }
+                                // digest モードでは優先順位付きのアクションリストをログに出す
+                                if self.config.ai.digest {
+                                    rally_state.push_log(LogEntry::new(
+                                        LogEventType::Review,
+                                        crate::ai::digest::format_digest(review),
+                                    ));
+                                }
                             }
                             RallyEvent::FixCompleted(fix) => {
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Fix,
-                                    format!("Fix completed: {}", fix.summary),
-                                ));
+                                // 詳細表示用にファイルごとの増減行数を添える
+                                let mut message = format!("Fix completed: {}", fix.summary);
+                                for stat in &fix.diff_stats {
+                                    message.push_str(&format!(
+                                        "\n  {} +{} -{}",
+                                        stat.path, stat.additions, stat.deletions
+                                    ));
+                                }
+                                rally_state.push_log(LogEntry::new(LogEventType::Fix, message));
+                                // エラー終了時はエージェントが報告した原因を詳細ペインに出す
+                                if fix.status == RevieweeStatus::Error {
+                                    rally_state.error_details = fix.error_details.clone();
+                                    rally_state.error_details_scroll = 0;
+                                }
                             }
                             RallyEvent::Error(e) => {
                                 rally_state.push_log(LogEntry::new(LogEventType::Error, e.clone()));
                             }
+                            RallyEvent::FileModified(path) => {
+                                rally_state.record_file_modified(path.clone());
+                            }
+                            RallyEvent::PushBlocked(target) => {
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Error,
+                                    format!("Blocked a git push by the reviewee: {}", target),
+                                ));
+                            }
+                            RallyEvent::MaxIterationsReached(summary) => {
+                                rally_state
+                                    .push_log(LogEntry::new(LogEventType::Info, summary.clone()));
+                            }
+                            RallyEvent::MergeOutlook { detail, .. } => {
+                                rally_state
+                                    .push_log(LogEntry::new(LogEventType::Info, detail.clone()));
+                            }
+                            RallyEvent::AgentRawResult(result) => {
+                                rally_state.last_raw_result = Some(result.clone());
+                            }
+                            RallyEvent::UsageReported {
+                                cost_usd,
+                                duration_ms,
+                            } => {
+                                rally_state.record_usage(*cost_usd, *duration_ms);
+                            }
                             RallyEvent::ClarificationNeeded(question) => {
                                 rally_state.pending_question = Some(question.clone());
                                 rally_state.push_log(LogEntry::new(
@@ -1470,6 +1982,8 @@ impl App {
                             }
                             RallyEvent::ReviewPostConfirmNeeded(info) => {
                                 rally_state.pending_review_post = Some(info.clone());
+                                rally_state.review_post_selection = None;
+                                rally_state.review_post_edited = false;
                                 rally_state.pending_fix_post = None; // exclusive
                                 rally_state.push_log(LogEntry::new(
                                     LogEventType::Info,
@@ -1516,15 +2030,19 @@ impl App {
     fn handle_data_result(&mut self, origin_pr: u32, result: DataLoadResult) {
         match result {
             DataLoadResult::Success { pr, files } => {
+                let head_sha = pr.head.sha.clone();
+                let refresh_origin = self.refresh_origin.take();
                 let changed_file_index = if self.local_mode && self.local_auto_focus {
                     self.find_changed_local_file_index(&files, self.selected_file)
                 } else {
                     None
                 };
+                // リフレッシュ中は files() が空なので、リフレッシュ前の選択を使う
                 let old_selected_file = self
                     .files()
                     .get(self.selected_file)
-                    .map(|file| file.filename.clone());
+                    .map(|file| file.filename.clone())
+                    .or_else(|| refresh_origin.as_ref()?.selected_file.clone());
                 let old_selected = self.selected_file;
                 let mut next_selected = if files.is_empty() {
                     0
@@ -1598,6 +2116,8 @@ impl App {
                     },
                 );
                 self.data_state = DataState::Loaded { pr, files };
+                // head が変わっているかもしれないので CI の状態も取り直す
+                self.load_ci_checks();
                 // selected_file が変更された場合、コメント位置キャッシュを再計算
                 if self.selected_file != old_selected {
                     self.update_file_comment_positions();
@@ -1623,8 +2143,14 @@ impl App {
                 // ファイル選択変更後も差分キャッシュを即座に復旧して
                 // split view 側の「Loading diff...」が発生しないようにする
                 self.ensure_diff_cache();
+                if let Some(origin) = refresh_origin {
+                    let message = refresh_message(&origin.head_sha, &head_sha, self.local_mode);
+                    self.submission_result = Some((true, message));
+                    self.submission_result_time = Some(Instant::now());
+                }
             }
             DataLoadResult::Error(msg) => {
+                self.refresh_origin = None;
                 // Loading状態の場合のみエラー表示（既にデータがある場合は無視）
                 if matches!(self.data_state, DataState::Loading) {
                     self.data_state = DataState::Error(msg);
@@ -1768,6 +2294,7 @@ impl App {
                     AppState::TextInput => self.handle_text_input(key)?,
                     AppState::CommentList => self.handle_comment_list_input(key, terminal).await?,
                     AppState::Help => self.handle_help_input(key)?,
+                    AppState::PrDetails => self.handle_pr_details_input(key),
                     AppState::AiRally => self.handle_ai_rally_input(key, terminal).await?,
                     AppState::SplitViewFileList => {
                         self.handle_split_view_file_list_input(key, terminal)
@@ -1869,12 +2396,28 @@ impl App {
             return Ok(());
         }
 
+        // PR details (title, labels, description)
+        if self.matches_single_key(&key, &kb.pr_details) {
+            if self.pr().is_some() {
+                self.previous_state = AppState::FileList;
+                self.pr_details_scroll = 0;
+                self.state = AppState::PrDetails;
+            }
+            return Ok(());
+        }
+
         // Refresh
         if self.matches_single_key(&key, &kb.refresh) {
             self.refresh_all();
             return Ok(());
         }
 
+        // CI チェックだけを取り直す
+        if !self.local_mode && self.matches_single_key(&key, &kb.refresh_checks) {
+            self.load_ci_checks();
+            return Ok(());
+        }
+
         // AI Rally — ローカルdiffモードでも新規起動・resumeの両方を許可する（仕様）。
         // ローカルモードではコメント投稿等のAPI呼び出しはオーケストレーター側でスキップされる。
         if self.matches_single_key(&key, &kb.ai_rally) {
@@ -1882,6 +2425,12 @@ impl App {
             return Ok(());
         }
 
+        // 終了済み Rally のレビューを選択ファイルだけ再実行する
+        if self.matches_single_key(&key, &kb.rerun_file_review) {
+            self.rerun_review_for_selected_file();
+            return Ok(());
+        }
+
         // Open in browser (disabled in local mode)
         if !self.local_mode && self.matches_single_key(&key, &kb.open_in_browser) {
             if let Some(pr_number) = self.pr_number {
@@ -1906,8 +2455,7 @@ impl App {
 
         // Help
         if self.matches_single_key(&key, &kb.help) {
-            self.previous_state = AppState::FileList;
-            self.state = AppState::Help;
+            self.open_help();
             return Ok(());
         }
 
@@ -2030,8 +2578,7 @@ impl App {
 
         // Help
         if self.matches_single_key(&key, &kb.help) {
-            self.previous_state = AppState::SplitViewFileList;
-            self.state = AppState::Help;
+            self.open_help();
             return Ok(());
         }
 
@@ -2075,6 +2622,12 @@ impl App {
         // Clone keybindings to avoid borrow issues with self
         let kb = self.config.keybindings.clone();
 
+        // Help
+        if self.matches_single_key(&key, &kb.help) {
+            self.open_help();
+            return Ok(());
+        }
+
         // コメントパネルフォーカス中
         if self.comment_panel_open {
             // Move down in panel
@@ -2135,6 +2688,14 @@ impl App {
                 return Ok(());
             }
 
+            // Apply the selected comment's suggestion to the local file
+            if self.matches_single_key(&key, &kb.apply_suggestion) {
+                if self.has_comment_at_current_line() {
+                    self.apply_selected_suggestion().await;
+                }
+                return Ok(());
+            }
+
             // Tab - select next inline comment
             if key.code == KeyCode::Tab {
                 if self.has_comment_at_current_line() {
@@ -2416,18 +2977,108 @@ impl App {
     ) -> Result<()> {
         // Handle modal state first
         if let Some(ref mut rally_state) = self.ai_rally_state {
+            // デバッグ用: エージェントが返した生の result JSON を表示する
+            if key.code == KeyCode::F(12) {
+                rally_state.showing_raw_result = !rally_state.showing_raw_result;
+                rally_state.raw_result_scroll = 0;
+                return Ok(());
+            }
+            if rally_state.showing_raw_result {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => rally_state.showing_raw_result = false,
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        rally_state.raw_result_scroll =
+                            rally_state.raw_result_scroll.saturating_add(1);
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        rally_state.raw_result_scroll =
+                            rally_state.raw_result_scroll.saturating_sub(1);
+                    }
+                    KeyCode::PageDown => {
+                        rally_state.raw_result_scroll =
+                            rally_state.raw_result_scroll.saturating_add(20);
+                    }
+                    KeyCode::PageUp => {
+                        rally_state.raw_result_scroll =
+                            rally_state.raw_result_scroll.saturating_sub(20);
+                    }
+                    KeyCode::Char('g') => rally_state.raw_result_scroll = 0,
+                    _ => {}
+                }
+                return Ok(());
+            }
+            if rally_state.answering_clarification {
+                self.handle_clarification_input(key);
+                return Ok(());
+            }
             if rally_state.showing_log_detail {
+                let mut requeue = None;
                 match key.code {
                     KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
                         rally_state.showing_log_detail = false;
                     }
+                    KeyCode::Char('y') => {
+                        // レビューコメントなら本文だけ、それ以外はログのメッセージをコピーする
+                        let text = rally_state
+                            .selected_log_index
+                            .and_then(|idx| rally_state.logs.get(idx))
+                            .map(|entry| match entry.review_comment {
+                                Some(ref comment) => comment.body.clone(),
+                                None => entry.message.clone(),
+                            });
+                        if let Some(text) = text {
+                            rally_state.copy_to_clipboard(&text, "log entry");
+                        }
+                    }
+                    KeyCode::Char('f') => {
+                        // 選択中のレビューコメント1件だけを reviewee に再送する
+                        let comment = rally_state
+                            .selected_log_index
+                            .and_then(|idx| rally_state.logs.get(idx))
+                            .and_then(|entry| entry.review_comment.clone());
+                        if let Some(comment) = comment.filter(|_| rally_state.state.is_active()) {
+                            rally_state.showing_log_detail = false;
+                            rally_state.push_log(LogEntry::new(
+                                LogEventType::Info,
+                                format!(
+                                    "Requeued {}:{} for the next reviewee turn",
+                                    comment.path, comment.line
+                                ),
+                            ));
+                            requeue = Some(comment);
+                        }
+                    }
                     _ => {}
                 }
+                if let Some(comment) = requeue {
+                    self.send_rally_command(OrchestratorCommand::RequeueComment(comment));
+                }
                 return Ok(());
             }
         }
 
+        if self.matches_single_key(&key, &self.config.keybindings.help) {
+            self.open_help();
+            return Ok(());
+        }
+
         match key.code {
+            // ログのページ送り（Ctrl-b/f は 'b' より先に判定する）
+            KeyCode::PageDown => self.page_rally_logs(true),
+            KeyCode::PageUp => self.page_rally_logs(false),
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.page_rally_logs(true)
+            }
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.page_rally_logs(false)
+            }
+            // エラー詳細ペインのスクロール
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_rally_error_details(true)
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_rally_error_details(false)
+            }
             KeyCode::Char('b') => {
                 // バックグラウンドで実行を継続したままFileListに戻る
                 // abort()を呼ばない、状態も保持したまま
@@ -2445,11 +3096,7 @@ impl App {
                         self.send_rally_command(OrchestratorCommand::Abort);
                     }
                 }
-                // Abort the orchestrator task if running
-                if let Some(handle) = self.rally_abort_handle.take() {
-                    handle.abort();
-                }
-                // Abort rally and return to file list
+                // Abort rally (kills the agent process) and return to file list
                 self.cleanup_rally_state();
                 self.state = AppState::FileList;
             }
@@ -2476,19 +3123,23 @@ impl App {
                         }
                     }
                     RallyState::WaitingForClarification => {
-                        // Get the question for the editor
-                        let question = self
-                            .ai_rally_state
-                            .as_ref()
-                            .and_then(|s| s.pending_question.clone())
-                            .unwrap_or_default();
-
-                        // Open editor synchronously (restore terminal first)
-                        self.open_clarification_editor_sync(&question, terminal)?;
+                        // 回答入力ポップアップを開く
+                        self.start_clarification_input();
                     }
                     RallyState::WaitingForPostConfirmation => {
-                        // Approve posting
-                        self.send_rally_command(OrchestratorCommand::PostConfirmResponse(true));
+                        // Approve posting（編集済みなら編集後の内容で投稿する）
+                        let edited = self
+                            .ai_rally_state
+                            .as_ref()
+                            .filter(|s| s.review_post_edited)
+                            .and_then(|s| s.pending_review_post.as_ref())
+                            .map(|info| OrchestratorCommand::PostConfirmEdited {
+                                summary: info.summary.clone(),
+                                comments: info.comments.clone(),
+                            });
+                        self.send_rally_command(
+                            edited.unwrap_or(OrchestratorCommand::PostConfirmResponse(true)),
+                        );
                         if let Some(ref mut rally_state) = self.ai_rally_state {
                             rally_state.pending_review_post = None;
                             rally_state.pending_fix_post = None;
@@ -2500,6 +3151,15 @@ impl App {
                             ));
                         }
                     }
+                    RallyState::Completed | RallyState::Aborted | RallyState::Error => {
+                        // 終了後は最後のレビューのサマリーをコピーする
+                        if let Some(ref mut rally_state) = self.ai_rally_state {
+                            let summary = rally_state.latest_review_summary().map(str::to_string);
+                            if let Some(summary) = summary {
+                                rally_state.copy_to_clipboard(&summary, "review summary");
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -2556,14 +3216,47 @@ impl App {
                     _ => {}
                 }
             }
+            KeyCode::Tab => {
+                // Select the summary or a comment to edit before publishing
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    if rally_state.state == RallyState::WaitingForPostConfirmation {
+                        rally_state.cycle_review_post_selection();
+                    }
+                }
+            }
+            KeyCode::Char('e') => {
+                // Edit the selected summary/comment before publishing
+                let editing = self.ai_rally_state.as_ref().is_some_and(|s| {
+                    s.state == RallyState::WaitingForPostConfirmation
+                        && s.pending_review_post.is_some()
+                });
+                // 長い回答は外部エディタで書く
+                let clarification = self.ai_rally_state.as_ref().and_then(|s| {
+                    (s.state == RallyState::WaitingForClarification)
+                        .then(|| s.pending_question.clone().unwrap_or_default())
+                });
+                if editing {
+                    self.open_review_post_editor_sync(terminal)?;
+                } else if let Some(question) = clarification {
+                    // Open editor synchronously (restore terminal first)
+                    self.open_clarification_editor_sync(&question, terminal)?;
+                }
+            }
             KeyCode::Char('r') => {
                 // Retry on error state
-                if let Some(ref state) = self.ai_rally_state {
+                if let Some(ref mut state) = self.ai_rally_state {
                     if state.state == RallyState::Error {
-                        // Abort current handle if any
-                        if let Some(handle) = self.rally_abort_handle.take() {
-                            handle.abort();
+                        // 失敗したターンで待機中のオーケストレーターがあれば、そのターンだけ再実行する
+                        let retried = self.rally_command_sender.as_ref().is_some_and(|sender| {
+                            sender.try_send(OrchestratorCommand::Retry).is_ok()
+                        });
+                        if retried {
+                            state.error_details = None;
+                            state.error_details_scroll = 0;
+                            return Ok(());
                         }
+                        // オーケストレーターが終了済みなら最初からやり直す
+                        self.stop_rally_task();
                         // Clear state and restart
                         self.ai_rally_state = None;
                         self.rally_event_receiver = None;
@@ -2640,6 +3333,26 @@ impl App {
         Ok(())
     }
 
+    fn page_rally_logs(&mut self, down: bool) {
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            rally_state.page_logs(down);
+        }
+    }
+
+    /// エラー詳細ペイン（reviewee が報告したエラー）を数行ずつスクロールする
+    fn scroll_rally_error_details(&mut self, down: bool) {
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            if rally_state.error_details.is_none() {
+                return;
+            }
+            rally_state.error_details_scroll = if down {
+                rally_state.error_details_scroll.saturating_add(5)
+            } else {
+                rally_state.error_details_scroll.saturating_sub(5)
+            };
+        }
+    }
+
     /// Adjust log scroll offset to keep the selected log visible
     fn adjust_log_scroll_to_selection(&mut self) {
         if let Some(ref mut rally_state) = self.ai_rally_state {
@@ -2684,33 +3397,90 @@ impl App {
         self.ai_rally_state = None;
         self.rally_command_sender = None;
         self.rally_event_receiver = None;
-        if let Some(handle) = self.rally_abort_handle.take() {
-            handle.abort();
-        }
+        self.stop_rally_task();
     }
 
-    /// Open editor for clarification input synchronously
-    fn open_clarification_editor_sync(
-        &mut self,
-        question: &str,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        // Restore terminal before opening editor
-        ui::restore_terminal(terminal)?;
-
-        // Open editor (blocking)
-        let answer = crate::editor::open_clarification_editor(&self.config.editor, question)?;
-
-        // Re-setup terminal after editor closes
-        *terminal = ui::setup_terminal()?;
-
-        // Process result
-        if let Some(ref mut rally_state) = self.ai_rally_state {
-            rally_state.pending_question = None;
+    /// Rally タスクを停止する。
+    /// 先にキャンセルを通知してエージェントのプロセスを kill・回収させ、
+    /// 猶予後にタスク自体を中断する（プロセスを持たない処理の途中でも止まるように）。
+    fn stop_rally_task(&mut self) {
+        let token = self.rally_cancel_token.take();
+        let Some(handle) = self.rally_abort_handle.take() else {
+            return;
+        };
+        match token {
+            Some(token) => {
+                token.cancel();
+                tokio::spawn(async move {
+                    tokio::time::sleep(RALLY_CANCEL_GRACE).await;
+                    handle.abort();
+                });
+            }
+            None => handle.abort(),
         }
+    }
 
-        match answer {
-            Some(text) if !text.trim().is_empty() => {
+    /// reviewee の質問への回答入力ポップアップを開く
+    fn start_clarification_input(&mut self) {
+        let Some(ref mut rally_state) = self.ai_rally_state else {
+            return;
+        };
+        rally_state.answering_clarification = true;
+        self.input_text_area.clear();
+    }
+
+    /// 回答入力ポップアップのキー処理。送信で回答を reviewee に渡し、Esc で質問画面に戻る
+    fn handle_clarification_input(&mut self, key: event::KeyEvent) {
+        match self.input_text_area.input(key) {
+            TextAreaAction::Submit => {
+                let answer = self.input_text_area.content();
+                if answer.trim().is_empty() {
+                    // 空の回答は送らない（スキップは 'n'）
+                    return;
+                }
+                self.input_text_area.clear();
+                self.send_rally_command(OrchestratorCommand::ClarificationResponse(answer.clone()));
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    rally_state.answering_clarification = false;
+                    rally_state.pending_question = None;
+                    rally_state.push_log(LogEntry::new(
+                        LogEventType::Info,
+                        format!("Clarification provided: {}", answer),
+                    ));
+                }
+            }
+            TextAreaAction::Cancel => {
+                self.input_text_area.clear();
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    rally_state.answering_clarification = false;
+                }
+            }
+            TextAreaAction::Continue | TextAreaAction::PendingSequence => {}
+        }
+    }
+
+    /// Open editor for clarification input synchronously
+    fn open_clarification_editor_sync(
+        &mut self,
+        question: &str,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        // Restore terminal before opening editor
+        ui::restore_terminal(terminal)?;
+
+        // Open editor (blocking)
+        let answer = crate::editor::open_clarification_editor(&self.config.editor, question)?;
+
+        // Re-setup terminal after editor closes
+        *terminal = ui::setup_terminal()?;
+
+        // Process result
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            rally_state.pending_question = None;
+        }
+
+        match answer {
+            Some(text) if !text.trim().is_empty() => {
                 // Send clarification response
                 self.send_rally_command(OrchestratorCommand::ClarificationResponse(text.clone()));
                 if let Some(ref mut rally_state) = self.ai_rally_state {
@@ -2735,6 +3505,61 @@ impl App {
         Ok(())
     }
 
+    /// 確認画面で選択中のサマリー／コメントをエディタで編集する（投稿は 'y' で行う）
+    fn open_review_post_editor_sync(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        let Some(rally_state) = self.ai_rally_state.as_ref() else {
+            return Ok(());
+        };
+        let Some(info) = rally_state.pending_review_post.as_ref() else {
+            return Ok(());
+        };
+        let selection = rally_state.review_post_selection;
+        let target = selection.and_then(|i| info.comments.get(i)).cloned();
+
+        ui::restore_terminal(terminal)?;
+        let edited = match target {
+            Some(ref comment) => crate::editor::open_review_comment_editor(
+                &self.config.editor,
+                &comment.path,
+                comment.line,
+                &comment.body,
+            ),
+            None => crate::editor::open_review_summary_editor(&self.config.editor, &info.summary),
+        };
+        *terminal = ui::setup_terminal()?;
+        let edited = edited?;
+
+        let Some(rally_state) = self.ai_rally_state.as_mut() else {
+            return Ok(());
+        };
+        let Some(info) = rally_state.pending_review_post.as_mut() else {
+            return Ok(());
+        };
+
+        let message = match (edited, target) {
+            (Some(text), Some(comment)) if !text.trim().is_empty() => {
+                if let Some(c) = selection.and_then(|i| info.comments.get_mut(i)) {
+                    c.body = text.trim().to_string();
+                }
+                rally_state.review_post_edited = true;
+                format!("Comment {}:{} edited", comment.path, comment.line)
+            }
+            (Some(text), None) if !text.trim().is_empty() => {
+                info.summary = text.trim().to_string();
+                rally_state.review_post_edited = true;
+                "Summary edited".to_string()
+            }
+            // 編集キャンセル時は元の内容のまま
+            _ => "Edit cancelled".to_string(),
+        };
+        rally_state.push_log(LogEntry::new(LogEventType::Info, message));
+
+        Ok(())
+    }
+
     /// 既存のRallyがあれば画面遷移のみ、なければ新規Rally開始
     fn resume_or_start_ai_rally(&mut self) {
         // 既存のRallyがあれば画面遷移のみ（完了/エラー状態でも結果確認のため）
@@ -2773,23 +3598,11 @@ impl App {
                 .unwrap_or(false)
     }
 
-    fn start_ai_rally(&mut self) {
-        // Get PR data for context
-        let Some(pr) = self.pr() else {
-            return;
-        };
-
-        let file_patches: Vec<(String, String)> = self
-            .files()
-            .iter()
-            .filter_map(|f| f.patch.as_ref().map(|p| (f.filename.clone(), p.clone())))
-            .collect();
+    /// 現在の PR / ファイル一覧から Rally 用の Context を組み立てる
+    fn build_rally_context(&self) -> Option<Context> {
+        let pr = self.pr()?;
 
-        let diff = file_patches
-            .iter()
-            .map(|(_, p)| p.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
+        let (file_patches, diff) = crate::ai::adapter::patches_from_files(self.files());
 
         let base_branch = if self.local_mode {
             Self::detect_local_base_branch(self.working_dir.as_deref())
@@ -2810,6 +3623,15 @@ impl App {
             external_comments: Vec::new(),
             local_mode: self.local_mode,
             file_patches,
+            linked_issues: Vec::new(),
+        };
+        Some(context)
+    }
+
+    fn start_ai_rally(&mut self) {
+        // Get PR data for context
+        let Some(context) = self.build_rally_context() else {
+            return;
         };
 
         let (event_tx, event_rx) = mpsc::channel(100);
@@ -2820,21 +3642,7 @@ impl App {
         self.rally_command_sender = Some(cmd_tx);
 
         // Initialize rally state
-        self.ai_rally_state = Some(AiRallyState {
-            iteration: 0,
-            max_iterations: self.config.ai.max_iterations,
-            state: RallyState::Initializing,
-            history: Vec::new(),
-            logs: Vec::new(),
-            log_scroll_offset: 0,
-            selected_log_index: None,
-            showing_log_detail: false,
-            pending_question: None,
-            pending_permission: None,
-            pending_review_post: None,
-            pending_fix_post: None,
-            last_visible_log_height: 10,
-        });
+        self.ai_rally_state = Some(new_rally_state(self.config.ai.max_iterations));
 
         self.state = AppState::AiRally;
 
@@ -2842,6 +3650,8 @@ impl App {
         let config = self.config.ai.clone();
         let repo = self.repo.clone();
         let pr_number = self.pr_number();
+        let cancel_token = CancellationToken::new();
+        self.rally_cancel_token = Some(cancel_token.clone());
 
         let handle = tokio::spawn(async move {
             let orchestrator_result =
@@ -2849,6 +3659,7 @@ impl App {
             match orchestrator_result {
                 Ok(mut orchestrator) => {
                     orchestrator.set_context(context);
+                    orchestrator.set_cancellation_token(cancel_token);
                     // Note: orchestrator.run() already emits RallyEvent::Error and
                     // StateChanged(Error) when it fails, so we don't emit them again here
                     // to avoid duplicate error logs in the UI
@@ -2870,7 +3681,78 @@ impl App {
         self.rally_abort_handle = Some(handle.abort_handle());
     }
 
+    /// 終了済み Rally に対して、選択中ファイルだけレビュアーを再実行する。
+    /// 結果は既存の Rally 状態（ログ・履歴）にマージされる。
+    fn rerun_review_for_selected_file(&mut self) {
+        // 実行中の Rally と並走させない（イベントチャネルを奪ってしまうため）
+        let rally_finished = self
+            .ai_rally_state
+            .as_ref()
+            .map(|s| !s.state.is_active())
+            .unwrap_or(false);
+        if !rally_finished || self.rally_event_receiver.is_some() {
+            return;
+        }
+        let Some(path) = self
+            .files()
+            .get(self.selected_file)
+            .map(|f| f.filename.clone())
+        else {
+            return;
+        };
+        let Some(context) = self.build_rally_context() else {
+            return;
+        };
+
+        let (event_tx, event_rx) = mpsc::channel(100);
+        self.rally_event_receiver = Some(event_rx);
+        self.rally_command_sender = None;
+
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            rally_state.pending_review_post = None;
+            rally_state.pending_fix_post = None;
+            rally_state.push_log(LogEntry::new(
+                LogEventType::Info,
+                format!("Re-running reviewer on {}", path),
+            ));
+        }
+
+        let config = self.config.ai.clone();
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+        let cancel_token = CancellationToken::new();
+        self.rally_cancel_token = Some(cancel_token.clone());
+
+        let handle = tokio::spawn(async move {
+            match Orchestrator::new(&repo, pr_number, config, event_tx.clone(), None) {
+                Ok(mut orchestrator) => {
+                    orchestrator.set_context(context);
+                    orchestrator.set_cancellation_token(cancel_token);
+                    // Errors are already reported through the event channel
+                    let _ = orchestrator.run_scoped_review(&path).await;
+                }
+                Err(e) => {
+                    let _ = event_tx
+                        .send(RallyEvent::Error(format!(
+                            "Failed to create orchestrator: {}",
+                            e
+                        )))
+                        .await;
+                }
+            }
+        });
+
+        self.rally_abort_handle = Some(handle.abort_handle());
+    }
+
     fn refresh_all(&mut self) {
+        self.refresh_origin = self.pr().map(|pr| RefreshOrigin {
+            head_sha: pr.head.sha.clone(),
+            selected_file: self
+                .files()
+                .get(self.selected_file)
+                .map(|file| file.filename.clone()),
+        });
         // インメモリキャッシュを全削除
         self.session_cache.invalidate_all();
         // コメントデータをクリア
@@ -2991,11 +3873,12 @@ impl App {
         self.comment_submit_receiver = Some((pr_number, rx));
         self.comment_submitting = true;
 
+        let vcs = self.vcs.clone();
+
         tokio::spawn(async move {
-            let result = github::create_review_comment(
-                &repo, pr_number, &commit_id, &filename, position, &body,
-            )
-            .await;
+            let result = vcs
+                .create_review_comment(&repo, pr_number, &commit_id, &filename, position, &body)
+                .await;
 
             let _ = tx
                 .send(match result {
@@ -3025,11 +3908,12 @@ impl App {
         self.comment_submit_receiver = Some((pr_number, rx));
         self.comment_submitting = true;
 
+        let vcs = self.vcs.clone();
+
         tokio::spawn(async move {
-            let result = github::create_review_comment(
-                &repo, pr_number, &commit_id, &filename, position, &body,
-            )
-            .await;
+            let result = vcs
+                .create_review_comment(&repo, pr_number, &commit_id, &filename, position, &body)
+                .await;
 
             let _ = tx
                 .send(match result {
@@ -3048,8 +3932,12 @@ impl App {
         self.comment_submit_receiver = Some((pr_number, rx));
         self.comment_submitting = true;
 
+        let vcs = self.vcs.clone();
+
         tokio::spawn(async move {
-            let result = github::create_reply_comment(&repo, pr_number, comment_id, &body).await;
+            let result = vcs
+                .create_reply_comment(&repo, pr_number, comment_id, &body)
+                .await;
 
             let _ = tx
                 .send(match result {
@@ -3060,16 +3948,34 @@ impl App {
         });
     }
 
-    fn handle_help_input(&mut self, key: event::KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?') => {
-                self.state = self.previous_state;
-            }
-            _ => {}
-        }
+    /// 現在の画面の上にキーバインド一覧のポップアップを開く
+    fn open_help(&mut self) {
+        self.help_return_state = self.state;
+        self.state = AppState::Help;
+    }
+
+    /// ヘルプポップアップは任意のキーで閉じる
+    fn handle_help_input(&mut self, _key: event::KeyEvent) -> Result<()> {
+        self.state = self.help_return_state;
         Ok(())
     }
 
+    fn handle_pr_details_input(&mut self, key: event::KeyEvent) {
+        let kb = &self.config.keybindings;
+        if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
+            self.state = self.previous_state;
+        } else if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
+            // 上限は描画時に本文の行数でクランプする
+            self.pr_details_scroll = self.pr_details_scroll.saturating_add(1);
+        } else if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
+            self.pr_details_scroll = self.pr_details_scroll.saturating_sub(1);
+        } else if self.matches_single_key(&key, &kb.page_down) {
+            self.pr_details_scroll = self.pr_details_scroll.saturating_add(20);
+        } else if self.matches_single_key(&key, &kb.page_up) {
+            self.pr_details_scroll = self.pr_details_scroll.saturating_sub(20);
+        }
+    }
+
     /// コメント入力を開始（組み込みTextArea）
     fn enter_comment_input(&mut self) {
         if self.local_mode {
@@ -3078,6 +3984,10 @@ impl App {
         let Some(file) = self.files().get(self.selected_file) else {
             return;
         };
+        // サブモジュールのポインタ更新は行コメントの対象外
+        if file.submodule_change().is_some() {
+            return;
+        }
         let Some(patch) = file.patch.as_ref() else {
             return;
         };
@@ -3145,7 +4055,11 @@ impl App {
         };
 
         tracing::debug!(body_len = body.len(), "submit_review: calling GitHub API");
-        match github::submit_review(&self.repo, self.pr_number(), action, &body).await {
+        match self
+            .vcs
+            .submit_review(&self.repo, self.pr_number(), action, &body)
+            .await
+        {
             Ok(()) => {
                 let action_str = match action {
                     ReviewAction::Approve => "approved",
@@ -3194,6 +4108,10 @@ impl App {
         let Some(file) = self.files().get(self.selected_file) else {
             return;
         };
+        // サブモジュールのポインタ更新は行コメントの対象外
+        if file.submodule_change().is_some() {
+            return;
+        }
         let Some(patch) = file.patch.as_ref() else {
             return;
         };
@@ -3260,6 +4178,7 @@ impl App {
             self.review_comments = Some(comments.to_vec());
             self.selected_comment = 0;
             self.comment_list_scroll_offset = 0;
+            self.review_comment_detail_scroll = 0;
             self.comments_loading = false;
             return;
         }
@@ -3271,12 +4190,12 @@ impl App {
         self.comment_receiver = Some((pr_number, rx));
 
         let repo = self.repo.clone();
+        let vcs = self.vcs.clone();
 
         tokio::spawn(async move {
             // Fetch both review comments and reviews
-            let review_comments_result =
-                github::comment::fetch_review_comments(&repo, pr_number).await;
-            let reviews_result = github::comment::fetch_reviews(&repo, pr_number).await;
+            let review_comments_result = vcs.fetch_review_comments(&repo, pr_number).await;
+            let reviews_result = vcs.fetch_reviews(&repo, pr_number).await;
 
             // Combine results
             let mut all_comments: Vec<ReviewComment> = Vec::new();
@@ -3295,6 +4214,8 @@ impl App {
                                 id: review.id,
                                 path: "[PR Review]".to_string(),
                                 line: None,
+                                start_line: None,
+                                diff_hunk: None,
                                 body,
                                 user: review.user,
                                 created_at: review.submitted_at.unwrap_or_default(),
@@ -3311,6 +4232,31 @@ impl App {
         });
     }
 
+    /// PR の head コミットの CI チェックを取得する（ローカルモードでは何もしない）
+    fn load_ci_checks(&mut self) {
+        if self.local_mode {
+            return;
+        }
+        let Some(head_sha) = self.pr().map(|pr| pr.head.sha.clone()) else {
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.ci_checks_receiver = Some((pr_number, rx));
+
+        let repo = self.repo.clone();
+        let vcs = self.vcs.clone();
+
+        tokio::spawn(async move {
+            let result = vcs
+                .fetch_checks(&repo, &head_sha)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
+    }
+
     fn load_discussion_comments(&mut self) {
         let cache_key = PrCacheKey {
             repo: self.repo.clone(),
@@ -3332,9 +4278,10 @@ impl App {
         self.discussion_comment_receiver = Some((pr_number, rx));
 
         let repo = self.repo.clone();
+        let vcs = self.vcs.clone();
 
         tokio::spawn(async move {
-            match github::comment::fetch_discussion_comments(&repo, pr_number).await {
+            match vcs.fetch_discussion_comments(&repo, pr_number).await {
                 Ok(comments) => {
                     let _ = tx.send(Ok(comments)).await;
                 }
@@ -3357,6 +4304,11 @@ impl App {
             return self.handle_discussion_detail_input(key, visible_lines);
         }
 
+        if self.matches_single_key(&key, &self.config.keybindings.help) {
+            self.open_help();
+            return Ok(());
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.state = self.previous_state;
@@ -3375,11 +4327,10 @@ impl App {
             }
             KeyCode::Char('j') | KeyCode::Down => match self.comment_tab {
                 CommentTab::Review => {
-                    if let Some(ref comments) = self.review_comments {
-                        if !comments.is_empty() {
-                            self.selected_comment =
-                                (self.selected_comment + 1).min(comments.len().saturating_sub(1));
-                        }
+                    let visible = self.visible_review_comment_indices();
+                    if let Some(&next) = visible.iter().find(|&&i| i > self.selected_comment) {
+                        self.selected_comment = next;
+                        self.review_comment_detail_scroll = 0;
                     }
                 }
                 CommentTab::Discussion => {
@@ -3394,16 +4345,58 @@ impl App {
             },
             KeyCode::Char('k') | KeyCode::Up => match self.comment_tab {
                 CommentTab::Review => {
-                    self.selected_comment = self.selected_comment.saturating_sub(1);
+                    let visible = self.visible_review_comment_indices();
+                    if let Some(&prev) = visible.iter().rev().find(|&&i| i < self.selected_comment)
+                    {
+                        self.selected_comment = prev;
+                        self.review_comment_detail_scroll = 0;
+                    }
                 }
                 CommentTab::Discussion => {
                     self.selected_discussion_comment =
                         self.selected_discussion_comment.saturating_sub(1);
                 }
             },
+            KeyCode::Char(c @ '0'..='4') if self.comment_tab == CommentTab::Review => {
+                match c.to_digit(10).unwrap_or(0) {
+                    0 => self.comment_severity_filter.reset(),
+                    n => self
+                        .comment_severity_filter
+                        .toggle(CommentSeverity::ALL[n as usize - 1]),
+                }
+                self.snap_selected_comment_to_filter();
+            }
+            KeyCode::Char(' ') | KeyCode::Char('o') => self.toggle_selected_comment_expanded(),
+            KeyCode::Char('y') => self.copy_selected_comment(),
+            KeyCode::Char('m') if self.comment_tab == CommentTab::Review => {
+                self.toggle_selected_comment_marked()
+            }
+            KeyCode::Char('f') if self.comment_tab == CommentTab::Review => {
+                self.send_comments_to_reviewee()
+            }
+            // 詳細ペインのスクロール（半ページ単位）
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.comment_tab == CommentTab::Review =>
+            {
+                self.review_comment_detail_scroll = self
+                    .review_comment_detail_scroll
+                    .saturating_add(visible_lines / 2);
+            }
+            KeyCode::Char('u')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.comment_tab == CommentTab::Review =>
+            {
+                self.review_comment_detail_scroll = self
+                    .review_comment_detail_scroll
+                    .saturating_sub(visible_lines / 2);
+            }
             KeyCode::Enter => match self.comment_tab {
                 CommentTab::Review => {
-                    self.jump_to_comment();
+                    // ローカルにファイルがあればエディタで開き、なければ diff ビューへジャンプ
+                    if !self.open_selected_comment_in_editor(terminal).await? {
+                        self.jump_to_comment();
+                    }
                 }
                 CommentTab::Discussion => {
                     // Enter detail mode for discussion comment
@@ -3423,6 +4416,210 @@ impl App {
         Ok(())
     }
 
+    /// コメント一覧で選択中のコメントの本文
+    fn selected_comment_body(&self) -> Option<String> {
+        match self.comment_tab {
+            CommentTab::Review => self
+                .review_comments
+                .as_ref()?
+                .get(self.selected_comment)
+                .map(|c| c.body.clone()),
+            CommentTab::Discussion => self
+                .discussion_comments
+                .as_ref()?
+                .get(self.selected_discussion_comment)
+                .map(|c| c.body.clone()),
+        }
+    }
+
+    /// 選択中のコメントの本文をクリップボードにコピーし、結果をフッターに表示する
+    fn copy_selected_comment(&mut self) {
+        let Some(body) = self.selected_comment_body() else {
+            return;
+        };
+        self.submission_result = Some(match crate::clipboard::copy(&body) {
+            Ok(()) => (true, "Copied comment to clipboard".to_string()),
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                (false, e.to_string())
+            }
+        });
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// 選択中のコメントの本文の全文表示／プレビュー表示を切り替える
+    fn toggle_selected_comment_expanded(&mut self) {
+        let id = match self.comment_tab {
+            CommentTab::Review => self
+                .review_comments
+                .as_ref()
+                .and_then(|c| c.get(self.selected_comment))
+                .map(|c| c.id),
+            CommentTab::Discussion => self
+                .discussion_comments
+                .as_ref()
+                .and_then(|c| c.get(self.selected_discussion_comment))
+                .map(|c| c.id),
+        };
+        if let Some(id) = id {
+            if !self.expanded_comment_ids.remove(&id) {
+                self.expanded_comment_ids.insert(id);
+            }
+        }
+    }
+
+    /// 選択中のレビューコメントを reviewee に送る対象に加える／外す
+    fn toggle_selected_comment_marked(&mut self) {
+        let Some(id) = self
+            .review_comments
+            .as_ref()
+            .and_then(|c| c.get(self.selected_comment))
+            .map(|c| c.id)
+        else {
+            return;
+        };
+        if !self.marked_comment_ids.remove(&id) {
+            self.marked_comment_ids.insert(id);
+        }
+    }
+
+    /// reviewee に送るコメント: m で選んだもの（一覧順）、なければ選択中の1件。
+    /// 行に紐づかないコメント（outdated など）は対象外
+    fn comments_for_reviewee(&self) -> Vec<crate::ai::adapter::ReviewComment> {
+        let Some(ref comments) = self.review_comments else {
+            return Vec::new();
+        };
+        let selected: Vec<&ReviewComment> = if self.marked_comment_ids.is_empty() {
+            comments.get(self.selected_comment).into_iter().collect()
+        } else {
+            comments
+                .iter()
+                .filter(|c| self.marked_comment_ids.contains(&c.id))
+                .collect()
+        };
+        selected.into_iter().filter_map(reviewee_comment).collect()
+    }
+
+    /// 選んだコメントだけを reviewee に対応させる。
+    /// Rally 実行中なら次の reviewee ターンに回し、そうでなければ reviewee だけを1ターン実行する
+    fn send_comments_to_reviewee(&mut self) {
+        let comments = self.comments_for_reviewee();
+        if comments.is_empty() {
+            return;
+        }
+        let count = comments.len();
+        let rally_active = self
+            .ai_rally_state
+            .as_ref()
+            .is_some_and(|s| s.state.is_active());
+
+        let message = if rally_active && self.rally_command_sender.is_some() {
+            if let Some(ref mut rally_state) = self.ai_rally_state {
+                rally_state.push_log(LogEntry::new(
+                    LogEventType::Info,
+                    format!("Requeued {} comment(s) for the next reviewee turn", count),
+                ));
+            }
+            for comment in comments {
+                self.send_rally_command(OrchestratorCommand::RequeueComment(comment));
+            }
+            format!("Requeued {} comment(s) for the next reviewee turn", count)
+        } else if rally_active || self.rally_event_receiver.is_some() {
+            // 実行中の Rally ターンとは並走させない（イベントチャネルを奪ってしまうため）
+            self.submission_result = Some((
+                false,
+                "Wait for the running AI Rally turn to finish".to_string(),
+            ));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        } else {
+            let Some(context) = self.build_rally_context() else {
+                return;
+            };
+            self.start_targeted_reviewee(context, comments);
+            format!("Running reviewee on {} comment(s) (A: view)", count)
+        };
+
+        self.marked_comment_ids.clear();
+        self.submission_result = Some((true, message));
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// reviewee だけを `comments` について実行する。結果は Rally 状態（ログ・履歴）に
+    /// マージされ、Rally がなければ新しく作る
+    fn start_targeted_reviewee(
+        &mut self,
+        context: Context,
+        comments: Vec<crate::ai::adapter::ReviewComment>,
+    ) {
+        let (event_tx, event_rx) = mpsc::channel(100);
+        self.rally_event_receiver = Some(event_rx);
+        self.rally_command_sender = None;
+
+        let rally_state = self
+            .ai_rally_state
+            .get_or_insert_with(|| new_rally_state(self.config.ai.max_iterations));
+        rally_state.pending_review_post = None;
+        rally_state.pending_fix_post = None;
+        rally_state.push_log(LogEntry::new(
+            LogEventType::Info,
+            format!("Sending {} selected comment(s) to reviewee", comments.len()),
+        ));
+
+        let config = self.config.ai.clone();
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+        let cancel_token = CancellationToken::new();
+        self.rally_cancel_token = Some(cancel_token.clone());
+
+        let handle = tokio::spawn(async move {
+            match Orchestrator::new(&repo, pr_number, config, event_tx.clone(), None) {
+                Ok(mut orchestrator) => {
+                    orchestrator.set_context(context);
+                    orchestrator.set_cancellation_token(cancel_token);
+                    // Errors are already reported through the event channel
+                    let _ = orchestrator.run_reviewee_for(comments).await;
+                }
+                Err(e) => {
+                    let _ = event_tx
+                        .send(RallyEvent::Error(format!(
+                            "Failed to create orchestrator: {}",
+                            e
+                        )))
+                        .await;
+                }
+            }
+        });
+
+        self.rally_abort_handle = Some(handle.abort_handle());
+    }
+
+    /// 重要度フィルタを通過するレビューコメントのインデックス（`review_comments` 基準）
+    pub fn visible_review_comment_indices(&self) -> Vec<usize> {
+        let Some(ref comments) = self.review_comments else {
+            return Vec::new();
+        };
+        comments
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                self.comment_severity_filter
+                    .matches(CommentSeverity::from_posted_comment(&c.body))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// フィルタ変更で選択中のコメントが隠れた場合、先頭の表示コメントを選択し直す
+    fn snap_selected_comment_to_filter(&mut self) {
+        let visible = self.visible_review_comment_indices();
+        if !visible.contains(&self.selected_comment) {
+            self.selected_comment = visible.first().copied().unwrap_or(0);
+            self.review_comment_detail_scroll = 0;
+        }
+        self.comment_list_scroll_offset = 0;
+    }
+
     fn handle_discussion_detail_input(
         &mut self,
         key: event::KeyEvent,
@@ -3692,22 +4889,19 @@ impl App {
         }
     }
 
-    /// 返信入力モードに遷移（統一TextArea）
-    fn enter_reply_input(&mut self) {
+    /// コメントパネルで選択中のコメント（現在行のコメントのうち Tab で選んだもの）
+    fn selected_inline_review_comment(&self) -> Option<&ReviewComment> {
         let indices = self.get_comment_indices_at_current_line();
-        if indices.is_empty() {
-            return;
-        }
-
         let local_idx = self
             .selected_inline_comment
             .min(indices.len().saturating_sub(1));
-        let comment_idx = indices[local_idx];
+        let comment_idx = *indices.get(local_idx)?;
+        self.review_comments.as_ref()?.get(comment_idx)
+    }
 
-        let Some(ref comments) = self.review_comments else {
-            return;
-        };
-        let Some(comment) = comments.get(comment_idx) else {
+    /// 返信入力モードに遷移（統一TextArea）
+    fn enter_reply_input(&mut self) {
+        let Some(comment) = self.selected_inline_review_comment() else {
             return;
         };
 
@@ -3849,21 +5043,8 @@ impl App {
         }
 
         // Phase 2: ローカルリポジトリ全体を検索
-        let repo_root = match &self.working_dir {
-            Some(dir) => {
-                let output = tokio::process::Command::new("git")
-                    .args(["rev-parse", "--show-toplevel"])
-                    .current_dir(dir)
-                    .output()
-                    .await;
-                match output {
-                    Ok(o) if o.status.success() => {
-                        String::from_utf8_lossy(&o.stdout).trim().to_string()
-                    }
-                    _ => return Ok(()),
-                }
-            }
-            None => return Ok(()),
+        let Some(repo_root) = self.repo_root().await else {
+            return Ok(());
         };
 
         let result =
@@ -3873,62 +5054,130 @@ impl App {
             let path_str = full_path.to_string_lossy().to_string();
 
             // ターミナルを一時停止して外部エディタを開く
-            crate::ui::restore_terminal(terminal)?;
-            let _ = crate::editor::open_file_at_line(&self.config.editor, &path_str, line_number);
-            *terminal = crate::ui::setup_terminal()?;
+            self.open_in_editor_suspended(terminal, &path_str, line_number)?;
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    /// 現在のファイルを外部エディタで開く（gf キー）
+    async fn open_current_file_in_editor(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        let file = match self.files().get(self.selected_file) {
+            Some(f) => f.clone(),
+            None => return Ok(()),
+        };
+
+        // 行番号: new_line_number があれば使用、なければ 1
+        let line_number = file.patch.as_ref().and_then(|patch| {
+            crate::diff::get_line_info(patch, self.selected_line)
+                .and_then(|info| info.new_line_number)
+        });
+
+        // リポジトリルート取得 → フルパス構築
+        let Some(root) = self.repo_root().await else {
+            return Ok(());
+        };
+        let full_path = std::path::Path::new(&root)
+            .join(&file.filename)
+            .to_string_lossy()
+            .to_string();
+
+        self.open_in_editor_suspended(terminal, &full_path, line_number.unwrap_or(1) as usize)
+    }
+
+    /// 選択中のレビューコメントのファイルを、コメント行で外部エディタを開く
+    ///
+    /// ローカルにファイルが無い（リポジトリ外・未チェックアウト）場合は false を返す
+    async fn open_selected_comment_in_editor(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<bool> {
+        let Some((path, line)) = self
+            .review_comments
+            .as_ref()
+            .and_then(|comments| comments.get(self.selected_comment))
+            .map(|c| (c.path.clone(), c.line.unwrap_or(1)))
+        else {
+            return Ok(false);
+        };
+        let Some(root) = self.repo_root().await else {
+            return Ok(false);
+        };
+        let full_path = std::path::Path::new(&root).join(&path);
+        if !full_path.is_file() {
+            return Ok(false);
+        }
+
+        self.open_in_editor_suspended(terminal, &full_path.to_string_lossy(), line as usize)?;
+        Ok(true)
+    }
+
+    /// 選択中のコメントの suggestion を作業ツリーのファイルに適用する
+    async fn apply_selected_suggestion(&mut self) {
+        let Some(suggestion) = self
+            .selected_inline_review_comment()
+            .and_then(crate::editor::suggestion_from_comment)
+        else {
+            self.submission_result = Some((
+                false,
+                "No applicable suggestion in this comment".to_string(),
+            ));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        };
+
+        let result = match self.repo_root().await {
+            None => Err(anyhow::anyhow!("working directory is not a git repository")),
+            Some(root) => crate::editor::apply_suggestion(std::path::Path::new(&root), &suggestion),
+        };
+        self.submission_result = Some(match result {
+            Ok(applied) => (
+                true,
+                format!(
+                    "Applied suggestion to {}:{}-{}",
+                    suggestion.path, applied.start_line, applied.end_line
+                ),
+            ),
+            Err(e) => (false, format!("Failed to apply suggestion: {:#}", e)),
+        });
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// working_dir を含む git リポジトリのルート
+    async fn repo_root(&self) -> Option<String> {
+        let dir = self.working_dir.as_ref()?;
+        let output = tokio::process::Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(dir)
+            .output()
+            .await
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 
-    /// 現在のファイルを外部エディタで開く（gf キー）
-    async fn open_current_file_in_editor(
-        &mut self,
+    /// TUI 一時停止 → エディタ → TUI 復帰
+    ///
+    /// エディタが異常終了・起動失敗しても必ず TUI を復帰させる
+    fn open_in_editor_suspended(
+        &self,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        path: &str,
+        line: usize,
     ) -> Result<()> {
-        let file = match self.files().get(self.selected_file) {
-            Some(f) => f.clone(),
-            None => return Ok(()),
-        };
-
-        // 行番号: new_line_number があれば使用、なければ 1
-        let line_number = file.patch.as_ref().and_then(|patch| {
-            crate::diff::get_line_info(patch, self.selected_line)
-                .and_then(|info| info.new_line_number)
-        });
-
-        // リポジトリルート取得 → フルパス構築
-        let full_path = match &self.working_dir {
-            Some(dir) => {
-                let output = tokio::process::Command::new("git")
-                    .args(["rev-parse", "--show-toplevel"])
-                    .current_dir(dir)
-                    .output()
-                    .await;
-                match output {
-                    Ok(o) if o.status.success() => {
-                        let root = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                        std::path::Path::new(&root)
-                            .join(&file.filename)
-                            .to_string_lossy()
-                            .to_string()
-                    }
-                    _ => return Ok(()),
-                }
+        let suspended = crate::ui::restore_terminal(terminal);
+        if suspended.is_ok() {
+            if let Err(e) = crate::editor::open_at(&self.config.editor, path, line) {
+                tracing::warn!("Failed to open {} in editor: {:#}", path, e);
             }
-            None => return Ok(()),
-        };
-
-        // TUI 一時停止 → エディタ → TUI 復帰
-        crate::ui::restore_terminal(terminal)?;
-        let _ = crate::editor::open_file_at_line(
-            &self.config.editor,
-            &full_path,
-            line_number.unwrap_or(1) as usize,
-        );
+        }
         *terminal = crate::ui::setup_terminal()?;
-
-        Ok(())
+        suspended
     }
 
     /// Diffキャッシュを構築または再利用
@@ -4244,8 +5493,7 @@ impl App {
 
         // ?: ヘルプ
         if self.matches_single_key(&key, &kb.help) {
-            self.previous_state = AppState::PullRequestList;
-            self.state = AppState::Help;
+            self.open_help();
             return Ok(());
         }
 
@@ -4266,9 +5514,16 @@ impl App {
 
         let repo = self.repo.clone();
         let state = self.pr_list_state_filter;
+        let vcs = self.vcs.clone();
+        let queue = self.pr_queue.clone();
 
         tokio::spawn(async move {
-            let result = github::fetch_pr_list(&repo, state, 30).await;
+            let result = match queue {
+                Some(pr_numbers) => {
+                    loader::fetch_pr_queue(vcs.as_ref(), &repo, &pr_numbers, state).await
+                }
+                None => vcs.fetch_pr_list(&repo, state, 0, 30).await,
+            };
             let _ = tx.send(result.map_err(|e| e.to_string())).await;
         });
     }
@@ -4288,9 +5543,10 @@ impl App {
 
         let repo = self.repo.clone();
         let state = self.pr_list_state_filter;
+        let vcs = self.vcs.clone();
 
         tokio::spawn(async move {
-            let result = github::fetch_pr_list_with_offset(&repo, state, offset, 30).await;
+            let result = vcs.fetch_pr_list(&repo, state, offset, 30).await;
             let _ = tx.send(result.map_err(|e| e.to_string())).await;
         });
     }
@@ -4360,6 +5616,7 @@ impl App {
             self.data_state = DataState::Loading;
             self.review_comments = None;
             self.discussion_comments = None;
+            self.expanded_comment_ids.clear();
             self.diff_cache = None;
             // in-flight view 系レシーバーをクリア（late response による panic 防止）
             // data_receiver / retry_sender は永続のため維持
@@ -4397,10 +5654,12 @@ impl App {
             pr_list_has_more: false,
             pr_list_state_filter: PrStateFilter::default(),
             started_from_pr_list: false,
+            pr_queue: None,
             pr_list_receiver: None,
             diff_view_return_state: AppState::FileList,
             preview_return_state: AppState::DiffView,
             previous_state: AppState::FileList,
+            help_return_state: AppState::FileList,
             selected_file: 0,
             file_list_scroll_offset: 0,
             selected_line: 0,
@@ -4413,6 +5672,10 @@ impl App {
             review_comments: None,
             selected_comment: 0,
             comment_list_scroll_offset: 0,
+            review_comment_detail_scroll: 0,
+            comment_severity_filter: SeverityFilter::default(),
+            expanded_comment_ids: HashSet::new(),
+            marked_comment_ids: HashSet::new(),
             comments_loading: false,
             file_comment_positions: vec![],
             file_comment_lines: HashSet::new(),
@@ -4426,17 +5689,24 @@ impl App {
             discussion_comments_loading: false,
             discussion_comment_detail_mode: false,
             discussion_comment_detail_scroll: 0,
+            pr_details_scroll: 0,
+            ci_checks: None,
             comment_tab: CommentTab::default(),
             ai_rally_state: None,
             working_dir: None,
+            hxprignore: None,
+            hxprignore_receiver: None,
             data_receiver: None,
             retry_sender: None,
+            vcs: Arc::new(GithubProvider),
             comment_receiver: None,
             diff_cache_receiver: None,
             prefetch_receiver: None,
             discussion_comment_receiver: None,
+            ci_checks_receiver: None,
             rally_event_receiver: None,
             rally_abort_handle: None,
+            rally_cancel_token: None,
             rally_command_sender: None,
             start_ai_rally_on_load: false,
             pending_ai_rally: false,
@@ -4444,6 +5714,7 @@ impl App {
             comment_submitting: false,
             submission_result: None,
             submission_result_time: None,
+            refresh_origin: None,
             spinner_frame: 0,
             selected_inline_comment: 0,
             jump_stack: Vec::new(),
@@ -4473,6 +5744,14 @@ impl App {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_set_pr_queue_shows_all_states() {
+        let mut app = App::new_for_test();
+        app.set_pr_queue(vec![10, 11, 12]);
+        assert_eq!(app.pr_queue.as_deref(), Some(&[10, 11, 12][..]));
+        assert_eq!(app.pr_list_state_filter, PrStateFilter::All);
+    }
+
     #[test]
     fn test_find_diff_line_index_basic() {
         let patch = r#"@@ -1,3 +1,4 @@
@@ -4508,6 +5787,120 @@ mod tests {
         assert_eq!(App::find_diff_line_index(patch, 11), Some(5));
     }
 
+    #[test]
+    fn test_severity_filter_visible_review_comments() {
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        let comment = |id: u64, body: String| ReviewComment {
+            id,
+            path: "src/lib.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            diff_hunk: None,
+            body,
+            user: crate::github::User {
+                login: "reviewer".to_string(),
+            },
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        app.review_comments = Some(vec![
+            comment(1, "Human comment".to_string()),
+            comment(2, CommentSeverity::Minor.posted_comment_header()),
+            comment(3, CommentSeverity::Critical.posted_comment_header()),
+        ]);
+        assert_eq!(app.visible_review_comment_indices(), vec![0, 1, 2]);
+
+        app.comment_severity_filter.toggle(CommentSeverity::Major);
+        app.comment_severity_filter
+            .toggle(CommentSeverity::Critical);
+        assert_eq!(app.comment_severity_filter.label(), "critical+major");
+        assert_eq!(app.visible_review_comment_indices(), vec![2]);
+
+        app.snap_selected_comment_to_filter();
+        assert_eq!(app.selected_comment, 2);
+
+        app.comment_severity_filter.reset();
+        assert!(!app.comment_severity_filter.is_active());
+        assert_eq!(app.visible_review_comment_indices(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_comments_for_reviewee_prefers_marked_comments() {
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        let comment = |id: u64, line: Option<u32>, body: String| ReviewComment {
+            id,
+            path: "src/lib.rs".to_string(),
+            line,
+            start_line: None,
+            diff_hunk: None,
+            body,
+            user: crate::github::User {
+                login: "reviewer".to_string(),
+            },
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        app.review_comments = Some(vec![
+            comment(1, Some(3), "Handle the error".to_string()),
+            comment(
+                2,
+                Some(7),
+                format!(
+                    "{}\n\nAvoid the clone",
+                    CommentSeverity::Minor.posted_comment_header()
+                ),
+            ),
+            comment(3, None, "Outdated".to_string()),
+        ]);
+
+        // Nothing marked: the selected comment; people's comments count as major
+        let selected = app.comments_for_reviewee();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].line, 3);
+        assert_eq!(selected[0].severity, CommentSeverity::Major);
+
+        app.selected_comment = 1;
+        app.toggle_selected_comment_marked();
+        app.selected_comment = 2;
+        app.toggle_selected_comment_marked();
+        let marked = app.comments_for_reviewee();
+        // The outdated comment has no line to point the reviewee at
+        assert_eq!(marked.len(), 1);
+        assert_eq!(marked[0].body, "Avoid the clone");
+        assert_eq!(marked[0].severity, CommentSeverity::Minor);
+    }
+
+    #[test]
+    fn test_selected_comment_body_follows_tab() {
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        let user = crate::github::User {
+            login: "reviewer".to_string(),
+        };
+        app.review_comments = Some(vec![ReviewComment {
+            id: 1,
+            path: "src/lib.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            diff_hunk: None,
+            body: "Inline".to_string(),
+            user: user.clone(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        }]);
+        app.discussion_comments = Some(vec![DiscussionComment {
+            id: 2,
+            body: "Conversation".to_string(),
+            user,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        }]);
+
+        assert_eq!(app.selected_comment_body().as_deref(), Some("Inline"));
+        app.comment_tab = CommentTab::Discussion;
+        assert_eq!(app.selected_comment_body().as_deref(), Some("Conversation"));
+        app.selected_discussion_comment = 1;
+        assert_eq!(app.selected_comment_body(), None);
+    }
+
     #[test]
     fn test_has_comment_at_current_line() {
         let config = Config::default();
@@ -4811,6 +6204,7 @@ mod tests {
                 login: "local".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         };
         let local_files = vec![ChangedFile {
             filename: "src/main.rs".to_string(),
@@ -4881,6 +6275,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         };
         tx.send(DataLoadResult::Success {
             pr: Box::new(pr),
@@ -4936,6 +6331,85 @@ mod tests {
         assert!(app.session_cache.get_review_comments(&cache_key).is_none());
     }
 
+    /// PR #1 of owner/repo を、remote が `remote_url` の git リポジトリを working_dir として
+    /// 読み込み、ファイル一覧に残ったファイル名を返す
+    async fn files_loaded_with_hxprignore(remote_url: &str) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".hxprignore"), "*.pb.go\ngenerated/\n").unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec!["remote", "add", "origin", remote_url],
+        ] {
+            let status = std::process::Command::new("git")
+                .args(&args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        let config = Config::default();
+        let (mut app, tx) = App::new_loading("owner/repo", 1, config);
+        app.set_working_dir(Some(dir.path().to_string_lossy().into_owned()));
+
+        let make_file = |name: &str| ChangedFile {
+            filename: name.to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: None,
+        };
+        let pr = PullRequest {
+            number: 1,
+            title: "PR 1".to_string(),
+            body: None,
+            state: "open".to_string(),
+            head: crate::github::Branch {
+                ref_name: "feature".to_string(),
+                sha: "abc123".to_string(),
+            },
+            base: crate::github::Branch {
+                ref_name: "main".to_string(),
+                sha: "def456".to_string(),
+            },
+            user: crate::github::User {
+                login: "user".to_string(),
+            },
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
+        };
+        tx.send(DataLoadResult::Success {
+            pr: Box::new(pr),
+            files: vec![
+                make_file("api/user.pb.go"),
+                make_file("src/main.rs"),
+                make_file("src/generated/schema.rs"),
+            ],
+        })
+        .await
+        .unwrap();
+
+        // `.hxprignore` はバックグラウンドで読み込まれる
+        while app.hxprignore_receiver.is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            app.poll_data_updates();
+        }
+        app.poll_data_updates();
+        app.files().iter().map(|f| f.filename.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_poll_data_updates_drops_hxprignore_files() {
+        let files = files_loaded_with_hxprignore("git@github.com:Owner/Repo.git").await;
+        assert_eq!(files, vec!["src/main.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_hxprignore_of_another_repository_is_not_applied() {
+        let files = files_loaded_with_hxprignore("https://github.com/other/repo").await;
+        assert_eq!(files.len(), 3);
+    }
+
     #[tokio::test]
     async fn test_handle_data_result_clamps_selected_file_when_files_shrink() {
         let config = Config::default();
@@ -4971,6 +6445,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         // Set initial loaded state with 5 files
@@ -4999,6 +6474,83 @@ mod tests {
         assert!(app.files().get(app.selected_file).is_some());
     }
 
+    #[tokio::test]
+    async fn test_refresh_reports_new_head_and_keeps_selection() {
+        let config = Config::default();
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
+
+        let make_file = |name: &str| ChangedFile {
+            filename: name.to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
+        };
+        let make_pr = |sha: &str| {
+            Box::new(PullRequest {
+                number: 1,
+                title: "Test PR".to_string(),
+                body: None,
+                state: "open".to_string(),
+                head: crate::github::Branch {
+                    ref_name: "feature".to_string(),
+                    sha: sha.to_string(),
+                },
+                base: crate::github::Branch {
+                    ref_name: "main".to_string(),
+                    sha: "def456".to_string(),
+                },
+                user: crate::github::User {
+                    login: "user".to_string(),
+                },
+                updated_at: "2024-01-01T00:00:00Z".to_string(),
+                labels: vec![],
+            })
+        };
+
+        app.data_state = DataState::Loaded {
+            pr: make_pr("aaaaaaa1111"),
+            files: vec![make_file("b.rs"), make_file("c.rs")],
+        };
+        app.selected_file = 1;
+
+        app.refresh_all();
+        assert!(matches!(app.data_state, DataState::Loading));
+        // A new commit added a file before the selected one
+        app.handle_data_result(
+            1,
+            DataLoadResult::Success {
+                pr: make_pr("bbbbbbb2222"),
+                files: vec![make_file("a.rs"), make_file("b.rs"), make_file("c.rs")],
+            },
+        );
+
+        assert_eq!(app.files()[app.selected_file].filename, "c.rs");
+        assert_eq!(
+            app.submission_result,
+            Some((
+                true,
+                "Refreshed: new commits (aaaaaaa → bbbbbbb)".to_string()
+            ))
+        );
+
+        // Reloads that were not asked for stay silent
+        app.submission_result = None;
+        app.handle_data_result(
+            1,
+            DataLoadResult::Success {
+                pr: make_pr("bbbbbbb2222"),
+                files: vec![make_file("a.rs")],
+            },
+        );
+        assert!(app.submission_result.is_none());
+
+        assert_eq!(
+            refresh_message("bbbbbbb2222", "bbbbbbb2222", false),
+            "Refreshed: no new commits"
+        );
+    }
+
     #[tokio::test]
     async fn test_handle_data_result_resyncs_diff_state_when_selected_file_changes() {
         let config = Config::default();
@@ -5033,6 +6585,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         // Set initial loaded state with 5 files
@@ -5113,6 +6666,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         // Set initial loaded state with 5 files, selected_file = 4
@@ -5127,6 +6681,8 @@ mod tests {
             id: 1,
             path: "file_4.rs".to_string(),
             line: Some(1),
+            start_line: None,
+            diff_hunk: None,
             body: "comment on old file".to_string(),
             user: crate::github::User {
                 login: "reviewer".to_string(),
@@ -5214,6 +6770,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         // Set initial loaded state
@@ -5297,6 +6854,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         app.data_state = DataState::Loaded {
@@ -5360,6 +6918,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         app.data_state = DataState::Loaded {
@@ -5428,6 +6987,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         app.data_state = DataState::Loaded {
@@ -5497,6 +7057,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         app.data_state = DataState::Loaded {
@@ -5558,6 +7119,7 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            labels: vec![],
         });
 
         app.handle_data_result(
@@ -5688,6 +7250,337 @@ mod tests {
         assert!(matches!(req, RefreshRequest::LocalRefresh));
     }
 
+    #[test]
+    fn test_poll_rally_events_accumulates_usage() {
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(AiRallyState {
+            iteration: 1,
+            state: crate::ai::RallyState::ReviewerReviewing,
+            ..new_rally_state(10)
+        });
+
+        // Codex reports time only; Claude reports both
+        for (cost_usd, duration_ms) in [(None, Some(1_500)), (Some(0.25), Some(2_000))] {
+            tx.try_send(RallyEvent::UsageReported {
+                cost_usd,
+                duration_ms,
+            })
+            .unwrap();
+        }
+        // 生の result はデバッグ表示用に最後のものだけ保持する
+        for result in [
+            serde_json::json!({"action": "comment"}),
+            serde_json::json!({"action": "approve"}),
+        ] {
+            tx.try_send(RallyEvent::AgentRawResult(result)).unwrap();
+        }
+        app.poll_rally_events();
+
+        let rally_state = app.ai_rally_state.as_ref().unwrap();
+        assert_eq!(rally_state.total_cost_usd, Some(0.25));
+        assert_eq!(rally_state.total_duration_ms, 3_500);
+        assert_eq!(
+            rally_state.last_raw_result,
+            Some(serde_json::json!({"action": "approve"}))
+        );
+    }
+
+    #[test]
+    fn test_poll_rally_events_records_max_iterations_reached() {
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(AiRallyState {
+            iteration: 3,
+            state: crate::ai::RallyState::RevieweeFix,
+            ..new_rally_state(3)
+        });
+
+        let summary = "Max iterations (3) reached, not approved".to_string();
+        tx.try_send(RallyEvent::MaxIterationsReached(summary.clone()))
+            .unwrap();
+        tx.try_send(RallyEvent::StateChanged(crate::ai::RallyState::Completed))
+            .unwrap();
+        app.poll_rally_events();
+
+        let rally_state = app.ai_rally_state.as_ref().unwrap();
+        assert_eq!(rally_state.state, crate::ai::RallyState::Completed);
+        assert!(rally_state
+            .history
+            .iter()
+            .any(|e| matches!(e, RallyEvent::MaxIterationsReached(s) if *s == summary)));
+        assert_eq!(rally_state.logs.last().unwrap().message, summary);
+    }
+
+    #[test]
+    fn test_poll_rally_events_keeps_reviewee_error_details() {
+        use crate::ai::adapter::RevieweeOutput;
+
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(AiRallyState {
+            iteration: 1,
+            state: crate::ai::RallyState::RevieweeFix,
+            error_details_scroll: 3,
+            ..new_rally_state(10)
+        });
+
+        let details = "cargo build failed:\nerror[E0425]: cannot find value `x`".to_string();
+        tx.try_send(RallyEvent::FixCompleted(RevieweeOutput {
+            status: RevieweeStatus::Error,
+            summary: "Could not build".to_string(),
+            files_modified: vec![],
+            question: None,
+            permission_request: None,
+            error_details: Some(details.clone()),
+            diff_stats: vec![],
+        }))
+        .unwrap();
+        tx.try_send(RallyEvent::StateChanged(crate::ai::RallyState::Error))
+            .unwrap();
+        app.poll_rally_events();
+
+        let rally_state = app.ai_rally_state.as_ref().unwrap();
+        assert_eq!(rally_state.error_details, Some(details));
+        assert_eq!(rally_state.error_details_scroll, 0);
+    }
+
+    #[test]
+    fn test_poll_rally_events_collects_files_changed_per_turn() {
+        use crate::ai::RallyState;
+
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(new_rally_state(3));
+
+        for event in [
+            RallyEvent::StateChanged(RallyState::RevieweeFix),
+            RallyEvent::FileModified(PathBuf::from("src/a.rs")),
+            RallyEvent::StateChanged(RallyState::WaitingForPermission),
+            RallyEvent::StateChanged(RallyState::RevieweeFix),
+            RallyEvent::FileModified(PathBuf::from("src/b.rs")),
+            RallyEvent::FileModified(PathBuf::from("src/a.rs")),
+        ] {
+            tx.try_send(event).unwrap();
+        }
+        app.poll_rally_events();
+        // Resuming after a permission answer is still the same turn
+        assert_eq!(
+            app.ai_rally_state.as_ref().unwrap().files_changed,
+            vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+
+        tx.try_send(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+            .unwrap();
+        tx.try_send(RallyEvent::StateChanged(RallyState::RevieweeFix))
+            .unwrap();
+        app.poll_rally_events();
+        let state = app.ai_rally_state.as_ref().unwrap();
+        assert!(state.files_changed.is_empty());
+    }
+
+    #[test]
+    fn test_poll_rally_events_tracks_turn_start() {
+        use crate::ai::RallyState;
+
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(AiRallyState {
+            iteration: 1,
+            ..new_rally_state(3)
+        });
+
+        tx.try_send(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+            .unwrap();
+        app.poll_rally_events();
+        let started = app.ai_rally_state.as_ref().unwrap().turn_started_at;
+        assert!(started.is_some());
+
+        // The same state again keeps the original start time
+        tx.try_send(RallyEvent::StateChanged(RallyState::ReviewerReviewing))
+            .unwrap();
+        app.poll_rally_events();
+        assert_eq!(
+            app.ai_rally_state.as_ref().unwrap().turn_started_at,
+            started
+        );
+
+        tx.try_send(RallyEvent::StateChanged(
+            RallyState::WaitingForPostConfirmation,
+        ))
+        .unwrap();
+        app.poll_rally_events();
+        assert!(app
+            .ai_rally_state
+            .as_ref()
+            .unwrap()
+            .turn_started_at
+            .is_none());
+    }
+
+    #[test]
+    fn test_iteration_durations_until_next_iteration_or_finish() {
+        use crate::ai::RallyState;
+
+        let mut app = App::new_for_test();
+        let (tx, rx) = mpsc::channel(10);
+        app.rally_event_receiver = Some(rx);
+        app.ai_rally_state = Some(new_rally_state(3));
+
+        tx.try_send(RallyEvent::IterationStarted(1)).unwrap();
+        tx.try_send(RallyEvent::IterationStarted(2)).unwrap();
+        tx.try_send(RallyEvent::StateChanged(RallyState::Completed))
+            .unwrap();
+        app.poll_rally_events();
+
+        let rally_state = app.ai_rally_state.as_mut().unwrap();
+        assert!(rally_state.finished_at.is_some());
+        let iterations: Vec<u32> = rally_state
+            .iteration_started_at
+            .iter()
+            .map(|&(i, _)| i)
+            .collect();
+        assert_eq!(iterations, vec![1, 2]);
+
+        // Pin the timestamps to check the arithmetic
+        let start = rally_state.started_at;
+        rally_state.iteration_started_at = vec![
+            (1, start + Duration::from_secs(2)),
+            (2, start + Duration::from_secs(62)),
+        ];
+        rally_state.finished_at = Some(start + Duration::from_secs(92));
+        assert_eq!(
+            rally_state.iteration_durations(),
+            vec![(1, Duration::from_secs(60)), (2, Duration::from_secs(30))]
+        );
+        assert_eq!(rally_state.total_elapsed(), Duration::from_secs(92));
+    }
+
+    #[test]
+    fn test_clarification_answer_is_typed_and_sent() {
+        use crate::ai::RallyState;
+
+        let mut app = App::new_for_test();
+        let (cmd_tx, mut cmd_rx) = mpsc::channel(10);
+        app.rally_command_sender = Some(cmd_tx);
+        app.ai_rally_state = Some(AiRallyState {
+            iteration: 1,
+            state: RallyState::WaitingForClarification,
+            pending_question: Some("Which API version?".to_string()),
+            ..new_rally_state(3)
+        });
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let submit = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        // Esc goes back to the question without answering
+        app.start_clarification_input();
+        app.handle_clarification_input(key(KeyCode::Char('x')));
+        app.handle_clarification_input(key(KeyCode::Esc));
+        assert!(!app.ai_rally_state.as_ref().unwrap().answering_clarification);
+        assert!(cmd_rx.try_recv().is_err());
+
+        // An empty answer is not sent
+        app.start_clarification_input();
+        app.handle_clarification_input(submit);
+        assert!(app.ai_rally_state.as_ref().unwrap().answering_clarification);
+        assert!(cmd_rx.try_recv().is_err());
+
+        for c in "v2".chars() {
+            app.handle_clarification_input(key(KeyCode::Char(c)));
+        }
+        app.handle_clarification_input(submit);
+
+        assert!(matches!(
+            cmd_rx.try_recv(),
+            Ok(OrchestratorCommand::ClarificationResponse(answer)) if answer == "v2"
+        ));
+        let rally_state = app.ai_rally_state.as_ref().unwrap();
+        assert!(!rally_state.answering_clarification);
+        assert!(rally_state.pending_question.is_none());
+        assert!(app.input_text_area.is_empty());
+    }
+
+    #[test]
+    fn test_page_logs_moves_by_visible_height_and_resumes_follow_at_tail() {
+        let mut rally_state = AiRallyState {
+            iteration: 1,
+            state: crate::ai::RallyState::ReviewerReviewing,
+            logs: (0..30)
+                .map(|i| LogEntry::new(LogEventType::Info, format!("log {}", i)))
+                .collect(),
+            ..new_rally_state(3)
+        };
+
+        rally_state.page_logs(false);
+        assert_eq!(rally_state.selected_log_index, Some(19));
+        assert_eq!(rally_state.log_scroll_offset, 10);
+
+        rally_state.page_logs(false);
+        rally_state.page_logs(false);
+        assert_eq!(rally_state.selected_log_index, Some(0));
+        assert_eq!(rally_state.log_scroll_offset, 1);
+
+        // New logs do not move the view while scrolled up
+        rally_state.push_log(LogEntry::new(LogEventType::Info, "new".to_string()));
+        assert_eq!(rally_state.selected_log_index, Some(0));
+
+        rally_state.page_logs(true);
+        assert_eq!(rally_state.selected_log_index, Some(10));
+        for _ in 0..3 {
+            rally_state.page_logs(true);
+        }
+        assert_eq!(rally_state.selected_log_index, Some(30));
+        assert_eq!(rally_state.log_scroll_offset, 0);
+
+        // Back at the tail, new logs are followed again
+        rally_state.push_log(LogEntry::new(LogEventType::Info, "newer".to_string()));
+        assert_eq!(rally_state.selected_log_index, Some(31));
+    }
+
+    #[test]
+    fn test_cycle_review_post_selection_wraps_back_to_summary() {
+        let comment = crate::ai::adapter::ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "issue".to_string(),
+            severity: crate::ai::adapter::CommentSeverity::Minor,
+        };
+        let mut rally_state = AiRallyState {
+            iteration: 1,
+            state: crate::ai::RallyState::WaitingForPostConfirmation,
+            pending_review_post: Some(crate::ai::orchestrator::ReviewPostInfo {
+                action: "Comment".to_string(),
+                summary: "summary".to_string(),
+                comment_count: 2,
+                comments: vec![comment.clone(), comment],
+            }),
+            ..new_rally_state(3)
+        };
+
+        rally_state.cycle_review_post_selection();
+        assert_eq!(rally_state.review_post_selection, Some(0));
+        rally_state.cycle_review_post_selection();
+        assert_eq!(rally_state.review_post_selection, Some(1));
+        rally_state.cycle_review_post_selection();
+        assert_eq!(rally_state.review_post_selection, None);
+
+        // Without comments only the summary can be selected
+        rally_state
+            .pending_review_post
+            .as_mut()
+            .unwrap()
+            .comments
+            .clear();
+        rally_state.cycle_review_post_selection();
+        assert_eq!(rally_state.review_post_selection, None);
+    }
+
     #[tokio::test]
     async fn test_handle_data_result_auto_focus_skips_state_transition_during_bg_rally() {
         let mut app = App::new_for_test();
@@ -5698,18 +7591,8 @@ mod tests {
         // Set up BG rally state (active but not in AiRally AppState)
         app.ai_rally_state = Some(AiRallyState {
             iteration: 1,
-            max_iterations: 10,
             state: crate::ai::RallyState::ReviewerReviewing,
-            history: vec![],
-            logs: vec![],
-            log_scroll_offset: 0,
-            selected_log_index: None,
-            showing_log_detail: false,
-            pending_question: None,
-            pending_permission: None,
-            pending_review_post: None,
-            pending_fix_post: None,
-            last_visible_log_height: 0,
+            ..new_rally_state(10)
         });
 
         let pr = Box::new(make_local_pr());
@@ -5747,6 +7630,7 @@ mod tests {
                 login: "local".to_string(),
             },
             updated_at: "".to_string(),
+            labels: vec![],
         }
     }
 }
@@ -48,7 +48,12 @@ pub fn sanitize_repo_name(repo: &str) -> Result<String> {
 
 /// キャッシュディレクトリ: ~/.cache/octorus/
 /// AI Rally セッション等で使用
+///
+/// テストではユーザーのキャッシュに書き込まないよう、プロセスごとの一時ディレクトリを使う
 pub fn cache_dir() -> PathBuf {
+    if cfg!(test) {
+        return std::env::temp_dir().join(format!("octorus-test-{}", std::process::id()));
+    }
     BaseDirectories::with_prefix("octorus")
         .map(|dirs| dirs.get_cache_home())
         .unwrap_or_else(|_| PathBuf::from(".cache"))
@@ -72,6 +77,16 @@ pub fn cleanup_rally_sessions() {
     }
 }
 
+/// エージェント応答キャッシュのディレクトリ: ~/.cache/octorus/responses/
+pub fn response_cache_dir() -> PathBuf {
+    cache_dir().join("responses")
+}
+
+/// エージェント応答キャッシュを削除
+pub fn cleanup_response_cache() {
+    let _ = std::fs::remove_dir_all(response_cache_dir());
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PrCacheKey {
     pub repo: String,
@@ -227,6 +242,7 @@ mod tests {
                 login: "testuser".to_string(),
             },
             updated_at: updated_at.to_string(),
+            labels: vec![],
         }
     }
 
@@ -0,0 +1,31 @@
+//! System clipboard access for copying comment bodies and review summaries.
+//!
+//! On headless machines (no X11/Wayland display) the clipboard is unavailable;
+//! callers report the error instead of failing.
+
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+
+/// X11 では所有者のプロセスがクリップボードの内容を保持するため、
+/// コピー後もクライアントを破棄せずに持ち続ける
+static CLIPBOARD: OnceLock<Mutex<Option<arboard::Clipboard>>> = OnceLock::new();
+
+/// Copy `text` to the system clipboard
+pub fn copy(text: &str) -> Result<()> {
+    let mut clipboard = CLIPBOARD
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .map_err(|_| anyhow!("Clipboard is not available"))?;
+    if clipboard.is_none() {
+        let client =
+            arboard::Clipboard::new().map_err(|e| anyhow!("Clipboard is not available: {}", e))?;
+        *clipboard = Some(client);
+    }
+    let Some(client) = clipboard.as_mut() else {
+        return Err(anyhow!("Clipboard is not available"));
+    };
+    client
+        .set_text(text)
+        .map_err(|e| anyhow!("Failed to copy to the clipboard: {}", e))
+}
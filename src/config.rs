@@ -5,26 +5,86 @@ use std::fs;
 use std::path::PathBuf;
 use xdg::BaseDirectories;
 
+use crate::ai::adapter::CommentSeverity;
+use crate::ai::adapters::OutputSchemas;
+use crate::github::{MergeMethod, Reaction};
 use crate::keybinding::{KeyBinding, KeySequence, NamedKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
+    /// Editor command (falls back to `$EDITOR`). May be a template with `{path}`
+    /// and `{line}` placeholders, e.g. `code --goto {path}:{line}`
     pub editor: String,
     pub diff: DiffConfig,
     pub keybindings: KeybindingsConfig,
     pub ai: AiConfig,
+    pub github: GithubConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct AiConfig {
     pub reviewer: String,
     pub reviewee: String,
+    /// Model passed to the reviewer's CLI as `--model` (Claude, Codex).
+    /// Unset uses the CLI's default
+    pub reviewer_model: Option<String>,
+    /// Model passed to the reviewee's CLI as `--model` (Claude, Codex)
+    pub reviewee_model: Option<String>,
+    /// Ordered fallback list of agents (e.g., ["codex", "claude"]).
+    /// When set, the first available agent is used for both reviewer and reviewee.
+    pub adapters: Vec<String>,
     pub max_iterations: u32,
+    /// Stop the rally after this many consecutive reviewee completions that
+    /// modified no files (0 disables stall detection)
+    pub max_stalled_iterations: u32,
+    /// Agent turns that may run at once when rallies run on several PRs
+    /// (`--headless --pr 10,11,12`)
+    pub max_concurrent_rallies: usize,
     pub timeout_secs: u64,
+    /// Timeout for a single Codex turn (seconds). The codex process is killed if it
+    /// produces no `turn.completed` within this time.
+    pub codex_turn_timeout_secs: u64,
+    /// Retries for an agent CLI (Claude, Codex) that exits with a non-zero status,
+    /// with exponential backoff. Authentication failures are not retried.
+    pub agent_max_retries: u32,
     /// Custom prompt directory (default: ~/.config/octorus/prompts/)
     pub prompt_dir: Option<String>,
+    /// Custom JSON schema for the reviewer's output (default: embedded schema).
+    /// May add fields (e.g., "risk_score") but must keep the embedded required ones
+    pub reviewer_schema_path: Option<String>,
+    /// Custom JSON schema for the reviewee's output (default: embedded schema)
+    pub reviewee_schema_path: Option<String>,
+    /// Language the agents write review comments and summaries in (e.g., "Japanese").
+    /// JSON keys, severity values and file paths stay in English.
+    pub review_language: String,
+    /// Areas the reviewer checks for (e.g., ["security", "performance"]). Replaces the
+    /// default checklist in the reviewer prompt; empty keeps the default.
+    pub review_focus: Vec<String>,
+    /// Files left out of the diff the reviewer sees, in gitignore syntax
+    /// (e.g., ["*.lock", "dist/"])
+    pub ignore_globs: Vec<String>,
+    /// Formatter/linter command run in working_dir after each reviewee fix
+    /// (e.g., "cargo fmt --check"). On failure its output is sent back to the reviewee.
+    pub post_fix_command: Option<String>,
+    /// Timeout for post_fix_command (seconds)
+    pub post_fix_timeout_secs: u64,
+    /// Command that must pass in working_dir before an approval completes the rally
+    /// (e.g., "cargo test"). On failure the rally continues with the output as a blocking issue.
+    pub verify_command: Option<String>,
+    /// Timeout for verify_command (seconds)
+    pub verify_timeout_secs: u64,
+    /// Withhold approvals the comments don't support: an approve with any comment at
+    /// or above this severity (e.g., "major") requests changes instead. Other actions
+    /// are kept; unset always keeps the reviewer's own action
+    pub blocking_severity_threshold: Option<CommentSeverity>,
+    /// Replaces the reviewer's built-in allowed tools (Claude adapter only).
+    /// Unset keeps the defaults; reviewer_additional_tools is appended either way.
+    pub reviewer_allowed_tools: Option<Vec<String>>,
+    /// Replaces the reviewee's built-in allowed tools (Claude adapter only).
+    /// Unset keeps the defaults; reviewee_additional_tools is appended either way.
+    pub reviewee_allowed_tools: Option<Vec<String>>,
     /// Additional tools for reviewer (Claude adapter only).
     /// Use Claude Code's --allowedTools format (e.g., "Skill", "Bash(git push:*)").
     #[serde(default)]
@@ -37,10 +97,64 @@ pub struct AiConfig {
     /// Default is false (confirmation prompt before posting).
     #[serde(default)]
     pub auto_post: bool,
+    /// If true, AI Rally never writes to GitHub: reviews, fix comments and
+    /// auto-merges are only logged as what would have been posted.
+    pub dry_run: bool,
+    /// If true, AI Rally stages the review as a pending (draft) GitHub review
+    /// instead of submitting it; submit it later from the file list.
+    pub pending_review: bool,
+    /// If true, AI Rally stops after posting (or previewing) the first review and
+    /// never runs the reviewee fix loop.
+    pub review_only: bool,
+    /// Append every rally event as a JSON line to this file (e.g., for dashboards)
+    pub event_log: Option<String>,
+    /// If true, the reviewer's output is presented (and posted) as a single
+    /// prioritized action list instead of inline comments.
+    pub digest: bool,
+    /// If true, the reviewer's reasoning is attached to the posted summary
+    /// as a collapsed `<details>` block.
+    pub attach_reasoning: bool,
+    /// If true, agent responses are cached on disk by (agent, model, prompt, schema)
+    /// and replayed for identical prompts. Clear with `or clean-cache`.
+    pub response_cache: bool,
+    /// If true, merge the PR after a clean approval (no blocking issues,
+    /// no critical/major comments, no local reviewee changes).
+    pub auto_merge: bool,
+    /// Merge method used by auto_merge: "merge", "squash" or "rebase"
+    pub merge_method: MergeMethod,
+    /// Require all CI checks (check runs and commit statuses) on the head commit to pass
+    /// before auto-merging
+    pub auto_merge_require_ci: bool,
+    /// Reaction added to the reviewer's comments on files the reviewee modified
+    /// (e.g., "+1", "rocket", "eyes"). Unset disables reactions
+    pub addressed_comment_reaction: Option<Reaction>,
+    /// Post the reviewee's fix note as a reply under each reviewer comment whose lines
+    /// it changed, instead of as a new top-level PR comment
+    pub reply_to_addressed_comments: bool,
+    /// Base URL of the Ollama server (used when reviewer/reviewee is "ollama")
+    pub ollama_host: String,
+    /// Model name passed to Ollama (e.g., "qwen2.5-coder:14b")
+    pub ollama_model: String,
+    /// Environment variables set for an agent's CLI process, by agent name
+    /// (e.g., `[ai.env.claude]` with `ANTHROPIC_API_KEY = "..."`), on top of the
+    /// inherited environment. Used by the claude, codex and gemini adapters
+    pub env: HashMap<String, HashMap<String, String>>,
+    /// Review only the changes after this commit (set with `--since`, not read from the
+    /// config file). When unset, the commit the previous rally reviewed is used
+    #[serde(skip)]
+    pub since_commit: Option<String>,
+    /// Replay this captured `codex exec --json` stream instead of running the Codex CLI
+    /// (set with `--replay` or `OCTORUS_CODEX_REPLAY`, not read from the config file)
+    #[serde(skip)]
+    pub codex_replay: Option<String>,
+    /// Check out the PR in working_dir (cloning it if the directory is missing) when its
+    /// HEAD is not the PR head (set with `--checkout`, not read from the config file)
+    #[serde(skip)]
+    pub checkout_pr: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct DiffConfig {
     pub theme: String,
 }
@@ -52,7 +166,7 @@ pub struct DiffConfig {
 /// - Object with modifiers: `page_down = { key = "d", ctrl = true }`
 /// - Array for sequences: `jump_to_first = ["g", "g"]`
 #[derive(Debug, Clone, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct KeybindingsConfig {
     // Navigation
     pub move_down: KeySequence,
@@ -73,7 +187,9 @@ pub struct KeybindingsConfig {
     pub comment: KeySequence,
     pub suggestion: KeySequence,
     pub reply: KeySequence,
+    pub apply_suggestion: KeySequence,
     pub refresh: KeySequence,
+    pub refresh_checks: KeySequence,
     pub submit: KeySequence,
 
     // Mode switching
@@ -81,7 +197,9 @@ pub struct KeybindingsConfig {
     pub help: KeySequence,
     pub comment_list: KeySequence,
     pub ai_rally: KeySequence,
+    pub rerun_file_review: KeySequence,
     pub open_panel: KeySequence,
+    pub pr_details: KeySequence,
 
     // Diff operations
     pub go_to_definition: KeySequence,
@@ -100,6 +218,7 @@ impl Default for Config {
             diff: DiffConfig::default(),
             keybindings: KeybindingsConfig::default(),
             ai: AiConfig::default(),
+            github: GithubConfig::default(),
         }
     }
 }
@@ -109,16 +228,81 @@ impl Default for AiConfig {
         Self {
             reviewer: "claude".to_owned(),
             reviewee: "claude".to_owned(),
+            reviewer_model: None,
+            reviewee_model: None,
+            adapters: Vec::new(),
             max_iterations: 10,
+            max_stalled_iterations: 3,
+            max_concurrent_rallies: 2,
             timeout_secs: 600,
+            codex_turn_timeout_secs: 300,
+            agent_max_retries: 2,
             prompt_dir: None,
+            reviewer_schema_path: None,
+            reviewee_schema_path: None,
+            review_language: "English".to_owned(),
+            review_focus: Vec::new(),
+            ignore_globs: Vec::new(),
+            post_fix_command: None,
+            post_fix_timeout_secs: 300,
+            verify_command: None,
+            verify_timeout_secs: 600,
+            blocking_severity_threshold: None,
+            reviewer_allowed_tools: None,
+            reviewee_allowed_tools: None,
             reviewer_additional_tools: Vec::new(),
             reviewee_additional_tools: Vec::new(),
             auto_post: false,
+            dry_run: false,
+            pending_review: false,
+            review_only: false,
+            event_log: None,
+            digest: false,
+            attach_reasoning: false,
+            response_cache: false,
+            auto_merge: false,
+            merge_method: MergeMethod::default(),
+            auto_merge_require_ci: true,
+            addressed_comment_reaction: None,
+            reply_to_addressed_comments: false,
+            ollama_host: "http://localhost:11434".to_owned(),
+            ollama_model: "qwen2.5-coder".to_owned(),
+            env: HashMap::new(),
+            since_commit: None,
+            codex_replay: None,
+            checkout_pr: false,
         }
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubConfig {
+    /// GitHub Enterprise Server hostname (e.g., "github.mycompany.com").
+    /// Unset means github.com
+    pub hostname: Option<String>,
+}
+
+impl AiConfig {
+    /// Environment variables configured for `agent`'s CLI process
+    pub fn agent_env(&self, agent: &str) -> HashMap<String, String> {
+        self.env.get(agent).cloned().unwrap_or_default()
+    }
+}
+
+impl GithubConfig {
+    /// Hostname without scheme or trailing slash, so a pasted base URL also works
+    pub fn host(&self) -> Option<&str> {
+        let host = self.hostname.as_deref()?.trim();
+        let host = host
+            .strip_prefix("https://")
+            .or_else(|| host.strip_prefix("http://"))
+            .unwrap_or(host)
+            .trim_end_matches('/');
+        (!host.is_empty()).then_some(host)
+    }
+}
+
 impl Default for DiffConfig {
     fn default() -> Self {
         Self {
@@ -149,7 +333,9 @@ impl Default for KeybindingsConfig {
             comment: KeySequence::single(KeyBinding::char('c')),
             suggestion: KeySequence::single(KeyBinding::char('s')),
             reply: KeySequence::single(KeyBinding::char('r')),
+            apply_suggestion: KeySequence::single(KeyBinding::char('a')),
             refresh: KeySequence::single(KeyBinding::char('R')),
+            refresh_checks: KeySequence::single(KeyBinding::char('S')),
             submit: KeySequence::single(KeyBinding::ctrl('s')),
 
             // Mode switching
@@ -157,7 +343,9 @@ impl Default for KeybindingsConfig {
             help: KeySequence::single(KeyBinding::char('?')),
             comment_list: KeySequence::single(KeyBinding::char('C')),
             ai_rally: KeySequence::single(KeyBinding::char('A')),
+            rerun_file_review: KeySequence::single(KeyBinding::char('V')),
             open_panel: KeySequence::single(KeyBinding::named(NamedKey::Enter)),
+            pr_details: KeySequence::single(KeyBinding::char('d')),
 
             // Diff operations
             go_to_definition: KeySequence::double(KeyBinding::char('g'), KeyBinding::char('d')),
@@ -200,13 +388,17 @@ impl KeybindingsConfig {
             ("comment", &self.comment),
             ("suggestion", &self.suggestion),
             ("reply", &self.reply),
+            ("apply_suggestion", &self.apply_suggestion),
             ("refresh", &self.refresh),
+            ("refresh_checks", &self.refresh_checks),
             ("submit", &self.submit),
             ("quit", &self.quit),
             ("help", &self.help),
             ("comment_list", &self.comment_list),
             ("ai_rally", &self.ai_rally),
+            ("rerun_file_review", &self.rerun_file_review),
             ("open_panel", &self.open_panel),
+            ("pr_details", &self.pr_details),
             ("go_to_definition", &self.go_to_definition),
             ("go_to_file", &self.go_to_file),
             ("open_in_browser", &self.open_in_browser),
@@ -273,11 +465,13 @@ impl KeybindingsConfig {
 fn is_context_compatible(name1: &str, name2: &str) -> bool {
     // These keybindings are used in different contexts:
     // - 'r' is used for 'reply' in comment panel and 'request_changes' in file list
+    // - 'a' is used for 'apply_suggestion' in comment panel and 'approve' in file list
     //
     // NOTE: 'comment' and 'suggestion' are NOT compatible - both are active in diff view
     // and comment panel contexts, so they must have different bindings.
     let context_groups: &[&[&str]] = &[
         &["reply", "request_changes"],
+        &["apply_suggestion", "approve"],
         &["toggle_local_mode", "move_right"], // L vs l: different cases
         &["toggle_auto_focus", "go_to_file"], // F vs gf: different sequence lengths
     ];
@@ -332,13 +526,17 @@ impl Serialize for KeybindingsConfig {
         map.serialize_entry("comment", &seq_to_value(&self.comment))?;
         map.serialize_entry("suggestion", &seq_to_value(&self.suggestion))?;
         map.serialize_entry("reply", &seq_to_value(&self.reply))?;
+        map.serialize_entry("apply_suggestion", &seq_to_value(&self.apply_suggestion))?;
         map.serialize_entry("refresh", &seq_to_value(&self.refresh))?;
+        map.serialize_entry("refresh_checks", &seq_to_value(&self.refresh_checks))?;
         map.serialize_entry("submit", &seq_to_value(&self.submit))?;
         map.serialize_entry("quit", &seq_to_value(&self.quit))?;
         map.serialize_entry("help", &seq_to_value(&self.help))?;
         map.serialize_entry("comment_list", &seq_to_value(&self.comment_list))?;
         map.serialize_entry("ai_rally", &seq_to_value(&self.ai_rally))?;
+        map.serialize_entry("rerun_file_review", &seq_to_value(&self.rerun_file_review))?;
         map.serialize_entry("open_panel", &seq_to_value(&self.open_panel))?;
+        map.serialize_entry("pr_details", &seq_to_value(&self.pr_details))?;
         map.serialize_entry("go_to_definition", &seq_to_value(&self.go_to_definition))?;
         map.serialize_entry("go_to_file", &seq_to_value(&self.go_to_file))?;
         map.serialize_entry("open_in_browser", &seq_to_value(&self.open_in_browser))?;
@@ -370,7 +568,21 @@ impl Config {
         Ok(config)
     }
 
-    fn config_path() -> PathBuf {
+    /// Problems in the content of a config file: parse errors (unknown keys, invalid
+    /// values), keybinding conflicts and unusable output schemas. Empty when valid
+    pub fn problems(content: &str) -> Vec<String> {
+        let config: Config = match toml::from_str(content) {
+            Ok(config) => config,
+            Err(e) => return vec![e.to_string().trim_end().to_string()],
+        };
+        let mut problems = config.keybindings.validate().err().unwrap_or_default();
+        if let Err(e) = OutputSchemas::load(&config.ai) {
+            problems.push(format!("{:#}", e));
+        }
+        problems
+    }
+
+    pub fn config_path() -> PathBuf {
         BaseDirectories::with_prefix("octorus")
             .map(|dirs| dirs.get_config_home().join("config.toml"))
             .unwrap_or_else(|_| PathBuf::from("config.toml"))
@@ -388,6 +600,30 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_problems_reports_typos_and_bad_values() {
+        assert!(Config::problems("[ai]\nreviewer = \"codex\"\n").is_empty());
+
+        let problems = Config::problems("[ai]\nreviwer = \"codex\"\n");
+        assert_eq!(problems.len(), 1);
+        assert!(
+            problems[0].contains("unknown field `reviwer`"),
+            "{problems:?}"
+        );
+
+        let problems = Config::problems("[ai]\nmerge_method = \"sqaush\"\n");
+        assert!(
+            problems[0].contains("unknown variant `sqaush`"),
+            "{problems:?}"
+        );
+
+        let problems = Config::problems("[keybindings]\ncomment = \"x\"\nsuggestion = \"x\"\n");
+        assert_eq!(
+            problems,
+            vec!["duplicate keybinding: 'suggestion' and 'comment' both use x".to_string()]
+        );
+    }
+
     #[test]
     fn test_parse_simple_keybinding() {
         let toml_str = r#"
@@ -428,6 +664,28 @@ mod tests {
         assert_eq!(config.keybindings.request_changes.display(), "r");
     }
 
+    #[test]
+    fn test_github_host() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.github.host(), None);
+
+        for hostname in [
+            "github.mycompany.com",
+            "https://github.mycompany.com/",
+            " http://github.mycompany.com ",
+        ] {
+            let github = GithubConfig {
+                hostname: Some(hostname.to_string()),
+            };
+            assert_eq!(github.host(), Some("github.mycompany.com"));
+        }
+
+        let github = GithubConfig {
+            hostname: Some("https://".to_string()),
+        };
+        assert_eq!(github.host(), None);
+    }
+
     #[test]
     fn test_parse_ai_config_defaults() {
         let config: Config = toml::from_str("").unwrap();
@@ -435,12 +693,46 @@ mod tests {
         {
           "reviewer": "claude",
           "reviewee": "claude",
+          "reviewer_model": null,
+          "reviewee_model": null,
+          "adapters": [],
           "max_iterations": 10,
+          "max_stalled_iterations": 3,
+          "max_concurrent_rallies": 2,
           "timeout_secs": 600,
+          "codex_turn_timeout_secs": 300,
+          "agent_max_retries": 2,
           "prompt_dir": null,
+          "reviewer_schema_path": null,
+          "reviewee_schema_path": null,
+          "review_language": "English",
+          "review_focus": [],
+          "ignore_globs": [],
+          "post_fix_command": null,
+          "post_fix_timeout_secs": 300,
+          "verify_command": null,
+          "verify_timeout_secs": 600,
+          "blocking_severity_threshold": null,
+          "reviewer_allowed_tools": null,
+          "reviewee_allowed_tools": null,
           "reviewer_additional_tools": [],
           "reviewee_additional_tools": [],
-          "auto_post": false
+          "auto_post": false,
+          "dry_run": false,
+          "pending_review": false,
+          "review_only": false,
+          "event_log": null,
+          "digest": false,
+          "attach_reasoning": false,
+          "response_cache": false,
+          "auto_merge": false,
+          "merge_method": "merge",
+          "auto_merge_require_ci": true,
+          "addressed_comment_reaction": null,
+          "reply_to_addressed_comments": false,
+          "ollama_host": "http://localhost:11434",
+          "ollama_model": "qwen2.5-coder",
+          "env": {}
         }
         "#);
     }
@@ -459,12 +751,46 @@ mod tests {
         {
           "reviewer": "codex",
           "reviewee": "claude",
+          "reviewer_model": null,
+          "reviewee_model": null,
+          "adapters": [],
           "max_iterations": 5,
+          "max_stalled_iterations": 3,
+          "max_concurrent_rallies": 2,
           "timeout_secs": 300,
+          "codex_turn_timeout_secs": 300,
+          "agent_max_retries": 2,
           "prompt_dir": null,
+          "reviewer_schema_path": null,
+          "reviewee_schema_path": null,
+          "review_language": "English",
+          "review_focus": [],
+          "ignore_globs": [],
+          "post_fix_command": null,
+          "post_fix_timeout_secs": 300,
+          "verify_command": null,
+          "verify_timeout_secs": 600,
+          "blocking_severity_threshold": null,
+          "reviewer_allowed_tools": null,
+          "reviewee_allowed_tools": null,
           "reviewer_additional_tools": [],
           "reviewee_additional_tools": [],
-          "auto_post": false
+          "auto_post": false,
+          "dry_run": false,
+          "pending_review": false,
+          "review_only": false,
+          "event_log": null,
+          "digest": false,
+          "attach_reasoning": false,
+          "response_cache": false,
+          "auto_merge": false,
+          "merge_method": "merge",
+          "auto_merge_require_ci": true,
+          "addressed_comment_reaction": null,
+          "reply_to_addressed_comments": false,
+          "ollama_host": "http://localhost:11434",
+          "ollama_model": "qwen2.5-coder",
+          "env": {}
         }
         "#);
     }
@@ -481,9 +807,28 @@ mod tests {
         {
           "reviewer": "claude",
           "reviewee": "claude",
+          "reviewer_model": null,
+          "reviewee_model": null,
+          "adapters": [],
           "max_iterations": 10,
+          "max_stalled_iterations": 3,
+          "max_concurrent_rallies": 2,
           "timeout_secs": 600,
+          "codex_turn_timeout_secs": 300,
+          "agent_max_retries": 2,
           "prompt_dir": null,
+          "reviewer_schema_path": null,
+          "reviewee_schema_path": null,
+          "review_language": "English",
+          "review_focus": [],
+          "ignore_globs": [],
+          "post_fix_command": null,
+          "post_fix_timeout_secs": 300,
+          "verify_command": null,
+          "verify_timeout_secs": 600,
+          "blocking_severity_threshold": null,
+          "reviewer_allowed_tools": null,
+          "reviewee_allowed_tools": null,
           "reviewer_additional_tools": [
             "Skill",
             "WebSearch"
@@ -491,11 +836,36 @@ mod tests {
           "reviewee_additional_tools": [
             "Bash(git push:*)"
           ],
-          "auto_post": false
+          "auto_post": false,
+          "dry_run": false,
+          "pending_review": false,
+          "review_only": false,
+          "event_log": null,
+          "digest": false,
+          "attach_reasoning": false,
+          "response_cache": false,
+          "auto_merge": false,
+          "merge_method": "merge",
+          "auto_merge_require_ci": true,
+          "addressed_comment_reaction": null,
+          "reply_to_addressed_comments": false,
+          "ollama_host": "http://localhost:11434",
+          "ollama_model": "qwen2.5-coder",
+          "env": {}
         }
         "#);
     }
 
+    #[test]
+    fn test_parse_ai_config_adapters() {
+        let toml_str = r#"
+            [ai]
+            adapters = ["codex", "claude"]
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.ai.adapters, vec!["codex", "claude"]);
+    }
+
     #[test]
     fn test_parse_ai_config_auto_post_true() {
         let toml_str = r#"
@@ -511,4 +881,25 @@ mod tests {
         let config: Config = toml::from_str("").unwrap();
         assert!(!config.ai.auto_post);
     }
+
+    #[test]
+    fn test_parse_ai_config_agent_env() {
+        let toml_str = r#"
+            [ai.env.claude]
+            ANTHROPIC_API_KEY = "sk-test"
+
+            [ai.env.codex]
+            HTTPS_PROXY = "http://proxy.example.com:8080"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.ai.agent_env("claude"),
+            HashMap::from([("ANTHROPIC_API_KEY".to_string(), "sk-test".to_string())])
+        );
+        assert_eq!(
+            config.ai.agent_env("codex")["HTTPS_PROXY"],
+            "http://proxy.example.com:8080"
+        );
+        assert!(config.ai.agent_env("gemini").is_empty());
+    }
 }
@@ -24,6 +24,15 @@ pub enum LineType {
     Meta,
 }
 
+/// Side of the diff a line belongs to (GitHub's `side` for review comments)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSide {
+    /// Old version (removed lines)
+    Left,
+    /// New version (added and context lines)
+    Right,
+}
+
 /// Information extracted from a single line in a diff patch
 #[derive(Debug, Clone)]
 pub struct DiffLineInfo {
@@ -55,6 +64,15 @@ fn parse_hunk_header(line: &str) -> Option<u32> {
     num_str.parse().ok()
 }
 
+/// Parse a hunk header to extract the starting line number for the old file
+/// Format: @@ -old_start,old_count +new_start,new_count @@
+fn parse_hunk_header_old_start(line: &str) -> Option<u32> {
+    let minus_pos = line.find('-')?;
+    let after_minus = &line[minus_pos + 1..];
+    let end_pos = after_minus.find([',', ' ']).unwrap_or(after_minus.len());
+    after_minus[..end_pos].parse().ok()
+}
+
 /// Get information about a specific line in a patch
 ///
 /// # Arguments
@@ -203,6 +221,81 @@ pub fn line_number_to_position(patch: &str, target_line: u32) -> Option<u32> {
     None
 }
 
+/// Convert an old file line number to the patch position of the removed line.
+///
+/// Counterpart of [`line_number_to_position`] for the LEFT side of the diff: only
+/// removed lines match, since context lines are already reachable by their new line
+/// number. Position counting follows the same rules.
+pub fn removed_line_to_position(patch: &str, target_line: u32) -> Option<u32> {
+    let mut old_line_number: Option<u32> = None;
+    let mut position_counter: Option<u32> = None;
+
+    for line in patch.lines() {
+        let (line_type, _) = classify_line(line);
+
+        match line_type {
+            LineType::Meta => continue,
+            LineType::Header => {
+                old_line_number = parse_hunk_header_old_start(line);
+                position_counter = Some(position_counter.map_or(0, |p| p + 1));
+            }
+            LineType::Removed => {
+                position_counter = position_counter.map(|p| p + 1);
+                if old_line_number == Some(target_line) {
+                    return position_counter;
+                }
+                old_line_number = old_line_number.map(|n| n + 1);
+            }
+            LineType::Context => {
+                position_counter = position_counter.map(|p| p + 1);
+                old_line_number = old_line_number.map(|n| n + 1);
+            }
+            LineType::Added => {
+                position_counter = position_counter.map(|p| p + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Find the commentable line (added or context) in the patch closest to `target_line`.
+///
+/// Returns `(new_line_number, position)` of the nearest line, preferring the earlier
+/// line on ties. Returns `None` if the patch has no commentable lines.
+pub fn nearest_commentable_line(patch: &str, target_line: u32) -> Option<(u32, u32)> {
+    let mut new_line_number: Option<u32> = None;
+    let mut position_counter: Option<u32> = None;
+    let mut nearest: Option<(u32, u32)> = None;
+
+    for line in patch.lines() {
+        let (line_type, _) = classify_line(line);
+
+        match line_type {
+            LineType::Meta => continue,
+            LineType::Header => {
+                new_line_number = parse_hunk_header(line);
+                position_counter = Some(position_counter.map_or(0, |p| p + 1));
+            }
+            LineType::Added | LineType::Context => {
+                position_counter = position_counter.map(|p| p + 1);
+                if let (Some(n), Some(pos)) = (new_line_number, position_counter) {
+                    let is_closer = nearest.is_none_or(|(best, _)| {
+                        n.abs_diff(target_line) < best.abs_diff(target_line)
+                    });
+                    if is_closer {
+                        nearest = Some((n, pos));
+                    }
+                }
+                new_line_number = new_line_number.map(|n| n + 1);
+            }
+            LineType::Removed => {
+                position_counter = position_counter.map(|p| p + 1);
+            }
+        }
+    }
+    nearest
+}
+
 /// Parse a unified diff output into a map of filename -> patch content
 ///
 /// This function splits the output of `git diff` or `gh pr diff` into individual
@@ -779,4 +872,42 @@ Binary files /dev/null and b/image.png differ
         assert_eq!(line_number_to_position(SAMPLE_PATCH, 999), None);
         assert_eq!(line_number_to_position(SAMPLE_PATCH, 0), None);
     }
+
+    #[test]
+    fn test_removed_line_to_position() {
+        let patch =
+            "@@ -1,3 +1,3 @@\n-old1\n+new1\n ctx\n@@ -10,3 +10,1 @@\n-old10\n-old11\n ctx12";
+        // Hunk 1: "-old1" -> old_line=1, position=1
+        // Hunk 2: second @@ is position=4; "-old10" -> 5, "-old11" -> 6
+        assert_eq!(removed_line_to_position(patch, 1), Some(1));
+        assert_eq!(removed_line_to_position(patch, 10), Some(5));
+        assert_eq!(removed_line_to_position(patch, 11), Some(6));
+        // Context lines are not removed lines
+        assert_eq!(removed_line_to_position(patch, 2), None);
+        assert_eq!(removed_line_to_position(patch, 12), None);
+        assert_eq!(parse_hunk_header_old_start("@@ -10,3 +15,7 @@"), Some(10));
+        assert_eq!(parse_hunk_header_old_start("@@ -1 +1 @@"), Some(1));
+    }
+
+    #[test]
+    fn test_nearest_commentable_line() {
+        let patch = "@@ -1,3 +1,3 @@\n-old1\n+new1\n ctx\n@@ -10,2 +10,2 @@\n-old2\n+new2";
+        // Exact match
+        assert_eq!(nearest_commentable_line(patch, 2), Some((2, 3)));
+        // Between hunks: line 5 is closer to 2 than to 10
+        assert_eq!(nearest_commentable_line(patch, 5), Some((2, 3)));
+        // Beyond the last hunk clamps to the last commentable line
+        assert_eq!(nearest_commentable_line(patch, 999), Some((10, 6)));
+        // Line 0 clamps to the first commentable line
+        assert_eq!(nearest_commentable_line(patch, 0), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_nearest_commentable_line_no_commentable_lines() {
+        assert_eq!(
+            nearest_commentable_line("@@ -1,1 +0,0 @@\n-removed", 1),
+            None
+        );
+        assert_eq!(nearest_commentable_line("", 1), None);
+    }
 }
@@ -2,9 +2,14 @@ use anyhow::Result;
 use std::borrow::Cow;
 use std::env;
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 use tempfile::NamedTempFile;
 
+mod suggestion;
+
+pub use suggestion::{apply_suggestion, suggestion_from_comment, AppliedSuggestion, Suggestion};
+
 /// エディタのテンプレート設定
 struct EditorTemplate<'a> {
     header: Cow<'a, str>,
@@ -24,7 +29,9 @@ fn open_editor_internal(editor: &str, template: EditorTemplate<'_>) -> Result<Op
     fs::write(temp_file.path(), &content)?;
 
     let editor_cmd = resolve_editor(editor);
-    let status = Command::new(&editor_cmd).arg(temp_file.path()).status()?;
+    let path = temp_file.path().to_string_lossy();
+    let (program, args) = editor_command(&editor_cmd, &path, None);
+    let status = Command::new(program).args(args).status()?;
 
     if !status.success() {
         return Ok(None);
@@ -109,14 +116,14 @@ pub fn open_suggestion_editor(
 
 /// Open external editor at a specific file and line number.
 ///
-/// Uses the format `$EDITOR +{line} {file_path}` to open the file.
+/// Helix (`hx`) is launched as `hx {file_path}:{line}`; other editors get
+/// `$EDITOR +{line} {file_path}`, unless the editor is a template (see
+/// [`editor_command`]).
 /// The caller is responsible for suspending/restoring the TUI terminal.
-pub fn open_file_at_line(editor: &str, file_path: &str, line: usize) -> Result<()> {
+pub fn open_at(editor: &str, file_path: &str, line: usize) -> Result<()> {
     let editor_cmd = resolve_editor(editor);
-    let status = Command::new(&editor_cmd)
-        .arg(format!("+{}", line))
-        .arg(file_path)
-        .status()?;
+    let (program, args) = editor_command(&editor_cmd, file_path, Some(line));
+    let status = Command::new(program).args(args).status()?;
 
     if !status.success() {
         anyhow::bail!("Editor exited with non-zero status");
@@ -125,6 +132,52 @@ pub fn open_file_at_line(editor: &str, file_path: &str, line: usize) -> Result<(
     Ok(())
 }
 
+/// エディタ設定をプログラムと引数に分解する
+///
+/// `{path}` を含む設定はテンプレートとして扱い、`{path}` と `{line}` を置き換える
+/// （例: `code --goto {path}:{line}`）。それ以外はファイルを末尾に追加し、行指定が
+/// あればエディタごとの形式で渡す。引数はシェルと同じ規則で区切るので、空白を含むパスは
+/// クォートする。設定全体が既存のファイルを指す場合は（クォートがなくても）そのままプログラムとする。
+fn editor_command(editor_cmd: &str, file_path: &str, line: Option<usize>) -> (String, Vec<String>) {
+    let words = if Path::new(editor_cmd).is_file() {
+        vec![editor_cmd.to_string()]
+    } else {
+        // 閉じていないクォートなどで解釈できなければ空白で区切る
+        shell_words::split(editor_cmd)
+            .unwrap_or_else(|_| editor_cmd.split_whitespace().map(str::to_string).collect())
+    };
+    let mut words = words.into_iter();
+    let program = words.next().unwrap_or_else(|| "vi".to_string());
+    let mut args: Vec<String> = words.collect();
+
+    if editor_cmd.contains("{path}") {
+        let line = line.unwrap_or(1).to_string();
+        let substitute = |word: &str| word.replace("{path}", file_path).replace("{line}", &line);
+        return (
+            substitute(&program),
+            args.iter().map(|a| substitute(a)).collect(),
+        );
+    }
+
+    match line {
+        Some(line) => args.extend(line_args(&program, file_path, line)),
+        None => args.push(file_path.to_string()),
+    }
+    (program, args)
+}
+
+fn line_args(editor_cmd: &str, file_path: &str, line: usize) -> Vec<String> {
+    let program = Path::new(editor_cmd)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(editor_cmd);
+    if matches!(program, "hx" | "helix") {
+        vec![format!("{}:{}", file_path, line)]
+    } else {
+        vec![format!("+{}", line), file_path.to_string()]
+    }
+}
+
 /// Open external editor for AI Rally clarification response
 /// Returns the user's answer to the clarification question
 pub fn open_clarification_editor(editor: &str, question: &str) -> Result<Option<String>> {
@@ -142,3 +195,112 @@ pub fn open_clarification_editor(editor: &str, question: &str) -> Result<Option<
         },
     )
 }
+
+/// Open external editor to revise the AI Rally review summary before posting
+/// Returns the edited summary
+pub fn open_review_summary_editor(editor: &str, summary: &str) -> Result<Option<String>> {
+    open_editor_internal(
+        editor,
+        EditorTemplate {
+            header: Cow::Borrowed(
+                "<!-- octorus: AI Rally review summary -->\n\
+                 <!-- Edit the summary below. Save and close to post the review. -->\n\
+                 <!-- Delete all content to cancel. -->",
+            ),
+            initial_content: Some(Cow::Borrowed(summary)),
+        },
+    )
+}
+
+/// Open external editor to revise one AI Rally review comment before posting
+/// Returns the edited comment body
+pub fn open_review_comment_editor(
+    editor: &str,
+    filename: &str,
+    line: u32,
+    body: &str,
+) -> Result<Option<String>> {
+    open_editor_internal(
+        editor,
+        EditorTemplate {
+            header: Cow::Owned(format!(
+                "<!-- octorus: AI Rally review comment -->\n\
+                 <!-- File: {} Line: {} -->\n\
+                 <!-- Edit the comment below. Save and close to keep it. -->\n\
+                 <!-- Delete all content to cancel. -->",
+                filename, line
+            )),
+            initial_content: Some(Cow::Borrowed(body)),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_args_helix_uses_path_colon_line() {
+        assert_eq!(line_args("hx", "src/main.rs", 42), vec!["src/main.rs:42"]);
+        assert_eq!(
+            line_args("/usr/local/bin/helix", "src/main.rs", 7),
+            vec!["src/main.rs:7"]
+        );
+    }
+
+    #[test]
+    fn test_editor_command_template() {
+        let command = editor_command("code --goto {path}:{line}", "src/main.rs", Some(42));
+        assert_eq!(
+            command,
+            (
+                "code".to_string(),
+                vec!["--goto".to_string(), "src/main.rs:42".to_string()]
+            )
+        );
+
+        // Temp files for comments open at the first line
+        let (_, args) = editor_command("hx {path}:{line}", "/tmp/comment.md", None);
+        assert_eq!(args, vec!["/tmp/comment.md:1"]);
+
+        // Plain commands keep their flags and get the file appended
+        let (program, args) = editor_command("code --wait", "/tmp/comment.md", None);
+        assert_eq!(program, "code");
+        assert_eq!(args, vec!["--wait", "/tmp/comment.md"]);
+        let (_, args) = editor_command("nvim", "src/main.rs", Some(3));
+        assert_eq!(args, vec!["+3", "src/main.rs"]);
+    }
+
+    #[test]
+    fn test_editor_command_paths_with_spaces() {
+        // Quoted program paths stay one word
+        let (program, args) = editor_command(
+            "'/Applications/Sublime Text.app/bin/subl' --wait {path}:{line}",
+            "src/main.rs",
+            Some(5),
+        );
+        assert_eq!(program, "/Applications/Sublime Text.app/bin/subl");
+        assert_eq!(args, vec!["--wait", "src/main.rs:5"]);
+
+        // An unquoted setting naming an existing file is the program itself
+        let dir = tempfile::tempdir().unwrap();
+        let editor = dir.path().join("my editor");
+        std::fs::write(&editor, "").unwrap();
+        let editor = editor.to_str().unwrap();
+        let (program, args) = editor_command(editor, "src/main.rs", None);
+        assert_eq!(program, editor);
+        assert_eq!(args, vec!["src/main.rs"]);
+
+        // File paths with spaces are passed as a single argument
+        let (_, args) = editor_command("code --goto {path}:{line}", "docs/my notes.md", Some(2));
+        assert_eq!(args, vec!["--goto", "docs/my notes.md:2"]);
+    }
+
+    #[test]
+    fn test_line_args_other_editors_use_plus_line() {
+        assert_eq!(
+            line_args("vim", "src/main.rs", 42),
+            vec!["+42", "src/main.rs"]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::github::comment::ReviewComment;
+
+/// A ```` ```suggestion ```` block from a review comment, mapped to the lines it replaces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub path: String,
+    /// First replaced line (1-based, as of the commit the comment was made on)
+    pub start_line: u32,
+    /// Last replaced line (inclusive)
+    pub end_line: u32,
+    /// Replacement text; empty deletes the lines
+    pub replacement: String,
+    /// The commented lines as they were when the comment was written (taken from the
+    /// comment's diff hunk). Used to find them again when the file changed since.
+    pub original: Option<Vec<String>>,
+}
+
+/// Lines a suggestion was applied to (1-based, inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedSuggestion {
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Extract the contents of every ```` ```suggestion ```` block in a comment body
+///
+/// Longer fences (```` ````suggestion ````) are supported so suggestions can contain
+/// code fences themselves. Unclosed blocks are ignored.
+pub fn parse_suggestion_blocks(body: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+
+    for line in body.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let trimmed = line.trim();
+        match current.as_mut() {
+            Some((fence_len, lines)) => {
+                let ticks = trimmed.chars().take_while(|&c| c == '`').count();
+                if ticks >= *fence_len && ticks == trimmed.len() {
+                    blocks.push(lines.join("\n"));
+                    current = None;
+                } else {
+                    lines.push(line);
+                }
+            }
+            None => {
+                let ticks = trimmed.chars().take_while(|&c| c == '`').count();
+                if ticks >= 3 && trimmed[ticks..].trim() == "suggestion" {
+                    current = Some((ticks, Vec::new()));
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+/// The suggestion in `comment`, if it has one that can be applied to the new side of
+/// the file (the first block when there are several)
+pub fn suggestion_from_comment(comment: &ReviewComment) -> Option<Suggestion> {
+    let replacement = parse_suggestion_blocks(&comment.body).into_iter().next()?;
+    let end_line = comment.line?;
+    let start_line = comment
+        .start_line
+        .filter(|&start| start >= 1 && start <= end_line)
+        .unwrap_or(end_line);
+
+    let original = match comment.diff_hunk.as_deref() {
+        Some(hunk) => {
+            // hunk の最終行がコメント対象行。削除行（LEFT 側）への提案は適用できない
+            if hunk.lines().last().is_some_and(|l| l.starts_with('-')) {
+                return None;
+            }
+            commented_lines(hunk, (end_line - start_line + 1) as usize)
+        }
+        None => None,
+    };
+
+    Some(Suggestion {
+        path: comment.path.clone(),
+        start_line,
+        end_line,
+        replacement,
+        original,
+    })
+}
+
+/// The last `count` new-side lines of a comment's diff hunk (the commented lines)
+fn commented_lines(hunk: &str, count: usize) -> Option<Vec<String>> {
+    let new_side: Vec<&str> = hunk
+        .lines()
+        .filter(|l| !l.starts_with("@@") && !l.starts_with('-') && !l.starts_with('\\'))
+        .map(|l| l.get(1..).unwrap_or(""))
+        .collect();
+    if new_side.len() < count {
+        return None;
+    }
+    Some(
+        new_side[new_side.len() - count..]
+            .iter()
+            .map(|l| l.to_string())
+            .collect(),
+    )
+}
+
+/// Replace the suggested lines in the working tree under `repo_root`
+///
+/// When the file changed since the comment was written, the commented lines are
+/// looked up by content (nearest match to the original position); the suggestion is
+/// refused if they no longer exist.
+pub fn apply_suggestion(repo_root: &Path, suggestion: &Suggestion) -> Result<AppliedSuggestion> {
+    let path = repo_root.join(&suggestion.path);
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", suggestion.path))?;
+
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let start = locate_range(&lines, suggestion)?;
+    let len = (suggestion.end_line - suggestion.start_line + 1) as usize;
+
+    let mut new_lines: Vec<&str> = lines[..start].to_vec();
+    new_lines.extend(suggestion.replacement.lines());
+    new_lines.extend(&lines[start + len..]);
+
+    let mut new_content = new_lines.join(newline);
+    if content.ends_with('\n') && !new_lines.is_empty() {
+        new_content.push_str(newline);
+    }
+    fs::write(&path, new_content)
+        .with_context(|| format!("Failed to write {}", suggestion.path))?;
+
+    let replaced = suggestion.replacement.lines().count() as u32;
+    Ok(AppliedSuggestion {
+        start_line: start as u32 + 1,
+        end_line: start as u32 + replaced.max(1),
+    })
+}
+
+/// 0-based index of the first line the suggestion replaces in `lines`
+fn locate_range(lines: &[&str], suggestion: &Suggestion) -> Result<usize> {
+    let start = suggestion.start_line as usize - 1;
+    let len = (suggestion.end_line - suggestion.start_line + 1) as usize;
+
+    let Some(original) = suggestion.original.as_ref() else {
+        if start + len > lines.len() {
+            bail!(
+                "Lines {}-{} are outside {} ({} lines)",
+                suggestion.start_line,
+                suggestion.end_line,
+                suggestion.path,
+                lines.len()
+            );
+        }
+        return Ok(start);
+    };
+
+    let matches_at = |i: usize| {
+        lines
+            .get(i..i + len)
+            .is_some_and(|window| window.iter().zip(original).all(|(a, b)| a == b))
+    };
+    if matches_at(start) {
+        return Ok(start);
+    }
+    // ファイルが変わって行がずれている場合は、元の位置に最も近い一致を探す
+    (0..lines.len())
+        .filter(|&i| matches_at(i))
+        .min_by_key(|&i| i.abs_diff(start))
+        .with_context(|| {
+            format!(
+                "The commented lines no longer exist in {} (the file changed since the comment)",
+                suggestion.path
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::User;
+    use tempfile::tempdir;
+
+    fn comment(body: &str, start_line: Option<u32>, line: u32, hunk: &str) -> ReviewComment {
+        ReviewComment {
+            id: 1,
+            path: "src/lib.rs".to_string(),
+            line: Some(line),
+            start_line,
+            diff_hunk: Some(hunk.to_string()),
+            body: body.to_string(),
+            user: User {
+                login: "reviewer".to_string(),
+            },
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_suggestion_blocks() {
+        let body = "Use this:\r\n```suggestion\r\nlet x = 1;\r\nlet y = 2;\r\n```\r\n\
+                    ```rust\nnot a suggestion\n```\n\
+                    ````suggestion\n```\nnested\n```\n````\n\
+                    ```suggestion\n```\n\
+                    ```suggestion\nunclosed";
+        assert_eq!(
+            parse_suggestion_blocks(body),
+            vec![
+                "let x = 1;\nlet y = 2;".to_string(),
+                "```\nnested\n```".to_string(),
+                String::new(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggestion_maps_multi_line_range_from_hunk() {
+        let hunk = "@@ -1,2 +1,3 @@\n fn a() {\n-    old();\n+    one();\n+    two();";
+        let c = comment("```suggestion\n    both();\n```", Some(2), 3, hunk);
+        assert_eq!(
+            suggestion_from_comment(&c).unwrap(),
+            Suggestion {
+                path: "src/lib.rs".to_string(),
+                start_line: 2,
+                end_line: 3,
+                replacement: "    both();".to_string(),
+                original: Some(vec!["    one();".to_string(), "    two();".to_string()]),
+            }
+        );
+
+        // Comments on deleted lines and comments without a block have nothing to apply
+        let left = comment("```suggestion\nx\n```", None, 2, "@@ -1,2 +1,1 @@\n a\n-b");
+        assert!(suggestion_from_comment(&left).is_none());
+        assert!(suggestion_from_comment(&comment("Looks good", None, 3, hunk)).is_none());
+    }
+
+    #[test]
+    fn test_apply_suggestion_follows_moved_lines() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        let file = dir.path().join("src/lib.rs");
+        // Two lines were inserted above the commented lines since the comment
+        std::fs::write(
+            &file,
+            "// new\n// header\nfn a() {\n    one();\n    two();\n}\n",
+        )
+        .unwrap();
+
+        let hunk = "@@ -1,1 +1,3 @@\n fn a() {\n+    one();\n+    two();";
+        let c = comment("```suggestion\n    both();\n```", Some(2), 3, hunk);
+        let applied = apply_suggestion(dir.path(), &suggestion_from_comment(&c).unwrap()).unwrap();
+
+        assert_eq!(
+            applied,
+            AppliedSuggestion {
+                start_line: 4,
+                end_line: 4
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "// new\n// header\nfn a() {\n    both();\n}\n"
+        );
+
+        // Applying again fails: the commented lines are gone
+        let err = apply_suggestion(dir.path(), &suggestion_from_comment(&c).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("no longer exist"));
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::future::Future;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,10 +16,91 @@ pub enum DetectRepoError {
     GhError(String),
 }
 
+/// secondary rate limit で失敗した POST を再試行する回数
+const RATE_LIMIT_MAX_RETRIES: u32 = 3;
+
+/// secondary rate limit 後の最初の待ち時間（以降は倍々に延ばす）。
+/// retry-after が取れないため、GitHub の推奨どおり少なくとも 1 分待つ
+const RATE_LIMIT_BASE_DELAY: Duration = Duration::from_secs(60);
+
+/// primary rate limit の解除を待つ上限。これより先に解除される場合だけ待って再試行する。
+/// 待っている間 TUI は読み込み中のまま何も表示できないため、短くしておく
+const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// GitHub Enterprise Server のホスト名（未設定なら github.com）
+static GH_HOST: OnceLock<String> = OnceLock::new();
+
+/// 以降のすべての gh 呼び出しの対象ホストを設定する（起動時に一度だけ）
+pub fn set_gh_host(host: &str) {
+    let _ = GH_HOST.set(host.to_string());
+}
+
+/// gh 呼び出しの対象ホスト
+pub fn gh_host() -> &'static str {
+    GH_HOST.get().map(String::as_str).unwrap_or("github.com")
+}
+
+/// 設定されたホストを GH_HOST で渡す gh コマンド
+fn gh() -> Command {
+    let mut command = Command::new("gh");
+    if let Some(host) = GH_HOST.get() {
+        command.env("GH_HOST", host);
+    }
+    command
+}
+
+/// gh CLI が使えない理由（起動時のプリフライトチェック用）
+#[derive(Debug, Error)]
+pub enum GhCliError {
+    #[error("gh CLI not found. Install it from https://cli.github.com/ and run `gh auth login`.")]
+    NotInstalled,
+    #[error(
+        "gh is not authenticated for {host}. Run `gh auth login --hostname {host}`.\n{detail}"
+    )]
+    NotAuthenticated { host: String, detail: String },
+    #[error("Failed to execute gh CLI: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// gh がインストールされていて対象ホストに認証済みか確認する
+///
+/// 未インストール・未ログインのまま進むと、以降の gh 呼び出しが分かりにくい
+/// エラーになるため、起動時（TUI 開始前）に一度だけ呼ぶ。
+pub async fn check_gh_cli() -> std::result::Result<(), GhCliError> {
+    let host = gh_host().to_string();
+    tokio::task::spawn_blocking(move || {
+        let output = Command::new("gh")
+            .args(["auth", "status", "--hostname", &host])
+            .output();
+        auth_status_result(output, &host)
+    })
+    .await
+    .unwrap_or_else(|e| Err(GhCliError::Io(std::io::Error::other(e))))
+}
+
+/// `gh auth status` の実行結果を GhCliError に振り分ける
+fn auth_status_result(
+    output: std::io::Result<std::process::Output>,
+    host: &str,
+) -> std::result::Result<(), GhCliError> {
+    let output = match output {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(GhCliError::NotInstalled),
+        Err(e) => return Err(GhCliError::Io(e)),
+    };
+    if !output.status.success() {
+        return Err(GhCliError::NotAuthenticated {
+            host: host.to_string(),
+            detail: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
 /// Detect the repository name from the current directory using `gh repo view`
 pub async fn detect_repo() -> std::result::Result<String, DetectRepoError> {
     let result = tokio::task::spawn_blocking(|| {
-        let output = Command::new("gh")
+        let output = gh()
             .args([
                 "repo",
                 "view",
@@ -68,13 +153,52 @@ pub async fn detect_repo() -> std::result::Result<String, DetectRepoError> {
 /// Execute gh CLI command and return stdout
 /// Uses spawn_blocking to avoid blocking the tokio runtime
 pub async fn gh_command(args: &[&str]) -> Result<String> {
+    gh_command_with_input(args, None, None).await
+}
+
+/// Execute gh CLI command in `dir` (for commands that act on a working tree, e.g. `gh pr checkout`)
+pub async fn gh_command_in_dir(dir: &str, args: &[&str]) -> Result<String> {
+    gh_command_with_input(args, None, Some(dir)).await
+}
+
+/// Execute gh CLI command, optionally writing `input` to its stdin
+async fn gh_command_with_input(
+    args: &[&str],
+    input: Option<Vec<u8>>,
+    current_dir: Option<&str>,
+) -> Result<String> {
     let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    let current_dir = current_dir.map(str::to_string);
 
     tokio::task::spawn_blocking(move || {
-        let output = Command::new("gh")
-            .args(&args)
-            .output()
-            .context("Failed to execute gh CLI - is it installed?")?;
+        let mut command = gh();
+        if let Some(dir) = current_dir {
+            command.current_dir(dir);
+        }
+        let output = match input {
+            Some(input) => {
+                let mut child = command
+                    .args(&args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .context("Failed to execute gh CLI - is it installed?")?;
+                // stdin は書き込み後に drop して EOF を送る
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin
+                        .write_all(&input)
+                        .context("Failed to write gh CLI stdin")?;
+                }
+                child
+                    .wait_with_output()
+                    .context("Failed to wait for gh CLI")?
+            }
+            None => command
+                .args(&args)
+                .output()
+                .context("Failed to execute gh CLI - is it installed?")?,
+        };
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -114,7 +238,8 @@ pub async fn gh_command(args: &[&str]) -> Result<String> {
 
 /// Execute gh api command with JSON output
 pub async fn gh_api(endpoint: &str) -> Result<serde_json::Value> {
-    let output = gh_command(&["api", endpoint]).await?;
+    let args = ["api", endpoint];
+    let output = retry_on_primary_rate_limit(fetch_rate_limit_wait, || gh_command(&args)).await?;
     serde_json::from_str(&output).context("Failed to parse gh api response as JSON")
 }
 
@@ -122,9 +247,15 @@ pub async fn gh_api(endpoint: &str) -> Result<serde_json::Value> {
 /// Fetches all pages and merges into a single JSON array.
 /// Caller should include `per_page=100` in endpoint if desired.
 pub async fn gh_api_paginate(endpoint: &str) -> Result<serde_json::Value> {
-    let output = gh_command(&["api", "--paginate", "--slurp", endpoint]).await?;
+    let args = ["api", "--paginate", "--slurp", endpoint];
+    let output = retry_on_primary_rate_limit(fetch_rate_limit_wait, || gh_command(&args)).await?;
+    parse_paginated_output(&output)
+}
+
+/// Parse `--paginate --slurp` output (a JSON array of pages) into a single array
+pub(super) fn parse_paginated_output(output: &str) -> Result<serde_json::Value> {
     let pages: Vec<serde_json::Value> =
-        serde_json::from_str(&output).context("Failed to parse gh api paginated response")?;
+        serde_json::from_str(output).context("Failed to parse gh api paginated response")?;
     flatten_pages(pages)
 }
 
@@ -156,6 +287,50 @@ fn other_type_name(value: &serde_json::Value) -> &'static str {
     }
 }
 
+/// Execute gh api POST with a JSON request body (for payloads with nested arrays/objects)
+pub async fn gh_api_post_json(
+    endpoint: &str,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value> {
+    let input = serde_json::to_vec(body).context("Failed to serialize gh api request body")?;
+    tracing::debug!(endpoint, "gh api post (json body)");
+    let (args, input) = (
+        &["api", "--method", "POST", endpoint, "--input", "-"],
+        &input,
+    );
+    let output = retry_on_primary_rate_limit(fetch_rate_limit_wait, || {
+        retry_on_secondary_rate_limit(RATE_LIMIT_BASE_DELAY, move || {
+            gh_command_with_input(args, Some(input.clone()), None)
+        })
+    })
+    .await?;
+    serde_json::from_str(&output).context("Failed to parse gh api response as JSON")
+}
+
+/// Execute a GraphQL query or mutation via `gh api graphql`
+pub async fn gh_api_graphql(
+    query: &str,
+    variables: &[(&str, FieldValue<'_>)],
+) -> Result<serde_json::Value> {
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={}", query),
+    ];
+    for (key, value) in variables {
+        let (flag, v) = match value {
+            FieldValue::String(v) => ("-f", v),
+            FieldValue::Raw(v) => ("-F", v),
+        };
+        args.push(flag.to_string());
+        args.push(format!("{}={}", key, v));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = gh_command(&args_refs).await?;
+    serde_json::from_str(&output).context("Failed to parse gh api graphql response as JSON")
+}
+
 /// Field type for gh api command
 pub enum FieldValue<'a> {
     /// String field (-f)
@@ -189,15 +364,295 @@ pub async fn gh_api_post(
     }
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     tracing::debug!(args = ?args_refs, "gh api post");
-    let output = gh_command(&args_refs).await?;
+    let args_refs = args_refs.as_slice();
+    let output = retry_on_primary_rate_limit(fetch_rate_limit_wait, || {
+        retry_on_secondary_rate_limit(RATE_LIMIT_BASE_DELAY, move || gh_command(args_refs))
+    })
+    .await?;
     serde_json::from_str(&output).context("Failed to parse gh api response as JSON")
 }
 
+/// gh のエラーが secondary rate limit（短時間の大量投稿などで返る HTTP 403/429）によるものか
+fn is_secondary_rate_limit(err: &anyhow::Error) -> bool {
+    let message = err.to_string().to_lowercase();
+    message.contains("secondary rate limit") || message.contains("http 429")
+}
+
+/// secondary rate limit で失敗したら待ってから `post` をやり直す（指数バックオフ）
+async fn retry_on_secondary_rate_limit<T, F, Fut>(base_delay: Duration, mut post: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        let err = match post().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        if attempt >= RATE_LIMIT_MAX_RETRIES || !is_secondary_rate_limit(&err) {
+            return Err(err);
+        }
+        attempt += 1;
+
+        let delay = base_delay * 2u32.pow(attempt - 1);
+        tracing::warn!(
+            "GitHub secondary rate limit hit, retrying in {}s ({}/{})",
+            delay.as_secs(),
+            attempt,
+            RATE_LIMIT_MAX_RETRIES
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// gh のエラーが primary rate limit（1 時間あたりのリクエスト上限、HTTP 403）によるものか
+fn is_primary_rate_limit(err: &anyhow::Error) -> bool {
+    err.to_string()
+        .to_lowercase()
+        .contains("api rate limit exceeded")
+}
+
+/// `gh api -i` のレスポンスヘッダから rate limit 解除までの待ち時間を求める
+///
+/// `Retry-After` を優先し、なければ `X-RateLimit-Remaining: 0` のときの
+/// `X-RateLimit-Reset`（UNIX 時刻）を使う。`now` も UNIX 時刻（秒）。
+fn rate_limit_wait(response: &str, now: u64) -> Option<Duration> {
+    let mut remaining = None;
+    let mut reset = None;
+    // ヘッダは最初の空行まで（以降はレスポンスボディ）
+    for line in response.lines().take_while(|l| !l.trim().is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "retry-after" => {
+                if let Ok(secs) = value.parse() {
+                    return Some(Duration::from_secs(secs));
+                }
+            }
+            "x-ratelimit-remaining" => remaining = value.parse::<u64>().ok(),
+            "x-ratelimit-reset" => reset = value.parse::<u64>().ok(),
+            _ => {}
+        }
+    }
+    match (remaining, reset) {
+        (Some(0), Some(reset)) => Some(Duration::from_secs(reset.saturating_sub(now))),
+        _ => None,
+    }
+}
+
+/// rate limit 解除までの待ち時間を `gh api -i rate_limit` のヘッダから取得する
+///
+/// rate_limit エンドポイントは上限に達していても呼べる（カウントもされない）。
+async fn fetch_rate_limit_wait() -> Option<Duration> {
+    let response = gh_command(&["api", "-i", "rate_limit"]).await.ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    rate_limit_wait(&response, now)
+}
+
+/// primary rate limit で失敗したら、解除時刻まで待って `call` を 1 回だけやり直す
+///
+/// 黙って長時間止まらないよう、解除まで [`RATE_LIMIT_MAX_WAIT`] より長い場合は
+/// 待たずにエラーを返す。
+async fn retry_on_primary_rate_limit<T, F, Fut, W, WFut>(
+    wait_for_reset: W,
+    mut call: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    W: FnOnce() -> WFut,
+    WFut: Future<Output = Option<Duration>>,
+{
+    let err = match call().await {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+    if !is_primary_rate_limit(&err) {
+        return Err(err);
+    }
+    let Some(wait) = wait_for_reset().await else {
+        return Err(err);
+    };
+    if wait > RATE_LIMIT_MAX_WAIT {
+        return Err(err.context(format!(
+            "GitHub API rate limit exceeded; it resets in {} min, not waiting that long",
+            wait.as_secs().div_ceil(60)
+        )));
+    }
+
+    // 解除時刻ちょうどだとまだ弾かれることがあるので 1 秒余分に待つ
+    let wait = wait + Duration::from_secs(1);
+    tracing::warn!(
+        "GitHub API rate limit exceeded, retrying once in {}s when it resets",
+        wait.as_secs()
+    );
+    tokio::time::sleep(wait).await;
+    call().await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[tokio::test]
+    async fn test_retry_on_secondary_rate_limit() {
+        let rate_limited = || {
+            anyhow::anyhow!(
+                "gh command failed: gh: You have exceeded a secondary rate limit. (HTTP 403)"
+            )
+        };
+
+        let calls = std::cell::Cell::new(0);
+        let result = retry_on_secondary_rate_limit(Duration::ZERO, || {
+            calls.set(calls.get() + 1);
+            let n = calls.get();
+            async move {
+                if n < 3 {
+                    Err(rate_limited())
+                } else {
+                    Ok(n)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // Gives up after the retry limit
+        calls.set(0);
+        let result: Result<()> = retry_on_secondary_rate_limit(Duration::ZERO, || {
+            calls.set(calls.get() + 1);
+            async { Err(rate_limited()) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), RATE_LIMIT_MAX_RETRIES + 1);
+
+        // Other failures are returned immediately
+        calls.set(0);
+        let result: Result<()> = retry_on_secondary_rate_limit(Duration::ZERO, || {
+            calls.set(calls.get() + 1);
+            async { Err(anyhow::anyhow!("gh command failed: Not Found (HTTP 404)")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_rate_limit_wait_from_headers() {
+        let response = "HTTP/2.0 403 Forbidden\r\nX-Ratelimit-Limit: 5000\r\n\
+                        X-Ratelimit-Remaining: 0\r\nX-Ratelimit-Reset: 1700000300\r\n\r\n\
+                        {\"message\": \"API rate limit exceeded\"}";
+        assert_eq!(
+            rate_limit_wait(response, 1_700_000_000),
+            Some(Duration::from_secs(300))
+        );
+        // Retry-After takes precedence; a reset in the past means no wait
+        assert_eq!(
+            rate_limit_wait(
+                "HTTP/2.0 429\nretry-after: 30\nX-RateLimit-Remaining: 0\n",
+                0
+            ),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_wait("X-RateLimit-Remaining: 0\nX-RateLimit-Reset: 10\n", 20),
+            Some(Duration::ZERO)
+        );
+        // Not exhausted
+        assert_eq!(
+            rate_limit_wait("X-RateLimit-Remaining: 12\nX-RateLimit-Reset: 10\n", 0),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_primary_rate_limit() {
+        let rate_limited = || {
+            anyhow::anyhow!(
+                "gh command failed: gh: API rate limit exceeded for user ID 1. (HTTP 403)"
+            )
+        };
+
+        // Retries once after the limit resets
+        let calls = std::cell::Cell::new(0);
+        let result = retry_on_primary_rate_limit(
+            || async { Some(Duration::ZERO) },
+            || {
+                calls.set(calls.get() + 1);
+                let n = calls.get();
+                async move {
+                    if n < 2 {
+                        Err(rate_limited())
+                    } else {
+                        Ok(n)
+                    }
+                }
+            },
+        )
+        .await;
+        assert_eq!(result.unwrap(), 2);
+
+        // Only once
+        calls.set(0);
+        let result: Result<()> = retry_on_primary_rate_limit(
+            || async { Some(Duration::ZERO) },
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(rate_limited()) }
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 2);
+
+        // A reset too far away is reported instead of waited for
+        calls.set(0);
+        let result: Result<()> = retry_on_primary_rate_limit(
+            || async { Some(RATE_LIMIT_MAX_WAIT + Duration::from_secs(1)) },
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(rate_limited()) }
+            },
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("resets in 2 min"));
+        assert_eq!(calls.get(), 1);
+
+        // Other failures are returned immediately
+        calls.set(0);
+        let result: Result<()> = retry_on_primary_rate_limit(
+            || async { Some(Duration::ZERO) },
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(anyhow::anyhow!("gh command failed: Not Found (HTTP 404)")) }
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_auth_status_result_classifies_failures() {
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let err = auth_status_result(Err(missing), "github.com").unwrap_err();
+        assert!(matches!(err, GhCliError::NotInstalled));
+        assert!(err.to_string().contains("gh auth login"));
+
+        let err = GhCliError::NotAuthenticated {
+            host: "ghe.example.com".to_string(),
+            detail: "You are not logged into any GitHub hosts.".to_string(),
+        };
+        assert!(err
+            .to_string()
+            .starts_with("gh is not authenticated for ghe.example.com. Run `gh auth login --hostname ghe.example.com`."));
+    }
+
     #[test]
     fn test_flatten_pages_single_page() {
         let pages = vec![json!([1, 2, 3])];
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use super::client::{gh_api_paginate, gh_api_post, FieldValue};
+use super::client::{gh_api, gh_api_paginate, gh_api_post, gh_api_post_json, FieldValue};
 use super::pr::User;
+use crate::app::ReviewAction;
 
 /// ジェネリックなfetch & parse関数（ページネーション対応）
 async fn fetch_and_parse<T: DeserializeOwned>(
@@ -19,6 +22,12 @@ pub struct ReviewComment {
     pub id: u64,
     pub path: String,
     pub line: Option<u32>,
+    /// 複数行コメントの開始行（単一行コメントでは None）
+    #[serde(default)]
+    pub start_line: Option<u32>,
+    /// コメント対象行で終わる diff の抜粋（PR レビュー本文などでは None）
+    #[serde(default)]
+    pub diff_hunk: Option<String>,
     pub body: String,
     pub user: User,
     pub created_at: String,
@@ -62,6 +71,15 @@ pub struct Review {
     pub submitted_at: Option<String>,
 }
 
+impl Review {
+    /// AI Rally が `pending_review` で作成したレビューか（本文の接頭辞で判定する）
+    pub fn is_staged_by_rally(&self) -> bool {
+        self.body
+            .as_deref()
+            .is_some_and(|body| body.starts_with(crate::ai::adapter::REVIEWER_COMMENT_PREFIX))
+    }
+}
+
 pub async fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<Review>> {
     fetch_and_parse(
         &format!("repos/{}/pulls/{}/reviews?per_page=100", repo, pr_number),
@@ -70,6 +88,169 @@ pub async fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<Review>> {
     .await
 }
 
+/// 現在有効な承認の数（ユーザーごとに最新の APPROVED / CHANGES_REQUESTED / DISMISSED で判定）
+///
+/// `reviews` は GitHub が返す時系列順を前提とする。COMMENTED は承認を取り消さない。
+pub fn count_approvals(reviews: &[Review]) -> u32 {
+    latest_decisions(reviews)
+        .values()
+        .filter(|state| **state == "APPROVED")
+        .count() as u32
+}
+
+/// `login` の承認が現在有効か（[`count_approvals`] と同じ判定）
+pub fn has_approved(reviews: &[Review], login: &str) -> bool {
+    latest_decisions(reviews).get(login) == Some(&"APPROVED")
+}
+
+/// ユーザーごとの最新の APPROVED / CHANGES_REQUESTED / DISMISSED
+fn latest_decisions(reviews: &[Review]) -> HashMap<&str, &str> {
+    let mut latest: HashMap<&str, &str> = HashMap::new();
+    for review in reviews {
+        if matches!(
+            review.state.as_str(),
+            "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+        ) {
+            latest.insert(&review.user.login, &review.state);
+        }
+    }
+    latest
+}
+
+/// gh で認証しているユーザーのログイン名（レビューはこのユーザーとして投稿される）
+pub async fn fetch_viewer_login() -> Result<String> {
+    let json = gh_api("user").await?;
+    json["login"]
+        .as_str()
+        .map(String::from)
+        .context("`gh api user` returned no login")
+}
+
+/// An inline comment batched into [`create_review`]
+#[derive(Debug, Clone)]
+pub struct DraftReviewComment {
+    pub path: String,
+    /// patch 内オフセット（`create_review_comment` と同じ position 指定）
+    pub position: u32,
+    pub body: String,
+    /// 複数行コメントの範囲（新しい側の開始行, 終了行）。None なら position の 1 行
+    pub range: Option<(u32, u32)>,
+}
+
+impl DraftReviewComment {
+    /// レビュー API の `comments` の要素。範囲は position では指定できないため、
+    /// 範囲コメントは `start_line`〜`line` の行番号（RIGHT 側）で指定する
+    fn to_payload(&self) -> serde_json::Value {
+        match self.range {
+            Some((start_line, line)) => serde_json::json!({
+                "path": self.path,
+                "body": self.body,
+                "start_line": start_line,
+                "start_side": "RIGHT",
+                "line": line,
+                "side": "RIGHT",
+            }),
+            None => serde_json::json!({
+                "path": self.path,
+                "position": self.position,
+                "body": self.body,
+            }),
+        }
+    }
+}
+
+fn review_event(event: ReviewAction) -> &'static str {
+    match event {
+        ReviewAction::Approve => "APPROVE",
+        ReviewAction::RequestChanges => "REQUEST_CHANGES",
+        ReviewAction::Comment => "COMMENT",
+    }
+}
+
+/// `POST repos/{repo}/pulls/{pr}/reviews` のリクエストボディ
+///
+/// `event` が None なら提出せず pending（下書き）レビューとして作成される
+fn build_review_payload(
+    commit_id: &str,
+    event: Option<ReviewAction>,
+    body: &str,
+    comments: &[DraftReviewComment],
+) -> serde_json::Value {
+    let comments: Vec<serde_json::Value> = comments.iter().map(|c| c.to_payload()).collect();
+    let mut payload = serde_json::json!({
+        "commit_id": commit_id,
+        "body": body,
+        "comments": comments,
+    });
+    if let Some(event) = event {
+        payload["event"] = review_event(event).into();
+    }
+    payload
+}
+
+/// レビュー本文とインラインコメントを 1 回の API 呼び出しでまとめて投稿する
+pub async fn create_review(
+    repo: &str,
+    pr_number: u32,
+    commit_id: &str,
+    event: ReviewAction,
+    body: &str,
+    comments: &[DraftReviewComment],
+) -> Result<Review> {
+    let endpoint = format!("repos/{}/pulls/{}/reviews", repo, pr_number);
+    let payload = build_review_payload(commit_id, Some(event), body, comments);
+    let json = gh_api_post_json(&endpoint, &payload).await?;
+    serde_json::from_value(json).context("Failed to parse created review response")
+}
+
+/// レビュー本文とインラインコメントを提出せずに pending（下書き）レビューとして作成する
+///
+/// 作成者には通知されず、[`submit_pending_review`] で提出するまで本人にしか見えない。
+/// pending レビューは PR ごとに 1 つまで。
+pub async fn create_pending_review(
+    repo: &str,
+    pr_number: u32,
+    commit_id: &str,
+    body: &str,
+    comments: &[DraftReviewComment],
+) -> Result<Review> {
+    let endpoint = format!("repos/{}/pulls/{}/reviews", repo, pr_number);
+    let payload = build_review_payload(commit_id, None, body, comments);
+    let json = gh_api_post_json(&endpoint, &payload).await?;
+    serde_json::from_value(json).context("Failed to parse created review response")
+}
+
+/// 自分の pending レビュー（GitHub は本人の pending レビューだけを返す）
+pub async fn find_pending_review(repo: &str, pr_number: u32) -> Result<Option<Review>> {
+    Ok(fetch_reviews(repo, pr_number)
+        .await?
+        .into_iter()
+        .find(|review| review.state == "PENDING"))
+}
+
+/// pending レビューを `event` で提出する
+pub async fn submit_pending_review(
+    repo: &str,
+    pr_number: u32,
+    review_id: u64,
+    event: ReviewAction,
+    body: &str,
+) -> Result<Review> {
+    let endpoint = format!(
+        "repos/{}/pulls/{}/reviews/{}/events",
+        repo, pr_number, review_id
+    );
+    let json = gh_api_post(
+        &endpoint,
+        &[
+            ("event", FieldValue::String(review_event(event))),
+            ("body", FieldValue::String(body)),
+        ],
+    )
+    .await?;
+    serde_json::from_value(json).context("Failed to parse submitted review response")
+}
+
 pub async fn create_review_comment(
     repo: &str,
     pr_number: u32,
@@ -96,6 +277,17 @@ pub async fn create_review_comment(
     serde_json::from_value(json).context("Failed to parse created comment response")
 }
 
+/// PR の会話タブにコメントを投稿する（ファイル・行に紐付かないフィードバック用）
+pub async fn create_issue_comment(
+    repo: &str,
+    pr_number: u32,
+    body: &str,
+) -> Result<DiscussionComment> {
+    let endpoint = format!("repos/{}/issues/{}/comments", repo, pr_number);
+    let json = gh_api_post(&endpoint, &[("body", FieldValue::String(body))]).await?;
+    serde_json::from_value(json).context("Failed to parse created issue comment response")
+}
+
 pub async fn create_reply_comment(
     repo: &str,
     pr_number: u32,
@@ -109,3 +301,186 @@ pub async fn create_reply_comment(
     let json = gh_api_post(&endpoint, &[("body", FieldValue::String(body))]).await?;
     serde_json::from_value(json).context("Failed to parse reply comment response")
 }
+
+/// コメントに付けるリアクション（GitHub の reactions API の content）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reaction {
+    #[serde(rename = "+1")]
+    ThumbsUp,
+    #[serde(rename = "-1")]
+    ThumbsDown,
+    #[serde(rename = "laugh")]
+    Laugh,
+    #[serde(rename = "confused")]
+    Confused,
+    #[serde(rename = "heart")]
+    Heart,
+    #[serde(rename = "hooray")]
+    Hooray,
+    #[serde(rename = "rocket")]
+    Rocket,
+    #[serde(rename = "eyes")]
+    Eyes,
+}
+
+impl Reaction {
+    pub fn as_api_value(&self) -> &'static str {
+        match self {
+            Self::ThumbsUp => "+1",
+            Self::ThumbsDown => "-1",
+            Self::Laugh => "laugh",
+            Self::Confused => "confused",
+            Self::Heart => "heart",
+            Self::Hooray => "hooray",
+            Self::Rocket => "rocket",
+            Self::Eyes => "eyes",
+        }
+    }
+}
+
+/// レビューコメントにリアクションを付ける（同じリアクションが既にあれば GitHub 側で無視される）
+pub async fn add_comment_reaction(repo: &str, comment_id: u64, reaction: Reaction) -> Result<()> {
+    let endpoint = format!("repos/{}/pulls/comments/{}/reactions", repo, comment_id);
+    gh_api_post(
+        &endpoint,
+        &[("content", FieldValue::String(reaction.as_api_value()))],
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::client::parse_paginated_output;
+    use super::*;
+    use insta::assert_json_snapshot;
+
+    #[test]
+    fn test_is_staged_by_rally() {
+        let review = |body: Option<&str>| -> Review {
+            serde_json::from_value(serde_json::json!({
+                "id": 1,
+                "body": body,
+                "state": "PENDING",
+                "user": {"login": "octocat"},
+                "submitted_at": null
+            }))
+            .unwrap()
+        };
+        assert!(review(Some("[AI Rally - Reviewer]\n\nLooks good")).is_staged_by_rally());
+        assert!(!review(Some("Half-written review")).is_staged_by_rally());
+        assert!(!review(None).is_staged_by_rally());
+    }
+
+    #[test]
+    fn test_review_comments_from_two_pages_keep_order() {
+        let comment = |id: u64| {
+            serde_json::json!({
+                "id": id,
+                "path": "src/lib.rs",
+                "line": id,
+                "body": format!("comment {}", id),
+                "user": { "login": "reviewer" },
+                "created_at": "2024-01-01T00:00:00Z",
+            })
+        };
+        let first_page: Vec<_> = (1..=100).map(comment).collect();
+        let second_page: Vec<_> = (101..=130).map(comment).collect();
+        let output = serde_json::json!([first_page, second_page]).to_string();
+
+        let comments: Vec<ReviewComment> =
+            serde_json::from_value(parse_paginated_output(&output).unwrap()).unwrap();
+        let ids: Vec<u64> = comments.iter().map(|c| c.id).collect();
+        assert_eq!(ids, (1..=130).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_count_approvals_uses_latest_decision_per_user() {
+        let review = |login: &str, state: &str| Review {
+            id: 1,
+            body: None,
+            state: state.to_string(),
+            user: User {
+                login: login.to_string(),
+            },
+            submitted_at: None,
+        };
+        let reviews = [
+            review("alice", "APPROVED"),
+            review("alice", "COMMENTED"),
+            review("bob", "APPROVED"),
+            review("bob", "CHANGES_REQUESTED"),
+            review("carol", "CHANGES_REQUESTED"),
+            review("carol", "APPROVED"),
+        ];
+        assert_eq!(count_approvals(&reviews), 2);
+        assert_eq!(count_approvals(&[]), 0);
+        assert!(has_approved(&reviews, "carol"));
+        assert!(!has_approved(&reviews, "bob"));
+        assert!(!has_approved(&reviews, "dave"));
+    }
+
+    #[test]
+    fn test_build_review_payload() {
+        let comments = vec![
+            DraftReviewComment {
+                path: "src/main.rs".to_string(),
+                position: 3,
+                body: "Handle the error".to_string(),
+                range: None,
+            },
+            DraftReviewComment {
+                path: "src/lib.rs".to_string(),
+                position: 9,
+                body: "Extract this block".to_string(),
+                range: Some((4, 7)),
+            },
+        ];
+        let payload = build_review_payload(
+            "abc123",
+            Some(ReviewAction::RequestChanges),
+            "Summary",
+            &comments,
+        );
+        assert_json_snapshot!(payload, @r#"
+        {
+          "body": "Summary",
+          "comments": [
+            {
+              "body": "Handle the error",
+              "path": "src/main.rs",
+              "position": 3
+            },
+            {
+              "body": "Extract this block",
+              "line": 7,
+              "path": "src/lib.rs",
+              "side": "RIGHT",
+              "start_line": 4,
+              "start_side": "RIGHT"
+            }
+          ],
+          "commit_id": "abc123",
+          "event": "REQUEST_CHANGES"
+        }
+        "#);
+
+        // Without an event the review stays pending
+        let pending = build_review_payload("abc123", None, "Summary", &comments);
+        assert!(pending.get("event").is_none());
+        assert_eq!(pending["comments"], payload["comments"]);
+    }
+
+    #[test]
+    fn test_reaction_matches_api_content() {
+        for reaction in [Reaction::ThumbsUp, Reaction::Hooray, Reaction::Eyes] {
+            assert_eq!(
+                serde_json::to_value(reaction).unwrap(),
+                reaction.as_api_value()
+            );
+        }
+        let parsed: Reaction = serde_json::from_str("\"+1\"").unwrap();
+        assert_eq!(parsed, Reaction::ThumbsUp);
+        assert!(serde_json::from_str::<Reaction>("\"thumbsup\"").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::client::{gh_api, gh_host};
+
+/// Issue（PR 本文から参照されたもの）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    pub number: u32,
+    pub title: String,
+    pub body: Option<String>,
+    /// issues API は PR も返す。PR の場合のみ設定される
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+impl Issue {
+    pub fn is_pull_request(&self) -> bool {
+        self.pull_request.is_some()
+    }
+}
+
+pub async fn fetch_issue(repo: &str, number: u32) -> Result<Issue> {
+    let json = gh_api(&format!("repos/{}/issues/{}", repo, number)).await?;
+    serde_json::from_value(json).context("Failed to parse issue response")
+}
+
+/// PR 本文から同一リポジトリの Issue 参照を重複なしで抽出する（`#123` 形式を出現順に、
+/// 続いて URL 形式を出現順に並べる）
+///
+/// `#123`（"fixes #123" など）と `https://{host}/{repo}/issues/123` を対象とする。
+/// `owner/other#1` のような他リポジトリ参照や、`abc#1` のような語中の `#` は無視する。
+pub fn extract_issue_references(repo: &str, body: &str) -> Vec<u32> {
+    let url_prefix = format!("{}/{}/issues/", gh_host(), repo);
+    let mut numbers = Vec::new();
+
+    for (i, _) in body.match_indices('#') {
+        let preceded_by_word = body[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '/' | '&' | '_' | '-'));
+        if !preceded_by_word {
+            push_number(&mut numbers, &body[i + 1..]);
+        }
+    }
+    for (i, _) in body.match_indices(&url_prefix) {
+        push_number(&mut numbers, &body[i + url_prefix.len()..]);
+    }
+
+    numbers
+}
+
+/// `rest` 先頭の数字列を Issue 番号として追加する（語の途中で終わるものは除外）
+fn push_number(numbers: &mut Vec<u32>, rest: &str) {
+    let digits_len = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    if digits_len == 0 || rest[digits_len..].starts_with(|c: char| c.is_alphanumeric()) {
+        return;
+    }
+    if let Ok(number) = rest[..digits_len].parse::<u32>() {
+        if number > 0 && !numbers.contains(&number) {
+            numbers.push(number);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_issue_references() {
+        let body = "Fixes #123 and closes #45.\n\
+                    See https://github.com/owner/repo/issues/7 and (#123) again.";
+        assert_eq!(
+            extract_issue_references("owner/repo", body),
+            vec![123, 45, 7]
+        );
+    }
+
+    #[test]
+    fn test_issue_is_pull_request() {
+        let issue: Issue =
+            serde_json::from_str(r#"{"number": 1, "title": "Bug", "body": null}"#).unwrap();
+        assert!(!issue.is_pull_request());
+
+        let pr: Issue = serde_json::from_str(
+            r#"{"number": 2, "title": "Fix", "body": null, "pull_request": {"url": "u"}}"#,
+        )
+        .unwrap();
+        assert!(pr.is_pull_request());
+    }
+
+    #[test]
+    fn test_extract_issue_references_ignores_non_references() {
+        let body = "Heading\n## Notes\ncolor #fff, a#1, other/repo#2, \
+                    https://github.com/other/repo/issues/3, #0, #12abc";
+        assert!(extract_issue_references("owner/repo", body).is_empty());
+    }
+}
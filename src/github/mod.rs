@@ -1,12 +1,25 @@
 mod client;
 pub mod comment;
+mod issue;
 mod pr;
+mod provider;
+mod repo;
+mod thread;
 
 // Explicit re-exports - only export what is actually used
-pub use client::{detect_repo, gh_command, DetectRepoError};
-pub use comment::{create_reply_comment, create_review_comment};
+pub use client::{check_gh_cli, detect_repo, gh_command, set_gh_host, DetectRepoError, GhCliError};
+pub use comment::{
+    add_comment_reaction, count_approvals, create_issue_comment, create_pending_review,
+    create_reply_comment, create_review, fetch_reviews, fetch_viewer_login, find_pending_review,
+    has_approved, DraftReviewComment, Reaction,
+};
+pub use issue::{extract_issue_references, fetch_issue};
 pub use pr::{
-    fetch_changed_files, fetch_pr, fetch_pr_diff, fetch_pr_list, fetch_pr_list_with_offset,
-    submit_review, Branch, ChangedFile, Label, PrListPage, PrStateFilter, PullRequest,
-    PullRequestSummary, User,
+    checkout_pr, clone_repo, fetch_branch_protection, fetch_changed_files, fetch_checks_passed,
+    fetch_compare_diff, fetch_mergeable_state, fetch_pr, fetch_pr_diff, fetch_unpassed_checks,
+    merge_pr, submit_review, Branch, BranchProtection, ChangedFile, ChecksSummary, Label,
+    MergeMethod, PrListPage, PrStateFilter, PullRequest, PullRequestSummary, User,
 };
+pub use provider::{GithubProvider, VcsProvider};
+pub use repo::RepoSpec;
+pub use thread::{fetch_review_threads, resolve_review_thread, ReviewThread};
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
-use super::client::{gh_api, gh_api_paginate, gh_command};
+use super::client::{gh_api, gh_api_paginate, gh_command, gh_command_in_dir};
 use crate::app::ReviewAction;
 
 /// PR状態フィルタ（型安全）
@@ -67,6 +67,8 @@ pub struct PullRequest {
     pub base: Branch,
     pub user: User,
     pub updated_at: String,
+    #[serde(default)]
+    pub labels: Vec<Label>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +92,53 @@ pub struct ChangedFile {
     pub patch: Option<String>,
 }
 
+/// サブモジュールのポインタ更新（`Subproject commit` 行のみの patch）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmoduleChange {
+    /// 変更前のコミット（サブモジュール追加時は None）
+    pub old: Option<String>,
+    /// 変更後のコミット（サブモジュール削除時は None）
+    pub new: Option<String>,
+}
+
+impl SubmoduleChange {
+    /// 表示用ラベル（例: "submodule: 1a2b3c4→5d6e7f8"）
+    pub fn label(&self) -> String {
+        let short = |sha: &Option<String>| match sha {
+            Some(sha) => sha.chars().take(7).collect(),
+            None => "(none)".to_string(),
+        };
+        format!("submodule: {}→{}", short(&self.old), short(&self.new))
+    }
+}
+
+impl ChangedFile {
+    /// GitHub はサブモジュールの変更を `Subproject commit <sha>` だけの patch で返す。
+    /// そうした patch ならサブモジュール変更として旧/新コミットを返す。
+    pub fn submodule_change(&self) -> Option<SubmoduleChange> {
+        let patch = self.patch.as_deref()?;
+        let mut change = SubmoduleChange {
+            old: None,
+            new: None,
+        };
+
+        for line in patch.lines() {
+            if line.starts_with("@@") || line.starts_with("\\") {
+                continue;
+            }
+            if let Some(sha) = line.strip_prefix("-Subproject commit ") {
+                change.old = Some(sha.trim().to_string());
+            } else if let Some(sha) = line.strip_prefix("+Subproject commit ") {
+                change.new = Some(sha.trim().to_string());
+            } else if !line.is_empty() {
+                return None;
+            }
+        }
+
+        (change.old.is_some() || change.new.is_some()).then_some(change)
+    }
+}
+
 pub async fn fetch_pr(repo: &str, pr_number: u32) -> Result<PullRequest> {
     let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
     let json = gh_api(&endpoint).await?;
@@ -129,11 +178,339 @@ pub async fn submit_review(
     Ok(())
 }
 
+/// Merge method for `PUT repos/{repo}/pulls/{pr}/merge`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_api_value(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+/// Build `gh api` args for the merge endpoint.
+///
+/// `sha` pins the merge to the reviewed head commit: GitHub rejects the merge (409)
+/// if the branch moved after the review.
+fn build_merge_args(repo: &str, pr_number: u32, method: MergeMethod, sha: &str) -> Vec<String> {
+    vec![
+        "api".to_string(),
+        "--method".to_string(),
+        "PUT".to_string(),
+        format!("repos/{}/pulls/{}/merge", repo, pr_number),
+        "-f".to_string(),
+        format!("merge_method={}", method.as_api_value()),
+        "-f".to_string(),
+        format!("sha={}", sha),
+    ]
+}
+
+/// Turn a failed merge response into a readable reason
+fn describe_merge_error(message: &str) -> String {
+    if message.contains("HTTP 405") {
+        format!(
+            "PR is not mergeable (merge conflict or blocked by branch protection): {}",
+            message
+        )
+    } else if message.contains("HTTP 409") {
+        format!(
+            "PR head changed since the review, merge refused: {}",
+            message
+        )
+    } else {
+        message.to_string()
+    }
+}
+
+/// Merge a PR via the REST merge endpoint
+pub async fn merge_pr(repo: &str, pr_number: u32, method: MergeMethod, sha: &str) -> Result<()> {
+    let args = build_merge_args(repo, pr_number, method, sha);
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    gh_command(&args_refs)
+        .await
+        .map_err(|e| anyhow::anyhow!(describe_merge_error(&format!("{:#}", e))))?;
+    Ok(())
+}
+
+/// Clone `repo` into `dir` (which must not exist yet)
+pub async fn clone_repo(repo: &str, dir: &str) -> Result<()> {
+    gh_command(&["repo", "clone", repo, dir]).await?;
+    Ok(())
+}
+
+/// Check out the PR's head branch in the git repository at `dir`
+pub async fn checkout_pr(repo: &str, pr_number: u32, dir: &str) -> Result<()> {
+    let number = pr_number.to_string();
+    gh_command_in_dir(dir, &["pr", "checkout", &number, "--repo", repo]).await?;
+    Ok(())
+}
+
+/// Fetch whether all checks (check runs and commit statuses) on `sha` passed.
+///
+/// A commit with no checks only counts as passing when `base_branch` has no required
+/// checks; otherwise the required ones may simply not have reported yet.
+pub async fn fetch_checks_passed(repo: &str, sha: &str, base_branch: &str) -> Result<bool> {
+    let checks = fetch_commit_checks(repo, sha).await?;
+    if !checks.is_empty() {
+        return Ok(checks_passed(&checks));
+    }
+    let protection = fetch_branch_protection(repo, base_branch).await?;
+    Ok(protection.is_none_or(|p| p.required_checks.is_empty()))
+}
+
+/// コミットのチェック 1 件（チェックランとコミットステータスを同じ形にしたもの）
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CommitCheck {
+    name: String,
+    state: CheckState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckState {
+    Passed,
+    Failed,
+    /// キュー待ち・実行中
+    Pending,
+}
+
+const CHECKS_PER_PAGE: usize = 100;
+
+/// `sha` のチェックランとコミットステータスをすべて取得する
+async fn fetch_commit_checks(repo: &str, sha: &str) -> Result<Vec<CommitCheck>> {
+    let mut checks = Vec::new();
+    // check-runs はページがオブジェクトなので gh_api_paginate が使えず、自前でページをたどる
+    for page in 1.. {
+        let endpoint = format!(
+            "repos/{}/commits/{}/check-runs?per_page={}&page={}",
+            repo, sha, CHECKS_PER_PAGE, page
+        );
+        let runs = parse_check_runs(&gh_api(&endpoint).await?);
+        let last_page = runs.len() < CHECKS_PER_PAGE;
+        checks.extend(runs);
+        if last_page {
+            break;
+        }
+    }
+    // 外部 CI の多くはチェックランではなくコミットステータスで報告する
+    let endpoint = format!(
+        "repos/{}/commits/{}/status?per_page={}",
+        repo, sha, CHECKS_PER_PAGE
+    );
+    checks.extend(parse_commit_statuses(&gh_api(&endpoint).await?));
+    Ok(checks)
+}
+
+fn parse_check_runs(json: &serde_json::Value) -> Vec<CommitCheck> {
+    json["check_runs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|run| CommitCheck {
+            name: run["name"].as_str().unwrap_or_default().to_string(),
+            state: if run["status"] != "completed" {
+                CheckState::Pending
+            } else if check_run_passed(run) {
+                CheckState::Passed
+            } else {
+                CheckState::Failed
+            },
+        })
+        .collect()
+}
+
+/// 複合ステータス（`/commits/{sha}/status`）の各コンテキスト
+fn parse_commit_statuses(json: &serde_json::Value) -> Vec<CommitCheck> {
+    json["statuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|status| CommitCheck {
+            name: status["context"].as_str().unwrap_or_default().to_string(),
+            state: match status["state"].as_str() {
+                Some("success") => CheckState::Passed,
+                Some("pending") => CheckState::Pending,
+                _ => CheckState::Failed,
+            },
+        })
+        .collect()
+}
+
+fn checks_passed(checks: &[CommitCheck]) -> bool {
+    checks.iter().all(|c| c.state == CheckState::Passed)
+}
+
+/// コミットのチェックランの集計（ファイル一覧ヘッダの CI 表示用）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChecksSummary {
+    pub passed: usize,
+    pub failed: usize,
+    /// キュー待ち・実行中
+    pub pending: usize,
+}
+
+/// `sha` のチェック（チェックランとコミットステータス）を成功・失敗・実行中に集計して取得する
+pub async fn fetch_checks_summary(repo: &str, sha: &str) -> Result<ChecksSummary> {
+    Ok(summarize_checks(&fetch_commit_checks(repo, sha).await?))
+}
+
+fn summarize_checks(checks: &[CommitCheck]) -> ChecksSummary {
+    let mut summary = ChecksSummary::default();
+    for check in checks {
+        match check.state {
+            CheckState::Passed => summary.passed += 1,
+            CheckState::Failed => summary.failed += 1,
+            CheckState::Pending => summary.pending += 1,
+        }
+    }
+    summary
+}
+
+fn check_run_passed(run: &serde_json::Value) -> bool {
+    run["status"] == "completed"
+        && matches!(
+            run["conclusion"].as_str(),
+            Some("success" | "neutral" | "skipped")
+        )
+}
+
+/// `required` のうち `sha` で成功していないチェック（未実行・実行中・失敗）
+pub async fn fetch_unpassed_checks(
+    repo: &str,
+    sha: &str,
+    required: &[String],
+) -> Result<Vec<String>> {
+    if required.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(unpassed_checks(
+        &fetch_commit_checks(repo, sha).await?,
+        required,
+    ))
+}
+
+fn unpassed_checks(checks: &[CommitCheck], required: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|name| {
+            !checks
+                .iter()
+                .any(|check| check.name == **name && check.state == CheckState::Passed)
+        })
+        .cloned()
+        .collect()
+}
+
+/// ブランチ保護のうち、マージ可否に関わる設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchProtection {
+    /// 必須ステータスチェック名
+    pub required_checks: Vec<String>,
+    /// マージに必要な承認数
+    pub required_approvals: u32,
+}
+
+/// `branch` の保護設定。保護されていなければ None
+///
+/// 保護設定の参照には管理者権限が必要で、権限がなければエラーになる。
+pub async fn fetch_branch_protection(repo: &str, branch: &str) -> Result<Option<BranchProtection>> {
+    let endpoint = format!(
+        "repos/{}/branches/{}/protection",
+        repo,
+        encode_path_segment(branch)
+    );
+    match gh_api(&endpoint).await {
+        Ok(json) => Ok(Some(parse_branch_protection(&json))),
+        Err(e) if format!("{:#}", e).contains("Branch not protected") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// URL のパス 1 要素としてエンコードする（ブランチ名の `/` や `#` もエスケープされる）
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn parse_branch_protection(json: &serde_json::Value) -> BranchProtection {
+    let status_checks = &json["required_status_checks"];
+    let mut required_checks: Vec<String> = status_checks["checks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|check| check["context"].as_str().map(String::from))
+        .collect();
+    // 古い設定では contexts にしか入っていない
+    for context in status_checks["contexts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.as_str())
+    {
+        if !required_checks.iter().any(|c| c == context) {
+            required_checks.push(context.to_string());
+        }
+    }
+    BranchProtection {
+        required_checks,
+        required_approvals: json["required_pull_request_reviews"]["required_approving_review_count"]
+            .as_u64()
+            .unwrap_or(0) as u32,
+    }
+}
+
+/// PR の `mergeable_state`（"clean", "blocked", "behind", "dirty", "unstable", "unknown" など）
+pub async fn fetch_mergeable_state(repo: &str, pr_number: u32) -> Result<String> {
+    let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
+    let state = gh_command(&["api", &endpoint, "--jq", ".mergeable_state"]).await?;
+    Ok(state.trim().to_string())
+}
+
 /// Fetch the raw diff for a PR using `gh pr diff`
 pub async fn fetch_pr_diff(repo: &str, pr_number: u32) -> Result<String> {
     gh_command(&["pr", "diff", &pr_number.to_string(), "-R", repo]).await
 }
 
+/// `base_sha` から `head_sha` までの差分（GitHub compare API）
+///
+/// force-push などで `base_sha` が `head_sha` の祖先でなくなっている場合は `None` を返す。
+/// `base_sha` 自体が存在しない場合はエラーになる。
+pub async fn fetch_compare_diff(
+    repo: &str,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<Option<String>> {
+    let endpoint = format!("repos/{}/compare/{}...{}", repo, base_sha, head_sha);
+    let status = gh_command(&["api", &endpoint, "--jq", ".status"]).await?;
+    if status.trim() != "ahead" {
+        return Ok(None);
+    }
+    let diff = gh_command(&[
+        "api",
+        "-H",
+        "Accept: application/vnd.github.diff",
+        &endpoint,
+    ])
+    .await?;
+    Ok(Some(diff))
+}
+
 /// ページネーション結果
 pub struct PrListPage {
     pub items: Vec<PullRequestSummary>,
@@ -207,6 +584,43 @@ pub async fn fetch_pr_list_with_offset(
 mod tests {
     use super::*;
 
+    fn changed_file(patch: &str) -> ChangedFile {
+        ChangedFile {
+            filename: "vendor/lib".to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: Some(patch.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_submodule_change_detected_and_labeled() {
+        let file = changed_file(
+            "@@ -1 +1 @@\n-Subproject commit 1a2b3c4d5e6f7a8b9c0d\n+Subproject commit 5d6e7f8a9b0c1d2e3f4a",
+        );
+        let change = file.submodule_change().unwrap();
+        assert_eq!(change.old.as_deref(), Some("1a2b3c4d5e6f7a8b9c0d"));
+        assert_eq!(change.new.as_deref(), Some("5d6e7f8a9b0c1d2e3f4a"));
+        assert_eq!(change.label(), "submodule: 1a2b3c4→5d6e7f8");
+
+        let added = changed_file("@@ -0,0 +1 @@\n+Subproject commit 5d6e7f8a9b0c");
+        assert_eq!(
+            added.submodule_change().unwrap().label(),
+            "submodule: (none)→5d6e7f8"
+        );
+    }
+
+    #[test]
+    fn test_submodule_change_ignores_regular_files() {
+        let file = changed_file("@@ -1,2 +1,2 @@\n fn main() {\n-    old();\n+    new();");
+        assert!(file.submodule_change().is_none());
+
+        let mut no_patch = changed_file("");
+        no_patch.patch = None;
+        assert!(no_patch.submodule_change().is_none());
+    }
+
     #[test]
     fn test_pr_state_filter_as_gh_arg() {
         assert_eq!(PrStateFilter::Open.as_gh_arg(), "open");
@@ -221,6 +635,173 @@ mod tests {
         assert_eq!(PrStateFilter::All.display_name(), "all");
     }
 
+    #[test]
+    fn test_build_merge_args() {
+        let args = build_merge_args("owner/repo", 42, MergeMethod::Squash, "abc123");
+        assert_eq!(
+            args,
+            vec![
+                "api",
+                "--method",
+                "PUT",
+                "repos/owner/repo/pulls/42/merge",
+                "-f",
+                "merge_method=squash",
+                "-f",
+                "sha=abc123",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_merge_error() {
+        assert!(describe_merge_error(
+            "gh command failed: Pull Request is not mergeable (HTTP 405)"
+        )
+        .starts_with("PR is not mergeable"));
+        assert!(
+            describe_merge_error("gh command failed: Head branch was modified (HTTP 409)")
+                .starts_with("PR head changed")
+        );
+        assert_eq!(describe_merge_error("network error"), "network error");
+    }
+
+    #[test]
+    fn test_summarize_checks() {
+        let json = serde_json::json!({"check_runs": [
+            {"name": "build", "status": "completed", "conclusion": "success"},
+            {"name": "lint", "status": "completed", "conclusion": "skipped"},
+            {"name": "test", "status": "completed", "conclusion": "failure"},
+            {"name": "e2e", "status": "in_progress", "conclusion": null},
+            {"name": "deploy", "status": "queued", "conclusion": null}
+        ]});
+        let statuses = serde_json::json!({"statuses": [
+            {"context": "ci/jenkins", "state": "failure"}
+        ]});
+        let mut checks = parse_check_runs(&json);
+        checks.extend(parse_commit_statuses(&statuses));
+        assert_eq!(
+            summarize_checks(&checks),
+            ChecksSummary {
+                passed: 2,
+                failed: 2,
+                pending: 2
+            }
+        );
+        assert_eq!(summarize_checks(&[]), ChecksSummary::default());
+    }
+
+    #[test]
+    fn test_parse_commit_checks() {
+        let runs = serde_json::json!({"check_runs": [
+            {"name": "build", "status": "completed", "conclusion": "success"},
+            {"name": "lint", "status": "completed", "conclusion": "skipped"},
+            {"name": "test", "status": "completed", "conclusion": "failure"},
+            {"name": "e2e", "status": "in_progress", "conclusion": null}
+        ]});
+        let states: Vec<CheckState> = parse_check_runs(&runs).iter().map(|c| c.state).collect();
+        assert_eq!(
+            states,
+            [
+                CheckState::Passed,
+                CheckState::Passed,
+                CheckState::Failed,
+                CheckState::Pending
+            ]
+        );
+
+        let statuses = serde_json::json!({"state": "pending", "statuses": [
+            {"context": "ci/jenkins", "state": "success"},
+            {"context": "ci/deploy", "state": "pending"},
+            {"context": "ci/coverage", "state": "error"}
+        ]});
+        assert_eq!(
+            parse_commit_statuses(&statuses),
+            vec![
+                CommitCheck {
+                    name: "ci/jenkins".to_string(),
+                    state: CheckState::Passed
+                },
+                CommitCheck {
+                    name: "ci/deploy".to_string(),
+                    state: CheckState::Pending
+                },
+                CommitCheck {
+                    name: "ci/coverage".to_string(),
+                    state: CheckState::Failed
+                },
+            ]
+        );
+        assert!(parse_check_runs(&serde_json::json!({"message": "Not Found"})).is_empty());
+    }
+
+    #[test]
+    fn test_checks_passed() {
+        let check = |state| CommitCheck {
+            name: "ci".to_string(),
+            state,
+        };
+        assert!(checks_passed(&[
+            check(CheckState::Passed),
+            check(CheckState::Passed)
+        ]));
+        assert!(!checks_passed(&[
+            check(CheckState::Passed),
+            check(CheckState::Pending)
+        ]));
+        assert!(!checks_passed(&[check(CheckState::Failed)]));
+    }
+
+    #[test]
+    fn test_parse_branch_protection() {
+        let json = serde_json::json!({
+            "required_status_checks": {
+                "strict": true,
+                "contexts": ["ci/test", "legacy"],
+                "checks": [{"context": "ci/test", "app_id": 1}]
+            },
+            "required_pull_request_reviews": {"required_approving_review_count": 2}
+        });
+        assert_eq!(
+            parse_branch_protection(&json),
+            BranchProtection {
+                required_checks: vec!["ci/test".to_string(), "legacy".to_string()],
+                required_approvals: 2,
+            }
+        );
+        assert_eq!(
+            parse_branch_protection(&serde_json::json!({})),
+            BranchProtection::default()
+        );
+    }
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(encode_path_segment("main"), "main");
+        assert_eq!(encode_path_segment("release/1.x"), "release%2F1.x");
+        assert_eq!(encode_path_segment("fix#12 ü"), "fix%2312%20%C3%BC");
+    }
+
+    #[test]
+    fn test_unpassed_checks() {
+        let json = serde_json::json!({"check_runs": [
+            {"name": "ci/test", "status": "completed", "conclusion": "success"},
+            {"name": "ci/lint", "status": "completed", "conclusion": "failure"},
+            {"name": "optional", "status": "in_progress", "conclusion": null}
+        ]});
+        // Required checks reported as commit statuses count too
+        let statuses = serde_json::json!({"statuses": [
+            {"context": "legacy", "state": "success"}
+        ]});
+        let mut checks = parse_check_runs(&json);
+        checks.extend(parse_commit_statuses(&statuses));
+        let required = ["ci/test", "ci/lint", "ci/missing", "legacy"].map(String::from);
+        assert_eq!(
+            unpassed_checks(&checks, &required),
+            vec!["ci/lint", "ci/missing"]
+        );
+    }
+
     #[test]
     fn test_pr_state_filter_next_cycle() {
         assert_eq!(PrStateFilter::Open.next(), PrStateFilter::Closed);
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::comment::{self, DiscussionComment, Review, ReviewComment};
+use super::pr::{self, ChangedFile, ChecksSummary, PrListPage, PrStateFilter, PullRequest};
+use crate::app::ReviewAction;
+
+/// PR のホスティングサービスへのアクセスを抽象化するトレイト
+///
+/// TUI からの読み書きはすべてこのトレイト経由で行う。GitHub 以外のサービス
+/// （GitLab など）に対応する場合はこのトレイトを実装する。
+#[async_trait]
+pub trait VcsProvider: Send + Sync {
+    /// PR の基本情報を取得する
+    async fn fetch_pr(&self, repo: &str, pr_number: u32) -> Result<PullRequest>;
+
+    /// PR の変更ファイル一覧（patch 付き）を取得する
+    async fn fetch_files(&self, repo: &str, pr_number: u32) -> Result<Vec<ChangedFile>>;
+
+    /// PR 全体の unified diff を取得する（patch が欠けたファイルのフォールバック用）
+    async fn fetch_diff(&self, repo: &str, pr_number: u32) -> Result<String>;
+
+    /// PR 一覧を `offset` 件目から最大 `limit` 件取得する
+    async fn fetch_pr_list(
+        &self,
+        repo: &str,
+        state: PrStateFilter,
+        offset: u32,
+        limit: u32,
+    ) -> Result<PrListPage>;
+
+    /// インラインのレビューコメントを取得する
+    async fn fetch_review_comments(&self, repo: &str, pr_number: u32)
+        -> Result<Vec<ReviewComment>>;
+
+    /// コミット `sha` の CI チェックの集計を取得する
+    async fn fetch_checks(&self, repo: &str, sha: &str) -> Result<ChecksSummary>;
+
+    /// 提出済みレビュー（本文付き）を取得する
+    async fn fetch_reviews(&self, repo: &str, pr_number: u32) -> Result<Vec<Review>>;
+
+    /// PR の会話（ディスカッション）コメントを取得する
+    async fn fetch_discussion_comments(
+        &self,
+        repo: &str,
+        pr_number: u32,
+    ) -> Result<Vec<DiscussionComment>>;
+
+    /// diff 上の位置（patch 内オフセット）にインラインコメントを投稿する
+    async fn create_review_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        commit_id: &str,
+        path: &str,
+        position: u32,
+        body: &str,
+    ) -> Result<ReviewComment>;
+
+    /// レビューコメントに返信する
+    async fn create_reply_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment>;
+
+    /// レビューを提出する（自分の pending レビューがあればそれを提出する）
+    async fn submit_review(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        action: ReviewAction,
+        body: &str,
+    ) -> Result<()>;
+}
+
+/// `gh` CLI 経由の GitHub 実装
+pub struct GithubProvider;
+
+#[async_trait]
+impl VcsProvider for GithubProvider {
+    async fn fetch_pr(&self, repo: &str, pr_number: u32) -> Result<PullRequest> {
+        pr::fetch_pr(repo, pr_number).await
+    }
+
+    async fn fetch_files(&self, repo: &str, pr_number: u32) -> Result<Vec<ChangedFile>> {
+        pr::fetch_changed_files(repo, pr_number).await
+    }
+
+    async fn fetch_diff(&self, repo: &str, pr_number: u32) -> Result<String> {
+        pr::fetch_pr_diff(repo, pr_number).await
+    }
+
+    async fn fetch_pr_list(
+        &self,
+        repo: &str,
+        state: PrStateFilter,
+        offset: u32,
+        limit: u32,
+    ) -> Result<PrListPage> {
+        if offset == 0 {
+            pr::fetch_pr_list(repo, state, limit).await
+        } else {
+            pr::fetch_pr_list_with_offset(repo, state, offset, limit).await
+        }
+    }
+
+    async fn fetch_review_comments(
+        &self,
+        repo: &str,
+        pr_number: u32,
+    ) -> Result<Vec<ReviewComment>> {
+        comment::fetch_review_comments(repo, pr_number).await
+    }
+
+    async fn fetch_checks(&self, repo: &str, sha: &str) -> Result<ChecksSummary> {
+        pr::fetch_checks_summary(repo, sha).await
+    }
+
+    async fn fetch_reviews(&self, repo: &str, pr_number: u32) -> Result<Vec<Review>> {
+        comment::fetch_reviews(repo, pr_number).await
+    }
+
+    async fn fetch_discussion_comments(
+        &self,
+        repo: &str,
+        pr_number: u32,
+    ) -> Result<Vec<DiscussionComment>> {
+        comment::fetch_discussion_comments(repo, pr_number).await
+    }
+
+    async fn create_review_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        commit_id: &str,
+        path: &str,
+        position: u32,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        comment::create_review_comment(repo, pr_number, commit_id, path, position, body).await
+    }
+
+    async fn create_reply_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        comment::create_reply_comment(repo, pr_number, comment_id, body).await
+    }
+
+    async fn submit_review(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        action: ReviewAction,
+        body: &str,
+    ) -> Result<()> {
+        // pending レビューがあるまま新しいレビューは作れないため、AI Rally が作成したものなら
+        // それを提出する。入力した本文は下書きの本文の前に付ける
+        let Some(pending) = comment::find_pending_review(repo, pr_number).await? else {
+            return pr::submit_review(repo, pr_number, action, body).await;
+        };
+        // ユーザーが GitHub 上で書きかけているレビューを黙って提出しない
+        if !pending.is_staged_by_rally() {
+            anyhow::bail!(
+                "You have a pending review on this PR that AI Rally did not stage; \
+                 submit or discard it on GitHub first"
+            );
+        }
+        let body = match pending.body.as_deref().map(str::trim) {
+            Some(staged) if !staged.is_empty() => format!("{}\n\n{}", body, staged),
+            _ => body.to_string(),
+        };
+        comment::submit_pending_review(repo, pr_number, pending.id, action, &body).await?;
+        Ok(())
+    }
+}
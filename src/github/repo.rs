@@ -0,0 +1,215 @@
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RepoSpecError {
+    #[error(
+        "invalid repository \"{0}\": expected owner/repo (e.g., octocat/hello-world), host/owner/repo or a URL like https://github.com/owner/repo"
+    )]
+    InvalidFormat(String),
+}
+
+/// `--repo` で指定されたリポジトリ（`owner/repo` 形式に正規化済み）
+///
+/// `owner/repo` のほか、gh と同じ `host/owner/repo`、`https://github.com/owner/repo`
+/// （.git や /pull/123 などの続きも可）、`git@github.com:owner/repo.git`、
+/// `ssh://git@github.com/owner/repo.git` 形式も受け付ける。ホストが書かれていれば保持する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSpec {
+    name: String,
+    host: Option<String>,
+}
+
+impl RepoSpec {
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// 指定に含まれていたホスト（`owner/repo` 形式なら None）
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+}
+
+impl fmt::Display for RepoSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl FromStr for RepoSpec {
+    type Err = RepoSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RepoSpecError::InvalidFormat(s.to_string());
+        let input = s.trim();
+
+        // URL 形式はホスト以降のパスから先頭 2 セグメントを取り出す
+        let (host, path, is_url) = if let Some(rest) = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+        {
+            let (host, path) = rest.split_once('/').ok_or_else(invalid)?;
+            (Some(host), path, true)
+        } else if let Some(rest) = input.strip_prefix("ssh://") {
+            let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+            // ssh のユーザー名とポートは GitHub のホスト名ではない
+            let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+            let host = host.split_once(':').map_or(host, |(h, _)| h);
+            (Some(host), path, true)
+        } else if let Some((user_host, path)) = input
+            .split_once(':')
+            .filter(|(user_host, _)| user_host.contains('@'))
+        {
+            let host = user_host.rsplit_once('@').map_or(user_host, |(_, h)| h);
+            (Some(host), path, true)
+        } else {
+            // gh と同じ host/owner/repo（ホストにはドットが含まれる）
+            match input.split_once('/') {
+                Some((host, path)) if host.contains('.') && path.contains('/') => {
+                    (Some(host), path, false)
+                }
+                _ => (None, input, false),
+            }
+        };
+        if host.is_some_and(|h| !is_valid_host(h)) {
+            return Err(invalid());
+        }
+
+        let mut segments = path.trim_end_matches('/').split('/');
+        let owner = segments.next().unwrap_or_default();
+        let name = segments.next().unwrap_or_default();
+        // owner/repo 形式では余分なセグメントを許さない（URL なら /pull/123 などが続いてよい）
+        if !is_url && segments.next().is_some() {
+            return Err(invalid());
+        }
+        let name = name.strip_suffix(".git").unwrap_or(name);
+
+        if !is_valid_segment(owner) || !is_valid_segment(name) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            name: format!("{}/{}", owner, name),
+            host: host.map(|h| h.to_ascii_lowercase()),
+        })
+    }
+}
+
+/// ホスト名（ポート付きも可）として使える文字だけか
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':'))
+}
+
+/// GitHub のユーザー名・リポジトリ名に使える文字だけか
+fn is_valid_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<String, RepoSpecError> {
+        s.parse::<RepoSpec>().map(|r| r.to_string())
+    }
+
+    fn host(s: &str) -> Option<String> {
+        s.parse::<RepoSpec>().unwrap().host().map(String::from)
+    }
+
+    #[test]
+    fn test_parse_owner_repo() {
+        assert_eq!(parse("octocat/hello-world").unwrap(), "octocat/hello-world");
+        assert_eq!(parse(" owner/repo.rs ").unwrap(), "owner/repo.rs");
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        assert_eq!(
+            parse("https://github.com/owner/repo").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("https://github.com/owner/repo.git").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("https://github.com/owner/repo/pull/123/files").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("https://ghe.example.com/owner/repo/").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("git@github.com:owner/repo.git").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("ssh://git@ghe.example.com:2222/owner/repo.git").unwrap(),
+            "owner/repo"
+        );
+        assert_eq!(
+            parse("org-12345@ghe.example.com:owner/repo.git").unwrap(),
+            "owner/repo"
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_host() {
+        assert_eq!(host("owner/repo"), None);
+        assert_eq!(parse("ghe.example.com/owner/repo").unwrap(), "owner/repo");
+        assert_eq!(
+            host("ghe.example.com/owner/repo").as_deref(),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            host("https://GHE.example.com/owner/repo/pull/1").as_deref(),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            host("git@github.com:owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            host("ssh://git@ghe.example.com:2222/owner/repo.git").as_deref(),
+            Some("ghe.example.com")
+        );
+    }
+
+    #[test]
+    fn test_reject_malformed() {
+        for input in [
+            "owner-repo",
+            "owner/",
+            "/repo",
+            "owner/repo/extra",
+            "ghe.example.com/owner/repo/extra",
+            "https://bad host/owner/repo",
+            "owner/re po",
+            "https://github.com/owner",
+            "https://github.com",
+            "",
+        ] {
+            assert_eq!(
+                parse(input),
+                Err(RepoSpecError::InvalidFormat(input.to_string())),
+                "{input:?} should be rejected"
+            );
+        }
+        assert!(parse("owner-repo")
+            .unwrap_err()
+            .to_string()
+            .contains("expected owner/repo"));
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::client::{gh_api_graphql, FieldValue};
+
+const REVIEW_THREADS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id
+          isResolved
+          path
+          line
+          startLine
+          comments(first: 1) { nodes { databaseId body } }
+        }
+      }
+    }
+  }
+}";
+
+const RESOLVE_REVIEW_THREAD_MUTATION: &str = "mutation($threadId: ID!) {
+  resolveReviewThread(input: { threadId: $threadId }) { thread { id } }
+}";
+
+/// PR のレビュースレッド（インラインコメントの会話単位）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewThread {
+    /// GraphQL のノード ID（resolveReviewThread に渡す）
+    pub id: String,
+    pub path: String,
+    pub is_resolved: bool,
+    /// スレッド最初のコメント本文
+    pub body: String,
+    /// スレッド最初のコメントの REST API 上の ID（リアクション用）
+    pub comment_id: Option<u64>,
+    /// 現在の diff 上でコメントが付いている行（複数行コメントは最終行）。古くなったスレッドは None
+    pub line: Option<u32>,
+    /// 複数行コメントの最初の行
+    pub start_line: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadNode {
+    id: String,
+    is_resolved: bool,
+    path: String,
+    line: Option<u32>,
+    start_line: Option<u32>,
+    comments: Connection<CommentNode>,
+}
+
+#[derive(Deserialize)]
+struct CommentNode {
+    #[serde(rename = "databaseId")]
+    database_id: Option<u64>,
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct Connection<T> {
+    nodes: Vec<T>,
+}
+
+/// PR のレビュースレッドを取得する（先頭 100 件まで）
+pub async fn fetch_review_threads(repo: &str, pr_number: u32) -> Result<Vec<ReviewThread>> {
+    let (owner, name) = repo
+        .split_once('/')
+        .with_context(|| format!("Invalid repository name: {}", repo))?;
+    let number = pr_number.to_string();
+    let json = gh_api_graphql(
+        REVIEW_THREADS_QUERY,
+        &[
+            ("owner", FieldValue::String(owner)),
+            ("name", FieldValue::String(name)),
+            ("number", FieldValue::Raw(&number)),
+        ],
+    )
+    .await?;
+    parse_review_threads(json)
+}
+
+fn parse_review_threads(json: serde_json::Value) -> Result<Vec<ReviewThread>> {
+    let threads = json
+        .pointer("/data/repository/pullRequest/reviewThreads")
+        .cloned()
+        .context("Missing reviewThreads in GraphQL response")?;
+    let threads: Connection<ThreadNode> =
+        serde_json::from_value(threads).context("Failed to parse review threads response")?;
+
+    Ok(threads
+        .nodes
+        .into_iter()
+        .map(|node| {
+            let first = node.comments.nodes.into_iter().next();
+            ReviewThread {
+                id: node.id,
+                path: node.path,
+                is_resolved: node.is_resolved,
+                line: node.line,
+                start_line: node.start_line,
+                comment_id: first.as_ref().and_then(|c| c.database_id),
+                body: first.map(|c| c.body).unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+/// レビュースレッドを解決済みにする
+pub async fn resolve_review_thread(thread_id: &str) -> Result<()> {
+    gh_api_graphql(
+        RESOLVE_REVIEW_THREAD_MUTATION,
+        &[("threadId", FieldValue::String(thread_id))],
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_review_threads() {
+        let response = json!({
+            "data": { "repository": { "pullRequest": { "reviewThreads": { "nodes": [
+                {
+                    "id": "PRRT_1",
+                    "isResolved": false,
+                    "path": "src/main.rs",
+                    "line": 12,
+                    "startLine": 10,
+                    "comments": { "nodes": [{ "databaseId": 42, "body": "first" }] }
+                },
+                {
+                    "id": "PRRT_2",
+                    "isResolved": true,
+                    "path": "src/lib.rs",
+                    "line": null,
+                    "startLine": null,
+                    "comments": { "nodes": [] }
+                }
+            ] } } } }
+        });
+
+        let threads = parse_review_threads(response).unwrap();
+        assert_eq!(
+            threads,
+            vec![
+                ReviewThread {
+                    id: "PRRT_1".to_string(),
+                    path: "src/main.rs".to_string(),
+                    is_resolved: false,
+                    body: "first".to_string(),
+                    comment_id: Some(42),
+                    line: Some(12),
+                    start_line: Some(10),
+                },
+                ReviewThread {
+                    id: "PRRT_2".to_string(),
+                    path: "src/lib.rs".to_string(),
+                    is_resolved: true,
+                    body: String::new(),
+                    comment_id: None,
+                    line: None,
+                    start_line: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_review_threads_missing_pull_request() {
+        let response = json!({ "data": { "repository": { "pullRequest": null } } });
+        assert!(parse_review_threads(response).is_err());
+    }
+}
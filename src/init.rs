@@ -3,6 +3,8 @@ use std::fs;
 use std::path::PathBuf;
 use xdg::BaseDirectories;
 
+use octorus::config::Config;
+
 /// Default config.toml content
 const DEFAULT_CONFIG: &str = r#"editor = "vi"
 
@@ -15,6 +17,10 @@ request_changes = 'r'
 comment = 'c'
 suggestion = 's'
 
+# GitHub Enterprise Server hostname (omit for github.com)
+# [github]
+# hostname = "github.mycompany.com"
+
 [ai]
 reviewer = "claude"
 reviewee = "claude"
@@ -22,6 +28,10 @@ max_iterations = 10
 timeout_secs = 600
 # prompt_dir = "/custom/path/to/prompts"  # Optional: custom prompt directory
 
+# Replace the built-in allowed tools per role (Claude only); unset keeps the defaults
+# reviewer_allowed_tools = ["Read", "Glob", "Grep"]
+# reviewee_allowed_tools = ["Read", "Edit", "Write", "Glob", "Grep", "Bash(make:*)"]
+
 # Additional tools for reviewer agent (Claude only)
 # Specify in Claude Code --allowedTools format
 # reviewer_additional_tools = ["Skill", "WebSearch"]
@@ -96,6 +106,45 @@ pub fn run_init(force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Run `config init`: write only config.toml
+pub fn run_config_init(force: bool) -> Result<()> {
+    let config_path = Config::config_path();
+    if let Some(dir) = config_path.parent().filter(|dir| !dir.exists()) {
+        println!("Creating configuration directory: {}", dir.display());
+        fs::create_dir_all(dir).context("Failed to create config directory")?;
+    }
+    write_file_if_needed(&config_path, DEFAULT_CONFIG, force, "config.toml")?;
+    println!("Config file: {}", config_path.display());
+    Ok(())
+}
+
+/// Run `config validate`: report every problem in config.toml
+pub fn run_config_validate() -> Result<()> {
+    let config_path = Config::config_path();
+    if !config_path.exists() {
+        println!(
+            "No config file at {} (defaults are used)",
+            config_path.display()
+        );
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
+    let problems = Config::problems(&content);
+    if problems.is_empty() {
+        println!("{} is valid", config_path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        eprintln!("{}\n", problem);
+    }
+    anyhow::bail!(
+        "{} problem(s) found in {}",
+        problems.len(),
+        config_path.display()
+    )
+}
+
 /// Write a file if it doesn't exist or force is true
 fn write_file_if_needed(path: &PathBuf, content: &str, force: bool, name: &str) -> Result<()> {
     if path.exists() && !force {
@@ -160,6 +209,11 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(Config::problems(DEFAULT_CONFIG), Vec::<String>::new());
+    }
+
     #[test]
     fn test_run_init_creates_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -7,6 +7,7 @@
 pub mod ai;
 pub mod app;
 pub mod cache;
+pub mod clipboard;
 pub mod config;
 pub mod diff;
 pub mod editor;
@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::process::Command;
 use tokio::sync::mpsc;
 use tracing::warn;
 
+use crate::ai::adapter::FileDiffStat;
 use crate::diff;
-use crate::github::{self, ChangedFile, PullRequest};
+use crate::github::{
+    self, ChangedFile, PrListPage, PrStateFilter, PullRequest, PullRequestSummary, VcsProvider,
+};
 
 pub enum DataLoadResult {
     /// APIからデータ取得成功
@@ -36,6 +41,7 @@ pub enum FetchMode {
 
 /// バックグラウンドでPRデータを取得
 pub async fn fetch_pr_data(
+    provider: Arc<dyn VcsProvider>,
     repo: String,
     pr_number: u32,
     mode: FetchMode,
@@ -43,14 +49,65 @@ pub async fn fetch_pr_data(
 ) {
     match mode {
         FetchMode::Fresh => {
-            fetch_and_send(&repo, pr_number, tx).await;
+            fetch_and_send(provider.as_ref(), &repo, pr_number, tx).await;
         }
         FetchMode::CheckUpdate(cached_updated_at) => {
-            check_for_updates(&repo, pr_number, &cached_updated_at, tx).await;
+            check_for_updates(provider.as_ref(), &repo, pr_number, &cached_updated_at, tx).await;
         }
     }
 }
 
+/// `--pr 10,11,12` で指定された PR を PR 一覧用のサマリとして取得する
+///
+/// 指定順を保ったまま、状態フィルタはクライアント側で適用する。取得に失敗した PR は
+/// ログに残して読み飛ばす（すべて失敗した場合は最初のエラーを返す）
+pub async fn fetch_pr_queue(
+    provider: &dyn VcsProvider,
+    repo: &str,
+    pr_numbers: &[u32],
+    state: PrStateFilter,
+) -> Result<PrListPage> {
+    let mut items = Vec::with_capacity(pr_numbers.len());
+    let mut first_error = None;
+    let mut fetched = 0;
+    for &pr_number in pr_numbers {
+        let pr = match provider.fetch_pr(repo, pr_number).await {
+            Ok(pr) => pr,
+            Err(e) => {
+                warn!("Skipping PR #{} in the queue: {:#}", pr_number, e);
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        fetched += 1;
+        let is_open = pr.state.eq_ignore_ascii_case("open");
+        let matches = match state {
+            PrStateFilter::Open => is_open,
+            PrStateFilter::Closed => !is_open,
+            PrStateFilter::All => true,
+        };
+        if matches {
+            items.push(PullRequestSummary {
+                number: pr.number,
+                title: pr.title,
+                state: pr.state,
+                author: pr.user,
+                // REST の PR 取得結果からは draft を読んでいない
+                is_draft: false,
+                labels: pr.labels,
+                updated_at: pr.updated_at,
+            });
+        }
+    }
+    if let (0, Some(e)) = (fetched, first_error) {
+        return Err(e);
+    }
+    Ok(PrListPage {
+        items,
+        has_more: false,
+    })
+}
+
 /// ローカル `git diff` から PR データを再構築して読み込み
 pub async fn fetch_local_diff(
     _repo: String,
@@ -102,6 +159,7 @@ pub async fn fetch_local_diff(
             login: "local".to_string(),
         },
         updated_at: Utc::now().to_rfc3339(),
+        labels: vec![],
     };
 
     let _ = tx
@@ -192,10 +250,15 @@ async fn merge_untracked_files(working_dir: Option<&str>, files: &mut Vec<Change
     files.sort_unstable_by(|a, b| a.filename.cmp(&b.filename));
 }
 
-async fn fetch_and_send(repo: &str, pr_number: u32, tx: mpsc::Sender<DataLoadResult>) {
+async fn fetch_and_send(
+    provider: &dyn VcsProvider,
+    repo: &str,
+    pr_number: u32,
+    tx: mpsc::Sender<DataLoadResult>,
+) {
     match tokio::try_join!(
-        github::fetch_pr(repo, pr_number),
-        github::fetch_changed_files(repo, pr_number)
+        provider.fetch_pr(repo, pr_number),
+        provider.fetch_files(repo, pr_number)
     ) {
         Ok((pr, mut files)) => {
             // Check if any files have missing patches (large file limitation)
@@ -203,7 +266,7 @@ async fn fetch_and_send(repo: &str, pr_number: u32, tx: mpsc::Sender<DataLoadRes
 
             if has_missing_patches {
                 // Fetch full diff using gh pr diff as fallback
-                match github::fetch_pr_diff(repo, pr_number).await {
+                match provider.fetch_diff(repo, pr_number).await {
                     Ok(full_diff) => {
                         let mut patch_map = diff::parse_unified_diff(&full_diff);
 
@@ -237,21 +300,163 @@ async fn fetch_and_send(repo: &str, pr_number: u32, tx: mpsc::Sender<DataLoadRes
 }
 
 async fn check_for_updates(
+    provider: &dyn VcsProvider,
     repo: &str,
     pr_number: u32,
     cached_updated_at: &str,
     tx: mpsc::Sender<DataLoadResult>,
 ) {
     // PRの基本情報だけ取得してupdated_atを比較
-    if let Ok(fresh_pr) = github::fetch_pr(repo, pr_number).await {
+    if let Ok(fresh_pr) = provider.fetch_pr(repo, pr_number).await {
         if fresh_pr.updated_at != cached_updated_at {
             // 更新あり → 全データ再取得
-            fetch_and_send(repo, pr_number, tx).await;
+            fetch_and_send(provider, repo, pr_number, tx).await;
         }
         // 更新なし → 何もしない（キャッシュデータをそのまま使用）
     }
 }
 
+/// reviewee の修正前の作業ツリーを指すコミットを返す
+///
+/// 未コミットの変更があれば `git stash create` で作ったコミット（作業ツリーや stash 一覧は
+/// 変更しない）、なければ HEAD。修正後に [`diff_stats_since`] で差分を測るのに使う
+pub async fn snapshot_working_tree(working_dir: Option<&str>) -> Result<String> {
+    // どこからも参照されないコミットなので、作者情報は固定値で良い（未設定の環境でも失敗しない）
+    let stash = run_git_command(
+        working_dir,
+        &[
+            "-c",
+            "user.name=octorus",
+            "-c",
+            "user.email=octorus@localhost",
+            "stash",
+            "create",
+        ],
+    )
+    .await?;
+    let stash = stash.trim();
+    if !stash.is_empty() {
+        return Ok(stash.to_string());
+    }
+    current_head_sha(working_dir).await
+}
+
+/// `.git` 内の `name`（hooks など）のパス。worktree や `core.hooksPath` も考慮される
+pub async fn git_path(working_dir: Option<&str>, name: &str) -> Result<PathBuf> {
+    git_rev_parse_path(working_dir, &["--git-path", name]).await
+}
+
+/// 作業ツリーのルート
+pub async fn work_tree_root(working_dir: Option<&str>) -> Result<PathBuf> {
+    git_rev_parse_path(working_dir, &["--show-toplevel"]).await
+}
+
+/// worktree 間で共有される git ディレクトリ（通常は `.git`）
+pub async fn git_common_dir(working_dir: Option<&str>) -> Result<PathBuf> {
+    git_rev_parse_path(working_dir, &["--git-common-dir"]).await
+}
+
+async fn git_rev_parse_path(working_dir: Option<&str>, args: &[&str]) -> Result<PathBuf> {
+    let mut command = vec!["rev-parse"];
+    command.extend_from_slice(args);
+    let output = run_git_command(working_dir, &command).await?;
+    let path = PathBuf::from(output.trim());
+    // 相対パスは git を実行したディレクトリ基準
+    let path = match working_dir {
+        Some(dir) if path.is_relative() => Path::new(dir).join(path),
+        _ => path,
+    };
+    // 比較できるよう絶対パスにそろえる（存在しないパスもあるので canonicalize は使わない）
+    Ok(std::path::absolute(&path).unwrap_or(path))
+}
+
+/// `base` から現在の作業ツリーまでの変更行数をファイルごとに返す（パス順）
+///
+/// 未追跡の新規ファイルは `git diff` に現れないため含まれない
+pub async fn diff_stats_since(working_dir: Option<&str>, base: &str) -> Result<Vec<FileDiffStat>> {
+    let output = run_git_command(working_dir, &["diff", "--numstat", base]).await?;
+    let mut stats: Vec<FileDiffStat> = parse_numstat_output(Some(&output))
+        .into_iter()
+        .map(|(path, (additions, deletions))| FileDiffStat {
+            path,
+            additions,
+            deletions,
+        })
+        .collect();
+    stats.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(stats)
+}
+
+/// `base` から現在の作業ツリーまでに変更された行範囲（`base` 側の行番号、両端を含む）を
+/// ファイルごとに返す
+///
+/// 削除を伴わない挿入は、挿入位置の前後の行 `(n, n + 1)` として扱う
+pub async fn changed_lines_since(
+    working_dir: Option<&str>,
+    base: &str,
+) -> Result<HashMap<String, Vec<(u32, u32)>>> {
+    let output = run_git_command(working_dir, &["diff", "-U0", base]).await?;
+    Ok(parse_changed_lines(&output))
+}
+
+fn parse_changed_lines(diff: &str) -> HashMap<String, Vec<(u32, u32)>> {
+    let mut result: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current = None;
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            // 新規ファイル（/dev/null）には base 側の行がない
+            current = path.strip_prefix("a/").map(str::to_string);
+        } else if let Some(hunk) = line.strip_prefix("@@ -") {
+            let Some(path) = current.as_ref() else {
+                continue;
+            };
+            let old = hunk.split_whitespace().next().unwrap_or_default();
+            let (start, count) = match old.split_once(',') {
+                Some((start, count)) => (start.parse().ok(), count.parse().ok()),
+                None => (old.parse().ok(), Some(1)),
+            };
+            let (Some(start), Some(count)) = (start, count) else {
+                continue;
+            };
+            let range = match count {
+                0u32 => (start, start + 1),
+                _ => (start, start + count - 1),
+            };
+            result.entry(path.clone()).or_default().push(range);
+        }
+    }
+
+    result
+}
+
+/// reviewee が編集する作業ディレクトリと PR の head の関係
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckoutStatus {
+    /// ディレクトリが存在しない
+    Missing,
+    /// git リポジトリではない
+    NotGitRepo,
+    /// HEAD が PR の head と一致
+    OnHead,
+    /// HEAD が PR の head と異なる（別ブランチ、未 pull など）
+    Mismatch { head: String },
+}
+
+/// `working_dir` が PR の head（`head_sha`）をチェックアウトしているかを調べる
+pub async fn checkout_status(working_dir: &str, head_sha: &str) -> CheckoutStatus {
+    if !std::path::Path::new(working_dir).is_dir() {
+        return CheckoutStatus::Missing;
+    }
+    match current_head_sha(Some(working_dir)).await {
+        Ok(head) if head == head_sha => CheckoutStatus::OnHead,
+        Ok(head) => CheckoutStatus::Mismatch { head },
+        Err(_) => CheckoutStatus::NotGitRepo,
+    }
+}
+
 async fn run_git_diff(working_dir: Option<&str>) -> Result<String> {
     run_git_command(working_dir, &["diff", "HEAD"]).await
 }
@@ -498,6 +703,136 @@ mod tests {
         assert!(!filenames.contains(&"ignored/skip.txt"));
     }
 
+    #[tokio::test]
+    async fn test_checkout_status() {
+        let tempdir = tempdir().unwrap();
+        let workdir = tempdir.path();
+        let dir = workdir.to_str().unwrap();
+
+        assert_eq!(
+            checkout_status(&format!("{}/missing", dir), "abc").await,
+            CheckoutStatus::Missing
+        );
+        assert_eq!(
+            checkout_status(dir, "abc").await,
+            CheckoutStatus::NotGitRepo
+        );
+
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["init", "-b", "main"],
+            "failed to initialize temp git repo",
+        );
+        write_file(&workdir.join("a.rs"), "a\n");
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["add", "."],
+            "failed to add initial files",
+        );
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["commit", "-m", "initial commit"],
+            "failed to create initial commit",
+        );
+        let head = current_head_sha(Some(dir)).await.unwrap();
+
+        assert_eq!(checkout_status(dir, &head).await, CheckoutStatus::OnHead);
+        assert_eq!(
+            checkout_status(dir, "0000000").await,
+            CheckoutStatus::Mismatch { head }
+        );
+    }
+
+    #[test]
+    fn test_parse_changed_lines() {
+        let diff = "\
+diff --git a/src/a.rs b/src/a.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3 +3 @@ fn main() {
+-    old();
++    new();
+@@ -10,2 +10,0 @@
+-removed 1
+-removed 2
+@@ -20,0 +19,3 @@
++added 1
++added 2
++added 3
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++new file
+";
+
+        let changed = parse_changed_lines(diff);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed["src/a.rs"], vec![(3, 3), (10, 11), (20, 21)]);
+    }
+
+    #[tokio::test]
+    async fn test_diff_stats_since_measures_changes_after_snapshot() {
+        let tempdir = tempdir().unwrap();
+        let workdir = tempdir.path();
+        let dir = workdir.to_str();
+
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["init", "-b", "main"],
+            "failed to initialize temp git repo",
+        );
+        write_file(&workdir.join("src/a.rs"), "a\n");
+        write_file(&workdir.join("src/b.rs"), "b\n");
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["add", "."],
+            "failed to add initial files",
+        );
+        run_git(
+            &mut Command::new("git"),
+            workdir,
+            &["commit", "-m", "initial commit"],
+            "failed to create initial commit",
+        );
+
+        // 修正前から残っている未コミットの変更は数えない
+        write_file(&workdir.join("src/a.rs"), "a\nuser edit\n");
+        let base = snapshot_working_tree(dir).await.unwrap();
+
+        write_file(&workdir.join("src/a.rs"), "a\nuser edit\nfix 1\nfix 2\n");
+        write_file(&workdir.join("src/b.rs"), "b2\n");
+
+        let stats = diff_stats_since(dir, &base).await.unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                FileDiffStat {
+                    path: "src/a.rs".to_string(),
+                    additions: 2,
+                    deletions: 0,
+                },
+                FileDiffStat {
+                    path: "src/b.rs".to_string(),
+                    additions: 1,
+                    deletions: 1,
+                },
+            ]
+        );
+        // 作業ツリーの変更は残ったまま
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("src/b.rs")).unwrap(),
+            "b2\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_fetch_local_diff_does_not_return_non_target_ignored_file() {
         let tempdir = tempdir().unwrap();
@@ -599,4 +934,198 @@ mod tests {
         assert!(patch.contains("new file mode"));
         assert!(patch.contains("+pub fn hello()"));
     }
+
+    /// 変更ファイルの patch が欠けている PR を返すテスト用プロバイダ
+    struct FakeProvider;
+
+    #[async_trait::async_trait]
+    impl VcsProvider for FakeProvider {
+        async fn fetch_pr(&self, _repo: &str, pr_number: u32) -> Result<PullRequest> {
+            if pr_number == 404 {
+                anyhow::bail!("Not Found");
+            }
+            Ok(PullRequest {
+                number: pr_number,
+                title: "Fake".to_string(),
+                body: None,
+                state: "open".to_string(),
+                head: github::Branch {
+                    ref_name: "feature".to_string(),
+                    sha: "abc".to_string(),
+                },
+                base: github::Branch {
+                    ref_name: "main".to_string(),
+                    sha: "def".to_string(),
+                },
+                user: github::User {
+                    login: "octocat".to_string(),
+                },
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+                labels: vec![],
+            })
+        }
+
+        async fn fetch_files(&self, _repo: &str, _pr_number: u32) -> Result<Vec<ChangedFile>> {
+            Ok(vec![ChangedFile {
+                filename: "src/a.rs".to_string(),
+                status: "modified".to_string(),
+                additions: 1,
+                deletions: 1,
+                patch: None,
+            }])
+        }
+
+        async fn fetch_diff(&self, _repo: &str, _pr_number: u32) -> Result<String> {
+            Ok("diff --git a/src/a.rs b/src/a.rs\n\
+                --- a/src/a.rs\n\
+                +++ b/src/a.rs\n\
+                @@ -1 +1 @@\n\
+                -old\n\
+                +new\n"
+                .to_string())
+        }
+
+        async fn fetch_pr_list(
+            &self,
+            _repo: &str,
+            _state: github::PrStateFilter,
+            _offset: u32,
+            _limit: u32,
+        ) -> Result<github::PrListPage> {
+            unimplemented!()
+        }
+
+        async fn fetch_review_comments(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+        ) -> Result<Vec<github::comment::ReviewComment>> {
+            unimplemented!()
+        }
+
+        async fn fetch_checks(&self, _repo: &str, _sha: &str) -> Result<github::ChecksSummary> {
+            unimplemented!()
+        }
+
+        async fn fetch_reviews(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+        ) -> Result<Vec<github::comment::Review>> {
+            unimplemented!()
+        }
+
+        async fn fetch_discussion_comments(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+        ) -> Result<Vec<github::comment::DiscussionComment>> {
+            unimplemented!()
+        }
+
+        async fn create_review_comment(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+            _commit_id: &str,
+            _path: &str,
+            _position: u32,
+            _body: &str,
+        ) -> Result<github::comment::ReviewComment> {
+            unimplemented!()
+        }
+
+        async fn create_reply_comment(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+            _comment_id: u64,
+            _body: &str,
+        ) -> Result<github::comment::ReviewComment> {
+            unimplemented!()
+        }
+
+        async fn submit_review(
+            &self,
+            _repo: &str,
+            _pr_number: u32,
+            _action: crate::app::ReviewAction,
+            _body: &str,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_data_uses_provider_and_fills_missing_patches() {
+        let (tx, mut rx) = mpsc::channel(1);
+        fetch_pr_data(
+            Arc::new(FakeProvider),
+            "owner/repo".to_string(),
+            7,
+            FetchMode::Fresh,
+            tx,
+        )
+        .await;
+
+        match rx.recv().await.unwrap() {
+            DataLoadResult::Success { pr, files } => {
+                assert_eq!(pr.number, 7);
+                let patch = files[0].patch.as_deref().expect("patch from full diff");
+                assert!(patch.contains("+new"));
+            }
+            DataLoadResult::Error(err) => panic!("unexpected error: {err}"),
+        }
+
+        // 更新がなければ何も送らない
+        let (tx, mut rx) = mpsc::channel(1);
+        fetch_pr_data(
+            Arc::new(FakeProvider),
+            "owner/repo".to_string(),
+            7,
+            FetchMode::CheckUpdate("2026-01-01T00:00:00Z".to_string()),
+            tx,
+        )
+        .await;
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_queue_keeps_order_and_filters_state() {
+        let page = fetch_pr_queue(
+            &FakeProvider,
+            "owner/repo",
+            &[12, 10, 11],
+            PrStateFilter::Open,
+        )
+        .await
+        .unwrap();
+        let numbers: Vec<u32> = page.items.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![12, 10, 11]);
+        assert_eq!(page.items[0].author.login, "octocat");
+        assert!(!page.has_more);
+
+        let page = fetch_pr_queue(&FakeProvider, "owner/repo", &[12], PrStateFilter::Closed)
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_queue_skips_prs_that_fail_to_load() {
+        let page = fetch_pr_queue(
+            &FakeProvider,
+            "owner/repo",
+            &[12, 404, 11],
+            PrStateFilter::Open,
+        )
+        .await
+        .unwrap();
+        let numbers: Vec<u32> = page.items.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![12, 11]);
+
+        // すべて失敗したらエラー
+        let result = fetch_pr_queue(&FakeProvider, "owner/repo", &[404], PrStateFilter::All).await;
+        assert!(result.is_err_and(|e| e.to_string().contains("Not Found")));
+    }
 }
@@ -16,7 +16,7 @@ use tokio_util::sync::CancellationToken;
 
 // Use modules from the library crate
 use octorus::app::RefreshRequest;
-use octorus::{app, cache, config, github, loader, syntax};
+use octorus::{ai, app, cache, config, github, loader, syntax};
 
 // init is only used by the binary, not needed for benchmarks
 mod init;
@@ -29,13 +29,15 @@ struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Repository name (e.g., "owner/repo"). Auto-detected from current directory if omitted.
+    /// Repository as "owner/repo", "host/owner/repo" or a URL (e.g., https://github.com/owner/repo).
+    /// Auto-detected from current directory if omitted.
     #[arg(short, long)]
-    repo: Option<String>,
+    repo: Option<github::RepoSpec>,
 
-    /// Pull request number. Shows PR list if omitted.
-    #[arg(short, long)]
-    pr: Option<u32>,
+    /// Pull request number. Pass several (e.g., "10,11,12") to pick from them as a review
+    /// queue. Shows the PR list if omitted.
+    #[arg(short, long, value_delimiter = ',')]
+    pr: Vec<u32>,
 
     /// Start AI Rally mode directly
     #[arg(long, default_value = "false")]
@@ -49,9 +51,83 @@ struct Args {
     #[arg(long, default_value = "false")]
     auto_focus: bool,
 
-    /// Working directory for AI agents (default: current directory)
+    /// Working directory for AI agents (default: current directory). AI Rally warns when it
+    /// is not a checkout of the PR head
     #[arg(long)]
     working_dir: Option<String>,
+
+    /// Before AI Rally, check out the PR in --working-dir with `gh pr checkout` when its HEAD
+    /// is not the PR head (cloning the repository first if the directory does not exist)
+    #[arg(long, default_value = "false", conflicts_with = "local")]
+    checkout: bool,
+
+    /// AI agent used as both reviewer and reviewee (default: `reviewer`/`reviewee` in config)
+    #[arg(long, value_enum, conflicts_with = "adapters")]
+    agent: Option<ai::adapter::SupportedAgent>,
+
+    /// Ordered fallback list of AI agents (e.g., "codex,claude"). The first available one is used.
+    #[arg(long, value_delimiter = ',')]
+    adapters: Vec<String>,
+
+    /// Present the AI review as a single prioritized action list instead of inline comments
+    #[arg(long, default_value = "false")]
+    digest: bool,
+
+    /// Language for AI review comments and summaries (e.g., "Japanese")
+    #[arg(long)]
+    review_language: Option<String>,
+
+    /// Areas the AI reviewer focuses on, replacing the default checklist (e.g., "security,perf")
+    #[arg(long, value_delimiter = ',')]
+    focus: Vec<String>,
+
+    /// Replay a captured `codex exec --json` NDJSON stream instead of running Codex
+    /// (also read from OCTORUS_CODEX_REPLAY). Each agent turn consumes the next turn in the file.
+    #[arg(long, value_name = "FILE")]
+    replay: Option<String>,
+
+    /// Command that must pass before an AI Rally approval completes (e.g., "cargo test")
+    #[arg(long)]
+    verify_cmd: Option<String>,
+
+    /// Maximum AI Rally iterations before stopping unapproved (default: `max_iterations` in config)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    max_iterations: Option<u32>,
+
+    /// Run AI Rally without writing to GitHub; would-be posts are shown in the rally logs
+    #[arg(long, default_value = "false")]
+    dry_run: bool,
+
+    /// Run the AI Rally reviewer once and stop: the review is posted (or previewed) but
+    /// the reviewee never fixes anything (see `review_only` in config)
+    #[arg(long, default_value = "false")]
+    review_only: bool,
+
+    /// Append every AI Rally event as a JSON line to this file (see `event_log` in config)
+    #[arg(long, value_name = "PATH")]
+    event_log: Option<String>,
+
+    /// Only review changes after this commit (default: the commit the previous AI Rally reviewed)
+    #[arg(long, value_name = "SHA", visible_alias = "since-commit")]
+    since: Option<String>,
+
+    /// Merge the PR after a clean AI Rally approval (see `auto_merge` in config)
+    #[arg(long, default_value = "false")]
+    auto_merge: bool,
+
+    /// Bypass the agent response cache even when `response_cache` is enabled
+    #[arg(long, default_value = "false")]
+    no_cache: bool,
+
+    /// Run the reviewer on each adapter (see --adapters) for --pr and print a comparison table
+    #[arg(long, default_value = "false")]
+    benchmark: bool,
+
+    /// Run AI Rally on --pr without the TUI: progress goes to stderr, the final review is
+    /// printed to stdout as JSON, and the exit code is 0 (approved), 2 (not approved) or 1 (failed).
+    /// Several PRs are reviewed in parallel (review only), bounded by max_concurrent_rallies
+    #[arg(long, default_value = "false", conflicts_with_all = ["local", "benchmark"])]
+    headless: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -62,8 +138,27 @@ enum Commands {
         #[arg(long, default_value = "false")]
         force: bool,
     },
+    /// Create or check the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
     /// Remove AI Rally session data
     Clean,
+    /// Remove cached agent responses (see `response_cache` in config)
+    CleanCache,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Write a commented default config.toml (without the prompt templates)
+    Init {
+        /// Force overwrite an existing config.toml
+        #[arg(long, default_value = "false")]
+        force: bool,
+    },
+    /// Check config.toml for unknown keys, invalid values and keybinding conflicts
+    Validate,
 }
 
 /// Restore terminal to normal state
@@ -111,21 +206,64 @@ async fn main() -> Result<()> {
     if let Some(command) = args.command {
         return match command {
             Commands::Init { force } => init::run_init(force),
+            Commands::Config { command } => match command {
+                ConfigCommand::Init { force } => init::run_config_init(force),
+                ConfigCommand::Validate => init::run_config_validate(),
+            },
             Commands::Clean => {
                 cache::cleanup_rally_sessions();
                 let rally_dir = cache::cache_dir().join("rally");
                 println!("Rally sessions cleaned: {}", rally_dir.display());
                 Ok(())
             }
+            Commands::CleanCache => {
+                cache::cleanup_response_cache();
+                println!(
+                    "Response cache cleaned: {}",
+                    cache::response_cache_dir().display()
+                );
+                Ok(())
+            }
         };
     }
 
+    let mut config = config::Config::load()?;
+
+    // GitHub Enterprise: 以降のすべての gh 呼び出しを設定ホストに向ける。
+    // --repo にホストが含まれていればそれを使い、設定と食い違うならエラーにする
+    let repo_host = args.repo.as_ref().and_then(|r| r.host());
+    let configured_host = config.github.host();
+    if let Some(host) = repo_host {
+        if !host.eq_ignore_ascii_case(configured_host.unwrap_or("github.com")) {
+            if let Some(configured) = configured_host {
+                eprintln!(
+                    "Error: --repo is on {} but github.hostname is {}",
+                    host, configured
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(host) = repo_host.or(configured_host) {
+        github::set_gh_host(host);
+    }
+
+    // gh が無い・未ログインなら、TUI や Rally を始める前に対処方法を表示して終了する
+    if !args.local {
+        if let Err(e) = github::check_gh_cli().await {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
     let repo = if args.local {
-        args.repo.clone().unwrap_or_else(|| "local".to_string())
+        args.repo
+            .as_ref()
+            .map_or_else(|| "local".to_string(), |r| r.to_string())
     } else {
         // Detect or use provided repo
-        match args.repo.clone() {
-            Some(r) => r,
+        match args.repo.as_ref() {
+            Some(r) => r.to_string(),
             None => match github::detect_repo().await {
                 Ok(r) => r,
                 Err(e) => {
@@ -142,17 +280,83 @@ async fn main() -> Result<()> {
         let _ = syntax::theme_set();
     });
 
-    let config = config::Config::load()?;
+    if let Some(agent) = args.agent {
+        // Rally 開始後ではなく起動時に CLI の未インストールを検出する
+        if let Err(e) = check_agent_available(agent, &config.ai).await {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+        config.ai.reviewer = agent.name().to_string();
+        config.ai.reviewee = agent.name().to_string();
+    }
+    if !args.adapters.is_empty() {
+        config.ai.adapters = args.adapters.clone();
+    }
+    if args.digest {
+        config.ai.digest = true;
+    }
+    if let Some(max_iterations) = args.max_iterations {
+        config.ai.max_iterations = max_iterations;
+    }
+    if args.dry_run {
+        config.ai.dry_run = true;
+    }
+    if args.review_only {
+        config.ai.review_only = true;
+    }
+    if let Some(ref path) = args.event_log {
+        config.ai.event_log = Some(path.clone());
+    }
+    if let Some(ref path) = args.replay {
+        config.ai.codex_replay = Some(path.clone());
+    }
+    if let Some(ref sha) = args.since {
+        config.ai.since_commit = Some(sha.clone());
+    }
+    if args.auto_merge {
+        config.ai.auto_merge = true;
+    }
+    if args.checkout {
+        config.ai.checkout_pr = true;
+    }
+    if args.no_cache {
+        config.ai.response_cache = false;
+    }
+    if let Some(ref language) = args.review_language {
+        config.ai.review_language = language.clone();
+    }
+    if !args.focus.is_empty() {
+        config.ai.review_focus = args.focus.clone();
+    }
+    if let Some(ref cmd) = args.verify_cmd {
+        config.ai.verify_command = Some(cmd.clone());
+    }
 
-    if args.local {
+    if args.benchmark {
+        run_benchmark(&repo, &config, &args).await
+    } else if args.headless {
+        run_headless(&repo, &config, &args).await
+    } else if args.local {
         run_with_local_diff(&repo, &config, &args).await
-    } else if let Some(pr) = args.pr {
+    } else if let [pr] = args.pr[..] {
         run_with_pr(&repo, pr, &config, &args).await
     } else {
         run_with_pr_list(&repo, config, &args).await
     }
 }
 
+/// Check that the agent selected with `--agent` can run
+async fn check_agent_available(
+    agent: ai::adapter::SupportedAgent,
+    config: &config::AiConfig,
+) -> Result<()> {
+    let adapter = ai::adapters::create_adapter(agent.name(), config)?;
+    adapter
+        .check_availability()
+        .await
+        .map_err(|e| e.context(format!("--agent {} is not available", agent.name())))
+}
+
 async fn run_with_local_diff(repo: &str, config: &config::Config, args: &Args) -> Result<()> {
     let (retry_tx, mut retry_rx) = mpsc::channel::<RefreshRequest>(1);
     let (mut app, tx) = app::App::new_loading(repo, 0, config.clone());
@@ -266,6 +470,86 @@ fn is_git_file(path: &Path) -> bool {
         .any(|component| component.as_os_str() == ".git")
 }
 
+/// TUI を介さずに Rally を実行するための PR の Context を取得する
+async fn fetch_pr_context(repo: &str, pr_number: u32, args: &Args) -> Result<ai::Context> {
+    let (pr, files) = tokio::try_join!(
+        github::fetch_pr(repo, pr_number),
+        github::fetch_changed_files(repo, pr_number)
+    )?;
+    let (file_patches, diff) = ai::adapter::patches_from_files(&files);
+    let working_dir = args.working_dir.clone().or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .map(|cwd| cwd.to_string_lossy().to_string())
+    });
+
+    Ok(ai::Context {
+        repo: repo.to_string(),
+        pr_number,
+        pr_title: pr.title,
+        pr_body: pr.body,
+        diff,
+        working_dir,
+        head_sha: pr.head.sha,
+        base_branch: pr.base.ref_name,
+        external_comments: Vec::new(),
+        local_mode: false,
+        file_patches,
+        linked_issues: Vec::new(),
+    })
+}
+
+/// Run the reviewer on every benchmarked adapter and print a comparison table.
+/// Nothing is posted to the PR.
+async fn run_benchmark(repo: &str, config: &config::Config, args: &Args) -> Result<()> {
+    let [pr_number] = args.pr[..] else {
+        anyhow::bail!("--benchmark requires a single --pr");
+    };
+    let context = fetch_pr_context(repo, pr_number, args).await?;
+
+    let names = ai::benchmark::benchmark_adapter_names(&config.ai);
+    eprintln!(
+        "Benchmarking reviewer on PR #{} with: {}",
+        pr_number,
+        names.join(", ")
+    );
+    let entries = ai::benchmark::run_benchmark(&config.ai, &context).await;
+    print!("{}", ai::benchmark::format_benchmark_table(&entries));
+    Ok(())
+}
+
+/// Run AI Rally without the TUI and exit with a code reflecting the reviewer's verdict
+async fn run_headless(repo: &str, config: &config::Config, args: &Args) -> Result<()> {
+    let code = match args.pr[..] {
+        [] => anyhow::bail!("--headless requires --pr"),
+        [pr_number] => {
+            let context = fetch_pr_context(repo, pr_number, args).await?;
+            ai::headless::run_headless(repo, config.ai.clone(), context).await?
+        }
+        ref pr_numbers => {
+            if config.ai.checkout_pr {
+                anyhow::bail!("--checkout needs a single --pr");
+            }
+            let mut contexts = Vec::with_capacity(pr_numbers.len());
+            for &pr_number in pr_numbers {
+                contexts.push(fetch_pr_context(repo, pr_number, args).await?);
+            }
+            // 同じ working_dir を共有するため reviewee（ファイル変更）は動かさない
+            eprintln!(
+                "Reviewing {} PRs, at most {} agent turn(s) at a time (review only: they share one working directory)",
+                pr_numbers.len(),
+                config.ai.max_concurrent_rallies.max(1)
+            );
+            let ai_config = config::AiConfig {
+                review_only: true,
+                ..config.ai.clone()
+            };
+            ai::headless::run_headless_queue(repo, ai_config, contexts).await?
+        }
+    };
+    std::process::exit(code);
+}
+
 /// Run the app with a specific PR number (existing flow)
 async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args) -> Result<()> {
     // リトライ用のチャンネル
@@ -291,18 +575,19 @@ async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args)
     let repo_clone = repo.to_string();
     let pr_number = pr;
     let working_dir = args.working_dir.clone();
+    let vcs = app.vcs_provider();
 
     tokio::spawn(async move {
         tokio::select! {
             _ = token_clone.cancelled() => {}
             _ = async {
-                loader::fetch_pr_data(repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx.clone()).await;
+                loader::fetch_pr_data(vcs.clone(), repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx.clone()).await;
 
                 while let Some(request) = retry_rx.recv().await {
                     match request {
                         RefreshRequest::PrRefresh { pr_number } => {
                             let tx_retry = tx.clone();
-                            loader::fetch_pr_data(repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
+                            loader::fetch_pr_data(vcs.clone(), repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
                                 .await;
                         }
                         RefreshRequest::LocalRefresh => {
@@ -341,13 +626,16 @@ async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args)
     std::process::exit(exit_code);
 }
 
-/// Run the app with PR list (new flow)
+/// Run the app with PR list (new flow), limited to the `--pr` queue when several PRs are given
 async fn run_with_pr_list(repo: &str, config: config::Config, args: &Args) -> Result<()> {
     // リトライ用のチャンネル（PR リスト画面から Local モードへの切替に対応）
     let (retry_tx, mut retry_rx) = mpsc::channel::<RefreshRequest>(1);
     let refresh_pending = Arc::new(AtomicBool::new(false));
 
     let mut app = app::App::new_pr_list(repo, config);
+    if !args.pr.is_empty() {
+        app.set_pr_queue(args.pr.clone());
+    }
     app.set_retry_sender(retry_tx);
     setup_working_dir(&mut app, args);
 
@@ -362,9 +650,22 @@ async fn run_with_pr_list(repo: &str, config: config::Config, args: &Args) -> Re
 
     let repo_clone = repo.to_string();
     let state_filter = app.pr_list_state_filter;
+    let vcs = app.vcs_provider();
+    let list_vcs = vcs.clone();
+    let queue = app.pr_queue.clone();
 
     tokio::spawn(async move {
-        let result = github::fetch_pr_list(&repo_clone, state_filter, 30).await;
+        let result = match queue {
+            Some(pr_numbers) => {
+                loader::fetch_pr_queue(list_vcs.as_ref(), &repo_clone, &pr_numbers, state_filter)
+                    .await
+            }
+            None => {
+                list_vcs
+                    .fetch_pr_list(&repo_clone, state_filter, 0, 30)
+                    .await
+            }
+        };
         let _ = pr_list_tx.send(result.map_err(|e| e.to_string())).await;
     });
 
@@ -388,7 +689,7 @@ async fn run_with_pr_list(repo: &str, config: config::Config, args: &Args) -> Re
                     match request {
                         RefreshRequest::PrRefresh { pr_number } => {
                             let tx_retry = data_tx.clone();
-                            loader::fetch_pr_data(repo_for_retry.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
+                            loader::fetch_pr_data(vcs.clone(), repo_for_retry.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
                                 .await;
                         }
                         RefreshRequest::LocalRefresh => {
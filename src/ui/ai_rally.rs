@@ -9,17 +9,23 @@ use ratatui::{
     Frame,
 };
 
-use super::common::build_pr_info;
+use super::common::{build_pr_info, severity_color};
+use super::text::{replace_emoji_shortcodes, truncate_to_width};
+use super::text_area::TextArea;
+use crate::ai::adapter::{CommentSeverity, ReviewComment};
+use crate::ai::orchestrator::ReviewPostInfo;
 use crate::ai::{RallyState, ReviewAction, RevieweeStatus};
 use crate::app::{AiRallyState, App, LogEntry, LogEventType};
 
 pub fn render(frame: &mut Frame, app: &mut App) {
     // Build PR info before borrowing ai_rally_state to avoid borrow conflict
     let pr_info = build_pr_info(app);
+    let spinner = app.spinner_char().to_string();
 
     let Some(rally_state) = &mut app.ai_rally_state else {
         return;
     };
+    let input = &app.input_text_area;
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -30,7 +36,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         ])
         .split(frame.area());
 
-    render_header(frame, chunks[0], rally_state, &pr_info);
+    render_header(frame, chunks[0], rally_state, &pr_info, &spinner);
     render_main_content(frame, chunks[1], rally_state);
     render_status_bar(frame, chunks[2], rally_state);
 
@@ -38,9 +44,21 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     if rally_state.showing_log_detail {
         render_log_detail_modal(frame, rally_state);
     }
+    if rally_state.showing_raw_result {
+        render_raw_result_modal(frame, rally_state);
+    }
+    if rally_state.answering_clarification {
+        render_clarification_input(frame, rally_state, input);
+    }
 }
 
-fn render_header(frame: &mut Frame, area: Rect, state: &AiRallyState, pr_info: &str) {
+fn render_header(
+    frame: &mut Frame,
+    area: Rect,
+    state: &AiRallyState,
+    pr_info: &str,
+    spinner: &str,
+) {
     let state_text = match state.state {
         RallyState::Initializing => "Initializing...",
         RallyState::ReviewerReviewing => "Reviewer reviewing...",
@@ -70,17 +88,38 @@ fn render_header(frame: &mut Frame, area: Rect, state: &AiRallyState, pr_info: &
         state.iteration, state.max_iterations
     );
 
+    let mut status_spans = vec![Span::styled("Status: ", Style::default().fg(Color::Gray))];
+    // Animate agent turns so long waits don't look frozen
+    if state.state.is_agent_turn() {
+        status_spans.push(Span::styled(
+            format!("{} ", spinner),
+            Style::default().fg(state_color),
+        ));
+    }
+    status_spans.push(Span::styled(
+        state_text,
+        Style::default()
+            .fg(state_color)
+            .add_modifier(Modifier::BOLD),
+    ));
+    if let Some(started) = state.turn_started_at {
+        status_spans.push(Span::styled(
+            format!(" ({})", format_duration(started.elapsed().as_secs())),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    if let Some(trend) = format_blocking_trend(&state.blocking_issue_counts()) {
+        status_spans.push(Span::styled("  |  ", Style::default().fg(Color::DarkGray)));
+        status_spans.push(Span::styled(trend, Style::default().fg(Color::Gray)));
+    }
+    if let Some(usage) = format_usage(state.total_cost_usd, state.total_duration_ms) {
+        status_spans.push(Span::styled("  |  ", Style::default().fg(Color::DarkGray)));
+        status_spans.push(Span::styled(usage, Style::default().fg(Color::Gray)));
+    }
+
     let header = Paragraph::new(vec![
         Line::from(Span::styled(pr_info, Style::default().fg(Color::White))),
-        Line::from(vec![
-            Span::styled("Status: ", Style::default().fg(Color::Gray)),
-            Span::styled(
-                state_text,
-                Style::default()
-                    .fg(state_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]),
+        Line::from(status_spans),
     ])
     .block(
         Block::default()
@@ -92,6 +131,54 @@ fn render_header(frame: &mut Frame, area: Rect, state: &AiRallyState, pr_info: &
     frame.render_widget(header, area);
 }
 
+/// Summarize how the blocking issue count evolved across reviewer iterations.
+///
+/// Returns `None` until the first review has completed. With a single data point
+/// only the current count is shown since there is no trend yet.
+fn format_blocking_trend(counts: &[usize]) -> Option<String> {
+    let (&last, rest) = counts.split_last()?;
+    let Some(&first) = rest.first() else {
+        return Some(format!("{} blocking issues", last));
+    };
+
+    let previous = rest[rest.len() - 1];
+    let trend = match last.cmp(&previous) {
+        std::cmp::Ordering::Less => "trending down",
+        std::cmp::Ordering::Greater => "trending up",
+        std::cmp::Ordering::Equal => "no change",
+    };
+
+    Some(format!("{} → {} blocking issues, {}", first, last, trend))
+}
+
+/// Running cost and agent time for the header.
+///
+/// Returns `None` until an agent has reported usage. Agents that don't report
+/// cost (e.g., Codex) only contribute time, so the cost shows "n/a" until one does.
+fn format_usage(cost_usd: Option<f64>, duration_ms: u64) -> Option<String> {
+    if cost_usd.is_none() && duration_ms == 0 {
+        return None;
+    }
+
+    let cost = cost_usd
+        .map(|c| format!("${:.2}", c))
+        .unwrap_or_else(|| "n/a".to_string());
+    Some(format!(
+        "Cost: {}, agent time {}",
+        cost,
+        format_duration(duration_ms / 1000)
+    ))
+}
+
+/// "42s" or "3m 05s"
+fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
 fn render_main_content(frame: &mut Frame, area: Rect, state: &mut AiRallyState) {
     // Add waiting prompt area when in clarification/permission/post-confirmation state
     let is_waiting = matches!(
@@ -100,8 +187,24 @@ fn render_main_content(frame: &mut Frame, area: Rect, state: &mut AiRallyState)
             | RallyState::WaitingForPermission
             | RallyState::WaitingForPostConfirmation
     );
+    // The reviewee's own account of what went wrong, when it reported one
+    let show_error_details = state.state == RallyState::Error && state.error_details.is_some();
+    let show_timing =
+        state.state == RallyState::Completed && !state.iteration_started_at.is_empty();
+    let show_files_changed =
+        state.state == RallyState::RevieweeFix && !state.files_changed.is_empty();
 
-    let chunks = if is_waiting {
+    let chunks = if is_waiting && state.pending_review_post.is_some() {
+        // The review confirmation lists every comment, so give it most of the space
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(20), // History
+                Constraint::Percentage(50), // Review to publish
+                Constraint::Min(6),         // Logs
+            ])
+            .split(area)
+    } else if is_waiting {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -110,6 +213,37 @@ fn render_main_content(frame: &mut Frame, area: Rect, state: &mut AiRallyState)
                 Constraint::Min(10),        // Logs
             ])
             .split(area)
+    } else if show_error_details {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(35), // History
+                Constraint::Percentage(30), // Error details
+                Constraint::Min(6),         // Logs
+            ])
+            .split(area)
+    } else if show_timing {
+        // One row per iteration plus the total, inside the borders
+        let rows = state.iteration_started_at.len().min(8) as u16 + 3;
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40), // History
+                Constraint::Length(rows),   // Timing
+                Constraint::Min(6),         // Logs
+            ])
+            .split(area)
+    } else if show_files_changed {
+        // One row per file, inside the borders
+        let rows = state.files_changed.len().min(8) as u16 + 2;
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40), // History
+                Constraint::Length(rows),   // Files changed
+                Constraint::Min(6),         // Logs
+            ])
+            .split(area)
     } else {
         Layout::default()
             .direction(Direction::Vertical)
@@ -125,12 +259,100 @@ fn render_main_content(frame: &mut Frame, area: Rect, state: &mut AiRallyState)
     if is_waiting {
         render_waiting_prompt(frame, chunks[1], state);
         render_logs(frame, chunks[2], state);
+    } else if show_error_details {
+        render_error_details(frame, chunks[1], state);
+        render_logs(frame, chunks[2], state);
+    } else if show_timing {
+        render_timing(frame, chunks[1], state);
+        render_logs(frame, chunks[2], state);
+    } else if show_files_changed {
+        render_files_changed(frame, chunks[1], state);
+        render_logs(frame, chunks[2], state);
     } else {
         render_logs(frame, chunks[1], state);
     }
 }
 
+/// Failure reason reported by the reviewee, scrollable with Ctrl-d/u
+fn render_error_details(frame: &mut Frame, area: Rect, state: &AiRallyState) {
+    let details = state.error_details.as_deref().unwrap_or_default();
+    let paragraph = Paragraph::new(replace_emoji_shortcodes(details))
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .scroll((state.error_details_scroll, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Error Details (Ctrl-d/u: scroll) ")
+                .border_style(Style::default().fg(Color::Red)),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+/// Wall-clock time of each iteration and of the whole rally
+fn render_timing(frame: &mut Frame, area: Rect, state: &AiRallyState) {
+    let durations = state.iteration_durations();
+    // Only the latest iterations fit when there were many
+    let visible = area.height.saturating_sub(3) as usize;
+    let mut lines: Vec<Line> = durations[durations.len().saturating_sub(visible)..]
+        .iter()
+        .map(|(iteration, duration)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("Iteration {:<4}", iteration),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(format_duration(duration.as_secs())),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:<14}", "Total"),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format_duration(state.total_elapsed().as_secs()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Timing ")
+            .border_style(Style::default().fg(Color::Green)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+/// Files the reviewee has written so far in this turn, updated as it reports them
+fn render_files_changed(frame: &mut Frame, area: Rect, state: &AiRallyState) {
+    // The latest writes when there are more files than rows
+    let visible = area.height.saturating_sub(2) as usize;
+    let files = &state.files_changed;
+    let lines: Vec<Line> = files[files.len().saturating_sub(visible)..]
+        .iter()
+        .map(|path| Line::from(Span::raw(path.display().to_string())))
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Files changed this turn ({}) ", files.len()))
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
 fn render_waiting_prompt(frame: &mut Frame, area: Rect, state: &AiRallyState) {
+    if state.state == RallyState::WaitingForPostConfirmation {
+        if let Some(ref info) = state.pending_review_post {
+            render_review_post_prompt(frame, area, info, state);
+            return;
+        }
+    }
+
     let (title, content, help) = match state.state {
         RallyState::WaitingForClarification => {
             let question = state
@@ -140,7 +362,7 @@ fn render_waiting_prompt(frame: &mut Frame, area: Rect, state: &AiRallyState) {
             (
                 " Clarification Required ",
                 format!("Question: {}", question),
-                "Press 'y' to open editor and respond, 'n' to skip, 'q' to abort",
+                "Press 'y' to answer, 'e' to answer in $EDITOR, 'n' to skip, 'q' to abort",
             )
         }
         RallyState::WaitingForPermission => {
@@ -156,17 +378,7 @@ fn render_waiting_prompt(frame: &mut Frame, area: Rect, state: &AiRallyState) {
             )
         }
         RallyState::WaitingForPostConfirmation => {
-            if let Some(ref info) = state.pending_review_post {
-                let summary = truncate_string(&info.summary, 120);
-                (
-                    " Review Post Confirmation ",
-                    format!(
-                        "Action: {}\nSummary: {}\nComments: {}",
-                        info.action, summary, info.comment_count
-                    ),
-                    "Press 'y' to post to PR, 'n' to skip, 'q' to abort",
-                )
-            } else if let Some(ref info) = state.pending_fix_post {
+            if let Some(ref info) = state.pending_fix_post {
                 let summary = truncate_string(&info.summary, 120);
                 let files_display = if info.files_modified.len() <= 5 {
                     info.files_modified.join(", ")
@@ -210,6 +422,97 @@ fn render_waiting_prompt(frame: &mut Frame, area: Rect, state: &AiRallyState) {
     frame.render_widget(prompt, area);
 }
 
+fn render_review_post_prompt(
+    frame: &mut Frame,
+    area: Rect,
+    info: &ReviewPostInfo,
+    state: &AiRallyState,
+) {
+    let mut lines = review_post_lines(info, state.review_post_selection);
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::styled(
+        "Press 'y' to publish, Tab to select, 'e' to edit the selection, 'n' to discard, 'q' to abort",
+        Style::default().fg(Color::Yellow),
+    )]));
+
+    let title = if state.review_post_edited {
+        " Publish Review? (edited) "
+    } else {
+        " Publish Review? "
+    };
+    let prompt = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Magenta)),
+    );
+
+    frame.render_widget(prompt, area);
+}
+
+/// Full summary and one line per inline comment of the review awaiting confirmation.
+/// The item selected for editing (`None` = summary) is shown reversed.
+fn review_post_lines(info: &ReviewPostInfo, selected: Option<usize>) -> Vec<Line<'static>> {
+    let highlight = |line: Line<'static>, is_selected: bool| {
+        if is_selected {
+            line.patch_style(Style::default().add_modifier(Modifier::REVERSED))
+        } else {
+            line
+        }
+    };
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Action: ", Style::default().fg(Color::Gray)),
+        Span::styled(
+            info.action.clone(),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ])];
+
+    lines.push(highlight(
+        Line::from(Span::styled("Summary:", Style::default().fg(Color::Gray))),
+        selected.is_none(),
+    ));
+    for text in replace_emoji_shortcodes(&info.summary).lines() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", text),
+            Style::default().fg(Color::White),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    let mut header = vec![Span::styled(
+        format!("Comments ({})", info.comment_count),
+        Style::default().fg(Color::Gray),
+    )];
+    let counts = severity_count_spans(&info.comments);
+    if !counts.is_empty() {
+        header.push(Span::raw(": "));
+        header.extend(counts);
+    }
+    lines.push(Line::from(header));
+
+    for (i, comment) in info.comments.iter().enumerate() {
+        let first_line = comment.body.lines().next().unwrap_or_default();
+        let line = Line::from(vec![
+            Span::styled(
+                format!("  [{}] ", comment.severity.label()),
+                Style::default().fg(severity_color(comment.severity)),
+            ),
+            Span::styled(
+                format!("{}:{} ", comment.path, comment.line),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(replace_emoji_shortcodes(first_line)),
+        ]);
+        lines.push(highlight(line, selected == Some(i)));
+    }
+
+    lines
+}
+
 fn render_history(frame: &mut Frame, area: Rect, state: &AiRallyState) {
     let visible_height = area.height.saturating_sub(2) as usize;
 
@@ -240,6 +543,20 @@ fn render_history(frame: &mut Frame, area: Rect, state: &AiRallyState) {
                         color,
                     )
                 }
+                crate::ai::orchestrator::RallyEvent::ReviewCommentStreamed(comment) => (
+                    "Comment".to_string(),
+                    truncate_string(
+                        &format!(
+                            "[{}] {}:{} {}",
+                            comment.severity.label(),
+                            comment.path,
+                            comment.line,
+                            comment.body
+                        ),
+                        60,
+                    ),
+                    severity_color(comment.severity),
+                ),
                 crate::ai::orchestrator::RallyEvent::FixCompleted(fix) => {
                     let status_text = match fix.status {
                         RevieweeStatus::Completed => "COMPLETED",
@@ -275,25 +592,80 @@ fn render_history(frame: &mut Frame, area: Rect, state: &AiRallyState) {
                     truncate_string(summary, 60),
                     Color::Green,
                 ),
+                crate::ai::orchestrator::RallyEvent::MergeOutlook { unblocks, detail } => (
+                    "MERGE".to_string(),
+                    truncate_string(detail, 60),
+                    if *unblocks {
+                        Color::Green
+                    } else {
+                        Color::Yellow
+                    },
+                ),
+                crate::ai::orchestrator::RallyEvent::MaxIterationsReached(summary) => (
+                    "NOT APPROVED".to_string(),
+                    truncate_string(summary, 60),
+                    Color::Yellow,
+                ),
                 crate::ai::orchestrator::RallyEvent::Error(e) => {
                     ("ERROR".to_string(), truncate_string(e, 60), Color::Red)
                 }
+                crate::ai::orchestrator::RallyEvent::PushBlocked(target) => (
+                    "PUSH BLOCKED".to_string(),
+                    truncate_string(target, 60),
+                    Color::Red,
+                ),
                 _ => return None,
             };
 
-            Some(ListItem::new(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     format!("{}: ", prefix),
                     Style::default().fg(color).add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(content, Style::default().fg(Color::White)),
-            ])))
+            ];
+            // Review weight at a glance: "(2 critical, 5 major)"
+            if let crate::ai::orchestrator::RallyEvent::ReviewCompleted(review) = event {
+                let counts = severity_count_spans(&review.comments);
+                if !counts.is_empty() {
+                    spans.push(Span::raw(" ("));
+                    spans.extend(counts);
+                    spans.push(Span::raw(")"));
+                }
+            }
+
+            // Size of the fix: total plus one line per file
+            let mut lines = vec![];
+            if let crate::ai::orchestrator::RallyEvent::FixCompleted(fix) = event {
+                if !fix.diff_stats.is_empty() {
+                    let additions: u32 = fix.diff_stats.iter().map(|s| s.additions).sum();
+                    let deletions: u32 = fix.diff_stats.iter().map(|s| s.deletions).sum();
+                    spans.push(Span::raw(" ("));
+                    spans.extend(diff_stat_spans(additions, deletions));
+                    spans.push(Span::raw(")"));
+                    lines.extend(fix.diff_stats.iter().map(|stat| {
+                        let mut file_spans = vec![Span::styled(
+                            format!("    {} ", stat.path),
+                            Style::default().fg(Color::Gray),
+                        )];
+                        file_spans.extend(diff_stat_spans(stat.additions, stat.deletions));
+                        Line::from(file_spans)
+                    }));
+                }
+            }
+            lines.insert(0, Line::from(spans));
+
+            Some(ListItem::new(lines))
         })
         .collect();
 
-    // Auto-scroll to show latest history entries
-    let total = items.len();
-    let scroll_offset = total.saturating_sub(visible_height);
+    // Auto-scroll to show latest history entries (fix entries can span several lines)
+    let mut height: usize = items.iter().map(ListItem::height).sum();
+    let mut scroll_offset = 0;
+    while height > visible_height && scroll_offset < items.len() {
+        height -= items[scroll_offset].height();
+        scroll_offset += 1;
+    }
     let visible_items: Vec<ListItem> = items.into_iter().skip(scroll_offset).collect();
 
     let list = List::new(visible_items).block(
@@ -306,6 +678,15 @@ fn render_history(frame: &mut Frame, area: Rect, state: &AiRallyState) {
     frame.render_widget(list, area);
 }
 
+/// "+12 -3" in diff colors
+fn diff_stat_spans(additions: u32, deletions: u32) -> Vec<Span<'static>> {
+    vec![
+        Span::styled(format!("+{}", additions), Style::default().fg(Color::Green)),
+        Span::raw(" "),
+        Span::styled(format!("-{}", deletions), Style::default().fg(Color::Red)),
+    ]
+}
+
 fn render_logs(frame: &mut Frame, area: Rect, state: &mut AiRallyState) {
     let visible_height = area.height.saturating_sub(2) as usize; // subtract borders
     state.last_visible_log_height = visible_height;
@@ -334,7 +715,7 @@ fn render_logs(frame: &mut Frame, area: Rect, state: &mut AiRallyState) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(format!(
-            " Logs ({}/{}) [j/k/↑↓: select, Enter: detail] ",
+            " Logs ({}/{}) [j/k/↑↓: select, PgUp/PgDn: page, Enter: detail] ",
             scroll_offset.saturating_add(visible_height).min(total_logs),
             total_logs
         ))
@@ -458,38 +839,140 @@ fn render_log_detail_modal(frame: &mut Frame, state: &AiRallyState) {
     };
 
     let title = format!(" {} - {} ", type_label, entry.timestamp);
+    let hint = if entry.review_comment.is_some() && state.state.is_active() {
+        " f: Send only this comment to reviewee | Esc/Enter/q: close "
+    } else {
+        " Press Esc/Enter/q to close "
+    };
 
     // Build content with word wrap
-    let content = Paragraph::new(entry.message.clone())
+    let content = Paragraph::new(replace_emoji_shortcodes(&entry.message))
         .wrap(Wrap { trim: false })
         .style(Style::default().fg(Color::White))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(title)
-                .title_bottom(Line::from(" Press Esc/Enter/q to close ").centered())
+                .title_bottom(Line::from(hint).centered())
                 .border_style(Style::default().fg(color)),
         );
 
     frame.render_widget(content, modal_area);
 }
 
+/// Popup to type the answer to the reviewee's question
+fn render_clarification_input(frame: &mut Frame, state: &AiRallyState, input: &TextArea) {
+    let area = frame.area();
+    let modal_width = (area.width as f32 * 0.7) as u16;
+    let modal_height = (area.height as f32 * 0.5) as u16;
+    let modal_area = Rect::new(
+        area.width.saturating_sub(modal_width) / 2,
+        area.height.saturating_sub(modal_height) / 2,
+        modal_width,
+        modal_height,
+    );
+    frame.render_widget(Clear, modal_area);
+
+    let question = state
+        .pending_question
+        .as_deref()
+        .unwrap_or("(No question provided)");
+    let question = replace_emoji_shortcodes(question);
+    // Leave at least 3 rows (one line plus borders) for the answer
+    let question_height = (question.lines().count() as u16 + 2)
+        .min(modal_area.height.saturating_sub(3) / 2)
+        .max(3);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(question_height), Constraint::Min(3)])
+        .split(modal_area);
+
+    let question = Paragraph::new(question)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Reviewee's question ")
+                .border_style(Style::default().fg(Color::Magenta)),
+        );
+    frame.render_widget(question, chunks[0]);
+
+    let title = format!(
+        " Your answer ({}: send, Esc: back) ",
+        input.submit_key_display()
+    );
+    input.render_with_title(frame, chunks[1], &title, "Type your answer...");
+}
+
+/// Debug pane with the last agent's raw `result` JSON, pretty-printed
+fn render_raw_result_modal(frame: &mut Frame, state: &AiRallyState) {
+    let area = frame.area();
+    let modal_width = (area.width as f32 * 0.8) as u16;
+    let modal_height = (area.height as f32 * 0.8) as u16;
+    let modal_area = Rect::new(
+        area.width.saturating_sub(modal_width) / 2,
+        area.height.saturating_sub(modal_height) / 2,
+        modal_width,
+        modal_height,
+    );
+    frame.render_widget(Clear, modal_area);
+
+    let text = match state.last_raw_result {
+        Some(ref result) => {
+            serde_json::to_string_pretty(result).unwrap_or_else(|_| result.to_string())
+        }
+        None => "No agent result received yet".to_string(),
+    };
+    // Keep the last line reachable without scrolling past it
+    let max_scroll = (text.lines().count() as u16).saturating_sub(1);
+    let content = Paragraph::new(text)
+        .scroll((state.raw_result_scroll.min(max_scroll), 0))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Debug: last agent result (raw JSON) ")
+                .title_bottom(Line::from(" j/k/PgDn/PgUp: scroll | F12/Esc/q: close ").centered())
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+    frame.render_widget(content, modal_area);
+}
+
 fn render_status_bar(frame: &mut Frame, area: Rect, state: &AiRallyState) {
-    let help_text = if state.showing_log_detail {
-        "Esc/Enter/q: Close detail"
+    let help_text = if state.showing_raw_result {
+        "j/k/PgDn/PgUp: Scroll | F12/Esc/q: Close debug pane"
+    } else if state.showing_log_detail {
+        let is_comment = state
+            .selected_log_index
+            .and_then(|idx| state.logs.get(idx))
+            .is_some_and(|entry| entry.review_comment.is_some());
+        if is_comment && state.state.is_active() {
+            "f: Send to reviewee | y: Copy | Esc/Enter/q: Close detail"
+        } else {
+            "y: Copy | Esc/Enter/q: Close detail"
+        }
     } else {
         match state.state {
             RallyState::WaitingForClarification => {
-                "y: Open editor | n: Skip | j/k/↑↓: select | Enter: detail | q: Abort"
+                "y: Answer | e: Answer in editor | n: Skip | j/k/↑↓: select | Enter: detail | q: Abort"
             }
             RallyState::WaitingForPermission => {
                 "y: Approve | n: Deny | j/k/↑↓: select | Enter: detail | q: Abort"
             }
+            RallyState::WaitingForPostConfirmation if state.pending_review_post.is_some() => {
+                "y: Publish | Tab: select item | e: Edit | n: Discard | j/k/↑↓: select | Enter: detail | q: Abort"
+            }
             RallyState::WaitingForPostConfirmation => {
                 "y: Post to PR | n: Skip | j/k/↑↓: select | Enter: detail | q: Abort"
             }
-            RallyState::Completed => "j/k/↑↓: select | Enter: detail | b: Background | q: Close",
+            RallyState::Completed => {
+                "y: Copy summary | j/k/↑↓: select | Enter: detail | b: Background | q: Close"
+            }
             RallyState::Aborted => "j/k/↑↓: select | Enter: detail | b: Background | q: Close",
+            RallyState::Error if state.error_details.is_some() => {
+                "r: Retry | Ctrl-d/u: scroll error | j/k/↑↓: select | Enter: detail | b: Background | q: Close"
+            }
             RallyState::Error => {
                 "r: Retry | j/k/↑↓: select | Enter: detail | b: Background | q: Close"
             }
@@ -510,12 +993,169 @@ fn render_status_bar(frame: &mut Frame, area: Rect, state: &AiRallyState) {
     frame.render_widget(status_bar, area);
 }
 
-fn truncate_string(s: &str, max_chars: usize) -> String {
-    let char_count = s.chars().count();
-    if char_count <= max_chars {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_chars.saturating_sub(3)).collect();
-        format!("{}...", truncated)
+/// Truncate agent text for one-line display, rendering emoji shortcodes first
+fn truncate_string(s: &str, max_width: usize) -> String {
+    truncate_to_width(&replace_emoji_shortcodes(s), max_width)
+}
+
+/// Number of comments per severity, most urgent first, omitting severities with none
+fn severity_counts(comments: &[ReviewComment]) -> Vec<(CommentSeverity, usize)> {
+    CommentSeverity::ALL
+        .into_iter()
+        .map(|severity| {
+            let count = comments.iter().filter(|c| c.severity == severity).count();
+            (severity, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// "2 critical, 5 major, 1 suggestion" with each count in its severity color
+fn severity_count_spans(comments: &[ReviewComment]) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (i, (severity, count)) in severity_counts(comments).into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(", "));
+        }
+        spans.push(Span::styled(
+            format!("{} {}", count, severity.label()),
+            Style::default().fg(severity_color(severity)),
+        ));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    fn comment(severity: CommentSeverity) -> ReviewComment {
+        ReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 1,
+            start_line: None,
+            body: "issue".to_string(),
+            severity,
+        }
+    }
+
+    #[test]
+    fn test_truncate_string_multibyte() {
+        // Cut points that would land inside a multi-byte character must not panic
+        let summary = "Corrigé la fonction réseau 🚀 — détails à suivre";
+        for width in 0..=summary.len() {
+            let truncated = truncate_string(summary, width);
+            assert!(
+                truncated.width() <= width.max(3),
+                "{truncated:?} at {width}"
+            );
+        }
+        assert_eq!(truncate_string("Corrigé 🚀 ok", 11), "Corrigé ...");
+        assert_eq!(truncate_string(":rocket: déployé", 12), "🚀 déployé");
+    }
+
+    #[test]
+    fn test_severity_count_spans() {
+        let comments = vec![
+            comment(CommentSeverity::Major),
+            comment(CommentSeverity::Suggestion),
+            comment(CommentSeverity::Critical),
+            comment(CommentSeverity::Major),
+        ];
+        let spans = severity_count_spans(&comments);
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "1 critical, 2 major, 1 suggestion");
+        assert_eq!(spans[0].style.fg, Some(Color::Red));
+
+        assert!(severity_count_spans(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_review_post_lines() {
+        let info = ReviewPostInfo {
+            action: "RequestChanges".to_string(),
+            summary: "Needs work\nSee comments".to_string(),
+            comment_count: 2,
+            comments: vec![
+                ReviewComment {
+                    path: "src/a.rs".to_string(),
+                    line: 3,
+                    start_line: None,
+                    body: "Unchecked unwrap\nThis panics on empty input".to_string(),
+                    severity: CommentSeverity::Major,
+                },
+                comment(CommentSeverity::Suggestion),
+            ],
+        };
+        let lines = review_post_lines(&info, Some(1));
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "Action: RequestChanges",
+                "Summary:",
+                "  Needs work",
+                "  See comments",
+                "",
+                "Comments (2): 1 major, 1 suggestion",
+                "  [major] src/a.rs:3 Unchecked unwrap",
+                "  [suggestion] src/lib.rs:1 issue",
+            ]
+        );
+
+        let reversed = |line: &Line| line.style.add_modifier.contains(Modifier::REVERSED);
+        assert!(reversed(&lines[7]));
+        assert!(!reversed(&lines[1]));
+        assert!(reversed(&review_post_lines(&info, None)[1]));
+    }
+
+    #[test]
+    fn test_format_blocking_trend_empty() {
+        assert_eq!(format_blocking_trend(&[]), None);
+    }
+
+    #[test]
+    fn test_format_blocking_trend_first_iteration() {
+        assert_eq!(
+            format_blocking_trend(&[3]).as_deref(),
+            Some("3 blocking issues")
+        );
+    }
+
+    #[test]
+    fn test_format_blocking_trend_sequence() {
+        assert_eq!(
+            format_blocking_trend(&[3, 2, 1]).as_deref(),
+            Some("3 → 1 blocking issues, trending down")
+        );
+        assert_eq!(
+            format_blocking_trend(&[1, 0, 2]).as_deref(),
+            Some("1 → 2 blocking issues, trending up")
+        );
+        assert_eq!(
+            format_blocking_trend(&[4, 2, 2]).as_deref(),
+            Some("4 → 2 blocking issues, no change")
+        );
+    }
+
+    #[test]
+    fn test_format_usage_hidden_until_reported() {
+        assert_eq!(format_usage(None, 0), None);
+    }
+
+    #[test]
+    fn test_format_usage() {
+        assert_eq!(
+            format_usage(Some(0.4213), 42_500).as_deref(),
+            Some("Cost: $0.42, agent time 42s")
+        );
+        assert_eq!(
+            format_usage(None, 192_000).as_deref(),
+            Some("Cost: n/a, agent time 3m 12s")
+        );
     }
 }
@@ -10,7 +10,10 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthChar;
 
-use super::common::render_rally_status_bar;
+use super::common::{render_rally_status_bar, severity_color};
+use super::keymap::{footer_text, key_hints, HelpMode};
+use super::text::{replace_emoji_shortcodes, truncate_to_width};
+use crate::ai::adapter::CommentSeverity;
 use crate::app::{App, CommentTab};
 
 /// Wrap text to fit within the specified width, handling multibyte characters
@@ -97,11 +100,9 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Footer
     let footer_chunk_idx = if has_rally { 3 } else { 2 };
-    let footer_text = match app.comment_tab {
-        CommentTab::Review => "j/k/↑↓: move | Enter: jump to file | [/]: switch tab | q: back",
-        CommentTab::Discussion => "j/k/↑↓: move | Enter: view detail | [/]: switch tab | q: back",
-    };
-    let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL));
+    let help_text = footer_text(&key_hints(HelpMode::CommentList, app));
+    let footer_line = super::footer::build_footer_line(app, &help_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
 
@@ -207,6 +208,16 @@ fn render_comment_list_generic<T, F>(
 
 fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let review_count = app.review_comments.as_ref().map(|c| c.len()).unwrap_or(0);
+    let review_label = if app.comment_severity_filter.is_active() {
+        format!(
+            "Review Comments ({} of {}, {})",
+            app.visible_review_comment_indices().len(),
+            review_count,
+            app.comment_severity_filter.label()
+        )
+    } else {
+        format!("Review Comments ({})", review_count)
+    };
     let discussion_count = app
         .discussion_comments
         .as_ref()
@@ -241,8 +252,8 @@ fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
         Span::raw(" "),
         Span::styled(
             format!(
-                "[Review Comments ({})]{}",
-                review_count,
+                "[{}]{}",
+                review_label,
                 loading_indicator(app.comments_loading)
             ),
             review_style,
@@ -266,19 +277,67 @@ fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
 fn render_review_comments(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     use crate::github::comment::ReviewComment;
 
+    // Render only the comments that pass the severity filter; selected_comment
+    // stays an index into the full list so jump_to_comment keeps working
+    let visible = app.visible_review_comment_indices();
+    let selected = visible
+        .iter()
+        .position(|&i| i == app.selected_comment)
+        .unwrap_or(0);
+    let filtered: Option<Vec<ReviewComment>> = app
+        .review_comments
+        .as_ref()
+        .map(|comments| visible.iter().map(|&i| comments[i].clone()).collect());
+    let label = if app.comment_severity_filter.is_active() {
+        "matching review comments"
+    } else {
+        "review comments"
+    };
+
+    // Split off a detail pane for the selected comment once there is something to show
+    let list_area = if filtered.as_ref().is_some_and(|c| !c.is_empty()) {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        render_review_comment_detail(frame, app, panes[1]);
+        panes[0]
+    } else {
+        area
+    };
+
+    let expanded = &app.expanded_comment_ids;
+    let marked = &app.marked_comment_ids;
     render_comment_list_generic(
         frame,
-        area,
-        app.review_comments.as_deref(),
+        list_area,
+        filtered.as_deref(),
         app.comments_loading,
-        app.selected_comment,
+        selected,
         &mut app.comment_list_scroll_offset,
-        "review comments",
+        label,
         |comment: &ReviewComment, _i: usize, is_selected: bool, body_width: usize| {
             let prefix = if is_selected { "> " } else { "  " };
             let line_info = comment.line.map(|l| format!(":{}", l)).unwrap_or_default();
-            let header_line = Line::from(vec![
-                Span::raw(prefix),
+            let mut header_spans = vec![Span::raw(prefix)];
+            // Marked with m for the reviewee
+            if marked.contains(&comment.id) {
+                header_spans.push(Span::styled(
+                    "✓ ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if let Some(severity) = CommentSeverity::from_posted_comment(&comment.body) {
+                header_spans.push(Span::styled(
+                    format!("[{}] ", severity.label()),
+                    Style::default()
+                        .fg(severity_color(severity))
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            header_spans.extend([
                 Span::styled(
                     format!("@{}", comment.user.login),
                     Style::default().fg(Color::Cyan),
@@ -289,14 +348,14 @@ fn render_review_comments(frame: &mut Frame, app: &mut App, area: ratatui::layou
                     Style::default().fg(Color::Green),
                 ),
             ]);
-
-            let body_text: String = comment.body.lines().collect::<Vec<_>>().join(" ");
-            let wrapped_lines = wrap_text(&body_text, body_width);
+            let header_line = Line::from(header_spans);
 
             let mut lines = vec![header_line];
-            for wrapped_line in wrapped_lines {
-                lines.push(Line::from(vec![Span::raw("    "), Span::raw(wrapped_line)]));
-            }
+            lines.extend(comment_preview_lines(
+                &comment.body,
+                body_width,
+                expanded.contains(&comment.id),
+            ));
             lines.push(Line::from(""));
 
             ListItem::new(lines)
@@ -304,9 +363,135 @@ fn render_review_comments(frame: &mut Frame, app: &mut App, area: ratatui::layou
     );
 }
 
+/// Full body of the selected review comment, word-wrapped and scrollable with Ctrl+d/u
+fn render_review_comment_detail(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let Some(comment) = app
+        .review_comments
+        .as_ref()
+        .and_then(|comments| comments.get(app.selected_comment))
+    else {
+        return;
+    };
+
+    let line_info = comment.line.map(|l| format!(":{}", l)).unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{}{}", comment.path, line_info),
+            Style::default().fg(Color::Green),
+        )),
+        Line::from(Span::styled(
+            format!("@{}", comment.user.login),
+            Style::default().fg(Color::Cyan),
+        )),
+        Line::from(""),
+    ];
+    lines.extend(comment_body_lines(&comment.body));
+    // Wrapped here rather than by the Paragraph, so the scroll clamp and the position
+    // in the title count the rows actually on screen
+    let lines = wrap_lines(lines, area.width.saturating_sub(2) as usize);
+
+    // Keep at least the last line on screen
+    app.review_comment_detail_scroll = app
+        .review_comment_detail_scroll
+        .min(lines.len().saturating_sub(1));
+    let scroll = app.review_comment_detail_scroll;
+
+    let title = if scroll > 0 {
+        format!("Comment (line {}/{})", scroll + 1, lines.len())
+    } else {
+        "Comment".to_string()
+    };
+    let detail = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .scroll((scroll.min(u16::MAX as usize) as u16, 0));
+    frame.render_widget(detail, area);
+}
+
+/// Split each line into rows of at most `width` columns, keeping its style
+fn wrap_lines(lines: Vec<Line<'static>>, width: usize) -> Vec<Line<'static>> {
+    lines
+        .into_iter()
+        .flat_map(|line| {
+            let style = line.spans.first().map_or(line.style, |span| span.style);
+            let text: String = line
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect();
+            wrap_text(&text, width)
+                .into_iter()
+                .map(move |row| Line::from(Span::styled(row, style)))
+        })
+        .collect()
+}
+
+/// Render a comment body as lightly styled markdown.
+///
+/// Fenced code blocks keep their indentation verbatim (no emoji replacement) and
+/// `suggestion`/`diff` blocks color their lines like a diff; headings are bold and
+/// quotes are dimmed.
+fn comment_body_lines(body: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    // Info string of the open code fence, if any
+    let mut fence: Option<String> = None;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            fence = match fence {
+                Some(_) => None,
+                None => Some(info.trim().to_string()),
+            };
+            lines.push(Line::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(Color::DarkGray),
+            )));
+            continue;
+        }
+
+        let style = match fence.as_deref() {
+            Some("suggestion") => Style::default().fg(Color::Green),
+            Some("diff") if line.starts_with('+') => Style::default().fg(Color::Green),
+            Some("diff") if line.starts_with('-') => Style::default().fg(Color::Red),
+            Some(_) => Style::default().fg(Color::Yellow),
+            None if trimmed.starts_with('#') => Style::default().add_modifier(Modifier::BOLD),
+            None if trimmed.starts_with('>') => Style::default().fg(Color::DarkGray),
+            None => Style::default(),
+        };
+        let text = if fence.is_some() {
+            line.to_string()
+        } else {
+            replace_emoji_shortcodes(line)
+        };
+        lines.push(Line::from(Span::styled(text, style)));
+    }
+
+    lines
+}
+
+/// Body lines of a list entry, indented under its header: a two-line preview, or
+/// every line of the body once the comment is expanded with Space/o
+fn comment_preview_lines(body: &str, body_width: usize, expanded: bool) -> Vec<Line<'static>> {
+    let wrapped_lines = if expanded {
+        body.lines()
+            .map(|line| replace_emoji_shortcodes(line.strip_suffix('\r').unwrap_or(line)))
+            .flat_map(|line| wrap_text(&line, body_width))
+            .collect()
+    } else {
+        let body_text = replace_emoji_shortcodes(&body.lines().collect::<Vec<_>>().join(" "));
+        let truncated = truncate_to_width(&body_text, body_width * 2);
+        wrap_text(&truncated, body_width)
+    };
+    wrapped_lines
+        .into_iter()
+        .map(|wrapped_line| Line::from(vec![Span::raw("    "), Span::raw(wrapped_line)]))
+        .collect()
+}
+
 fn render_discussion_comments(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     use crate::github::comment::DiscussionComment;
 
+    let expanded = &app.expanded_comment_ids;
     render_comment_list_generic(
         frame,
         area,
@@ -335,19 +520,12 @@ fn render_discussion_comments(frame: &mut Frame, app: &mut App, area: ratatui::l
                 Span::styled(date.to_string(), Style::default().fg(Color::DarkGray)),
             ]);
 
-            // Truncate body for list view
-            let body_text: String = comment.body.lines().collect::<Vec<_>>().join(" ");
-            let truncated = if body_text.len() > body_width * 2 {
-                format!("{}...", &body_text[..body_width * 2])
-            } else {
-                body_text
-            };
-            let wrapped_lines = wrap_text(&truncated, body_width);
-
             let mut lines = vec![header_line];
-            for wrapped_line in wrapped_lines {
-                lines.push(Line::from(vec![Span::raw("    "), Span::raw(wrapped_line)]));
-            }
+            lines.extend(comment_preview_lines(
+                &comment.body,
+                body_width,
+                expanded.contains(&comment.id),
+            ));
             lines.push(Line::from(""));
 
             ListItem::new(lines)
@@ -412,7 +590,7 @@ fn render_discussion_detail(frame: &mut Frame, app: &App) {
         .lines()
         .skip(app.discussion_comment_detail_scroll)
         .take(content_height)
-        .map(|line| Line::from(line.to_string()))
+        .map(|line| Line::from(replace_emoji_shortcodes(line)))
         .collect();
 
     let total_lines = comment.body.lines().count();
@@ -446,3 +624,82 @@ fn render_discussion_detail(frame: &mut Frame, app: &App) {
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_comment_body_lines_preserves_code_blocks() {
+        let body = "## Why :bug:\r\n\r\nUse this:\r\n```rust\r\n    let x = \":bug:\";\r\n```\r\n```suggestion\r\nfixed();\r\n```";
+        let lines = comment_body_lines(body);
+        let texts: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "## Why 🐛",
+                "",
+                "Use this:",
+                "```rust",
+                "    let x = \":bug:\";",
+                "```",
+                "```suggestion",
+                "fixed();",
+                "```",
+            ]
+        );
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+        assert_eq!(lines[4].spans[0].style.fg, Some(Color::Yellow));
+        assert_eq!(lines[7].spans[0].style.fg, Some(Color::Green));
+        assert_eq!(lines[2].spans[0].style.fg, None);
+    }
+
+    #[test]
+    fn test_wrap_lines_counts_rows_on_screen() {
+        let lines = vec![
+            Line::from(Span::styled(
+                "a".repeat(25),
+                Style::default().fg(Color::Green),
+            )),
+            Line::from(""),
+            Line::from("short"),
+        ];
+        let wrapped = wrap_lines(lines, 10);
+        assert_eq!(wrapped.len(), 5);
+        assert_eq!(line_text(&wrapped[2]), "aaaaa");
+        assert_eq!(wrapped[2].spans[0].style.fg, Some(Color::Green));
+        assert_eq!(line_text(&wrapped[4]), "short");
+    }
+
+    #[test]
+    fn test_comment_preview_lines_expand_full_body() {
+        let body = "First line\r\n\r\nSecond paragraph that is long";
+        let collapsed: Vec<String> = comment_preview_lines(body, 20, false)
+            .iter()
+            .map(line_text)
+            .collect();
+        assert_eq!(collapsed.len(), 2);
+        assert!(collapsed[0].starts_with("    First line"));
+
+        let expanded: Vec<String> = comment_preview_lines(body, 20, true)
+            .iter()
+            .map(line_text)
+            .collect();
+        assert_eq!(
+            expanded,
+            vec![
+                "    First line",
+                "    ",
+                "    Second paragraph tha",
+                "    t is long",
+            ]
+        );
+    }
+}
@@ -5,9 +5,20 @@ use ratatui::{
     Frame,
 };
 
+use crate::ai::adapter::CommentSeverity;
 use crate::ai::RallyState;
 use crate::app::{App, DataState};
 
+/// Color for a review comment severity badge (shared between comment_list and ai_rally)
+pub fn severity_color(severity: CommentSeverity) -> Color {
+    match severity {
+        CommentSeverity::Critical => Color::Red,
+        CommentSeverity::Major => Color::LightRed,
+        CommentSeverity::Minor => Color::Cyan,
+        CommentSeverity::Suggestion => Color::Gray,
+    }
+}
+
 /// Build PR info string for header display (shared between file_list and ai_rally)
 pub fn build_pr_info(app: &App) -> String {
     if app.is_local_mode() {
@@ -82,6 +93,7 @@ mod tests {
                     login: "alice".to_string(),
                 },
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
+                labels: vec![],
             }),
             files: vec![],
         };
@@ -11,13 +11,14 @@ use ratatui::{
 use syntect::easy::HighlightLines;
 
 use super::common::render_rally_status_bar;
+use super::keymap::{footer_text, key_hints, HelpMode};
 use crate::app::{
     hash_string, App, CachedDiffLine, DiffCache, InputMode, InternedSpan, LineInputContext,
 };
 use crate::diff::{classify_line, LineType};
 use crate::syntax::{
     apply_line_highlights, collect_line_highlights, collect_line_highlights_with_injections,
-    get_theme, highlight_code_line, syntax_for_file, Highlighter, ParserPool,
+    get_theme, highlight_code_line, syntax_for_file, syntax_set, Highlighter, ParserPool,
 };
 
 /// Build a plain DiffCache without syntax highlighting (diff coloring only).
@@ -469,9 +470,8 @@ fn build_lines_with_syntect(
     // <script> tag (which may not be included in the diff hunk) gets highlighted.
     if filename.ends_with(".vue") {
         if let Some(ref mut hl) = highlighter {
-            let ss = two_face::syntax::extra_newlines();
             // Process virtual script tag to enter JavaScript mode
-            let _ = hl.highlight_line("<script lang=\"ts\">\n", &ss);
+            let _ = hl.highlight_line("<script lang=\"ts\">\n", syntax_set());
         }
     }
 
@@ -851,15 +851,9 @@ fn highlight_or_fallback(
 }
 
 fn render_footer(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let help_text = if app.comment_panel_open {
-        "j/k/↑↓: scroll | n/N: jump | Tab: switch | r: reply | c: comment | s: suggest | ←/h: back | Esc/q: close"
-    } else if app.is_local_mode() {
-        "j/k/↑↓: move | Ctrl-d/u: page | ←/h/q: back"
-    } else {
-        "j/k/↑↓: move | n/N: next/prev comment | Enter: comments | Ctrl-d/u: page | ←/h/q: back"
-    };
+    let help_text = footer_text(&key_hints(HelpMode::DiffView, app));
 
-    let footer_line = super::footer::build_footer_line(app, help_text);
+    let footer_line = super::footer::build_footer_line(app, &help_text);
     let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, area);
 }
@@ -903,8 +897,8 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
                 Span::raw("")
             };
 
-            // Header: [>] @user (line N)
-            lines.push(Line::from(vec![
+            // Header: [>] @user (line N) [suggestion]
+            let mut header = Line::from(vec![
                 indicator,
                 Span::styled(
                     format!("@{}", comment.user.login),
@@ -914,7 +908,17 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
                     format!(" (line {})", comment.line.unwrap_or(0)),
                     Style::default().fg(Color::DarkGray),
                 ),
-            ]));
+            ]);
+            if crate::editor::suggestion_from_comment(comment).is_some() {
+                header.spans.push(Span::styled(
+                    format!(
+                        " [suggestion, {}: apply]",
+                        app.config.keybindings.apply_suggestion.display()
+                    ),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            lines.push(header);
 
             // Body
             for line in comment.body.lines() {
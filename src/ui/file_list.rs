@@ -10,8 +10,9 @@ use ratatui::{
 };
 
 use super::common::{build_pr_info, render_rally_status_bar};
+use super::keymap::{footer_text, key_hints, HelpMode};
 use crate::app::App;
-use crate::github::ChangedFile;
+use crate::github::{ChangedFile, ChecksSummary};
 
 pub fn render(frame: &mut Frame, app: &mut App) {
     let has_rally = app.has_background_rally();
@@ -36,10 +37,16 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         .split(frame.area());
 
     // Header
-    let pr_info = build_pr_info(app);
+    let mut header_spans = vec![Span::raw(build_pr_info(app))];
+    if let Some((pr_number, checks)) = app.ci_checks {
+        if app.pr_number == Some(pr_number) && !app.is_local_mode() {
+            header_spans.push(Span::raw("  "));
+            header_spans.extend(build_checks_spans(&checks));
+        }
+    }
 
-    let header =
-        Paragraph::new(pr_info).block(Block::default().borders(Borders::ALL).title("octorus"));
+    let header = Paragraph::new(Line::from(header_spans))
+        .block(Block::default().borders(Borders::ALL).title("octorus"));
     frame.render_widget(header, chunks[0]);
 
     // File list
@@ -94,27 +101,35 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Footer (dynamic based on rally state)
     let footer_chunk_idx = if has_rally { 3 } else { 2 };
-    let ai_rally_text = if app.has_background_rally() {
-        "A: Resume Rally"
-    } else {
-        "A: AI Rally"
-    };
-    let help_text = if app.is_local_mode() {
-        format!(
-            "j/k/↑↓: move | Enter/→/l: split view | {} | R: refresh | q: quit | ?: help",
-            ai_rally_text
-        )
-    } else {
-        format!(
-            "j/k/↑↓: move | Enter/→/l: split view | O: browser | a: approve | r: request changes | c: comment | C: comments | {} | R: refresh | q: quit | ?: help",
-            ai_rally_text
-        )
-    };
+    let help_text = footer_text(&key_hints(HelpMode::FileList, app));
     let footer_line = super::footer::build_footer_line(app, &help_text);
     let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
 
+/// Compact CI status for the header: "✓3 ✗1 ●2" (zero counts are left out)
+fn build_checks_spans(checks: &ChecksSummary) -> Vec<Span<'static>> {
+    let counts = [
+        ("✓", checks.passed, Color::Green),
+        ("✗", checks.failed, Color::Red),
+        ("●", checks.pending, Color::Yellow),
+    ];
+    let spans: Vec<Span<'static>> = counts
+        .into_iter()
+        .filter(|(_, count, _)| *count > 0)
+        .map(|(symbol, count, color)| {
+            Span::styled(format!("{}{} ", symbol, count), Style::default().fg(color))
+        })
+        .collect();
+    if spans.is_empty() {
+        return vec![Span::styled(
+            "no checks",
+            Style::default().fg(Color::DarkGray),
+        )];
+    }
+    spans
+}
+
 /// Loading状態の表示
 pub fn render_loading(frame: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -229,13 +244,22 @@ pub(crate) fn build_file_list_items<'a>(
                 _ => '?',
             };
 
+            // サブモジュールは行数ではなくポインタの移動を表示する
+            let stats = match file.submodule_change() {
+                Some(change) => Span::styled(
+                    format!(" {}", change.label()),
+                    Style::default().fg(Color::Magenta),
+                ),
+                None => Span::raw(format!(" +{} -{}", file.additions, file.deletions)),
+            };
+
             let line = Line::from(vec![
                 Span::styled(
                     format!("[{}] ", status_char),
                     Style::default().fg(status_color),
                 ),
                 Span::styled(&file.filename, style),
-                Span::raw(format!(" +{} -{}", file.additions, file.deletions)),
+                stats,
             ]);
 
             ListItem::new(line)
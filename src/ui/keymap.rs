@@ -0,0 +1,357 @@
+//! Keybinding table shared by the footers and the help popup.
+//!
+//! Every screen lists its keys here once; the footer shows the hints that have a
+//! short label and the help popup shows all of them with their descriptions.
+
+use crate::app::{App, AppState, CommentTab};
+
+/// Screen whose keys are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpMode {
+    PullRequestList,
+    FileList,
+    DiffView,
+    CommentList,
+    AiRally,
+}
+
+impl HelpMode {
+    pub fn from_state(state: AppState) -> Self {
+        match state {
+            AppState::PullRequestList => HelpMode::PullRequestList,
+            AppState::DiffView | AppState::SplitViewDiff | AppState::TextInput => {
+                HelpMode::DiffView
+            }
+            AppState::CommentList => HelpMode::CommentList,
+            AppState::AiRally => HelpMode::AiRally,
+            AppState::FileList
+            | AppState::SplitViewFileList
+            | AppState::PrDetails
+            | AppState::Help => HelpMode::FileList,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            HelpMode::PullRequestList => "Pull Request List",
+            HelpMode::FileList => "File List",
+            HelpMode::DiffView => "Diff View",
+            HelpMode::CommentList => "Comment List",
+            HelpMode::AiRally => "AI Rally",
+        }
+    }
+}
+
+/// One key (or key group) and what it does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyHint {
+    pub keys: String,
+    pub description: &'static str,
+    /// Short label for the footer; `None` keeps the hint to the help popup
+    pub footer: Option<&'static str>,
+}
+
+fn hint(keys: impl Into<String>, description: &'static str, footer: &'static str) -> KeyHint {
+    KeyHint {
+        keys: keys.into(),
+        description,
+        footer: Some(footer),
+    }
+}
+
+fn help_only(keys: impl Into<String>, description: &'static str) -> KeyHint {
+    KeyHint {
+        keys: keys.into(),
+        description,
+        footer: None,
+    }
+}
+
+/// Keys available in `mode`, given the current app state (local mode, open
+/// panels, comment tab, ...)
+pub fn key_hints(mode: HelpMode, app: &App) -> Vec<KeyHint> {
+    let kb = &app.config.keybindings;
+    let move_keys = format!("{}/{}/↑↓", kb.move_down.display(), kb.move_up.display());
+    let page_keys = format!("{}/{}", kb.page_down.display(), kb.page_up.display());
+    let local = app.is_local_mode();
+
+    match mode {
+        HelpMode::PullRequestList => vec![
+            hint(move_keys, "Move selection", "move"),
+            hint(kb.open_panel.display(), "Open pull request", "select"),
+            hint(
+                format!(
+                    "{}/{}",
+                    kb.jump_to_first.display(),
+                    kb.jump_to_last.display()
+                ),
+                "Jump to top/bottom",
+                "top/bottom",
+            ),
+            hint(kb.open_in_browser.display(), "Open in browser", "browser"),
+            hint("o", "Show open pull requests", "open"),
+            hint("c", "Show closed pull requests", "closed"),
+            hint("a", "Show all pull requests", "all"),
+            hint("r", "Refresh", "refresh"),
+            help_only(kb.toggle_local_mode.display(), "Toggle local diff mode"),
+            hint(kb.quit.display(), "Quit", "quit"),
+            hint(kb.help.display(), "Toggle help", "help"),
+        ],
+        HelpMode::FileList => {
+            let rally = if app.has_background_rally() {
+                "Resume AI Rally"
+            } else {
+                "AI Rally"
+            };
+            let mut hints = vec![
+                hint(move_keys, "Move selection", "move"),
+                hint(
+                    format!("{}/→/{}", kb.open_panel.display(), kb.move_right.display()),
+                    "Open split view",
+                    "split view",
+                ),
+            ];
+            if !local {
+                hints.extend([
+                    hint(
+                        kb.open_in_browser.display(),
+                        "Open PR in browser",
+                        "browser",
+                    ),
+                    hint(kb.approve.display(), "Approve PR", "approve"),
+                    hint(
+                        kb.request_changes.display(),
+                        "Request changes",
+                        "request changes",
+                    ),
+                    hint(kb.comment.display(), "Comment only", "comment"),
+                    hint(
+                        kb.comment_list.display(),
+                        "View review comments",
+                        "comments",
+                    ),
+                    help_only(kb.pr_details.display(), "View PR details (description)"),
+                ]);
+            }
+            hints.extend([
+                hint(kb.ai_rally.display(), "Start or resume AI Rally", rally),
+                help_only(
+                    kb.rerun_file_review.display(),
+                    "Re-run AI reviewer on selected file (finished rally)",
+                ),
+                hint(
+                    kb.refresh.display(),
+                    "Refresh (clear cache and reload)",
+                    "refresh",
+                ),
+                help_only(kb.toggle_local_mode.display(), "Toggle local diff mode"),
+            ]);
+            if !local {
+                hints.push(help_only(
+                    kb.refresh_checks.display(),
+                    "Refresh CI check status",
+                ));
+            }
+            if local {
+                hints.push(help_only(
+                    kb.toggle_auto_focus.display(),
+                    "Toggle auto-focus",
+                ));
+            }
+            hints.extend([
+                hint(kb.quit.display(), "Quit", "quit"),
+                hint(kb.help.display(), "Toggle help", "help"),
+            ]);
+            hints
+        }
+        HelpMode::DiffView if app.comment_panel_open => vec![
+            hint(move_keys, "Scroll panel", "scroll"),
+            hint(
+                format!(
+                    "{}/{}",
+                    kb.next_comment.display(),
+                    kb.prev_comment.display()
+                ),
+                "Jump to next/prev comment",
+                "jump",
+            ),
+            hint("Tab", "Select reply target (Shift-Tab: previous)", "switch"),
+            hint(kb.reply.display(), "Reply to comment", "reply"),
+            help_only(
+                kb.apply_suggestion.display(),
+                "Apply the comment's suggestion to the local file",
+            ),
+            hint(kb.comment.display(), "Add comment", "comment"),
+            hint(kb.suggestion.display(), "Add suggestion", "suggest"),
+            hint(
+                format!("←/{}", kb.move_left.display()),
+                "Back to diff",
+                "back",
+            ),
+            hint(format!("Esc/{}", kb.quit.display()), "Close panel", "close"),
+            help_only(kb.help.display(), "Toggle help"),
+        ],
+        HelpMode::DiffView => {
+            let mut hints = vec![hint(move_keys, "Move line selection", "move")];
+            if !local {
+                hints.extend([
+                    hint(
+                        format!(
+                            "{}/{}",
+                            kb.next_comment.display(),
+                            kb.prev_comment.display()
+                        ),
+                        "Jump to next/prev comment",
+                        "next/prev comment",
+                    ),
+                    hint(kb.open_panel.display(), "Open comment panel", "comments"),
+                ]);
+            }
+            hints.push(hint(page_keys, "Page down/up", "page"));
+            hints.extend([
+                help_only(
+                    format!(
+                        "{}/{}",
+                        kb.jump_to_first.display(),
+                        kb.jump_to_last.display()
+                    ),
+                    "Jump to first/last line",
+                ),
+                help_only(kb.jump_back.display(), "Jump back"),
+                help_only(kb.go_to_definition.display(), "Go to definition"),
+                help_only(kb.go_to_file.display(), "Open file in $EDITOR"),
+            ]);
+            if !local {
+                hints.extend([
+                    help_only(kb.comment.display(), "Add comment at line"),
+                    help_only(kb.suggestion.display(), "Add suggestion at line"),
+                ]);
+            }
+            hints.extend([
+                hint(
+                    format!("←/{}/{}", kb.move_left.display(), kb.quit.display()),
+                    "Back",
+                    "back",
+                ),
+                help_only(kb.help.display(), "Toggle help"),
+            ]);
+            hints
+        }
+        HelpMode::CommentList => match app.comment_tab {
+            CommentTab::Review => vec![
+                hint("j/k/↑↓", "Move selection", "move"),
+                hint("Ctrl+d/u", "Scroll comment detail", "scroll detail"),
+                hint(
+                    "Enter",
+                    "Open in editor (or jump to the diff)",
+                    "open in editor",
+                ),
+                hint("1-4", "Filter by severity", "severity filter"),
+                hint("0", "Reset severity filter", "reset"),
+                help_only("Space/o", "Expand/collapse the comment body"),
+                help_only("y", "Copy the comment body to the clipboard"),
+                help_only("m", "Mark/unmark the comment for the reviewee"),
+                help_only(
+                    "f",
+                    "Send the marked (or selected) comments to the AI Rally reviewee",
+                ),
+                hint("[/]", "Switch tab (Review/Discussion)", "switch tab"),
+                hint("q", "Back", "back"),
+                help_only(kb.help.display(), "Toggle help"),
+            ],
+            CommentTab::Discussion => vec![
+                hint("j/k/↑↓", "Move selection", "move"),
+                hint("Enter", "View detail", "view detail"),
+                help_only("Space/o", "Expand/collapse the comment body"),
+                help_only("y", "Copy the comment body to the clipboard"),
+                hint("[/]", "Switch tab (Review/Discussion)", "switch tab"),
+                hint("q", "Back", "back"),
+                help_only(kb.help.display(), "Toggle help"),
+            ],
+        },
+        HelpMode::AiRally => vec![
+            help_only("j/k/↑↓", "Select log entry"),
+            help_only("Enter", "Show log detail"),
+            help_only("g/G", "Jump to first/last log"),
+            help_only("PgDn/PgUp", "Page through logs (also Ctrl-f/Ctrl-b)"),
+            help_only(
+                "y",
+                "Grant permission / answer / publish; copy the open log entry, or the summary once finished",
+            ),
+            help_only("n", "Deny permission / skip / discard"),
+            help_only("Tab", "Select the review summary or a comment"),
+            help_only(
+                "e",
+                "Edit the selected summary/comment before publishing / answer in $EDITOR",
+            ),
+            help_only("f", "Send only the selected comment to reviewee"),
+            help_only("r", "Retry the failed agent turn"),
+            help_only("Ctrl-d/u", "Scroll the reviewee's error details"),
+            help_only("b", "Continue the rally in the background"),
+            help_only("F12", "Show the last agent's raw JSON result (debug)"),
+            help_only("q", "Abort rally (close when finished)"),
+            help_only(kb.help.display(), "Toggle help"),
+        ],
+    }
+}
+
+/// Footer text for `hints`: "keys: label | keys: label | ..."
+pub fn footer_text(hints: &[KeyHint]) -> String {
+    hints
+        .iter()
+        .filter_map(|h| h.footer.map(|label| format!("{}: {}", h.keys, label)))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_list_footer_matches_help() {
+        let app = App::new_for_test();
+        let hints = key_hints(HelpMode::FileList, &app);
+        assert_eq!(
+            footer_text(&hints),
+            "j/k/↑↓: move | Enter/→/l: split view | O: browser | a: approve | r: request changes | c: comment | C: comments | A: AI Rally | R: refresh | q: quit | ?: help"
+        );
+        // Everything in the footer is also described in the help popup
+        assert!(hints.iter().all(|h| !h.description.is_empty()));
+        assert!(hints.iter().any(|h| h.footer.is_none()));
+    }
+
+    #[test]
+    fn test_hints_follow_custom_keybindings() {
+        use crate::keybinding::{KeyBinding, KeySequence};
+
+        let mut app = App::new_for_test();
+        app.config.keybindings.approve = KeySequence::single(KeyBinding::char('y'));
+        let footer = footer_text(&key_hints(HelpMode::FileList, &app));
+        assert!(footer.contains("y: approve"));
+        assert!(!footer.contains("a: approve"));
+    }
+
+    #[test]
+    fn test_comment_list_hints_follow_tab() {
+        let mut app = App::new_for_test();
+        app.comment_tab = CommentTab::Discussion;
+        assert_eq!(
+            footer_text(&key_hints(HelpMode::CommentList, &app)),
+            "j/k/↑↓: move | Enter: view detail | [/]: switch tab | q: back"
+        );
+    }
+
+    #[test]
+    fn test_help_mode_from_state() {
+        assert_eq!(
+            HelpMode::from_state(AppState::SplitViewDiff),
+            HelpMode::DiffView
+        );
+        assert_eq!(
+            HelpMode::from_state(AppState::SplitViewFileList),
+            HelpMode::FileList
+        );
+        assert_eq!(HelpMode::from_state(AppState::AiRally), HelpMode::AiRally);
+    }
+}
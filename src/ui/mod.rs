@@ -5,11 +5,14 @@ pub mod diff_view;
 mod file_list;
 mod footer;
 mod help;
+mod keymap;
+mod pr_details;
 mod pr_list;
 mod split_view;
+mod text;
 pub mod text_area;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -22,14 +25,32 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem},
     Frame, Terminal,
 };
-use std::io::{self, Stdout};
+use std::io::{self, IsTerminal, Stdout};
 
 use crate::app::{App, AppState, DataState};
 
+const NOT_A_TTY_MESSAGE: &str = "or needs an interactive terminal, but stdin/stdout is not a TTY \
+(piped, redirected or running in CI). Run it from a terminal, or use --headless \
+(with --pr) to run AI Rally without the TUI.";
+
+/// TUI を起動できる環境か（stdin / stdout が端末か）を確認する
+fn check_tty(stdin_is_tty: bool, stdout_is_tty: bool) -> Result<()> {
+    if !(stdin_is_tty && stdout_is_tty) {
+        bail!(NOT_A_TTY_MESSAGE);
+    }
+    Ok(())
+}
+
 pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
-    enable_raw_mode()?;
+    // raw mode に入る前に弾き、ratatui/crossterm の奥でのエラーや中途半端な端末状態を避ける
+    check_tty(io::stdin().is_terminal(), io::stdout().is_terminal())?;
+
+    enable_raw_mode().context("Failed to enable raw mode for the terminal")?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+        let _ = disable_raw_mode();
+        return Err(e).context("Failed to enter the alternate screen");
+    }
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -56,21 +77,33 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         }
     }
 
-    match app.state {
+    if app.state == AppState::Help {
+        // ヘルプは元の画面の上にポップアップとして重ねる
+        render_screen(frame, app, app.help_return_state);
+        help::render(frame, app);
+    } else {
+        render_screen(frame, app, app.state);
+    }
+
+    // シンボル選択ポップアップ（最前面に描画）
+    if let Some(ref popup) = app.symbol_popup {
+        render_symbol_popup(frame, popup);
+    }
+}
+
+fn render_screen(frame: &mut Frame, app: &mut App, state: AppState) {
+    match state {
         AppState::PullRequestList => pr_list::render(frame, app),
         AppState::FileList => file_list::render(frame, app),
         AppState::DiffView => diff_view::render(frame, app),
         AppState::TextInput => diff_view::render_text_input(frame, app),
         AppState::CommentList => comment_list::render(frame, app),
-        AppState::Help => help::render(frame, app),
+        // ヘルプから別のヘルプには戻らないため描画するものはない
+        AppState::Help => {}
+        AppState::PrDetails => pr_details::render(frame, app),
         AppState::AiRally => ai_rally::render(frame, app),
         AppState::SplitViewFileList | AppState::SplitViewDiff => split_view::render(frame, app),
     }
-
-    // シンボル選択ポップアップ（最前面に描画）
-    if let Some(ref popup) = app.symbol_popup {
-        render_symbol_popup(frame, popup);
-    }
 }
 
 /// 中央配置のフローティングポップアップ領域を計算
@@ -127,3 +160,22 @@ fn render_symbol_popup(frame: &mut Frame, popup: &crate::app::SymbolPopupState)
 
     frame.render_widget(list, popup_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_tty_rejects_non_tty_with_guidance() {
+        for (stdin_is_tty, stdout_is_tty) in [(false, true), (true, false), (false, false)] {
+            let err = check_tty(stdin_is_tty, stdout_is_tty).unwrap_err();
+            assert!(err.to_string().contains("not a TTY"));
+            assert!(err.to_string().contains("--headless"));
+        }
+    }
+
+    #[test]
+    fn test_check_tty_accepts_terminal() {
+        assert!(check_tty(true, true).is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use syntect::easy::HighlightLines;
+
+use super::common::render_rally_status_bar;
+use crate::app::App;
+use crate::github::PullRequest;
+use crate::syntax::{get_theme, highlight_code_line_legacy, syntax_for_file};
+
+const NO_DESCRIPTION: &str = "(no description)";
+
+pub fn render(frame: &mut Frame, app: &mut App) {
+    let Some(pr) = app.pr() else {
+        return;
+    };
+
+    let has_rally = app.has_background_rally();
+    let constraints = if has_rally {
+        vec![
+            Constraint::Length(5), // Header (title, author/state, labels)
+            Constraint::Min(0),    // Body
+            Constraint::Length(1), // Rally status bar
+            Constraint::Length(3), // Footer
+        ]
+    } else {
+        vec![
+            Constraint::Length(5), // Header (title, author/state, labels)
+            Constraint::Min(0),    // Body
+            Constraint::Length(3), // Footer
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(frame.area());
+
+    let header = Paragraph::new(build_header_lines(pr)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("PR #{}", pr.number)),
+    );
+    frame.render_widget(header, chunks[0]);
+
+    let body_lines = build_body_lines(pr.body.as_deref(), &app.config.diff.theme);
+    let content_height = chunks[1].height.saturating_sub(2) as usize;
+    let max_scroll = body_lines.len().saturating_sub(content_height);
+    app.pr_details_scroll = app.pr_details_scroll.min(max_scroll);
+
+    let scroll_info = if body_lines.len() > content_height {
+        format!(" ({}/{})", app.pr_details_scroll + 1, max_scroll + 1)
+    } else {
+        String::new()
+    };
+
+    let body = Paragraph::new(body_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Description{}", scroll_info)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.pr_details_scroll as u16, 0));
+    frame.render_widget(body, chunks[1]);
+
+    if has_rally {
+        render_rally_status_bar(frame, chunks[2], app);
+    }
+
+    let footer_chunk_idx = if has_rally { 3 } else { 2 };
+    let footer = Paragraph::new("j/k/↑↓: scroll | Ctrl+d/u: page | q/Esc: back")
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[footer_chunk_idx]);
+}
+
+/// Build header lines: title, author/state/branches, and labels
+fn build_header_lines(pr: &PullRequest) -> Vec<Line<'static>> {
+    let labels = if pr.labels.is_empty() {
+        vec![Span::styled("(none)", Style::default().fg(Color::DarkGray))]
+    } else {
+        pr.labels
+            .iter()
+            .flat_map(|label| {
+                [
+                    Span::styled(
+                        format!("[{}]", label.name),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::raw(" "),
+                ]
+            })
+            .collect()
+    };
+
+    let mut label_line = vec![Span::styled("Labels: ", Style::default().fg(Color::Gray))];
+    label_line.extend(labels);
+
+    vec![
+        Line::from(Span::styled(
+            pr.title.clone(),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::styled(
+                format!("@{}", pr.user.login),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw("  "),
+            Span::styled(pr.state.clone(), Style::default().fg(Color::Green)),
+            Span::raw("  "),
+            Span::styled(
+                format!("{} ← {}", pr.base.ref_name, pr.head.ref_name),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(label_line),
+    ]
+}
+
+/// Build body lines with markdown highlighting, or a placeholder for an empty body
+fn build_body_lines(body: Option<&str>, theme_name: &str) -> Vec<Line<'static>> {
+    let body = body.map(str::trim).unwrap_or_default();
+    if body.is_empty() {
+        return vec![Line::from(Span::styled(
+            NO_DESCRIPTION,
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let Some(syntax) = syntax_for_file("README.md") else {
+        return body.lines().map(|l| Line::from(l.to_string())).collect();
+    };
+    let mut highlighter = HighlightLines::new(syntax, get_theme(theme_name));
+
+    // lines() also strips the CR of GitHub's CRLF line endings
+    body.lines()
+        .map(|line| Line::from(highlight_code_line_legacy(line, &mut highlighter)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_build_body_lines_renders_markdown_body() {
+        let body = "## Summary\r\n\r\n- Add **feature** X\r\n- Fix `bug`";
+        let lines = build_body_lines(Some(body), "base16-ocean.dark");
+        let texts: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            texts,
+            vec!["## Summary", "", "- Add **feature** X", "- Fix `bug`"]
+        );
+    }
+
+    #[test]
+    fn test_build_body_lines_empty_body() {
+        for body in [None, Some(""), Some("  \n ")] {
+            let lines = build_body_lines(body, "base16-ocean.dark");
+            assert_eq!(lines.len(), 1);
+            assert_eq!(line_text(&lines[0]), NO_DESCRIPTION);
+        }
+    }
+}
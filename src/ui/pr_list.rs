@@ -9,6 +9,7 @@ use ratatui::{
     Frame,
 };
 
+use super::keymap::{footer_text, key_hints, HelpMode};
 use crate::app::App;
 use crate::github::PullRequestSummary;
 
@@ -24,7 +25,15 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Header
     let filter_str = app.pr_list_state_filter.display_name();
-    let header_text = format!("PR List: {} ({})", app.repo, filter_str);
+    let header_text = match app.pr_queue {
+        Some(ref queue) => format!(
+            "Review queue: {} ({} PRs, {})",
+            app.repo,
+            queue.len(),
+            filter_str
+        ),
+        None => format!("PR List: {} ({})", app.repo, filter_str),
+    };
     let header =
         Paragraph::new(header_text).block(Block::default().borders(Borders::ALL).title("octorus"));
     frame.render_widget(header, chunks[0]);
@@ -101,8 +110,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     }
 
     // Footer
-    let footer_text =
-        "j/k/↑↓: move | Enter: select | gg/G: top/bottom | O: browser | o: open | c: closed | a: all | r: refresh | q: quit | ?: help";
+    let footer_text = footer_text(&key_hints(HelpMode::PullRequestList, app));
     let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[2]);
 }
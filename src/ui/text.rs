@@ -0,0 +1,155 @@
+//! Display helpers for agent and comment text: emoji shortcodes and width-aware truncation.
+
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "...";
+
+/// Look up a GitHub-style `:shortcode:` (without colons)
+fn shortcode_to_emoji(name: &str) -> Option<&'static str> {
+    let emoji = match name {
+        "+1" | "thumbsup" => "👍",
+        "-1" | "thumbsdown" => "👎",
+        "smile" => "😄",
+        "laughing" => "😆",
+        "tada" => "🎉",
+        "heart" => "❤️",
+        "rocket" => "🚀",
+        "eyes" => "👀",
+        "confused" => "😕",
+        "thinking" => "🤔",
+        "fire" => "🔥",
+        "bug" => "🐛",
+        "sparkles" => "✨",
+        "memo" => "📝",
+        "bulb" => "💡",
+        "warning" => "⚠️",
+        "rotating_light" => "🚨",
+        "white_check_mark" => "✅",
+        "heavy_check_mark" => "✔️",
+        "x" => "❌",
+        "no_entry" => "⛔",
+        "lock" => "🔒",
+        "wrench" => "🔧",
+        "hammer" => "🔨",
+        "recycle" => "♻️",
+        "zap" => "⚡",
+        "lipstick" => "💄",
+        "art" => "🎨",
+        "construction" => "🚧",
+        "boom" => "💥",
+        "pencil2" => "✏️",
+        "question" => "❓",
+        "exclamation" => "❗",
+        "information_source" => "ℹ️",
+        "mag" => "🔍",
+        "pray" => "🙏",
+        "clap" => "👏",
+        "100" => "💯",
+        "robot" => "🤖",
+        "point_right" => "👉",
+        _ => return None,
+    };
+    Some(emoji)
+}
+
+/// Replace known `:shortcode:`s with their emoji; unknown ones are left as-is
+/// (so text like `10:30:00` or `a::b` is not mangled).
+pub fn replace_emoji_shortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')))
+            .unwrap_or(after.len());
+
+        let emoji = after[name_len..]
+            .starts_with(':')
+            .then(|| shortcode_to_emoji(&after[..name_len]))
+            .flatten();
+        match emoji {
+            Some(emoji) => {
+                out.push_str(emoji);
+                rest = &after[name_len + 1..];
+            }
+            None => {
+                out.push(':');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Truncate text to `max_width` terminal columns, appending "..." when cut.
+///
+/// Uses display width rather than char count, so wide characters (emoji, CJK)
+/// don't overflow the column.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
+    let budget = max_width.saturating_sub(ELLIPSIS.len());
+    let mut truncated = String::new();
+    for ch in text.chars() {
+        truncated.push(ch);
+        // Measure the whole prefix: variation selectors can widen the previous char
+        if truncated.width() > budget {
+            truncated.pop();
+            break;
+        }
+    }
+    truncated.push_str(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_emoji_shortcodes() {
+        assert_eq!(
+            replace_emoji_shortcodes(":rocket: Ship it :+1:"),
+            "🚀 Ship it 👍"
+        );
+    }
+
+    #[test]
+    fn test_replace_emoji_shortcodes_keeps_unknown_and_non_shortcodes() {
+        assert_eq!(
+            replace_emoji_shortcodes("at 10:30:00 see :not_an_emoji: and a::b"),
+            "at 10:30:00 see :not_an_emoji: and a::b"
+        );
+        assert_eq!(replace_emoji_shortcodes("trailing:"), "trailing:");
+    }
+
+    #[test]
+    fn test_truncate_to_width_ascii() {
+        assert_eq!(truncate_to_width("hello", 10), "hello");
+        assert_eq!(truncate_to_width("hello world", 8), "hello...");
+    }
+
+    #[test]
+    fn test_truncate_to_width_wide_emoji() {
+        // Each emoji is 2 columns wide: 4 emoji = 8 columns
+        let text = "🚀🚀🚀🚀";
+        assert_eq!(truncate_to_width(text, 8), text);
+
+        let truncated = truncate_to_width(text, 7);
+        assert_eq!(truncated, "🚀🚀...");
+        assert!(truncated.width() <= 7);
+    }
+
+    #[test]
+    fn test_truncate_to_width_does_not_split_wide_char() {
+        // 6 columns leave a budget of 3: "a" + one emoji fits, a second one would not
+        let truncated = truncate_to_width("a🎉🎉🎉", 6);
+        assert_eq!(truncated, "a🎉...");
+        assert!(truncated.width() <= 6);
+    }
+}